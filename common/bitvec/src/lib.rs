@@ -9,9 +9,9 @@ use std::ops::BitAnd;
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 
-// Every u8 is used as a bucket of 8 bits. Total max buckets = 256 / 8 = 32.
+// Every u8 is used as a bucket of 8 bits. Total max buckets = 65536 / 8 = 8192.
 const BUCKET_SIZE: usize = 8;
-const MAX_BUCKETS: usize = 32;
+const MAX_BUCKETS: usize = 8192;
 
 /// BitVec represents a bit vector that supports 4 operations:
 /// 1. Marking a position as set.
@@ -24,8 +24,11 @@ const MAX_BUCKETS: usize = 32;
 /// * Bits are read from left to right. For instance, in the following bitvec
 ///   [0b0001_0000, 0b0000_0000, 0b0000_0000, 0b0000_0001], the 3rd and 31st positions are set.
 /// * Each bit of a u8 is set to 1 if the position is set and to 0 if it's not.
-/// * We only allow setting positions upto u8::MAX. As a result, the size of the inner vector is
-///   limited to 32 (= 256 / 8).
+/// * We only allow setting positions upto u16::MAX. As a result, the size of the inner vector is
+///   limited to 8192 (= 65536 / 8).
+/// * Setting a position only grows the inner vector up to the bucket of that position, so a
+///   BitVec whose positions are all lower than 256 has the same encoding as when positions were
+///   limited to u8::MAX.
 /// * Once a bit has been set, it cannot be unset. As a result, the inner vector cannot shrink.
 /// * The positions can be set in any order.
 /// * A position can set more than once -- it remains set after the first time.
@@ -42,6 +45,11 @@ const MAX_BUCKETS: usize = 32;
 /// assert_eq!(bv.count_ones(), 2);
 /// assert_eq!(bv.last_set_bit(), Some(5));
 ///
+/// // Positions go beyond 256.
+/// bv.set(1000);
+/// assert!(bv.is_set(1000));
+/// assert_eq!(bv.iter_ones().collect::<Vec<_>>(), vec![2, 5, 1000]);
+///
 /// // A bitwise AND of BitVec can be performed by using the `&` operator.
 /// let mut bv1 = BitVec::default();
 /// bv1.set(2);
@@ -63,7 +71,7 @@ impl BitVec {
     // TODO(abhayb): Remove after migration to new wire format.
     #[allow(dead_code)]
     /// Sets the bit at position @pos.
-    pub fn set(&mut self, pos: u8) {
+    pub fn set(&mut self, pos: u16) {
         // This is optimised to: let bucket = pos >> 3;
         let bucket: usize = pos as usize / BUCKET_SIZE;
         if self.inner.len() <= bucket {
@@ -77,7 +85,7 @@ impl BitVec {
     // TODO(abhayb): Remove after migration to new wire format.
     #[allow(dead_code)]
     /// Checks if the bit at position @pos is set.
-    pub fn is_set(&self, pos: u8) -> bool {
+    pub fn is_set(&self, pos: u16) -> bool {
         // This is optimised to: let bucket = pos >> 3;
         let bucket: usize = pos as usize / BUCKET_SIZE;
         if self.inner.len() <= bucket {
@@ -98,14 +106,32 @@ impl BitVec {
     // TODO(kostas): Remove after applying it to multi-sig.
     #[allow(dead_code)]
     /// Returns the index of the last set bit.
-    pub fn last_set_bit(&self) -> Option<u8> {
+    pub fn last_set_bit(&self) -> Option<u16> {
         self.inner
             .iter()
             .rev()
             .enumerate()
             .find(|(_, byte)| byte != &&0u8)
             .map(|(i, byte)| {
-                (8 * (self.inner.len() - i) - byte.trailing_zeros() as usize - 1) as u8
+                (8 * (self.inner.len() - i) - byte.trailing_zeros() as usize - 1) as u16
+            })
+    }
+
+    /// Returns the number of buckets of the inner vector, i.e. of bytes in the encoding.
+    pub fn num_buckets(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns an iterator over the set positions, from smallest to largest.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.inner
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte != 0)
+            .flat_map(|(bucket, byte)| {
+                (0..BUCKET_SIZE)
+                    .filter(move |bucket_pos| byte & (0b1000_0000 >> bucket_pos) != 0)
+                    .map(move |bucket_pos| bucket * BUCKET_SIZE + bucket_pos)
             })
     }
 }
@@ -127,7 +153,7 @@ impl BitAnd for BitVec {
 }
 
 // We impl custom deserialization to ensure that the length of inner vector does not exceed
-// 32 (= 256 / 8).
+// 8192 (= 65536 / 8).
 impl<'de> Deserialize<'de> for BitVec {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -146,6 +172,7 @@ mod test {
 
     use super::*;
     use proptest::{arbitrary::any, collection::vec, prelude::*};
+    use std::collections::BTreeSet;
 
     #[test]
     fn test_count_ones() {
@@ -195,7 +222,7 @@ mod test {
             inner: vec![255u8; MAX_BUCKETS],
         };
         assert_eq!(p3.inner.len(), MAX_BUCKETS);
-        assert_eq!(p3.last_set_bit(), Some(255));
+        assert_eq!(p3.last_set_bit(), Some(std::u16::MAX));
 
         let p4 = BitVec {
             inner: vec![0u8; MAX_BUCKETS],
@@ -226,32 +253,43 @@ mod test {
     #[test]
     fn test_empty() {
         let p = BitVec::default();
-        for i in 0..=std::u8::MAX {
+        for i in 0..=std::u16::MAX {
             assert_eq!(false, p.is_set(i));
         }
+        assert_eq!(p.iter_ones().next(), None);
     }
 
     #[test]
     fn test_extremes() {
         let mut p = BitVec::default();
-        p.set(std::u8::MAX);
+        p.set(std::u16::MAX);
         p.set(0);
-        assert!(p.is_set(std::u8::MAX));
+        assert!(p.is_set(std::u16::MAX));
         assert!(p.is_set(0));
-        for i in 1..std::u8::MAX {
+        for i in 1..std::u16::MAX {
             assert_eq!(false, p.is_set(i));
         }
+        assert_eq!(p.num_buckets(), MAX_BUCKETS);
+        assert_eq!(p.last_set_bit(), Some(std::u16::MAX));
+        assert_eq!(
+            p.iter_ones().collect::<Vec<_>>(),
+            vec![0, std::u16::MAX as usize]
+        );
     }
 
     #[test]
     fn test_deserialization() {
-        // When the length is smaller than 128, it is encoded in the first byte.
-        // (see comments in BCS crate)
-        let mut bytes = [0u8; 47];
-        bytes[0] = 46;
+        // The length is encoded as ULEB128 (see comments in BCS crate).
+        let bytes = bcs::to_bytes(&vec![0u8; MAX_BUCKETS + 1]).unwrap();
         assert!(bcs::from_bytes::<Vec<u8>>(&bytes).is_ok());
-        // However, 46 > MAX_BUCKET:
+        // However, the length is > MAX_BUCKETS:
         assert!(bcs::from_bytes::<BitVec>(&bytes).is_err());
+        let bytes = bcs::to_bytes(&vec![0u8; MAX_BUCKETS]).unwrap();
+        let bv = BitVec {
+            inner: vec![0u8; MAX_BUCKETS],
+        };
+        assert_eq!(Ok(bv), bcs::from_bytes::<BitVec>(&bytes));
+        // When the length is smaller than 128, it is encoded in the first byte.
         let mut bytes = [0u8; 33];
         bytes[0] = 32;
         let bv = BitVec {
@@ -262,7 +300,7 @@ mod test {
 
     // Constructs a bit vector by setting the positions specified in the argument vector. The
     // vector can have duplicates and need not be sorted.
-    fn construct_bitvec(posns: &[u8]) -> BitVec {
+    fn construct_bitvec(posns: &[u16]) -> BitVec {
         let mut bv = BitVec::default();
         posns.iter().for_each(|x| bv.set(*x));
         bv
@@ -271,40 +309,133 @@ mod test {
     // Proptest for ensuring is_set returns true iff corresponding position was set.
     proptest! {
         #[test]
-        fn test_arbitrary(mut v in vec(any::<u8>(), 0..256)) {
+        fn test_arbitrary(mut v in vec(any::<u16>(), 0..256)) {
             let bv = construct_bitvec(&v);
             // Sort and dedup the vector so we can iterate over its elements from smallest to largest.
             v.sort_unstable();
             v.dedup();
-            let mut viter = v.into_iter().peekable();
-            // Positions in bv should be set iff they are in v.
-            for i in 0..std::u8::MAX {
-                if viter.peek() == Some(&i) {
-                    prop_assert!(bv.is_set(i));
-                    viter.next();
-                } else {
-                    prop_assert_eq!(false, bv.is_set(i));
-                }
+            prop_assert_eq!(
+                bv.iter_ones().collect::<Vec<_>>(),
+                v.iter().map(|pos| *pos as usize).collect::<Vec<_>>()
+            );
+            prop_assert_eq!(bv.count_ones() as usize, v.len());
+            prop_assert_eq!(bv.last_set_bit(), v.last().copied());
+            // Positions in bv should be set iff they are in v. Positions beyond the last bucket
+            // are never set, so only the ones backed by a bucket need checking.
+            let set: BTreeSet<u16> = v.into_iter().collect();
+            for i in 0..bv.num_buckets() * BUCKET_SIZE {
+                let i = i as u16;
+                prop_assert_eq!(set.contains(&i), bv.is_set(i));
             }
+        }
+    }
 
+    // Proptest for ensuring BCS round-trips of bit vectors with positions beyond u8::MAX.
+    proptest! {
+        #[test]
+        fn test_bcs_roundtrip(v in vec(any::<u16>(), 0..256)) {
+            let bv = construct_bitvec(&v);
+            let bytes = bcs::to_bytes(&bv).unwrap();
+            prop_assert_eq!(bcs::from_bytes::<BitVec>(&bytes), Ok(bv));
         }
     }
 
     // Test for bitwise AND operation on 2 bitvecs.
     proptest! {
         #[test]
-        fn test_and(v1 in vec(any::<u8>(), 0..256), v2 in vec(any::<u8>(), 0..256)) {
+        fn test_and(v1 in vec(any::<u16>(), 0..256), v2 in vec(any::<u16>(), 0..256)) {
             let bv1 = construct_bitvec(&v1);
             let bv2 = construct_bitvec(&v2);
-            let intersection = bv1.clone() & bv2.clone();
-            for i in 0..std::u8::MAX {
-                if bv1.is_set(i) && bv2.is_set(i) {
-                    prop_assert!(intersection.is_set(i));
-                } else {
-                    prop_assert_eq!(false, intersection.is_set(i));
+            let intersection = bv1 & bv2;
+            let s1: BTreeSet<usize> = v1.into_iter().map(usize::from).collect();
+            let s2: BTreeSet<usize> = v2.into_iter().map(usize::from).collect();
+            prop_assert_eq!(
+                intersection.iter_ones().collect::<Vec<_>>(),
+                s1.intersection(&s2).copied().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    // The BitVec as it was when positions were limited to u8::MAX, to check that the encoding
+    // stays backward-compatible for small sizes.
+    mod legacy {
+        use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+        const MAX_BUCKETS: usize = 32;
+
+        #[derive(Clone, Default, Debug, Eq, PartialEq, Serialize)]
+        pub struct BitVec {
+            #[serde(with = "serde_bytes")]
+            inner: Vec<u8>,
+        }
+
+        impl BitVec {
+            pub fn set(&mut self, pos: u8) {
+                let bucket: usize = pos as usize / super::BUCKET_SIZE;
+                if self.inner.len() <= bucket {
+                    self.inner.resize(bucket + 1, 0);
                 }
+                let bucket_pos = pos as usize - (bucket * super::BUCKET_SIZE);
+                self.inner[bucket] |= 0b1000_0000 >> bucket_pos as u8;
             }
 
+            pub fn is_set(&self, pos: u8) -> bool {
+                let bucket: usize = pos as usize / super::BUCKET_SIZE;
+                if self.inner.len() <= bucket {
+                    return false;
+                }
+                let bucket_pos = pos as usize - (bucket * super::BUCKET_SIZE);
+                (self.inner[bucket] & (0b1000_0000 >> bucket_pos as u8)) != 0
+            }
+        }
+
+        impl<'de> Deserialize<'de> for BitVec {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let v = serde_bytes::ByteBuf::deserialize(deserializer)?.into_vec();
+                if v.len() > MAX_BUCKETS {
+                    return Err(D::Error::custom(format!("BitVec too long: {}", v.len())));
+                }
+                Ok(BitVec { inner: v })
+            }
+        }
+    }
+
+    fn construct_legacy_bitvec(posns: &[u8]) -> legacy::BitVec {
+        let mut bv = legacy::BitVec::default();
+        posns.iter().for_each(|x| bv.set(*x));
+        bv
+    }
+
+    // Proptests for ensuring round-trips between the legacy and the current formats.
+    proptest! {
+        #[test]
+        fn test_legacy_encoding(v in vec(any::<u8>(), 0..256)) {
+            let bv = construct_bitvec(&v.iter().map(|pos| u16::from(*pos)).collect::<Vec<_>>());
+            let legacy_bv = construct_legacy_bitvec(&v);
+            prop_assert_eq!(bcs::to_bytes(&bv).unwrap(), bcs::to_bytes(&legacy_bv).unwrap());
+        }
+
+        #[test]
+        fn test_legacy_roundtrip(v in vec(any::<u8>(), 0..256)) {
+            // Legacy -> current -> legacy
+            let legacy_bv = construct_legacy_bitvec(&v);
+            let bv: BitVec = bcs::from_bytes(&bcs::to_bytes(&legacy_bv).unwrap()).unwrap();
+            for i in 0..=std::u8::MAX {
+                prop_assert_eq!(legacy_bv.is_set(i), bv.is_set(u16::from(i)));
+            }
+            let roundtrip: legacy::BitVec = bcs::from_bytes(&bcs::to_bytes(&bv).unwrap()).unwrap();
+            prop_assert_eq!(roundtrip, legacy_bv);
+        }
+
+        #[test]
+        fn test_legacy_rejects_large(v in vec(256..=std::u16::MAX, 1..16)) {
+            // Bit vectors with positions beyond u8::MAX don't fit the legacy format.
+            let bv = construct_bitvec(&v);
+            let bytes = bcs::to_bytes(&bv).unwrap();
+            prop_assert!(bcs::from_bytes::<legacy::BitVec>(&bytes).is_err());
         }
     }
 }
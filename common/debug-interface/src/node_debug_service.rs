@@ -28,6 +28,10 @@ impl NodeDebugService {
         // GET /events
         let events = warp::path("events").map(|| warp::reply::json(&json_log::pop_last_entries()));
 
+        // GET /peers/banned
+        let banned_peers = warp::path!("peers" / "banned")
+            .map(|| warp::reply::json(&network::reputation::banned_peers()));
+
         // Post /log/filter
         let local_filter = {
             let logger = logger.clone();
@@ -65,7 +69,7 @@ impl NodeDebugService {
             .and(warp::path("log"))
             .and(local_filter.or(remote_filter));
 
-        let routes = log.or(warp::get().and(metrics.or(events)));
+        let routes = log.or(warp::get().and(metrics.or(events).or(banned_peers)));
 
         runtime
             .handle()
@@ -68,6 +68,11 @@ pub enum SecurityEvent {
     /// HealthChecker received an invalid message
     InvalidHealthCheckerMsg,
 
+    // Peer Exchange
+    // -------------
+    /// PeerExchange received an invalid message or peer record
+    InvalidPeerExchangeMsg,
+
     // Network
     // -------
     /// Network received an invalid message from a remote peer
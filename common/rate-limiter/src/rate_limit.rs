@@ -171,6 +171,15 @@ impl<Key: Eq + Hash + Clone + Debug> TokenBucketRateLimiter<Key> {
         }
         remove
     }
+
+    /// Garbage collects the buckets which aren't in use and have refilled completely, as a new
+    /// bucket for their key would have at most as many tokens.  Returns the number of removed keys
+    pub fn garbage_collect_full_buckets(&self) -> usize {
+        let mut buckets = self.buckets.write();
+        let num_buckets = buckets.len();
+        buckets.retain(|_, bucket| Arc::strong_count(bucket) > 1 || !bucket.lock().is_full());
+        num_buckets - buckets.len()
+    }
 }
 
 /// A token bucket object that keeps track of everything related to a key
@@ -364,6 +373,12 @@ impl Bucket {
         }
     }
 
+    /// Whether the bucket is filled up to its size, after refilling it
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.size
+    }
+
     /// Add new tokens
     /// Ensures bucket doesn't overfill
     fn add_tokens(&mut self, new_tokens: usize) {
@@ -546,4 +561,32 @@ mod tests {
         assert!(!rate_limiter.try_garbage_collect_key(&key_to_keep));
         assert_num_keys(&rate_limiter, 1);
     }
+
+    #[test]
+    fn test_garbage_collect_full_buckets() {
+        let rate_limiter = TokenBucketRateLimiter::test(2, 1);
+
+        // Full buckets which aren't in use are removed
+        {
+            let _bucket_arc = rate_limiter.bucket("full");
+        }
+        assert_num_keys(&rate_limiter, 1);
+        assert_eq!(1, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 0);
+
+        // Buckets which are in use, or aren't full yet, are kept
+        let _bucket_arc = rate_limiter.bucket("in use");
+        rate_limiter
+            .bucket("not full")
+            .lock()
+            .acquire_all_tokens(1)
+            .unwrap();
+        assert_eq!(0, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 2);
+
+        // Until they're refilled
+        sleep(Duration::from_secs(1));
+        assert_eq!(1, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 1);
+    }
 }
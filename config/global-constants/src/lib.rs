@@ -24,5 +24,6 @@ pub const VALIDATOR_NETWORK_KEY: &str = "validator_network";
 /// Definitions of global data items (e.g., as held in secure storage)
 pub const SAFETY_DATA: &str = "safety_data";
 pub const WAYPOINT: &str = "waypoint";
+pub const VALIDATOR_NETWORK_KEY_IN_USE: &str = "validator_network_in_use";
 pub const GENESIS_WAYPOINT: &str = "genesis-waypoint";
 pub const MOVE_MODULES: &str = "move-modules";
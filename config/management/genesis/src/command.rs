@@ -175,8 +175,8 @@ pub mod tests {
     use crate::storage_helper::StorageHelper;
     use dijets_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
     use dijets_global_constants::{OPERATOR_KEY, OWNER_KEY};
-    use dijets_management::constants;
-    use dijets_secure_storage::KVStorage;
+    use dijets_management::{constants, secure_backend::GIT};
+    use dijets_secure_storage::{GitStorage, KVStorage, Namespaced};
     use dijets_types::{account_address, chain_id::ChainId, transaction::TransactionPayload};
     use std::{
         fs::File,
@@ -346,6 +346,65 @@ pub mod tests {
         assert_eq!(layout_text, stored_layout);
     }
 
+    #[test]
+    fn test_set_layout_git() {
+        let remote = dijets_temppath::TempPath::new();
+        remote.create_as_dir().unwrap();
+        let status = std::process::Command::new("git")
+            .args(&["init", "-q", "--bare"])
+            .arg(remote.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let temppath = dijets_temppath::TempPath::new();
+        temppath.create_as_file().unwrap();
+        let mut file = File::create(temppath.path()).unwrap();
+        let layout_text = "\
+            operators = [\"alice\", \"bob\"]\n\
+            owners = [\"carol\"]\n\
+            dijets_root = \"dave\"\n\
+            treasury_compliance = \"other_dave\"\n\
+        ";
+        file.write_all(&layout_text.to_string().into_bytes())
+            .unwrap();
+        file.sync_all().unwrap();
+
+        // The shared backend is a local clone of a remote repository, e.g., on removable media
+        let local = dijets_temppath::TempPath::new();
+        let args = format!(
+            "
+                dijets-genesis-tool
+                set-layout
+                --path {path}
+                --shared-backend backend={backend};\
+                    path={local};\
+                    remote={remote};\
+                    branch=genesis
+            ",
+            path = temppath.path().to_str().unwrap(),
+            backend = GIT,
+            local = local.path().to_str().unwrap(),
+            remote = remote.path().to_str().unwrap(),
+        );
+        Command::from_iter(args.split_whitespace())
+            .set_layout()
+            .unwrap();
+
+        // Another participant reads the layout through their own clone
+        let other = dijets_temppath::TempPath::new();
+        let storage = Namespaced::new(
+            constants::COMMON_NS,
+            GitStorage::new(
+                other.path().to_path_buf(),
+                Some(remote.path().to_str().unwrap().into()),
+                "genesis".into(),
+            ),
+        );
+        let stored_layout = storage.get::<String>(constants::LAYOUT).unwrap().value;
+        assert_eq!(layout_text, stored_layout);
+    }
+
     #[test]
     fn test_validator_config() {
         use dijets_types::account_address::AccountAddress;
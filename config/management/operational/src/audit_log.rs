@@ -0,0 +1,113 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_config::config::Token;
+use dijets_crypto::HashValue;
+use dijets_management::error::Error;
+use dijets_secure_storage::{read_audit_log, verify_audit_log};
+use serde::Serialize;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Verifies the MAC chain of a secure storage audit log. As records removed from the end of a
+/// log leave an intact chain, a head recorded earlier can also be checked to still be part of it.
+#[derive(Debug, StructOpt)]
+pub struct VerifyAuditLog {
+    /// Location of the audit log
+    #[structopt(long)]
+    path: PathBuf,
+    /// File holding the key of the log, as configured for the audited backend
+    #[structopt(long)]
+    key_file: PathBuf,
+    /// Head of the log as previously reported, which must be the MAC of one of its records
+    #[structopt(long)]
+    expected_head: Option<HashValue>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AuditLogSummary {
+    /// The number of records in the log
+    pub records: usize,
+    /// The MAC of the last record, if any
+    pub head: Option<HashValue>,
+}
+
+impl VerifyAuditLog {
+    pub fn execute(self) -> Result<AuditLogSummary, Error> {
+        let key = Token::FromDisk(self.key_file)
+            .read_bytes()
+            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
+        let records = read_audit_log(&self.path)
+            .map_err(|e| Error::UnexpectedError(format!("Unable to read audit log: {}", e)))?;
+        let head = verify_audit_log(&records, &key)
+            .map_err(|e| Error::UnexpectedError(format!("Audit log is corrupted: {}", e)))?;
+
+        // The log may have received more records since the expected head was reported
+        if let Some(expected_head) = self.expected_head {
+            if !records.iter().any(|record| record.mac == expected_head) {
+                return Err(Error::UnexpectedError(format!(
+                    "Audit log with head {:?} has no record with the expected head {}",
+                    head, expected_head
+                )));
+            }
+        }
+
+        Ok(AuditLogSummary {
+            records: records.len(),
+            head,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dijets_secure_storage::{AuditLog, Audited, InMemoryStorage, KVStorage};
+    use dijets_temppath::TempPath;
+
+    const KEY: &[u8] = b"audit log key";
+
+    fn verify(
+        log: &TempPath,
+        key_file: &TempPath,
+        expected_head: Option<HashValue>,
+    ) -> Result<AuditLogSummary, Error> {
+        VerifyAuditLog {
+            path: log.path().to_path_buf(),
+            key_file: key_file.path().to_path_buf(),
+            expected_head,
+        }
+        .execute()
+    }
+
+    #[test]
+    fn test_verify_audit_log() {
+        let key_file = TempPath::new();
+        std::fs::write(key_file.path(), KEY).unwrap();
+        let log = TempPath::new();
+        let mut storage = Audited::new(
+            "safety_rules",
+            AuditLog::file(log.path(), KEY.to_vec()).unwrap(),
+            InMemoryStorage::new(),
+        );
+
+        storage.set("key", 0u64).unwrap();
+        let summary = verify(&log, &key_file, None).unwrap();
+        assert_eq!(summary.records, 1);
+        let earlier_head = summary.head.unwrap();
+        verify(&log, &key_file, Some(earlier_head)).unwrap();
+
+        // A head reported earlier is still part of the chain after more records
+        storage.set("key", 1u64).unwrap();
+        let summary = verify(&log, &key_file, Some(earlier_head)).unwrap();
+        assert_eq!(summary.records, 2);
+        assert_ne!(summary.head, Some(earlier_head));
+        verify(&log, &key_file, summary.head).unwrap();
+
+        verify(&log, &key_file, Some(HashValue::random())).unwrap_err();
+
+        // The chain can't be verified with another key
+        std::fs::write(key_file.path(), b"other key").unwrap();
+        verify(&log, &key_file, None).unwrap_err();
+    }
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account_resource::SimplifiedAccountResource, validator_config::DecryptedValidatorConfig,
-    validator_set::DecryptedValidatorInfo, TransactionContext,
+    account_resource::SimplifiedAccountResource, audit_log::AuditLogSummary,
+    validator_config::DecryptedValidatorConfig, validator_set::DecryptedValidatorInfo,
+    TransactionContext,
 };
 use dijets_config::config::Peer;
 use dijets_crypto::{ed25519::Ed25519PublicKey, x25519};
@@ -42,6 +43,8 @@ pub enum Command {
     GenerateKey(crate::keys::GenerateKey),
     #[structopt(about = "Set the waypoint in the validator storage")]
     InsertWaypoint(dijets_management::waypoint::InsertWaypoint),
+    #[structopt(about = "Converts a plaintext on disk storage into an encrypted one")]
+    MigrateOnDiskStorage(crate::migrate_storage::MigrateOnDiskStorage),
     #[structopt(about = "Prints an account from the validator storage")]
     PrintAccount(crate::print::PrintAccount),
     #[structopt(about = "Prints an ed25519 public key from the validator storage")]
@@ -72,6 +75,8 @@ pub enum Command {
     ValidatorConfig(crate::validator_config::ValidatorConfig),
     #[structopt(about = "Displays the current validator set infos registered on the blockchain")]
     ValidatorSet(crate::validator_set::ValidatorSet),
+    #[structopt(about = "Verifies the MAC chain of a secure storage audit log")]
+    VerifyAuditLog(crate::audit_log::VerifyAuditLog),
 }
 
 #[derive(Debug, PartialEq)]
@@ -89,6 +94,7 @@ pub enum CommandName {
     ExtractPublicKey,
     GenerateKey,
     InsertWaypoint,
+    MigrateOnDiskStorage,
     PrintAccount,
     PrintKey,
     PrintXKey,
@@ -103,6 +109,7 @@ pub enum CommandName {
     ValidateTransaction,
     ValidatorConfig,
     ValidatorSet,
+    VerifyAuditLog,
 }
 
 impl From<&Command> for CommandName {
@@ -121,6 +128,7 @@ impl From<&Command> for CommandName {
             Command::ExtractPeersFromKeys(_) => CommandName::ExtractPeersFromKeys,
             Command::GenerateKey(_) => CommandName::GenerateKey,
             Command::InsertWaypoint(_) => CommandName::InsertWaypoint,
+            Command::MigrateOnDiskStorage(_) => CommandName::MigrateOnDiskStorage,
             Command::PrintAccount(_) => CommandName::PrintAccount,
             Command::PrintKey(_) => CommandName::PrintKey,
             Command::PrintXKey(_) => CommandName::PrintXKey,
@@ -135,6 +143,7 @@ impl From<&Command> for CommandName {
             Command::ValidateTransaction(_) => CommandName::ValidateTransaction,
             Command::ValidatorConfig(_) => CommandName::ValidatorConfig,
             Command::ValidatorSet(_) => CommandName::ValidatorSet,
+            Command::VerifyAuditLog(_) => CommandName::VerifyAuditLog,
         }
     }
 }
@@ -155,6 +164,7 @@ impl std::fmt::Display for CommandName {
             CommandName::ExtractPeersFromKeys => "extract-peers-from-keys",
             CommandName::GenerateKey => "generate-key",
             CommandName::InsertWaypoint => "insert-waypoint",
+            CommandName::MigrateOnDiskStorage => "migrate-on-disk-storage",
             CommandName::PrintAccount => "print-account",
             CommandName::PrintKey => "print-key",
             CommandName::PrintXKey => "print-x-key",
@@ -169,6 +179,7 @@ impl std::fmt::Display for CommandName {
             CommandName::ValidateTransaction => "validate-transaction",
             CommandName::ValidatorConfig => "validator-config",
             CommandName::ValidatorSet => "validator-set",
+            CommandName::VerifyAuditLog => "verify-audit-log",
         };
         write!(f, "{}", name)
     }
@@ -194,6 +205,7 @@ impl Command {
             Command::ExtractPrivateKey(cmd) => Self::print_success(cmd.execute()),
             Command::ExtractPublicKey(cmd) => Self::print_success(cmd.execute()),
             Command::GenerateKey(cmd) => Self::print_success(cmd.execute().map(|_| ())),
+            Command::MigrateOnDiskStorage(cmd) => Self::print_success(cmd.execute()),
             Command::PrintAccount(cmd) => Self::pretty_print(cmd.execute()),
             Command::PrintKey(cmd) => Self::pretty_print(cmd.execute()),
             Command::PrintXKey(cmd) => Self::pretty_print(cmd.execute()),
@@ -216,6 +228,7 @@ impl Command {
             Command::ValidateTransaction(cmd) => Self::print_transaction_context(cmd.execute()),
             Command::ValidatorConfig(cmd) => Self::pretty_print(cmd.execute()),
             Command::ValidatorSet(cmd) => Self::pretty_print(cmd.execute()),
+            Command::VerifyAuditLog(cmd) => Self::pretty_print(cmd.execute()),
         }
     }
 
@@ -333,6 +346,14 @@ impl Command {
         execute_command!(self, Command::InsertWaypoint, CommandName::InsertWaypoint)
     }
 
+    pub fn migrate_on_disk_storage(self) -> Result<(), Error> {
+        execute_command!(
+            self,
+            Command::MigrateOnDiskStorage,
+            CommandName::MigrateOnDiskStorage
+        )
+    }
+
     pub fn print_account(self) -> Result<AccountAddress, Error> {
         execute_command!(self, Command::PrintAccount, CommandName::PrintAccount)
     }
@@ -420,6 +441,10 @@ impl Command {
     pub fn validator_set(self) -> Result<Vec<DecryptedValidatorInfo>, Error> {
         execute_command!(self, Command::ValidatorSet, CommandName::ValidatorSet)
     }
+
+    pub fn verify_audit_log(self) -> Result<AuditLogSummary, Error> {
+        execute_command!(self, Command::VerifyAuditLog, CommandName::VerifyAuditLog)
+    }
 }
 
 /// A result wrapper for displaying either a correct execution result or an error.
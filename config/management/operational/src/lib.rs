@@ -4,11 +4,13 @@
 #![forbid(unsafe_code)]
 
 mod account_resource;
+mod audit_log;
 mod auto_validate;
 pub mod command;
 mod governance;
 pub mod json_rpc;
 pub mod keys;
+mod migrate_storage;
 mod owner;
 mod print;
 mod validate_transaction;
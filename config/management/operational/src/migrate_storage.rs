@@ -0,0 +1,44 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_config::config::Token;
+use dijets_management::error::Error;
+use dijets_secure_storage::{EncryptedOnDiskStorage, OnDiskStorage};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Converts a plaintext on disk storage into an encrypted one. The plaintext file is left in
+/// place, so that it can be securely deleted once the nodes are configured to use the encrypted
+/// storage.
+#[derive(Debug, StructOpt)]
+pub struct MigrateOnDiskStorage {
+    /// Location of the existing plaintext storage
+    #[structopt(long)]
+    plaintext_path: PathBuf,
+    /// Location of the encrypted storage to create, it must not exist yet
+    #[structopt(long)]
+    encrypted_path: PathBuf,
+    /// File whose whole contents are the secret from which the encryption key is derived
+    #[structopt(long)]
+    key_file: PathBuf,
+}
+
+impl MigrateOnDiskStorage {
+    pub fn execute(self) -> Result<(), Error> {
+        if !self.plaintext_path.exists() {
+            return Err(Error::CommandArgumentError(format!(
+                "{} does not exist",
+                self.plaintext_path.display()
+            )));
+        }
+        // Read the same way as the secret of the node's encrypted storage config
+        let secret = Token::FromDisk(self.key_file)
+            .read_bytes()
+            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
+
+        let plaintext = OnDiskStorage::new(self.plaintext_path);
+        EncryptedOnDiskStorage::migrate_from(&plaintext, self.encrypted_path, &secret)
+            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
+        Ok(())
+    }
+}
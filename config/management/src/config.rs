@@ -123,12 +123,7 @@ impl Config {
 
     pub fn shared_backend_with_namespace(&self, namespace: String) -> StorageWrapper {
         let mut shared_backend = self.shared_backend.clone();
-        match &mut shared_backend {
-            config::SecureBackend::GitHub(config) => config.namespace = Some(namespace),
-            config::SecureBackend::InMemoryStorage => panic!("Unsupported namespace for InMemory"),
-            config::SecureBackend::Vault(config) => config.namespace = Some(namespace),
-            config::SecureBackend::OnDiskStorage(config) => config.namespace = Some(namespace),
-        };
+        set_namespace(&mut shared_backend, namespace);
         StorageWrapper {
             storage_name: "shared",
             storage: std::convert::From::from(&shared_backend),
@@ -143,6 +138,20 @@ impl Config {
     }
 }
 
+/// Sets the namespace of a backend, or that of the backend it wraps.
+fn set_namespace(backend: &mut config::SecureBackend, namespace: String) {
+    match backend {
+        config::SecureBackend::Git(config) => config.namespace = Some(namespace),
+        config::SecureBackend::GitHub(config) => config.namespace = Some(namespace),
+        config::SecureBackend::InMemoryStorage => panic!("Unsupported namespace for InMemory"),
+        config::SecureBackend::Vault(config) => config.namespace = Some(namespace),
+        config::SecureBackend::OnDiskStorage(config) => config.namespace = Some(namespace),
+        config::SecureBackend::EncryptedOnDiskStorage(config) => config.namespace = Some(namespace),
+        config::SecureBackend::Pkcs11(config) => config.namespace = Some(namespace),
+        config::SecureBackend::Audited(config) => set_namespace(&mut config.backend, namespace),
+    };
+}
+
 #[derive(Clone, Debug, Default, StructOpt)]
 pub struct ConfigPath {
     /// Path to a dijets-management configuration file
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::Error;
-use dijets_config::config::{self, GitHubConfig, OnDiskStorageConfig, Token, VaultConfig};
+use dijets_config::config::{
+    self, EncryptedOnDiskStorageConfig, GitConfig, GitHubConfig, OnDiskStorageConfig, Pkcs11Config,
+    Token, VaultConfig,
+};
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
@@ -13,8 +16,11 @@ use structopt::StructOpt;
 
 pub const BACKEND: &str = "backend";
 pub const DISK: &str = "disk";
+pub const ENCRYPTED_DISK: &str = "encrypted_disk";
+pub const GIT: &str = "git";
 pub const GITHUB: &str = "github";
 pub const MEMORY: &str = "memory";
+pub const PKCS11: &str = "pkcs11";
 pub const VAULT: &str = "vault";
 
 // Custom timeouts for vault backend operations when using the management tooling.
@@ -80,6 +86,35 @@ impl TryInto<config::SecureBackend> for SecureBackend {
                 config.namespace = self.parameters.remove("namespace");
                 config::SecureBackend::OnDiskStorage(config)
             }
+            ENCRYPTED_DISK => {
+                let path = self
+                    .parameters
+                    .remove("path")
+                    .ok_or_else(|| Error::BackendParsingError("missing path".into()))?;
+                let key_file = self
+                    .parameters
+                    .remove("key_file")
+                    .ok_or_else(|| Error::BackendParsingError("missing key file".into()))?;
+                let mut config = EncryptedOnDiskStorageConfig::new(
+                    PathBuf::from(path),
+                    Token::FromDisk(PathBuf::from(key_file)),
+                );
+                config.set_data_dir(PathBuf::from(""));
+                config.namespace = self.parameters.remove("namespace");
+                config::SecureBackend::EncryptedOnDiskStorage(config)
+            }
+            GIT => {
+                let path = self
+                    .parameters
+                    .remove("path")
+                    .ok_or_else(|| Error::BackendParsingError("missing path".into()))?;
+                config::SecureBackend::Git(GitConfig {
+                    path: PathBuf::from(path),
+                    remote: self.parameters.remove("remote"),
+                    branch: self.parameters.remove("branch"),
+                    namespace: self.parameters.remove("namespace"),
+                })
+            }
             GITHUB => {
                 let repository_owner = self
                     .parameters
@@ -103,6 +138,26 @@ impl TryInto<config::SecureBackend> for SecureBackend {
                 })
             }
             MEMORY => config::SecureBackend::InMemoryStorage,
+            PKCS11 => {
+                let library = self
+                    .parameters
+                    .remove("library")
+                    .ok_or_else(|| Error::BackendParsingError("missing library".into()))?;
+                let token_label = self
+                    .parameters
+                    .remove("token_label")
+                    .ok_or_else(|| Error::BackendParsingError("missing token label".into()))?;
+                let pin = self
+                    .parameters
+                    .remove("pin")
+                    .ok_or_else(|| Error::BackendParsingError("missing pin".into()))?;
+                config::SecureBackend::Pkcs11(Pkcs11Config {
+                    library: PathBuf::from(library),
+                    token_label,
+                    pin: Token::FromDisk(PathBuf::from(pin)),
+                    namespace: self.parameters.remove("namespace"),
+                })
+            }
             VAULT => {
                 let certificate = self.parameters.remove("ca_certificate").map(PathBuf::from);
                 let server = self
@@ -154,11 +209,19 @@ pair: "k0=v0;k1=v1;...".  The current supported formats are:
     Vault: "backend=vault;server=URL;token=PATH_TO_TOKEN"
         an optional namespace: "namespace=NAMESPACE"
         an optional server certificate: "ca_certificate=PATH_TO_CERT"
+    Git: "backend=git;path=LOCAL_PATH"
+        an optional remote to pull from and push to: "remote=URL_OR_PATH"
+        an optional branch: "branch=BRANCH", defaults to master
+        an optional namespace: "namespace=NAMESPACE"
     GitHub: "backend=github;repository_owner=REPOSITORY_OWNER;repository=REPOSITORY;token=PATH_TO_TOKEN"
         an optional branch: "branch=BRANCH", defaults to master
         an optional namespace: "namespace=NAMESPACE"
     InMemory: "backend=memory"
     OnDisk: "backend=disk;path=LOCAL_PATH"
+    EncryptedOnDisk: "backend=encrypted_disk;path=LOCAL_PATH;key_file=PATH_TO_KEY_FILE"
+        an optional namespace: "namespace=NAMESPACE"
+    PKCS#11: "backend=pkcs11;library=PATH_TO_LIBRARY;token_label=LABEL;pin=PATH_TO_PIN"
+        an optional namespace: "namespace=NAMESPACE"
                 "#)
             )]
             pub $field_name: Option<SecureBackend>,
@@ -212,6 +275,32 @@ mod tests {
         assert!(storage(disk).is_err());
     }
 
+    #[test]
+    fn test_encrypted_disk() {
+        let encrypted_disk = "backend=encrypted_disk;path=/storage.json;key_file=/key";
+        storage(encrypted_disk).unwrap();
+
+        let encrypted_disk =
+            "backend=encrypted_disk;path=/storage.json;key_file=/key;namespace=test";
+        assert_eq!(storage(encrypted_disk).unwrap().namespace(), Some("test"));
+
+        let encrypted_disk = "backend=encrypted_disk;path=/storage.json";
+        storage(encrypted_disk).unwrap_err();
+    }
+
+    #[test]
+    fn test_git() {
+        let git = "backend=git;path=/genesis";
+        storage(git).unwrap();
+
+        let git =
+            "backend=git;path=/genesis;remote=/media/usb/genesis.git;branch=genesis;namespace=test";
+        assert_eq!(storage(git).unwrap().namespace(), Some("test"));
+
+        let git = "backend=git;remote=/media/usb/genesis.git";
+        storage(git).unwrap_err();
+    }
+
     #[test]
     fn test_github() {
         let path = dijets_temppath::TempPath::new();
@@ -267,6 +356,19 @@ mod tests {
         storage(vault).unwrap_err();
     }
 
+    #[test]
+    fn test_pkcs11() {
+        let pkcs11 =
+            "backend=pkcs11;library=/usr/lib/softhsm/libsofthsm2.so;token_label=dijets;pin=/pin";
+        storage(pkcs11).unwrap();
+
+        let pkcs11 = "backend=pkcs11;library=/usr/lib/softhsm/libsofthsm2.so;token_label=dijets;pin=/pin;namespace=test";
+        assert_eq!(storage(pkcs11).unwrap().namespace(), Some("test"));
+
+        let pkcs11 = "backend=pkcs11;library=/usr/lib/softhsm/libsofthsm2.so;token_label=dijets";
+        storage(pkcs11).unwrap_err();
+    }
+
     fn storage(s: &str) -> Result<config::SecureBackend, Error> {
         let management_backend: SecureBackend = s.try_into()?;
         management_backend.try_into()
@@ -25,10 +25,16 @@ pub struct ConsensusConfig {
     // how many times to wait for txns from mempool when propose
     pub mempool_poll_count: u64,
     // global switch for the decoupling execution feature
-    // only when decoupled is true, the execution and committing will be pipelined in different phases
+    // only when decoupled is true, the execution and committing will be pipelined in different phases.
+    // The votes then only order blocks, which validators with the switch off can't certify, so it
+    // has to be turned on by all the validators at once (along with safety rules)
     pub decoupled_execution: bool,
     pub channel_size: usize,
     pub back_pressure_limit: u64,
+    // switch to the 2-chain protocol: votes, timeouts and commits follow the 2-chain safety rules,
+    // which commits a block one round earlier than the 3-chain protocol. The consensus messages
+    // are sent in a layout only validators with the switch on can decode
+    pub two_chain: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -47,9 +53,10 @@ impl Default for ConsensusConfig {
             safety_rules: SafetyRulesConfig::default(),
             sync_only: false,
             mempool_poll_count: 1,
-            decoupled_execution: false, // by default, we turn of the decoupling execution feature
+            decoupled_execution: false, // by default, we turn off the decoupling execution feature
             channel_size: 30,           // hard-coded
             back_pressure_limit: 1,
+            two_chain: false,
         }
     }
 }
@@ -23,6 +23,8 @@ pub struct ExecutionConfig {
     pub service: ExecutionCorrectnessService,
     pub backend: SecureBackend,
     pub network_timeout_ms: u64,
+    /// Number of threads used to execute a block, 1 executes blocks sequentially.
+    pub concurrency_level: usize,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -40,8 +42,8 @@ impl std::fmt::Debug for ExecutionConfig {
         )?;
         write!(
             f,
-            ", sign_vote_proposal: {:?}, service: {:?}, backend: {:?} }}",
-            self.sign_vote_proposal, self.service, self.backend
+            ", sign_vote_proposal: {:?}, service: {:?}, backend: {:?}, concurrency_level: {:?} }}",
+            self.sign_vote_proposal, self.service, self.backend, self.concurrency_level
         )?;
         self.service.fmt(f)
     }
@@ -57,6 +59,8 @@ impl Default for ExecutionConfig {
             sign_vote_proposal: true,
             // Default value of 30 seconds for the network timeout.
             network_timeout_ms: 30_000,
+            // Sequential execution by default.
+            concurrency_level: 1,
         }
     }
 }
@@ -91,9 +95,7 @@ impl ExecutionConfig {
     }
 
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
-        if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
-            backend.set_data_dir(data_dir);
-        }
+        self.backend.set_data_dir(data_dir);
     }
 }
 
@@ -3,7 +3,7 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{collections::HashMap, net::SocketAddr};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -16,6 +16,8 @@ pub struct JsonRpcConfig {
     pub tls_key_path: Option<String>,
     #[serde(default)]
     pub stream_rpc: StreamConfig,
+    #[serde(default)]
+    pub rate_limit: JsonRpcRateLimitConfig,
 }
 
 pub const DEFAULT_JSON_RPC_ADDRESS: &str = "127.0.0.1";
@@ -36,6 +38,7 @@ impl Default for JsonRpcConfig {
             tls_cert_path: None,
             tls_key_path: None,
             stream_rpc: StreamConfig::default(),
+            rate_limit: JsonRpcRateLimitConfig::default(),
         }
     }
 }
@@ -74,3 +77,52 @@ impl Default for StreamConfig {
         }
     }
 }
+
+/// Per-client rate limiting of JSON-RPC requests.
+///
+/// Clients presenting one of the configured `api_keys` in the `Authorization` header are limited
+/// per API key, all other clients are limited per remote IP address. Every request costs
+/// `method_costs[method]` tokens (or `default_method_cost` if the method isn't listed), and a batch
+/// costs the sum of its requests.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JsonRpcRateLimitConfig {
+    pub enabled: bool,
+    /// Maximum burst of tokens for an IP
+    pub ip_bucket_size: usize,
+    /// Number of tokens/s refilled for an IP
+    pub ip_bucket_rate: usize,
+    /// Maximum burst of tokens for an API key
+    pub api_key_bucket_size: usize,
+    /// Number of tokens/s refilled for an API key
+    pub api_key_bucket_rate: usize,
+    /// Amount of tokens initially in a new bucket, as a percentage of its size
+    pub initial_bucket_fill_percentage: u8,
+    /// API keys which get their own (usually larger) quota
+    pub api_keys: Vec<String>,
+    pub default_method_cost: usize,
+    /// Cost in tokens of a JSON-RPC method, keyed on the method name (e.g. "get_transactions")
+    pub method_costs: HashMap<String, usize>,
+}
+
+pub const DEFAULT_RATE_LIMIT_IP_BUCKET_SIZE: usize = 200;
+pub const DEFAULT_RATE_LIMIT_IP_BUCKET_RATE: usize = 100;
+pub const DEFAULT_RATE_LIMIT_API_KEY_BUCKET_SIZE: usize = 2000;
+pub const DEFAULT_RATE_LIMIT_API_KEY_BUCKET_RATE: usize = 1000;
+pub const DEFAULT_RATE_LIMIT_METHOD_COST: usize = 1;
+
+impl Default for JsonRpcRateLimitConfig {
+    fn default() -> JsonRpcRateLimitConfig {
+        JsonRpcRateLimitConfig {
+            enabled: false,
+            ip_bucket_size: DEFAULT_RATE_LIMIT_IP_BUCKET_SIZE,
+            ip_bucket_rate: DEFAULT_RATE_LIMIT_IP_BUCKET_RATE,
+            api_key_bucket_size: DEFAULT_RATE_LIMIT_API_KEY_BUCKET_SIZE,
+            api_key_bucket_rate: DEFAULT_RATE_LIMIT_API_KEY_BUCKET_RATE,
+            initial_bucket_fill_percentage: 100,
+            api_keys: Vec::new(),
+            default_method_cost: DEFAULT_RATE_LIMIT_METHOD_COST,
+            method_costs: HashMap::new(),
+        }
+    }
+}
@@ -2,14 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
     pub capacity: usize,
+    // total size of all transactions in mempool, in bytes
+    pub capacity_bytes: usize,
     pub capacity_per_user: usize,
+    // total size of a single account's transactions in mempool, in bytes
+    pub capacity_per_user_bytes: usize,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
+    /// Path of the journal of accepted transactions, which is replayed on startup so pending
+    /// transactions survive restarts. None disables the journal. Relative paths are relative
+    /// to the data directory.
+    pub journal_path: Option<PathBuf>,
+    pub journal_compaction_interval_secs: u64,
+    #[serde(skip)]
+    data_dir: PathBuf,
     pub max_broadcasts_per_peer: usize,
     pub mempool_snapshot_interval_secs: u64,
     pub shared_mempool_ack_timeout_ms: u64,
@@ -32,10 +44,31 @@ impl Default for MempoolConfig {
             max_broadcasts_per_peer: 1,
             mempool_snapshot_interval_secs: 180,
             capacity: 1_000_000,
+            capacity_bytes: 2 * 1024 * 1024 * 1024,
             capacity_per_user: 100,
+            capacity_per_user_bytes: 10 * 1024 * 1024,
             default_failovers: 3,
+            journal_path: None,
+            journal_compaction_interval_secs: 600,
+            data_dir: PathBuf::from("/opt/dijets/data"),
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
         }
     }
 }
+
+impl MempoolConfig {
+    pub fn journal_path(&self) -> Option<PathBuf> {
+        self.journal_path.as_ref().map(|path| {
+            if path.is_relative() {
+                self.data_dir.join(path)
+            } else {
+                path.clone()
+            }
+        })
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
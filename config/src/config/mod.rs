@@ -220,6 +220,7 @@ impl NodeConfig {
         self.base.data_dir = data_dir.clone();
         self.consensus.set_data_dir(data_dir.clone());
         self.execution.set_data_dir(data_dir.clone());
+        self.mempool.set_data_dir(data_dir.clone());
         self.storage.set_data_dir(data_dir);
     }
 
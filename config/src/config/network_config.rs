@@ -22,6 +22,7 @@ use short_hex_str::AsShortHexStr;
 use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
+    net::SocketAddr,
     path::PathBuf,
     string::ToString,
     time::Duration,
@@ -42,9 +43,20 @@ pub const MAX_CONNECTION_DELAY_MS: u64 = 60_000; /* 1 minute */
 pub const MAX_FULLNODE_OUTBOUND_CONNECTIONS: usize = 3;
 pub const MAX_INBOUND_CONNECTIONS: usize = 100;
 pub const MAX_FRAME_SIZE: usize = 8 * 1024 * 1024; /* 8 MiB */
+pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const CONNECTION_BACKOFF_BASE: u64 = 2;
 pub const IP_BYTE_BUCKET_RATE: usize = 102400 /* 100 KiB */;
 pub const IP_BYTE_BUCKET_SIZE: usize = IP_BYTE_BUCKET_RATE;
+pub const NOISE_REKEY_MAX_MESSAGES: u64 = 1 << 20;
+pub const NOISE_REKEY_INTERVAL_MS: u64 = 60 * 60 * 1000; /* 1 hour */
+pub const PEER_EXCHANGE_INTERVAL_MS: u64 = 60_000; /* 1 minute */
+pub const PEER_EXCHANGE_SAMPLE_SIZE: usize = 16;
+pub const PEER_EXCHANGE_MAX_PEERS: usize = 256;
+pub const PEER_EXCHANGE_PEER_TTL_MS: u64 = 60 * 60 * 1000; /* 1 hour */
+pub const PEER_EXCHANGE_MIN_REQUEST_INTERVAL_MS: u64 = 30_000; /* 30 seconds */
+pub const BAN_THRESHOLD: u64 = 100;
+pub const PENALTY_WINDOW_MS: u64 = 10 * 60 * 1000; /* 10 minutes */
+pub const BAN_DURATION_MS: u64 = 60 * 60 * 1000; /* 1 hour */
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -82,6 +94,9 @@ pub struct NetworkConfig {
     pub seeds: PeerSet,
     // The maximum size of an inbound or outbound request frame
     pub max_frame_size: usize,
+    // The maximum size of an inbound or outbound message. Messages larger than a frame are
+    // streamed as multiple frames.
+    pub max_message_size: usize,
     // Enables proxy protocol on incoming connections to get original source addresses
     pub enable_proxy_protocol: bool,
     // Interval to send healthcheck pings to peers
@@ -98,6 +113,15 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     // Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    // Noise session rekeying configuration, if not specified, connections keep the keys from the
+    // handshake. Only enable once all peers support rekeying, as older peers drop the connection.
+    pub noise_rekey_config: Option<NoiseRekeyConfig>,
+    // Peer exchange configuration, if not specified, peers aren't exchanged. Only used on networks
+    // without mutual authentication.
+    pub peer_exchange_config: Option<PeerExchangeConfig>,
+    // Peer reputation configuration, if not specified, peers reported as misbehaving by
+    // applications are never banned. Peers are never banned on the validator network either.
+    pub reputation_config: Option<ReputationConfig>,
 }
 
 impl Default for NetworkConfig {
@@ -119,6 +143,7 @@ impl NetworkConfig {
             seed_addrs: HashMap::new(),
             seeds: PeerSet::default(),
             max_frame_size: MAX_FRAME_SIZE,
+            max_message_size: MAX_MESSAGE_SIZE,
             enable_proxy_protocol: false,
             max_connection_delay_ms: MAX_CONNECTION_DELAY_MS,
             connectivity_check_interval_ms: CONNECTIVITY_CHECK_INTERVAL_MS,
@@ -132,6 +157,9 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            noise_rekey_config: None,
+            peer_exchange_config: None,
+            reputation_config: None,
         };
         config.prepare_identity();
         config
@@ -304,9 +332,42 @@ impl NetworkConfig {
 pub enum DiscoveryMethod {
     Onchain,
     File(PathBuf, Duration),
+    Dns(DnsDiscoveryConfig),
+    Rest(RestDiscoveryConfig),
     None,
 }
 
+/// Discovers peers from DNS records. Every host name is a single peer: its addresses come from
+/// its A/AAAA records, and its keys from TXT records of the form `dijets-key=<x25519 key>`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DnsDiscoveryConfig {
+    pub name: String,
+    pub record_type: DnsRecordType,
+    pub role: PeerRole,
+    pub interval_ms: u64,
+    // Name servers to query instead of the system's resolver configuration
+    #[serde(default)]
+    pub name_servers: Vec<SocketAddr>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsRecordType {
+    /// `name` is a single peer, reachable on `port` at any of its addresses
+    Ip { port: u16 },
+    /// `name` has SRV records, whose targets are each a peer
+    Srv,
+}
+
+/// Discovers peers by periodically fetching a JSON-serialized `PeerSet` over HTTP(S).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestDiscoveryConfig {
+    pub url: String,
+    pub interval_ms: u64,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
@@ -371,6 +432,75 @@ impl Default for RateLimitConfig {
     }
 }
 
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NoiseRekeyConfig {
+    /// Number of messages encrypted with the same key before rekeying
+    pub max_messages: u64,
+    /// Maximum time a key is used before rekeying
+    pub interval_ms: u64,
+}
+
+impl Default for NoiseRekeyConfig {
+    fn default() -> Self {
+        Self {
+            max_messages: NOISE_REKEY_MAX_MESSAGES,
+            interval_ms: NOISE_REKEY_INTERVAL_MS,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerExchangeConfig {
+    /// Interval between requests for peers, each sent to a random connected peer
+    pub interval_ms: u64,
+    /// Maximum number of peers shared in a response, and kept from a response
+    pub sample_size: usize,
+    /// Maximum number of peers learned through peer exchange
+    pub max_peers: usize,
+    /// Time after which the peers received from a peer are forgotten, unless refreshed
+    pub peer_ttl_ms: u64,
+    /// Minimum time between two requests of the same peer which get a response
+    pub min_request_interval_ms: u64,
+}
+
+impl Default for PeerExchangeConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: PEER_EXCHANGE_INTERVAL_MS,
+            sample_size: PEER_EXCHANGE_SAMPLE_SIZE,
+            max_peers: PEER_EXCHANGE_MAX_PEERS,
+            peer_ttl_ms: PEER_EXCHANGE_PEER_TTL_MS,
+            min_request_interval_ms: PEER_EXCHANGE_MIN_REQUEST_INTERVAL_MS,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReputationConfig {
+    /// Sum of the penalties of a peer within the penalty window at which it gets banned
+    pub ban_threshold: u64,
+    /// Time after which a penalty no longer counts towards a ban
+    pub penalty_window_ms: u64,
+    /// Time a misbehaving peer stays banned
+    pub ban_duration_ms: u64,
+    /// Whether the IP address a banned peer was connected from is banned along with it
+    pub ban_ips: bool,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            ban_threshold: BAN_THRESHOLD,
+            penalty_window_ms: PENALTY_WINDOW_MS,
+            ban_duration_ms: BAN_DURATION_MS,
+            ban_ips: true,
+        }
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
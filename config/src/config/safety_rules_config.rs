@@ -48,9 +48,7 @@ impl Default for SafetyRulesConfig {
 
 impl SafetyRulesConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
-        if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
-            backend.set_data_dir(data_dir);
-        }
+        self.backend.set_data_dir(data_dir);
     }
 }
 
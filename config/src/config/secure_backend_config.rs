@@ -3,11 +3,12 @@
 
 use crate::config::Error;
 use dijets_secure_storage::{
-    GitHubStorage, InMemoryStorage, Namespaced, OnDiskStorage, Storage, VaultStorage,
+    AuditLog, Audited, EncryptedOnDiskStorage, GitHubStorage, GitStorage, InMemoryStorage,
+    Namespaced, OnDiskStorage, Pkcs11Storage, Storage, VaultStorage,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    fs::File,
+    fs::{self, File},
     io::Read,
     path::{Path, PathBuf},
 };
@@ -15,34 +16,95 @@ use std::{
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum SecureBackend {
+    Audited(AuditedConfig),
+    Git(GitConfig),
     GitHub(GitHubConfig),
     InMemoryStorage,
     Vault(VaultConfig),
     OnDiskStorage(OnDiskStorageConfig),
+    EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig),
+    Pkcs11(Pkcs11Config),
 }
 
 impl SecureBackend {
     pub fn namespace(&self) -> Option<&str> {
         match self {
-            SecureBackend::GitHub(GitHubConfig { namespace, .. })
+            SecureBackend::Git(GitConfig { namespace, .. })
+            | SecureBackend::GitHub(GitHubConfig { namespace, .. })
             | SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
-                namespace.as_deref()
-            }
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            })
+            | SecureBackend::Pkcs11(Pkcs11Config { namespace, .. }) => namespace.as_deref(),
+            SecureBackend::Audited(AuditedConfig { backend, .. }) => backend.namespace(),
             SecureBackend::InMemoryStorage => None,
         }
     }
 
     pub fn clear_namespace(&mut self) {
         match self {
-            SecureBackend::GitHub(GitHubConfig { namespace, .. })
+            SecureBackend::Git(GitConfig { namespace, .. })
+            | SecureBackend::GitHub(GitHubConfig { namespace, .. })
             | SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::EncryptedOnDiskStorage(EncryptedOnDiskStorageConfig {
+                namespace,
+                ..
+            })
+            | SecureBackend::Pkcs11(Pkcs11Config { namespace, .. }) => {
                 *namespace = None;
             }
+            SecureBackend::Audited(AuditedConfig { backend, .. }) => backend.clear_namespace(),
             SecureBackend::InMemoryStorage => {}
         }
     }
+
+    /// Sets the directory against which relative paths of on disk storages are resolved.
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        match self {
+            SecureBackend::OnDiskStorage(config) => config.set_data_dir(data_dir),
+            SecureBackend::EncryptedOnDiskStorage(config) => config.set_data_dir(data_dir),
+            SecureBackend::Audited(config) => config.backend.set_data_dir(data_dir),
+            SecureBackend::Git(_)
+            | SecureBackend::GitHub(_)
+            | SecureBackend::InMemoryStorage
+            | SecureBackend::Vault(_)
+            | SecureBackend::Pkcs11(_) => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditedConfig {
+    /// The identity of this process, recorded along with every operation on the backend
+    pub identity: String,
+    /// The file to which the audit records are appended, this is expected to be a full path.
+    pub log_path: PathBuf,
+    /// The key of the MACs chaining the records. It must be kept away from the log, so that
+    /// whoever can write the log cannot forge records.
+    pub key: Token,
+    /// The audited backend
+    pub backend: Box<SecureBackend>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitConfig {
+    /// The local repository where storage will mount, it is created if it does not exist. This is
+    /// expected to be a full path.
+    pub path: PathBuf,
+    /// An optional remote repository, either a URL or a path, that the local repository pulls
+    /// from and pushes to on every operation
+    pub remote: Option<String>,
+    /// The branch containing storage, defaults to master
+    pub branch: Option<String>,
+    /// A namespace is an optional portion of the path to a key stored within GitConfig. For
+    /// example, a key, S, without a namespace would be available in S, with a namespace, N, it
+    /// would be in N/S.
+    pub namespace: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -110,6 +172,37 @@ pub struct OnDiskStorageConfig {
     data_dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedOnDiskStorageConfig {
+    // Required path for encrypted on disk storage
+    pub path: PathBuf,
+    /// The secret from which the encryption key is derived: either a passphrase within this
+    /// config or a key file on disk, whose whole contents are used.
+    pub secret: Token,
+    /// A namespace is an optional portion of the path to a key stored within
+    /// EncryptedOnDiskStorage. For example, a key, S, without a namespace would be available in
+    /// S, with a namespace, N, it would be in N/S.
+    pub namespace: Option<String>,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pkcs11Config {
+    /// The PKCS#11 library of the hardware security module, this is expected to be a full path.
+    pub library: PathBuf,
+    /// The label of the token holding the keys
+    pub token_label: String,
+    /// The user PIN of the token
+    pub pin: Token,
+    /// A namespace is an optional portion of the label of a key stored within the token. For
+    /// example, a key, S, without a namespace would be labelled S, with a namespace, N, it would
+    /// be labelled N/S.
+    pub namespace: Option<String>,
+}
+
 /// Tokens can either be directly within this config or stored somewhere on disk.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -126,6 +219,16 @@ impl Token {
             Token::FromConfig(token) => Ok(token.clone()),
         }
     }
+
+    /// Reads the token as raw bytes, so that key files don't need to be valid UTF-8.
+    pub fn read_bytes(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            Token::FromDisk(path) => {
+                fs::read(path).map_err(|e| Error::IO(path.display().to_string(), e))
+            }
+            Token::FromConfig(token) => Ok(token.as_bytes().to_vec()),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -164,6 +267,34 @@ impl OnDiskStorageConfig {
     }
 }
 
+impl EncryptedOnDiskStorageConfig {
+    pub fn new(path: PathBuf, secret: Token) -> Self {
+        Self {
+            path,
+            secret,
+            namespace: None,
+            data_dir: PathBuf::from("/opt/dijets/data"),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        if self.path.is_relative() {
+            self.data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+
+    /// Reads the secret from which the encryption key is derived.
+    pub fn read_secret(&self) -> Result<Vec<u8>, Error> {
+        self.secret.read_bytes()
+    }
+}
+
 fn read_file(path: &Path) -> Result<String, Error> {
     let mut file =
         File::open(path).map_err(|e| Error::IO(path.to_str().unwrap().to_string(), e))?;
@@ -176,6 +307,35 @@ fn read_file(path: &Path) -> Result<String, Error> {
 impl From<&SecureBackend> for Storage {
     fn from(backend: &SecureBackend) -> Self {
         match backend {
+            SecureBackend::Audited(config) => {
+                let key = config
+                    .key
+                    .read_bytes()
+                    .expect("Unable to read audit log key");
+                let log = AuditLog::file(&config.log_path, key).expect("Unable to open audit log");
+                let storage = Storage::from(config.backend.as_ref());
+                Storage::from(Audited::new(
+                    config.identity.clone(),
+                    log,
+                    Box::new(storage),
+                ))
+            }
+            SecureBackend::Git(config) => {
+                let storage = Storage::from(GitStorage::new(
+                    config.path.clone(),
+                    config.remote.clone(),
+                    config
+                        .branch
+                        .as_ref()
+                        .cloned()
+                        .unwrap_or_else(|| "master".to_string()),
+                ));
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            }
             SecureBackend::GitHub(config) => {
                 let storage = Storage::from(GitHubStorage::new(
                     config.repository_owner.clone(),
@@ -202,6 +362,30 @@ impl From<&SecureBackend> for Storage {
                     storage
                 }
             }
+            SecureBackend::EncryptedOnDiskStorage(config) => {
+                let secret = config.read_secret().expect("Unable to read secret");
+                let storage = Storage::from(
+                    EncryptedOnDiskStorage::new(config.path(), &secret)
+                        .expect("Unable to open encrypted storage"),
+                );
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            }
+            SecureBackend::Pkcs11(config) => {
+                let storage = Storage::from(Pkcs11Storage::new(
+                    config.library.clone(),
+                    config.token_label.clone(),
+                    config.pin.read_token().expect("Unable to read PIN"),
+                ));
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            }
             SecureBackend::Vault(config) => {
                 let storage = Storage::from(VaultStorage::new(
                     config.server.clone(),
@@ -227,6 +411,7 @@ impl From<&SecureBackend> for Storage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dijets_secure_storage::KVStorage;
     use std::io::Write;
 
     #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -320,6 +505,120 @@ vault:
         serde_yaml::to_string(&from_disk).unwrap();
     }
 
+    #[test]
+    fn test_audited_parsing() {
+        let from_config = SecureBackend::Audited(AuditedConfig {
+            identity: "safety_rules".to_string(),
+            log_path: PathBuf::from("/opt/dijets/data/audit.log"),
+            key: Token::FromDisk(PathBuf::from("/audit_key")),
+            backend: Box::new(SecureBackend::Vault(VaultConfig {
+                namespace: Some("validator".to_string()),
+                server: "127.0.0.1:8200".to_string(),
+                ca_certificate: None,
+                token: Token::FromConfig("test".to_string()),
+                renew_ttl_secs: None,
+                disable_cas: None,
+                connection_timeout_ms: None,
+                response_timeout_ms: None,
+            })),
+        });
+
+        let text_from_config = r#"
+type: audited
+identity: "safety_rules"
+log_path: "/opt/dijets/data/audit.log"
+key:
+    from_disk: "/audit_key"
+backend:
+    type: vault
+    server: "127.0.0.1:8200"
+    namespace: "validator"
+    token:
+        from_config: "test"
+        "#;
+
+        let de_from_config: SecureBackend = serde_yaml::from_str(text_from_config).unwrap();
+        assert_eq!(de_from_config, from_config);
+        assert_eq!(de_from_config.namespace(), Some("validator"));
+        // Just assert that it can be serialized, not about to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+    }
+
+    #[test]
+    fn test_git_parsing() {
+        let from_config = SecureBackend::Git(GitConfig {
+            path: PathBuf::from("/opt/dijets/genesis"),
+            remote: Some("/media/usb/genesis.git".to_string()),
+            branch: Some("genesis".to_string()),
+            namespace: None,
+        });
+
+        let text_from_config = r#"
+type: git
+path: "/opt/dijets/genesis"
+remote: "/media/usb/genesis.git"
+branch: "genesis"
+        "#;
+
+        let de_from_config: SecureBackend = serde_yaml::from_str(text_from_config).unwrap();
+        assert_eq!(de_from_config, from_config);
+        // Just assert that it can be serialized, not about to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+    }
+
+    #[test]
+    fn test_pkcs11_parsing() {
+        let from_config = SecureBackend::Pkcs11(Pkcs11Config {
+            library: PathBuf::from("/usr/lib/softhsm/libsofthsm2.so"),
+            token_label: "dijets".to_string(),
+            pin: Token::FromDisk(PathBuf::from("/pin")),
+            namespace: Some("validator".to_string()),
+        });
+
+        let text_from_config = r#"
+type: pkcs11
+library: "/usr/lib/softhsm/libsofthsm2.so"
+token_label: "dijets"
+pin:
+    from_disk: "/pin"
+namespace: "validator"
+        "#;
+
+        let de_from_config: SecureBackend = serde_yaml::from_str(text_from_config).unwrap();
+        assert_eq!(de_from_config, from_config);
+        assert_eq!(de_from_config.namespace(), Some("validator"));
+        // Just assert that it can be serialized, not about to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+    }
+
+    #[test]
+    fn test_encrypted_on_disk_parsing() {
+        let mut config = EncryptedOnDiskStorageConfig::new(
+            PathBuf::from("secure_storage.json"),
+            Token::FromDisk(PathBuf::from("/key")),
+        );
+        config.namespace = Some("validator".to_string());
+        let from_config = SecureBackend::EncryptedOnDiskStorage(config.clone());
+
+        let text_from_config = r#"
+type: encrypted_on_disk_storage
+path: "secure_storage.json"
+secret:
+    from_disk: "/key"
+namespace: "validator"
+        "#;
+
+        let mut de_from_config: SecureBackend = serde_yaml::from_str(text_from_config).unwrap();
+        de_from_config.set_data_dir(PathBuf::from("/opt/dijets/data"));
+        assert_eq!(de_from_config, from_config);
+        assert_eq!(de_from_config.namespace(), Some("validator"));
+        // Just assert that it can be serialized, not about to do string comparison
+        serde_yaml::to_string(&from_config).unwrap();
+
+        config.set_data_dir(PathBuf::from("/data"));
+        assert_eq!(config.path(), PathBuf::from("/data/secure_storage.json"));
+    }
+
     #[test]
     fn test_token_reading() {
         let temppath = dijets_temppath::TempPath::new();
@@ -333,4 +632,29 @@ vault:
         let config = Token::FromConfig("config_token".to_string());
         assert_eq!("config_token", config.read_token().unwrap());
     }
+
+    #[test]
+    fn test_encrypted_on_disk_migration() {
+        let temppath = dijets_temppath::TempPath::new();
+        temppath.create_as_dir().unwrap();
+        let dir = temppath.path();
+
+        // A key file which isn't valid UTF-8
+        let key_file = dir.join("key");
+        fs::write(&key_file, [0xff, 0xfe, 0x00, 0x0a]).unwrap();
+        let secret = Token::FromDisk(key_file);
+
+        let mut plaintext = OnDiskStorage::new(dir.join("plaintext.json"));
+        plaintext.set("key", "value".to_string()).unwrap();
+        let config = EncryptedOnDiskStorageConfig::new(dir.join("encrypted.json"), secret);
+        EncryptedOnDiskStorage::migrate_from(
+            &plaintext,
+            config.path(),
+            &config.read_secret().unwrap(),
+        )
+        .unwrap();
+
+        let storage = Storage::from(&SecureBackend::EncryptedOnDiskStorage(config));
+        assert_eq!(storage.get::<String>("key").unwrap().value, "value");
+    }
 }
@@ -10,10 +10,15 @@ pub struct StateSyncConfig {
     pub chunk_limit: u64,
     // The timeout of the state sync client to process a commit notification (in milliseconds)
     pub client_commit_timeout_ms: u64,
+    // Whether a new full node should bootstrap from an account state snapshot at its waypoint
+    // version instead of replaying every transaction up to the waypoint
+    pub enable_state_snapshot_sync: bool,
     // default timeout used for long polling to remote peer
     pub long_poll_timeout_ms: u64,
     // valid maximum chunk limit for sanity check
     pub max_chunk_limit: u64,
+    // valid maximum number of accounts in a state snapshot chunk for sanity check
+    pub max_state_snapshot_chunk_limit: u64,
     // valid maximum timeout limit for sanity check
     pub max_timeout_ms: u64,
     // The timeout of the state sync coordinator to receive a commit ack from mempool (in milliseconds)
@@ -22,6 +27,8 @@ pub struct StateSyncConfig {
     // if no progress is made by sending chunk requests to a number of networks,
     // the next sync request will be multicasted, i.e. sent to more networks
     pub multicast_timeout_ms: u64,
+    // Number of accounts to request per state snapshot chunk
+    pub state_snapshot_chunk_limit: u64,
     // The timeout for ensuring sync requests are making progress (i.e., the maximum time between
     // commits when processing a sync request).
     pub sync_request_timeout_ms: u64,
@@ -34,11 +41,14 @@ impl Default for StateSyncConfig {
         Self {
             chunk_limit: 1000,
             client_commit_timeout_ms: 5_000,
+            enable_state_snapshot_sync: false,
             long_poll_timeout_ms: 10_000,
             max_chunk_limit: 1000,
+            max_state_snapshot_chunk_limit: 1000,
             max_timeout_ms: 120_000,
             mempool_commit_timeout_ms: 5_000,
             multicast_timeout_ms: 30_000,
+            state_snapshot_chunk_limit: 1000,
             sync_request_timeout_ms: 60_000,
             tick_interval_ms: 100,
         }
@@ -17,6 +17,7 @@ pub mod sync_info;
 pub mod timeout;
 pub mod timeout_2chain;
 pub mod timeout_certificate;
+pub mod two_chain_layout;
 pub mod vote;
 pub mod vote_data;
 pub mod vote_msg;
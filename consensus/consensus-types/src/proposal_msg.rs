@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{block::Block, common::Author, sync_info::SyncInfo};
-use anyhow::{anyhow, ensure, format_err, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use dijets_types::validator_verifier::ValidatorVerifier;
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
@@ -63,9 +63,7 @@ impl ProposalMsg {
 
         let highest_certified_round = std::cmp::max(
             self.proposal.quorum_cert().certified_block().round(),
-            self.sync_info
-                .highest_timeout_certificate()
-                .map_or(0, |tc| tc.round()),
+            self.sync_info.highest_timeout_round(),
         );
         ensure!(
             previous_round == highest_certified_round,
@@ -82,12 +80,13 @@ impl ProposalMsg {
     }
 
     pub fn verify(&self, validator: &ValidatorVerifier) -> Result<()> {
-        self.proposal
-            .validate_signature(validator)
-            .map_err(|e| format_err!("{:?}", e))?;
+        self.proposal.validate_signature(validator)?;
         // if there is a timeout certificate, verify its signatures
         if let Some(tc) = self.sync_info.highest_timeout_certificate() {
-            tc.verify(validator).map_err(|e| format_err!("{:?}", e))?;
+            tc.verify(validator)?;
+        }
+        if let Some(tc) = self.sync_info.highest_2chain_timeout_cert() {
+            tc.verify(validator)?;
         }
         // Note that we postpone the verification of SyncInfo until it's being used.
         self.verify_well_formed()
@@ -1,7 +1,10 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{common::Round, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate};
+use crate::{
+    common::Round, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
+    timeout_certificate::TimeoutCertificate,
+};
 use anyhow::{ensure, Context};
 use dijets_types::{
     block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures,
@@ -23,6 +26,11 @@ pub struct SyncInfo {
     highest_ledger_info: Option<LedgerInfoWithSignatures>,
     /// Optional highest timeout certificate if available.
     highest_timeout_cert: Option<TimeoutCertificate>,
+    /// Optional highest 2-chain timeout certificate if available. It's not part of the layout
+    /// of the sync info, validators running the 2-chain mode send it in the layout of
+    /// `two_chain_layout`.
+    #[serde(skip)]
+    highest_2chain_timeout_cert: Option<TwoChainTimeoutCertificate>,
 }
 
 // this is required by structured log
@@ -34,9 +42,13 @@ impl Debug for SyncInfo {
 
 impl Display for SyncInfo {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let htc_repr = match self.highest_timeout_certificate() {
-            Some(tc) => format!("{}", tc.round()),
-            None => "None".to_string(),
+        let htc_repr = match (
+            self.highest_timeout_certificate(),
+            self.highest_2chain_timeout_cert(),
+        ) {
+            (Some(tc), _) => format!("{}", tc.round()),
+            (None, Some(tc)) => format!("{} (2-chain)", tc.round()),
+            (None, None) => "None".to_string(),
         };
         write!(
             f,
@@ -55,10 +67,13 @@ impl SyncInfo {
         highest_ordered_cert: QuorumCert,
         highest_ledger_info: Option<LedgerInfoWithSignatures>,
         highest_timeout_cert: Option<TimeoutCertificate>,
+        highest_2chain_timeout_cert: Option<TwoChainTimeoutCertificate>,
     ) -> Self {
         // No need to include HTC if it's lower than HQC
         let highest_timeout_cert = highest_timeout_cert
             .filter(|tc| tc.round() > highest_quorum_cert.certified_block().round());
+        let highest_2chain_timeout_cert = highest_2chain_timeout_cert
+            .filter(|tc| tc.round() > highest_quorum_cert.certified_block().round());
 
         let highest_ordered_cert =
             Some(highest_ordered_cert).filter(|hoc| hoc != &highest_quorum_cert);
@@ -68,6 +83,7 @@ impl SyncInfo {
             highest_ordered_cert,
             highest_ledger_info,
             highest_timeout_cert,
+            highest_2chain_timeout_cert,
         }
     }
 
@@ -81,6 +97,7 @@ impl SyncInfo {
             highest_ordered_cert,
             None,
             highest_timeout_cert,
+            None,
         )
     }
 
@@ -108,13 +125,27 @@ impl SyncInfo {
         self.highest_timeout_cert.as_ref()
     }
 
+    /// Highest 2-chain timeout certificate if available
+    pub fn highest_2chain_timeout_cert(&self) -> Option<&TwoChainTimeoutCertificate> {
+        self.highest_2chain_timeout_cert.as_ref()
+    }
+
+    /// Attaches the 2-chain timeout certificate decoded next to the sync info.
+    pub(crate) fn set_2chain_timeout_cert(&mut self, tc: Option<TwoChainTimeoutCertificate>) {
+        self.highest_2chain_timeout_cert = tc;
+    }
+
     pub fn highest_certified_round(&self) -> Round {
         self.highest_quorum_cert.certified_block().round()
     }
 
     pub fn highest_timeout_round(&self) -> Round {
-        self.highest_timeout_certificate()
-            .map_or(0, |tc| tc.round())
+        std::cmp::max(
+            self.highest_timeout_certificate()
+                .map_or(0, |tc| tc.round()),
+            self.highest_2chain_timeout_cert()
+                .map_or(0, |tc| tc.round()),
+        )
     }
 
     pub fn highest_ordered_round(&self) -> Round {
@@ -139,6 +170,16 @@ impl SyncInfo {
         if let Some(tc) = &self.highest_timeout_cert {
             ensure!(epoch == tc.epoch(), "Multi epoch in SyncInfo - TC and HQC");
         }
+        if let Some(tc) = &self.highest_2chain_timeout_cert {
+            ensure!(
+                epoch == tc.epoch(),
+                "Multi epoch in SyncInfo - 2-chain TC and HQC"
+            );
+            ensure!(
+                self.highest_certified_round() >= tc.highest_hqc_round(),
+                "HQC has lower round than the highest HQC of the 2-chain TC"
+            );
+        }
 
         ensure!(
             self.highest_quorum_cert.certified_block().round()
@@ -173,6 +214,9 @@ impl SyncInfo {
                 if let Some(tc) = &self.highest_timeout_cert {
                     tc.verify(validator)?;
                 }
+                if let Some(tc) = &self.highest_2chain_timeout_cert {
+                    tc.verify(validator)?;
+                }
                 Ok(())
             })
             .and_then(|_| {
@@ -199,3 +243,38 @@ impl SyncInfo {
             || self.highest_ledger_info_round() > other.highest_ledger_info_round()
     }
 }
+
+#[test]
+fn test_sync_info_layout() {
+    use crate::timeout_2chain::TwoChainTimeout;
+    use dijets_crypto::HashValue;
+    use dijets_types::ledger_info::LedgerInfo;
+
+    #[derive(Deserialize, Serialize)]
+    struct OldSyncInfo {
+        highest_quorum_cert: QuorumCert,
+        highest_ordered_cert: Option<QuorumCert>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        highest_ledger_info: Option<LedgerInfoWithSignatures>,
+        highest_timeout_cert: Option<TimeoutCertificate>,
+    }
+    let qc = QuorumCert::certificate_for_genesis_from_ledger_info(
+        &LedgerInfo::mock_genesis(None),
+        HashValue::zero(),
+    );
+    let sync_info = SyncInfo::new(qc.clone(), qc.clone(), None);
+    let old_sync_info = OldSyncInfo {
+        highest_quorum_cert: qc.clone(),
+        highest_ordered_cert: None,
+        highest_ledger_info: None,
+        highest_timeout_cert: None,
+    };
+    let old_bytes = bcs::to_bytes(&old_sync_info).unwrap();
+    assert_eq!(bcs::from_bytes::<SyncInfo>(&old_bytes).unwrap(), sync_info);
+
+    // the 2-chain timeout certificate doesn't change the layout
+    let tc = TwoChainTimeoutCertificate::new(TwoChainTimeout::new(1, 1, qc.clone()));
+    let sync_info = SyncInfo::new_decoupled(qc.clone(), qc, None, None, Some(tc));
+    assert!(sync_info.highest_2chain_timeout_cert().is_some());
+    assert_eq!(bcs::to_bytes(&sync_info).unwrap(), old_bytes);
+}
@@ -51,6 +51,17 @@ impl TwoChainTimeout {
         &self.quorum_cert
     }
 
+    /// Verifies that the timeout is for a round higher than its quorum cert and that the quorum
+    /// cert is valid. The signature on the timeout is verified by the message carrying it.
+    pub fn verify(&self, validators: &ValidatorVerifier) -> anyhow::Result<()> {
+        ensure!(
+            self.hqc_round() < self.round(),
+            "Timeout round should be larger than the QC round"
+        );
+        self.quorum_cert.verify(validators)?;
+        Ok(())
+    }
+
     pub fn sign(&self, signer: &ValidatorSigner) -> Ed25519Signature {
         signer.sign(&self.signing_format())
     }
@@ -88,7 +99,7 @@ pub struct TimeoutSigningRepr {
 /// TimeoutCertificate is a proof that 2f+1 participants in epoch i
 /// have voted in round r and we can now move to round r+1. DijetsBFT v4 requires signature to sign on
 /// the TimeoutSigningRepr and carry the TimeoutWithHighestQC with highest quorum cert among 2f+1.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct TwoChainTimeoutCertificate {
     timeout: TwoChainTimeout,
     signatures: BTreeMap<Author, (Round, Ed25519Signature)>,
@@ -150,6 +161,11 @@ impl TwoChainTimeoutCertificate {
         Ok(())
     }
 
+    /// The epoch of the timeout.
+    pub fn epoch(&self) -> u64 {
+        self.timeout.epoch()
+    }
+
     /// The round of the timeout.
     pub fn round(&self) -> Round {
         self.timeout.round()
@@ -160,6 +176,11 @@ impl TwoChainTimeoutCertificate {
         self.timeout.hqc_round()
     }
 
+    /// The highest quorum cert of the 2f+1 participants
+    pub fn highest_quorum_cert(&self) -> &QuorumCert {
+        self.timeout.quorum_cert()
+    }
+
     /// Returns the signatures certifying the round
     pub fn signers(&self) -> impl Iterator<Item = &Author> {
         self.signatures.iter().map(|(k, _)| k)
@@ -0,0 +1,222 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The layout of the consensus messages sent by validators running the 2-chain mode.
+//!
+//! `Vote` and `SyncInfo` keep the layout validators had before the 2-chain mode: BCS has no field
+//! names, so a new field would shift every field after it and older validators couldn't decode
+//! any vote, proposal or sync info. In this layout, the 2-chain timeout of a vote and the 2-chain
+//! timeout certificate of a sync info follow the struct carrying them. Each module is meant to be
+//! used with `#[serde(with = "...")]` on a message only sent when the 2-chain mode is enabled.
+
+use crate::{
+    block::Block,
+    proposal_msg::ProposalMsg,
+    sync_info::SyncInfo,
+    timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
+    vote::Vote,
+    vote_msg::VoteMsg,
+};
+use dijets_crypto::ed25519::Ed25519Signature;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+
+type SignedTwoChainTimeout = Option<(TwoChainTimeout, Ed25519Signature)>;
+
+fn with_2chain_timeout(mut vote: Vote, two_chain_timeout: SignedTwoChainTimeout) -> Vote {
+    if let Some((timeout, signature)) = two_chain_timeout {
+        vote.add_2chain_timeout(timeout, signature);
+    }
+    vote
+}
+
+fn with_2chain_timeout_cert(
+    mut sync_info: SyncInfo,
+    tc: Option<TwoChainTimeoutCertificate>,
+) -> SyncInfo {
+    sync_info.set_2chain_timeout_cert(tc);
+    sync_info
+}
+
+/// A `Vote` followed by its 2-chain timeout.
+pub mod vote {
+    use super::*;
+
+    pub fn serialize<T: Borrow<Vote>, S: Serializer>(
+        vote: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let vote = vote.borrow();
+        (vote, vote.two_chain_timeout()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: From<Vote>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let (vote, two_chain_timeout) = <(Vote, SignedTwoChainTimeout)>::deserialize(deserializer)?;
+        Ok(with_2chain_timeout(vote, two_chain_timeout).into())
+    }
+}
+
+/// A `SyncInfo` followed by its 2-chain timeout certificate.
+pub mod sync_info {
+    use super::*;
+
+    pub fn serialize<T: Borrow<SyncInfo>, S: Serializer>(
+        sync_info: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let sync_info = sync_info.borrow();
+        (sync_info, sync_info.highest_2chain_timeout_cert()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: From<SyncInfo>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let (sync_info, tc) =
+            <(SyncInfo, Option<TwoChainTimeoutCertificate>)>::deserialize(deserializer)?;
+        Ok(with_2chain_timeout_cert(sync_info, tc).into())
+    }
+}
+
+/// A `VoteMsg` with both its vote and sync info in the 2-chain layout.
+pub mod vote_msg {
+    use super::*;
+
+    pub fn serialize<T: Borrow<VoteMsg>, S: Serializer>(
+        vote_msg: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let vote_msg = vote_msg.borrow();
+        (
+            vote_msg.vote(),
+            vote_msg.vote().two_chain_timeout(),
+            vote_msg.sync_info(),
+            vote_msg.sync_info().highest_2chain_timeout_cert(),
+        )
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: From<VoteMsg>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let (vote, two_chain_timeout, sync_info, tc) = <(
+            Vote,
+            SignedTwoChainTimeout,
+            SyncInfo,
+            Option<TwoChainTimeoutCertificate>,
+        )>::deserialize(deserializer)?;
+        Ok(VoteMsg::new(
+            with_2chain_timeout(vote, two_chain_timeout),
+            with_2chain_timeout_cert(sync_info, tc),
+        )
+        .into())
+    }
+}
+
+/// A `ProposalMsg` with its sync info in the 2-chain layout.
+pub mod proposal_msg {
+    use super::*;
+
+    pub fn serialize<T: Borrow<ProposalMsg>, S: Serializer>(
+        proposal_msg: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let proposal_msg = proposal_msg.borrow();
+        (
+            proposal_msg.proposal(),
+            proposal_msg.sync_info(),
+            proposal_msg.sync_info().highest_2chain_timeout_cert(),
+        )
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: From<ProposalMsg>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let (proposal, sync_info, tc) =
+            <(Block, SyncInfo, Option<TwoChainTimeoutCertificate>)>::deserialize(deserializer)?;
+        Ok(ProposalMsg::new(proposal, with_2chain_timeout_cert(sync_info, tc)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quorum_cert::QuorumCert, vote_data::VoteData};
+    use dijets_crypto::HashValue;
+    use dijets_types::{
+        block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+    };
+
+    #[derive(Deserialize, Serialize)]
+    struct TwoChainVote(#[serde(with = "vote")] Vote);
+
+    #[derive(Deserialize, Serialize)]
+    struct TwoChainVoteMsg(#[serde(with = "vote_msg")] VoteMsg);
+
+    fn genesis_qc() -> QuorumCert {
+        QuorumCert::certificate_for_genesis_from_ledger_info(
+            &LedgerInfo::mock_genesis(None),
+            HashValue::zero(),
+        )
+    }
+
+    fn vote_msg(two_chain: bool) -> VoteMsg {
+        let signer = ValidatorSigner::random(None);
+        let mut vote = Vote::new(
+            VoteData::new(BlockInfo::random(1), BlockInfo::random(0)),
+            signer.author(),
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            &signer,
+        );
+        let mut tc = None;
+        if two_chain {
+            let timeout = vote.generate_2chain_timeout(genesis_qc());
+            let signature = timeout.sign(&signer);
+            let mut cert = TwoChainTimeoutCertificate::new(timeout.clone());
+            cert.add(signer.author(), timeout.clone(), signature.clone());
+            tc = Some(cert);
+            vote.add_2chain_timeout(timeout, signature);
+        }
+        let sync_info = SyncInfo::new_decoupled(genesis_qc(), genesis_qc(), None, None, tc);
+        VoteMsg::new(vote, sync_info)
+    }
+
+    #[test]
+    fn test_layouts_round_trip() {
+        let msg = vote_msg(false);
+        let bytes = bcs::to_bytes(&msg).unwrap();
+        assert_eq!(bcs::from_bytes::<VoteMsg>(&bytes).unwrap(), msg);
+
+        for two_chain in [false, true].iter() {
+            let msg = vote_msg(*two_chain);
+            let bytes = bcs::to_bytes(&TwoChainVoteMsg(msg.clone())).unwrap();
+            let decoded = bcs::from_bytes::<TwoChainVoteMsg>(&bytes).unwrap().0;
+            assert_eq!(decoded, msg);
+            assert_eq!(decoded.vote().two_chain_timeout().is_some(), *two_chain);
+            assert_eq!(
+                decoded.sync_info().highest_2chain_timeout_cert().is_some(),
+                *two_chain
+            );
+        }
+    }
+
+    #[test]
+    fn test_two_chain_layout_extends_old_layout() {
+        let msg = vote_msg(true);
+        let old_bytes = bcs::to_bytes(msg.vote()).unwrap();
+        let bytes = bcs::to_bytes(&TwoChainVote(msg.vote().clone())).unwrap();
+        assert_eq!(&bytes[..old_bytes.len()], &old_bytes[..]);
+
+        // an older validator decodes the vote without its 2-chain timeout
+        let vote = bcs::from_bytes::<Vote>(&old_bytes).unwrap();
+        assert!(vote.two_chain_timeout().is_none());
+        let vote = bcs::from_bytes::<TwoChainVote>(&bytes).unwrap().0;
+        assert_eq!(&vote, msg.vote());
+
+        // neither layout of a vote decodes as the other one
+        assert!(bcs::from_bytes::<Vote>(&bytes).is_err());
+        assert!(bcs::from_bytes::<TwoChainVote>(&old_bytes).is_err());
+    }
+}
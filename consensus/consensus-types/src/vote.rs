@@ -1,7 +1,10 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{common::Author, timeout::Timeout, vote_data::VoteData};
+use crate::{
+    common::Author, quorum_cert::QuorumCert, timeout::Timeout, timeout_2chain::TwoChainTimeout,
+    vote_data::VoteData,
+};
 use anyhow::{ensure, Context};
 use dijets_crypto::{ed25519::Ed25519Signature, hash::CryptoHash};
 use dijets_types::{
@@ -28,6 +31,11 @@ pub struct Vote {
     signature: Ed25519Signature,
     /// The round signatures can be aggregated into a timeout certificate if present.
     timeout_signature: Option<Ed25519Signature>,
+    /// The 2-chain timeout and its signature, which can be aggregated into a 2-chain timeout
+    /// certificate if present. It's not part of the layout of the vote, validators running the
+    /// 2-chain mode send it in the layout of `two_chain_layout`.
+    #[serde(skip)]
+    two_chain_timeout: Option<(TwoChainTimeout, Ed25519Signature)>,
 }
 
 // this is required by structured log
@@ -77,6 +85,7 @@ impl Vote {
             ledger_info,
             signature,
             timeout_signature: None,
+            two_chain_timeout: None,
         }
     }
 
@@ -90,6 +99,22 @@ impl Vote {
         self.timeout_signature.replace(signature);
     }
 
+    /// Generates a 2-chain timeout for the round of this vote, carrying the highest quorum cert
+    /// known to the voter.
+    pub fn generate_2chain_timeout(&self, qc: QuorumCert) -> TwoChainTimeout {
+        TwoChainTimeout::new(self.epoch(), self.vote_data.proposed().round(), qc)
+    }
+
+    /// Adds a signed 2-chain timeout, which can then be used for aggregating a 2-chain timeout
+    /// certificate.
+    pub fn add_2chain_timeout(&mut self, timeout: TwoChainTimeout, signature: Ed25519Signature) {
+        if self.two_chain_timeout.is_some() {
+            return; // 2-chain timeout is already set
+        }
+
+        self.two_chain_timeout.replace((timeout, signature));
+    }
+
     pub fn vote_data(&self) -> &VoteData {
         &self.vote_data
     }
@@ -128,10 +153,16 @@ impl Vote {
         self.timeout_signature.as_ref()
     }
 
+    /// Returns the signed 2-chain timeout that can be aggregated for TwoChainTimeoutCertificate.
+    pub fn two_chain_timeout(&self) -> Option<&(TwoChainTimeout, Ed25519Signature)> {
+        self.two_chain_timeout.as_ref()
+    }
+
     /// The vote message is considered a timeout vote message if it carries a signature on the
-    /// round, which can then be used for aggregating it to the TimeoutCertificate.
+    /// round (or a signed 2-chain timeout), which can then be used for aggregating it to the
+    /// TimeoutCertificate (or TwoChainTimeoutCertificate).
     pub fn is_timeout(&self) -> bool {
-        self.timeout_signature.is_some()
+        self.timeout_signature.is_some() || self.two_chain_timeout.is_some()
     }
 
     /// Verifies that the consensus data hash of LedgerInfo corresponds to the vote info,
@@ -149,8 +180,62 @@ impl Vote {
                 .verify(self.author(), &self.timeout(), timeout_signature)
                 .context("Failed to verify Timeout Vote")?;
         }
+        if let Some((timeout, signature)) = &self.two_chain_timeout {
+            ensure!(
+                (timeout.epoch(), timeout.round())
+                    == (self.epoch(), self.vote_data.proposed().round()),
+                "2-chain timeout has different (epoch, round) than Vote"
+            );
+            timeout.verify(validator)?;
+            validator
+                .verify(self.author(), &timeout.signing_format(), signature)
+                .context("Failed to verify 2-chain Timeout Vote")?;
+        }
         // Let us verify the vote data as well
         self.vote_data().verify()?;
         Ok(())
     }
 }
+
+#[test]
+fn test_vote_layout() {
+    use dijets_crypto::HashValue;
+    use dijets_types::block_info::BlockInfo;
+
+    #[derive(Deserialize, Serialize)]
+    struct OldVote {
+        vote_data: VoteData,
+        author: Author,
+        ledger_info: LedgerInfo,
+        signature: Ed25519Signature,
+        timeout_signature: Option<Ed25519Signature>,
+    }
+    let signer = ValidatorSigner::random(None);
+    let mut vote = Vote::new(
+        VoteData::new(BlockInfo::random(1), BlockInfo::random(0)),
+        signer.author(),
+        LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+        &signer,
+    );
+    let old_vote = OldVote {
+        vote_data: vote.vote_data.clone(),
+        author: vote.author,
+        ledger_info: vote.ledger_info.clone(),
+        signature: vote.signature.clone(),
+        timeout_signature: None,
+    };
+    let old_bytes = bcs::to_bytes(&old_vote).unwrap();
+    assert_eq!(bcs::from_bytes::<Vote>(&old_bytes).unwrap(), vote);
+
+    // the 2-chain timeout doesn't change the layout
+    let timeout =
+        vote.generate_2chain_timeout(QuorumCert::certificate_for_genesis_from_ledger_info(
+            &LedgerInfo::mock_genesis(None),
+            HashValue::zero(),
+        ));
+    let signature = timeout.sign(&signer);
+    vote.add_2chain_timeout(timeout, signature);
+    assert_eq!(bcs::to_bytes(&vote).unwrap(), old_bytes);
+    let value = serde_json::to_value(&vote).unwrap();
+    assert_eq!(value, serde_json::to_value(&old_vote).unwrap());
+}
@@ -19,7 +19,7 @@ use anyhow::{bail, ensure, format_err, Context};
 
 use consensus_types::{
     block::Block, executed_block::ExecutedBlock, quorum_cert::QuorumCert, sync_info::SyncInfo,
-    timeout_certificate::TimeoutCertificate,
+    timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
 };
 use dijets_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
 use dijets_infallible::RwLock;
@@ -143,12 +143,14 @@ pub fn update_counters_and_prune_blocks(
 impl BlockStore {
     pub fn new(
         storage: Arc<dyn PersistentLivenessStorage>,
-        initial_data: RecoveryData,
+        mut initial_data: RecoveryData,
         state_computer: Arc<dyn StateComputer>,
         max_pruned_blocks_in_mem: usize,
         time_service: Arc<dyn TimeService>,
     ) -> Self {
         let highest_tc = initial_data.highest_timeout_certificate();
+        let highest_2chain_tc = initial_data.highest_2chain_timeout_certificate();
+        let ordered_certs = initial_data.take_ordered_certs();
         let (root, root_metadata, blocks, quorum_certs) = initial_data.take();
         let block_store = Self::build(
             root,
@@ -161,25 +163,44 @@ impl BlockStore {
             max_pruned_blocks_in_mem,
             time_service,
         );
-        block_on(block_store.try_commit());
+        if let Some(tc) = highest_2chain_tc {
+            block_store
+                .inner
+                .write()
+                .replace_2chain_timeout_cert(Arc::new(tc));
+        }
+        block_on(block_store.try_commit(ordered_certs));
         block_store
     }
 
-    async fn try_commit(&self) {
-        // reproduce the same batches (important for the commit phase)
+    async fn try_commit(&self, ordered_certs: Vec<LedgerInfoWithSignatures>) {
+        // reproduce the same batches (important for the commit phase): the batches persisted by
+        // the decoupled execution pipeline are replayed first, the quorum certs only order the
+        // blocks beyond them.
+        counters::DECOUPLED_EXECUTION__REPLAYED_ORDERED_BATCHES.inc_by(ordered_certs.len() as u64);
+        let last_ordered_round = ordered_certs
+            .last()
+            .map_or(0, |li| li.ledger_info().round());
 
         let mut certs = self.inner.read().get_all_quorum_certs_with_commit_info();
         certs.sort_unstable_by_key(|qc| qc.commit_info().round());
 
-        for qc in certs {
-            if qc.commit_info().round() > self.commit_root().round() {
+        let finality_proofs = ordered_certs.into_iter().chain(
+            certs
+                .into_iter()
+                .filter(|qc| qc.commit_info().round() > last_ordered_round)
+                .map(|qc| qc.ledger_info().clone()),
+        );
+
+        for finality_proof in finality_proofs {
+            if finality_proof.ledger_info().round() > self.ordered_root().round() {
                 info!(
                     "trying to commit to round {} with ledger info {}",
-                    qc.commit_info().round(),
-                    qc.ledger_info()
+                    finality_proof.ledger_info().round(),
+                    finality_proof
                 );
 
-                if let Err(e) = self.commit(qc.ledger_info().clone()).await {
+                if let Err(e) = self.commit(finality_proof).await {
                     error!("Error in try-committing blocks. {}", e.to_string());
                 }
             }
@@ -330,10 +351,12 @@ impl BlockStore {
         root_metadata: RootMetadata,
         blocks: Vec<Block>,
         quorum_certs: Vec<QuorumCert>,
+        ordered_certs: Vec<LedgerInfoWithSignatures>,
     ) {
         let max_pruned_blocks_in_mem = self.inner.read().max_pruned_blocks_in_mem();
         // Rollover the previous highest TC from the old tree to the new one.
         let prev_htc = self.highest_timeout_cert().map(|tc| tc.as_ref().clone());
+        let prev_2chain_htc = self.highest_2chain_timeout_cert();
         let BlockStore { inner, .. } = Self::build(
             root,
             root_metadata,
@@ -355,7 +378,10 @@ impl BlockStore {
         *self.inner.write() = Arc::try_unwrap(inner)
             .unwrap_or_else(|_| panic!("New block tree is not shared"))
             .into_inner();
-        self.try_commit().await;
+        if let Some(tc) = prev_2chain_htc {
+            self.inner.write().replace_2chain_timeout_cert(tc);
+        }
+        self.try_commit(ordered_certs).await;
     }
 
     /// Execute and insert a block if it passes all validation tests.
@@ -456,6 +482,25 @@ impl BlockStore {
         Ok(())
     }
 
+    /// Replace the highest 2-chain timeout certificate in case the given one has a higher round.
+    /// In case a 2-chain timeout certificate is updated, persist it to storage.
+    pub fn insert_2chain_timeout_certificate(
+        &self,
+        tc: Arc<TwoChainTimeoutCertificate>,
+    ) -> anyhow::Result<()> {
+        let cur_tc_round = self
+            .highest_2chain_timeout_cert()
+            .map_or(0, |tc| tc.round());
+        if tc.round() <= cur_tc_round {
+            return Ok(());
+        }
+        self.storage
+            .save_highest_2chain_timeout_cert(tc.as_ref().clone())
+            .context("2-chain timeout certificate insert failed when persisting to DB")?;
+        self.inner.write().replace_2chain_timeout_cert(tc);
+        Ok(())
+    }
+
     /// Prune the tree up to next_root_id (keep next_root_id's block).  Any branches not part of
     /// the next_root_id's tree should be removed as well.
     ///
@@ -538,12 +583,18 @@ impl BlockReader for BlockStore {
         self.inner.read().highest_timeout_cert()
     }
 
+    fn highest_2chain_timeout_cert(&self) -> Option<Arc<TwoChainTimeoutCertificate>> {
+        self.inner.read().highest_2chain_timeout_cert()
+    }
+
     fn sync_info(&self) -> SyncInfo {
         SyncInfo::new_decoupled(
             self.highest_quorum_cert().as_ref().clone(),
             self.highest_ordered_cert().as_ref().clone(),
             Some(self.highest_ledger_info()),
             self.highest_timeout_cert().map(|tc| tc.as_ref().clone()),
+            self.highest_2chain_timeout_cert()
+                .map(|tc| tc.as_ref().clone()),
         )
     }
 }
@@ -52,6 +52,8 @@ fn get_initial_data_and_qc(db: &dyn DbReader) -> (RecoveryData, QuorumCert) {
             ),
             vec![],
             None,
+            None,
+            vec![],
         )
         .unwrap(),
         qc,
@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::{block_store::sync_manager::NeedFetchResult, BlockReader},
+    block_storage::{block_store::sync_manager::NeedFetchResult, BlockReader, BlockStore},
     pending_votes::{PendingVotes, VoteReceptionResult},
-    test_utils::{build_empty_tree, build_simple_tree, TreeInserter},
+    test_utils::{
+        build_empty_tree, build_simple_tree, EmptyStateComputer, MockStorage, TreeInserter,
+    },
+    util::mock_time_service::SimulatedTimeService,
 };
 use consensus_types::{
     block::{
@@ -15,6 +18,7 @@ use consensus_types::{
         Block,
     },
     common::Author,
+    timeout_2chain::{TwoChainTimeout, TwoChainTimeoutCertificate},
     vote::Vote,
     vote_data::VoteData,
 };
@@ -23,7 +27,7 @@ use dijets_types::{
     validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
 };
 use proptest::prelude::*;
-use std::{cmp::min, collections::HashSet};
+use std::{cmp::min, collections::HashSet, sync::Arc};
 
 #[test]
 fn test_highest_block_and_quorum_cert() {
@@ -403,3 +407,52 @@ fn test_need_fetch_for_qc() {
         NeedFetchResult::QCAlreadyExist,
     );
 }
+
+#[test]
+fn test_2chain_timeout_certificate_recovery() {
+    let (signers, validators) = random_validator_verifier(1, None, false);
+    let (initial_data, storage) = MockStorage::start_for_testing((&validators).into());
+    let block_store = BlockStore::new(
+        storage.clone(),
+        initial_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        Arc::new(SimulatedTimeService::new()),
+    );
+    let timeout = TwoChainTimeout::new(
+        block_store.ordered_root().epoch(),
+        1,
+        block_store.highest_quorum_cert().as_ref().clone(),
+    );
+    let mut tc = TwoChainTimeoutCertificate::new(timeout.clone());
+    tc.add(
+        signers[0].author(),
+        timeout.clone(),
+        timeout.sign(&signers[0]),
+    );
+    block_store
+        .insert_2chain_timeout_certificate(Arc::new(tc.clone()))
+        .unwrap();
+
+    // the certificate is recovered after a restart
+    let recovery_data = storage.try_start().unwrap();
+    assert_eq!(
+        recovery_data.highest_2chain_timeout_certificate(),
+        Some(tc.clone())
+    );
+    let block_store = BlockStore::new(
+        storage,
+        recovery_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        Arc::new(SimulatedTimeService::new()),
+    );
+    assert_eq!(
+        block_store.highest_2chain_timeout_cert().as_deref(),
+        Some(&tc)
+    );
+    assert_eq!(
+        block_store.sync_info().highest_2chain_timeout_cert(),
+        Some(&tc)
+    );
+}
@@ -4,7 +4,8 @@
 use crate::counters;
 use anyhow::bail;
 use consensus_types::{
-    executed_block::ExecutedBlock, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate,
+    executed_block::ExecutedBlock, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
 };
 use dijets_crypto::HashValue;
 use dijets_logger::prelude::*;
@@ -72,6 +73,8 @@ pub struct BlockTree {
     highest_quorum_cert: Arc<QuorumCert>,
     /// The highest timeout certificate (if any).
     highest_timeout_cert: Option<Arc<TimeoutCertificate>>,
+    /// The highest 2-chain timeout certificate (if any).
+    highest_2chain_timeout_cert: Option<Arc<TwoChainTimeoutCertificate>>,
     /// The quorum certificate that has highest commit info.
     highest_ordered_cert: Arc<QuorumCert>,
     /// The quorum certificate that has highest commit decision info.
@@ -120,6 +123,7 @@ impl BlockTree {
             highest_certified_block_id: root_id,
             highest_quorum_cert: Arc::clone(&root_quorum_cert),
             highest_timeout_cert,
+            highest_2chain_timeout_cert: None,
             highest_ordered_cert: Arc::new(root_ordered_cert),
             highest_ledger_info: root_commit_ledger_info,
             id_to_quorum_cert,
@@ -199,6 +203,15 @@ impl BlockTree {
         self.highest_timeout_cert.replace(tc);
     }
 
+    pub(super) fn highest_2chain_timeout_cert(&self) -> Option<Arc<TwoChainTimeoutCertificate>> {
+        self.highest_2chain_timeout_cert.clone()
+    }
+
+    /// Replace highest 2-chain timeout cert with the given value.
+    pub(super) fn replace_2chain_timeout_cert(&mut self, tc: Arc<TwoChainTimeoutCertificate>) {
+        self.highest_2chain_timeout_cert.replace(tc);
+    }
+
     pub(super) fn highest_ordered_cert(&self) -> Arc<QuorumCert> {
         Arc::clone(&self.highest_ordered_cert)
     }
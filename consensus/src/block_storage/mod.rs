@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use consensus_types::{
-    executed_block::ExecutedBlock, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate,
+    executed_block::ExecutedBlock, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, timeout_certificate::TimeoutCertificate,
 };
 use dijets_crypto::HashValue;
 use std::sync::Arc;
@@ -53,6 +54,9 @@ pub trait BlockReader: Send + Sync {
     /// Return the highest timeout certificate if available.
     fn highest_timeout_cert(&self) -> Option<Arc<TimeoutCertificate>>;
 
+    /// Return the highest 2-chain timeout certificate if available.
+    fn highest_2chain_timeout_cert(&self) -> Option<Arc<TwoChainTimeoutCertificate>>;
+
     /// Return the highest commit decision ledger info.
     fn highest_ledger_info(&self) -> LedgerInfoWithSignatures;
 
@@ -100,6 +100,9 @@ impl BlockStore {
         if let Some(tc) = sync_info.highest_timeout_certificate() {
             self.insert_timeout_certificate(Arc::new(tc.clone()))?;
         }
+        if let Some(tc) = sync_info.highest_2chain_timeout_cert() {
+            self.insert_2chain_timeout_certificate(Arc::new(tc.clone()))?;
+        }
         Ok(())
     }
 
@@ -178,21 +181,22 @@ impl BlockStore {
         if !self.need_sync_for_quorum_cert(&highest_ordered_cert, &highest_ledger_info) {
             return Ok(());
         }
-        let (root, root_metadata, blocks, quorum_certs) = Self::fast_forward_sync(
+        let mut recovery_data = Self::fast_forward_sync(
             &highest_ordered_cert,
             highest_ledger_info.clone(),
             retriever,
             self.storage.clone(),
             self.state_computer.clone(),
         )
-        .await?
-        .take();
+        .await?;
+        let ordered_certs = recovery_data.take_ordered_certs();
+        let (root, root_metadata, blocks, quorum_certs) = recovery_data.take();
         debug!(
             LogSchema::new(LogEvent::CommitViaSync).round(self.ordered_root().round()),
             committed_round = root.0.round(),
             block_id = root.0.id(),
         );
-        self.rebuild(root, root_metadata, blocks, quorum_certs)
+        self.rebuild(root, root_metadata, blocks, quorum_certs, ordered_certs)
             .await;
 
         if highest_ledger_info.ledger_info().ends_epoch() {
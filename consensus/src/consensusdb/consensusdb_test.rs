@@ -26,18 +26,28 @@ fn test_put_get() {
     let tc = vec![0u8, 1, 2];
     db.save_highest_timeout_certificate(tc.clone()).unwrap();
 
+    let two_chain_tc = vec![1u8, 2, 0];
+    db.save_highest_2chain_timeout_certificate(two_chain_tc.clone())
+        .unwrap();
+
     let vote = vec![2u8, 1, 0];
     db.save_vote(vote.clone()).unwrap();
 
-    let (vote_1, tc_1, blocks_1, qc_1) = db.get_data().unwrap();
+    let (vote_1, tc_1, two_chain_tc_1, blocks_1, qc_1, _) = db.get_data().unwrap();
     assert_eq!(blocks, blocks_1);
     assert_eq!(qcs, qc_1);
     assert_eq!(Some(tc), tc_1);
+    assert_eq!(Some(two_chain_tc), two_chain_tc_1);
     assert_eq!(Some(vote), vote_1);
 
     db.delete_highest_timeout_certificate().unwrap();
+    db.delete_highest_2chain_timeout_certificate().unwrap();
     db.delete_last_vote_msg().unwrap();
     assert!(db.get_highest_timeout_certificate().unwrap().is_none());
+    assert!(db
+        .get_highest_2chain_timeout_certificate()
+        .unwrap()
+        .is_none());
     assert!(db.get_last_vote().unwrap().is_none());
 }
 
@@ -65,3 +75,29 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_put_get_delete_ordered_cert() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    assert_eq!(db.get_ordered_certs().unwrap().len(), 0);
+
+    let blocks = vec![Block::make_genesis_block()];
+    let block_id = blocks[0].id();
+    let qc = certificate_for_genesis();
+    let ordered_cert = qc.ledger_info().clone();
+    assert_eq!(ordered_cert.ledger_info().consensus_block_id(), block_id);
+
+    db.save_blocks_and_quorum_certificates(blocks, vec![qc])
+        .unwrap();
+    db.save_ordered_cert(ordered_cert.clone()).unwrap();
+
+    let (_, _, _, _, _, ordered_certs) = db.get_data().unwrap();
+    assert_eq!(ordered_certs, vec![ordered_cert]);
+
+    // The ordering proof goes away together with the last block of the batch
+    db.delete_blocks_and_quorum_certificates(vec![block_id])
+        .unwrap();
+    assert_eq!(db.get_ordered_certs().unwrap().len(), 0);
+}
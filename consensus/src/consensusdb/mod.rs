@@ -8,6 +8,7 @@ mod schema;
 use crate::{
     consensusdb::schema::{
         block::BlockSchema,
+        ordered_cert::OrderedCertSchema,
         quorum_certificate::QCSchema,
         single_entry::{SingleEntryKey, SingleEntrySchema},
     },
@@ -17,7 +18,8 @@ use anyhow::Result;
 use consensus_types::{block::Block, quorum_cert::QuorumCert};
 use dijets_crypto::HashValue;
 use dijets_logger::prelude::*;
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+use dijets_types::ledger_info::LedgerInfoWithSignatures;
+use schema::{BLOCK_CF_NAME, ORDERED_CERT_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
 use schemadb::{Options, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME};
 use std::{collections::HashMap, iter::Iterator, path::Path, time::Instant};
 
@@ -32,6 +34,7 @@ impl ConsensusDB {
             BLOCK_CF_NAME,
             QC_CF_NAME,
             SINGLE_ENTRY_CF_NAME,
+            ORDERED_CERT_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join("consensusdb");
@@ -54,13 +57,16 @@ impl ConsensusDB {
     pub fn get_data(
         &self,
     ) -> Result<(
+        Option<Vec<u8>>,
         Option<Vec<u8>>,
         Option<Vec<u8>>,
         Vec<Block>,
         Vec<QuorumCert>,
+        Vec<LedgerInfoWithSignatures>,
     )> {
         let last_vote = self.get_last_vote()?;
         let highest_timeout_certificate = self.get_highest_timeout_certificate()?;
+        let highest_2chain_timeout_certificate = self.get_highest_2chain_timeout_certificate()?;
         let consensus_blocks = self
             .get_blocks()?
             .into_iter()
@@ -71,11 +77,18 @@ impl ConsensusDB {
             .into_iter()
             .map(|(_block_hash, qc)| qc)
             .collect::<Vec<_>>();
+        let ordered_certs = self
+            .get_ordered_certs()?
+            .into_iter()
+            .map(|(_block_hash, ordered_cert)| ordered_cert)
+            .collect::<Vec<_>>();
         Ok((
             last_vote,
             highest_timeout_certificate,
+            highest_2chain_timeout_certificate,
             consensus_blocks,
             consensus_qcs,
+            ordered_certs,
         ))
     }
 
@@ -92,6 +105,18 @@ impl ConsensusDB {
         Ok(())
     }
 
+    pub fn save_highest_2chain_timeout_certificate(
+        &self,
+        highest_2chain_timeout_certificate: Vec<u8>,
+    ) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<SingleEntrySchema>(
+            &SingleEntryKey::Highest2ChainTimeoutCertificate,
+            &highest_2chain_timeout_certificate,
+        )?;
+        self.commit(batch)
+    }
+
     pub fn save_vote(&self, last_vote: Vec<u8>) -> Result<(), DbError> {
         let mut batch = SchemaBatch::new();
         batch.put::<SingleEntrySchema>(&SingleEntryKey::LastVoteMsg, &last_vote)?;
@@ -116,6 +141,19 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Save the ordering proof of a batch of blocks that is not committed yet, identified by the
+    /// last block of the batch.
+    pub fn save_ordered_cert(&self, ordered_cert: LedgerInfoWithSignatures) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<OrderedCertSchema>(
+            &ordered_cert.ledger_info().consensus_block_id(),
+            &ordered_cert,
+        )?;
+        self.commit(batch)
+    }
+
+    /// Delete the blocks and the quorum certs certifying them, as well as the ordering proofs
+    /// of the batches ending at them.
     pub fn delete_blocks_and_quorum_certificates(
         &self,
         block_ids: Vec<HashValue>,
@@ -126,7 +164,8 @@ impl ConsensusDB {
         let mut batch = SchemaBatch::new();
         block_ids.iter().try_for_each(|hash| {
             batch.delete::<BlockSchema>(hash)?;
-            batch.delete::<QCSchema>(hash)
+            batch.delete::<QCSchema>(hash)?;
+            batch.delete::<OrderedCertSchema>(hash)
         })?;
         self.commit(batch)
     }
@@ -152,6 +191,20 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Get the latest 2-chain timeout certificate (only the highest one is stored).
+    fn get_highest_2chain_timeout_certificate(&self) -> Result<Option<Vec<u8>>, DbError> {
+        Ok(self
+            .db
+            .get::<SingleEntrySchema>(&SingleEntryKey::Highest2ChainTimeoutCertificate)?)
+    }
+
+    /// Delete the 2-chain timeout certificate
+    pub fn delete_highest_2chain_timeout_certificate(&self) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batch.delete::<SingleEntrySchema>(&SingleEntryKey::Highest2ChainTimeoutCertificate)?;
+        self.commit(batch)
+    }
+
     /// Get serialized latest vote (if available)
     fn get_last_vote(&self) -> Result<Option<Vec<u8>>, DbError> {
         Ok(self
@@ -179,4 +232,11 @@ impl ConsensusDB {
         iter.seek_to_first();
         Ok(iter.collect::<Result<HashMap<HashValue, QuorumCert>>>()?)
     }
+
+    /// Get all the ordering proofs of the batches that are not committed yet.
+    fn get_ordered_certs(&self) -> Result<HashMap<HashValue, LedgerInfoWithSignatures>, DbError> {
+        let mut iter = self.db.iter::<OrderedCertSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.collect::<Result<HashMap<HashValue, LedgerInfoWithSignatures>>>()?)
+    }
 }
@@ -0,0 +1,50 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the ordering proof of a batch of blocks sent
+//! to the decoupled execution pipeline.
+//!
+//! Serialized ledger info with signatures identified by the hash of the last ordered block.
+//! ```text
+//! |<---key---->|<-----------value---------->|
+//! | block_hash |  LedgerInfoWithSignatures  |
+//! ```
+
+use super::ORDERED_CERT_CF_NAME;
+use anyhow::Result;
+use dijets_crypto::HashValue;
+use dijets_types::ledger_info::LedgerInfoWithSignatures;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+
+define_schema!(
+    OrderedCertSchema,
+    HashValue,
+    LedgerInfoWithSignatures,
+    ORDERED_CERT_CF_NAME
+);
+
+impl KeyCodec<OrderedCertSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<OrderedCertSchema> for LedgerInfoWithSignatures {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
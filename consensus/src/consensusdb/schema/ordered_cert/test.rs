@@ -0,0 +1,15 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use consensus_types::block::block_test_utils::certificate_for_genesis;
+use schemadb::schema::assert_encode_decode;
+
+#[test]
+fn test_encode_decode() {
+    let ordered_cert = certificate_for_genesis().ledger_info().clone();
+    assert_encode_decode::<OrderedCertSchema>(
+        &ordered_cert.ledger_info().consensus_block_id(),
+        &ordered_cert,
+    );
+}
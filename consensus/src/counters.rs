@@ -336,3 +336,24 @@ pub static DECOUPLED_EXECUTION__EXECUTION_PHASE_RESET_CHANNEL: Lazy<IntGauge> =
     )
     .unwrap()
 });
+
+/// Histogram of the time a batch of blocks spends in each phase of the decoupled execution
+/// pipeline: "execution" (executing the ordered blocks), "signature_aggregation" (collecting a
+/// quorum of commit votes) and "commit" (persisting the executed blocks).
+pub static DECOUPLED_EXECUTION__PHASE_DURATION_S: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "decoupled_execution__phase_duration_s",
+        "Histogram of the time a batch of blocks spends in each decoupled execution phase",
+        &["phase"]
+    )
+    .unwrap()
+});
+
+/// Counter of the ordered batches replayed through the decoupled execution pipeline on startup
+pub static DECOUPLED_EXECUTION__REPLAYED_ORDERED_BATCHES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "decoupled_execution__replayed_ordered_batches",
+        "Number of ordered batches replayed through the decoupled execution pipeline on startup"
+    )
+    .unwrap()
+});
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters, experimental::errors::Error, metrics_safety_rules::MetricsSafetyRules,
+    counters, decoupled_execution::errors::Error, metrics_safety_rules::MetricsSafetyRules,
     network::NetworkSender, network_interface::ConsensusMsg, round_manager::VerifiedEvent,
     state_replication::StateComputer,
 };
@@ -27,13 +27,14 @@ use executor_types::Error as ExecutionError;
 use futures::{FutureExt, SinkExt, StreamExt};
 use safety_rules::TSafetyRules;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
     sync::{atomic::AtomicU64, Arc},
+    time::Instant,
 };
 use tokio::time;
 
 use crate::{
-    experimental::execution_phase::{reset_ack_new, ResetAck},
+    decoupled_execution::execution_phase::{reset_ack_new, ResetAck},
     state_replication::StateComputerCommitCallBackType,
 };
 use dijets_logger::error;
@@ -48,9 +49,15 @@ the commit phase commits the blocks as well as broadcasts a commit
 decision message together with the quorum of signatures. The commit
 decision message helps the slower nodes to quickly catch up without
 having to collect the signatures.
+
+The commit phase also remembers the most recent commit decisions: a validator
+that restarted and replays its ordered blocks keeps sending commit votes for
+blocks the others have already committed, it gets back the commit decision
+instead of waiting for signatures that never come.
 */
 
 const COMMIT_PHASE_TIMEOUT_SEC: u64 = 1; // retry timeout in seconds
+const MAX_RECENT_COMMIT_DECISIONS: usize = 10; // commit decisions kept to help lagging validators
 
 pub struct CommitChannelType(
     pub Vec<ExecutedBlock>,
@@ -76,6 +83,8 @@ pub struct PendingBlocks {
     ledger_info_sig: LedgerInfoWithSignatures,
     block_info: BlockInfo,
     callback: StateComputerCommitCallBackType,
+    // when the commit phase started collecting the signatures
+    aggregation_start: Instant,
 }
 
 impl PendingBlocks {
@@ -91,6 +100,7 @@ impl PendingBlocks {
             ledger_info_sig,
             block_info,
             callback,
+            aggregation_start: Instant::now(),
         }
     }
 
@@ -144,6 +154,7 @@ pub struct CommitPhase {
     timeout_event_tx: Sender<CommitVote>,
     timeout_event_rx: Receiver<CommitVote>,
     reset_event_rx: Receiver<oneshot::Sender<ResetAck>>,
+    recent_commit_decisions: VecDeque<LedgerInfoWithSignatures>,
 }
 
 /// Wrapper for ExecutionProxy.commit
@@ -222,6 +233,7 @@ impl CommitPhase {
             timeout_event_tx,
             timeout_event_rx,
             reset_event_rx,
+            recent_commit_decisions: VecDeque::new(),
         }
     }
 
@@ -230,6 +242,18 @@ impl CommitPhase {
         &mut self,
         commit_vote: &CommitVote,
     ) -> anyhow::Result<(), Error> {
+        // the voter is still collecting signatures for blocks we've already committed (e.g. it
+        // restarted and replayed them), send it the commit decision.
+        if let Some(commit_decision) = self.recent_commit_decision(commit_vote.commit_info()) {
+            if commit_vote.author() != self.author {
+                self.network_sender.send_commit_decision(
+                    CommitDecision::new(commit_decision.clone()),
+                    commit_vote.author(),
+                );
+            }
+            return Ok(());
+        }
+
         if let Some(pending_blocks) = self.blocks.as_mut() {
             let commit_ledger_info = commit_vote.ledger_info();
 
@@ -257,6 +281,11 @@ impl CommitPhase {
         &mut self,
         commit_decision: &CommitDecision,
     ) -> anyhow::Result<(), Error> {
+        // we've already committed these blocks
+        if self.is_recently_committed(commit_decision.ledger_info().ledger_info().commit_info()) {
+            return Ok(());
+        }
+
         if let Some(pending_blocks) = self.blocks.as_mut() {
             let commit_ledger_info = commit_decision.ledger_info();
 
@@ -280,6 +309,10 @@ impl CommitPhase {
     pub async fn check_commit(&mut self) -> anyhow::Result<()> {
         if let Some(pending_blocks) = self.blocks.as_ref() {
             if pending_blocks.verify(&self.verifier).is_ok() {
+                counters::DECOUPLED_EXECUTION__PHASE_DURATION_S
+                    .with_label_values(&["signature_aggregation"])
+                    .observe(pending_blocks.aggregation_start.elapsed().as_secs_f64());
+
                 // asynchronously broadcast the commit decision first to
                 // save the time of other nodes.
                 self.network_sender
@@ -290,10 +323,20 @@ impl CommitPhase {
 
                 let pending_blocks = self.blocks.take().unwrap();
                 let round = pending_blocks.round();
+                let commit_decision = pending_blocks.ledger_info_sig().clone();
 
+                let timer = counters::DECOUPLED_EXECUTION__PHASE_DURATION_S
+                    .with_label_values(&["commit"])
+                    .start_timer();
                 commit(&self.execution_proxy, pending_blocks)
                     .await
                     .expect("Failed to commit the executed blocks.");
+                timer.observe_duration();
+
+                if self.recent_commit_decisions.len() == MAX_RECENT_COMMIT_DECISIONS {
+                    self.recent_commit_decisions.pop_front();
+                }
+                self.recent_commit_decisions.push_back(commit_decision);
 
                 // update the back pressure
                 self.back_pressure.store(round, Ordering::SeqCst);
@@ -351,14 +394,26 @@ impl CommitPhase {
         Ok(())
     }
 
+    fn recent_commit_decision(&self, block_info: &BlockInfo) -> Option<&LedgerInfoWithSignatures> {
+        self.recent_commit_decisions
+            .iter()
+            .find(|li| li.ledger_info().commit_info() == block_info)
+    }
+
+    fn is_recently_committed(&self, block_info: &BlockInfo) -> bool {
+        self.recent_commit_decision(block_info).is_some()
+    }
+
     pub fn set_blocks(&mut self, blocks_or_none: Option<PendingBlocks>) {
         self.blocks = blocks_or_none;
     }
 
+    #[cfg(test)]
     pub fn blocks(&self) -> &Option<PendingBlocks> {
         &self.blocks
     }
 
+    #[cfg(test)]
     pub fn load_back_pressure(&self) -> u64 {
         self.back_pressure.load(Ordering::SeqCst)
     }
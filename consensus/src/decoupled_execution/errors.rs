@@ -10,8 +10,6 @@ use thiserror::Error;
 pub enum Error {
     #[error("The block in the message, {0}, does not match expected block, {1}")]
     InconsistentBlockInfo(BlockInfo, BlockInfo),
-    #[error("Verification Error")]
-    VerificationError,
     #[error("Reset host dropped")]
     ResetDropped,
 }
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    experimental::{commit_phase::CommitChannelType, errors::Error},
+    counters,
+    decoupled_execution::{commit_phase::CommitChannelType, errors::Error},
     state_replication::{StateComputer, StateComputerCommitCallBackType},
 };
 use channel::{Receiver, Sender};
@@ -92,6 +93,9 @@ impl ExecutionPhase {
             select! {
                 ExecutionChannelType(vecblock, ledger_info, callback) = self.executor_channel_rx.select_next_some() => {
                     // execute the blocks with execution_correctness_client
+                    let timer = counters::DECOUPLED_EXECUTION__PHASE_DURATION_S
+                        .with_label_values(&["execution"])
+                        .start_timer();
                     let executed_blocks: Vec<ExecutedBlock> = vecblock
                         .into_iter()
                         .map(|b| {
@@ -100,6 +104,7 @@ impl ExecutionPhase {
                             ExecutedBlock::new(b, state_compute_result)
                         })
                         .collect();
+                    timer.observe_duration();
                     // TODO: add error handling. Err(Error::BlockNotFound(parent_block_id))
 
                     // pass the executed blocks into the commit phase
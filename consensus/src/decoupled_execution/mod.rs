@@ -19,7 +19,11 @@
 //                               │ Send Commit │
 //                               │ Decision    │ (Asynchronously)
 //                               └─────────────┘
-#![allow(dead_code)]
+//
+// The ordering proof of every batch is persisted in ConsensusDB before the batch
+// enters the execution phase, and pruned with the blocks once they're committed.
+// On startup, the pending batches are replayed through the pipeline as they were
+// originally ordered, so that the commit votes can be aggregated again.
 pub mod commit_phase;
 pub mod errors;
 pub mod execution_phase;
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    error::StateSyncError, experimental::execution_phase::ExecutionChannelType,
-    state_replication::StateComputer,
+    decoupled_execution::execution_phase::ExecutionChannelType, error::StateSyncError,
+    persistent_liveness_storage::PersistentLivenessStorage, state_replication::StateComputer,
 };
 use anyhow::Result;
 use channel::Sender;
@@ -16,7 +16,7 @@ use futures::SinkExt;
 use std::{boxed::Box, sync::Arc};
 
 use crate::{
-    experimental::{errors::Error, execution_phase::ResetAck},
+    decoupled_execution::{errors::Error, execution_phase::ResetAck},
     state_replication::StateComputerCommitCallBackType,
 };
 use futures::channel::oneshot;
@@ -30,6 +30,8 @@ pub struct OrderingStateComputer {
     executor_channel: Sender<ExecutionChannelType>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: Sender<oneshot::Sender<ResetAck>>,
+    // persists the ordering proofs so that the pipeline can be replayed after a restart.
+    storage: Arc<dyn PersistentLivenessStorage>,
 }
 
 impl OrderingStateComputer {
@@ -37,11 +39,13 @@ impl OrderingStateComputer {
         executor_channel: Sender<ExecutionChannelType>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: Sender<oneshot::Sender<ResetAck>>,
+        storage: Arc<dyn PersistentLivenessStorage>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            storage,
         }
     }
 }
@@ -62,7 +66,8 @@ impl StateComputer for OrderingStateComputer {
         Ok(StateComputeResult::new_dummy())
     }
 
-    /// Send ordered blocks to the real execution phase through the channel.
+    /// Persist the ordering proof and send ordered blocks to the real execution phase through
+    /// the channel.
     /// A future is fulfilled right away when the blocks are sent into the channel.
     async fn commit(
         &self,
@@ -72,6 +77,14 @@ impl StateComputer for OrderingStateComputer {
     ) -> Result<(), ExecutionError> {
         assert!(!blocks.is_empty());
 
+        // The blocks themselves stay in consensusdb until they're committed, the ordering proof
+        // is enough to replay the very same batch after a crash.
+        self.storage
+            .save_ordered_cert(finality_proof.clone())
+            .map_err(|e| ExecutionError::InternalError {
+                error: e.to_string(),
+            })?;
+
         let ordered_block = blocks.iter().map(|b| b.block().clone()).collect();
 
         self.executor_channel
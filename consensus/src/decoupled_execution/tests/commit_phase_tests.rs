@@ -1,7 +1,7 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{experimental::commit_phase::CommitPhase, test_utils::consensus_runtime};
+use crate::{decoupled_execution::commit_phase::CommitPhase, test_utils::consensus_runtime};
 
 use consensus_types::executed_block::ExecutedBlock;
 use dijets_logger::debug;
@@ -15,7 +15,8 @@ use dijets_infallible::Mutex;
 use futures::{SinkExt, StreamExt};
 
 use crate::{
-    experimental::ordering_state_computer::OrderingStateComputer, state_replication::StateComputer,
+    decoupled_execution::ordering_state_computer::OrderingStateComputer,
+    state_replication::StateComputer,
 };
 use consensus_types::block::{block_test_utils::certificate_for_genesis, Block};
 use dijets_crypto::{ed25519::Ed25519Signature, hash::ACCUMULATOR_PLACEHOLDER_HASH};
@@ -35,7 +36,7 @@ use dijets_types::block_info::BlockInfo;
 
 use crate::{
     block_storage::BlockStore,
-    experimental::{
+    decoupled_execution::{
         commit_phase::{CommitChannelType, PendingBlocks},
         errors::Error,
         execution_phase::ExecutionChannelType,
@@ -44,7 +45,7 @@ use crate::{
     state_replication::empty_state_computer_call_back,
 };
 
-use crate::experimental::{
+use crate::decoupled_execution::{
     execution_phase::ResetAck,
     tests::test_utils::{
         prepare_commit_phase_with_block_store_state_computer,
@@ -488,7 +489,7 @@ mod commit_phase_e2e_tests {
 
 mod commit_phase_function_tests {
     use super::*;
-    use crate::experimental::tests::test_utils::new_executed_ledger_info_with_empty_signature;
+    use crate::decoupled_execution::tests::test_utils::new_executed_ledger_info_with_empty_signature;
 
     /// negative tests for commit_phase.process_commit_vote
     #[test]
@@ -729,4 +730,64 @@ mod commit_phase_function_tests {
             ));
         });
     }
+
+    #[test]
+    fn test_commit_phase_recent_commit_decisions() {
+        let mut runtime = consensus_runtime();
+        let (
+            _commit_tx,
+            _msg_tx,
+            _commit_phase_reset_tx,
+            _commit_result_rx,
+            _self_loop_rx,
+            _safety_rules_container,
+            signers,
+            _state_computer,
+            _validator,
+            mut commit_phase,
+            _block_store,
+        ) = prepare_commit_phase(&runtime);
+
+        timed_block_on(&mut runtime, async move {
+            let signer = &signers[0];
+
+            let (vecblocks, li_sig) = prepare_executed_blocks_with_executed_ledger_info(signer);
+
+            commit_phase.set_blocks(Some(PendingBlocks::new(
+                vecblocks,
+                li_sig.clone(),
+                empty_state_computer_call_back(),
+            )));
+            commit_phase.check_commit().await.ok();
+            assert!(commit_phase.blocks().is_none());
+
+            // the next batch is pending
+            let (next_vecblocks, next_li_sig) =
+                prepare_executed_blocks_with_executed_ledger_info(signer);
+            commit_phase.set_blocks(Some(PendingBlocks::new(
+                next_vecblocks,
+                next_li_sig.clone(),
+                empty_state_computer_call_back(),
+            )));
+
+            // messages about the committed batch are not errors: the votes are answered with
+            // the commit decision and the decisions are ignored
+            let stale_commit_vote =
+                CommitVote::new(signer.author(), li_sig.ledger_info().clone(), signer);
+            assert!(commit_phase
+                .process_commit_vote(&stale_commit_vote)
+                .await
+                .is_ok());
+            assert!(commit_phase
+                .process_commit_decision(&CommitDecision::new(li_sig))
+                .await
+                .is_ok());
+
+            // ..and leave the pending batch untouched
+            assert_eq!(
+                commit_phase.blocks().as_ref().unwrap().ledger_info_sig(),
+                &next_li_sig
+            );
+        });
+    }
 }
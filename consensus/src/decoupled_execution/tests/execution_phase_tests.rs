@@ -1,14 +1,14 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::experimental::{
+use crate::decoupled_execution::{
     execution_phase::ExecutionPhase, tests::ordering_state_computer_tests::random_empty_block,
 };
 
 use dijets_types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
 
 use crate::{
-    experimental::{
+    decoupled_execution::{
         commit_phase::CommitChannelType,
         execution_phase::{reset_ack_new, ExecutionChannelType, ResetAck},
     },
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    experimental::tests::test_utils::prepare_executed_blocks_with_ordered_ledger_info,
+    decoupled_execution::tests::test_utils::prepare_executed_blocks_with_ordered_ledger_info,
     round_manager::UnverifiedEvent,
     test_utils::{consensus_runtime, timed_block_on, RandomComputeResultStateComputer},
 };
@@ -10,8 +10,8 @@ use futures::{SinkExt, StreamExt};
 use network::protocols::network::Event;
 
 use crate::{
-    block_storage::BlockReader,
-    experimental::{
+    block_storage::{BlockReader, BlockStore},
+    decoupled_execution::{
         execution_phase::{ExecutionChannelType, ExecutionPhase},
         ordering_state_computer::OrderingStateComputer,
     },
@@ -23,16 +23,18 @@ use crate::network_interface::ConsensusMsg;
 use consensus_types::block::block_test_utils::certificate_for_genesis;
 
 use crate::{
-    experimental::{
+    decoupled_execution::{
         commit_phase::CommitChannelType, execution_phase::ResetAck,
         tests::test_utils::prepare_commit_phase_with_block_store_state_computer,
     },
     state_replication::empty_state_computer_call_back,
-    test_utils::{EmptyStateComputer, TreeInserter},
+    test_utils::{EmptyStateComputer, EmptyStorage, MockStorage, TreeInserter},
+    util::mock_time_service::SimulatedTimeService,
 };
 use consensus_types::executed_block::ExecutedBlock;
+use dijets_types::validator_verifier::random_validator_verifier;
 use executor_types::StateComputeResult;
-use futures::channel::oneshot;
+use futures::{channel::oneshot, FutureExt};
 
 #[test]
 fn decoupled_execution_integration() {
@@ -49,6 +51,7 @@ fn decoupled_execution_integration() {
         execution_phase_tx,
         Arc::new(EmptyStateComputer {}), // we will not call sync_to in this test
         execution_phase_reset_tx,
+        Arc::new(EmptyStorage::new()),
     ));
 
     // now we need to replace the state computer instance (previously the one directly outputs to commit_result_rx)
@@ -173,3 +176,83 @@ fn decoupled_execution_integration() {
         assert!(block_store_handle.block_exists(a3.block().id()));
     });
 }
+
+#[test]
+fn decoupled_execution_recovery() {
+    let channel_size = 30;
+    let mut runtime = consensus_runtime();
+
+    let (signers, validators) = random_validator_verifier(1, None, false);
+    let (initial_data, storage) = MockStorage::start_for_testing((&validators).into());
+
+    let (execution_phase_tx, _execution_phase_rx) =
+        channel::new_test::<ExecutionChannelType>(channel_size);
+    let (execution_phase_reset_tx, _) = channel::new_test::<oneshot::Sender<ResetAck>>(1);
+    let block_store = Arc::new(BlockStore::new(
+        storage.clone(),
+        initial_data,
+        Arc::new(OrderingStateComputer::new(
+            execution_phase_tx,
+            Arc::new(EmptyStateComputer {}),
+            execution_phase_reset_tx,
+            storage.clone(),
+        )),
+        10, // max pruned blocks in mem
+        Arc::new(SimulatedTimeService::new()),
+    ));
+
+    let mut inserter = TreeInserter::new_with_store(signers[0].clone(), block_store.clone());
+    let genesis = block_store.ordered_root();
+
+    // genesis --> a1 --> a2 --> a3 --> a4 --> a5, every qc commits a block
+    let a1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let a2 = inserter.insert_block(&a1, 2, None);
+    let a3 = inserter.insert_block(&a2, 3, Some(a1.block_info()));
+    let a4 = inserter.insert_block(&a3, 4, Some(a2.block_info()));
+    let a5 = inserter.insert_block(&a4, 5, Some(a3.block_info()));
+
+    // a1, a2 and a3 are ordered in a single batch, then the node crashes before committing them
+    let ordered_cert = a5.quorum_cert().ledger_info().clone();
+    timed_block_on(&mut runtime, async {
+        block_store.commit(ordered_cert.clone()).await.unwrap();
+    });
+    assert_eq!(
+        storage
+            .shared_storage
+            .ordered_certs
+            .lock()
+            .get(&a3.id())
+            .cloned(),
+        Some(ordered_cert.clone())
+    );
+
+    // restart: the very same batch is sent to the execution phase
+    let (execution_phase_tx, mut execution_phase_rx) =
+        channel::new_test::<ExecutionChannelType>(channel_size);
+    let (execution_phase_reset_tx, _) = channel::new_test::<oneshot::Sender<ResetAck>>(1);
+    let recovered_block_store = BlockStore::new(
+        storage.clone(),
+        storage.try_start().unwrap(),
+        Arc::new(OrderingStateComputer::new(
+            execution_phase_tx,
+            Arc::new(EmptyStateComputer {}),
+            execution_phase_reset_tx,
+            storage.clone(),
+        )),
+        10, // max pruned blocks in mem
+        Arc::new(SimulatedTimeService::new()),
+    );
+    assert_eq!(recovered_block_store.ordered_root().id(), a3.id());
+
+    timed_block_on(&mut runtime, async move {
+        let ExecutionChannelType(ordered_blocks, finality_proof, _) =
+            execution_phase_rx.next().await.unwrap();
+        assert_eq!(
+            ordered_blocks.iter().map(|b| b.id()).collect::<Vec<_>>(),
+            vec![a1.id(), a2.id(), a3.id()]
+        );
+        assert_eq!(finality_proof, ordered_cert);
+        // the qcs committing a1 and a2 alone are not replayed
+        assert!(execution_phase_rx.next().now_or_never().is_none());
+    });
+}
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    experimental::ordering_state_computer::OrderingStateComputer, state_replication::StateComputer,
+    decoupled_execution::ordering_state_computer::OrderingStateComputer,
+    state_replication::StateComputer,
 };
 use channel::Receiver;
 use consensus_types::block::{block_test_utils::certificate_for_genesis, Block};
@@ -15,9 +16,9 @@ use executor_types::StateComputeResult;
 use std::sync::Arc;
 
 use crate::{
-    experimental::execution_phase::{ExecutionChannelType, ResetAck},
+    decoupled_execution::execution_phase::{ExecutionChannelType, ResetAck},
     state_replication::empty_state_computer_call_back,
-    test_utils::{consensus_runtime, timed_block_on, EmptyStateComputer},
+    test_utils::{consensus_runtime, timed_block_on, EmptyStateComputer, EmptyStorage},
 };
 use consensus_types::{executed_block::ExecutedBlock, quorum_cert::QuorumCert};
 use dijets_crypto::ed25519::Ed25519Signature;
@@ -41,6 +42,7 @@ pub fn prepare_ordering_state_computer(
         commit_result_tx,
         Arc::new(EmptyStateComputer {}),
         execution_phase_reset_tx,
+        Arc::new(EmptyStorage::new()),
     ));
 
     (state_computer, commit_result_rx, execution_phase_reset_rx)
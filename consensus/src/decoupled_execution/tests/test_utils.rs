@@ -3,7 +3,7 @@
 
 use crate::{
     block_storage::BlockStore,
-    experimental::{
+    decoupled_execution::{
         commit_phase::{CommitChannelType, CommitPhase},
         execution_phase::{ExecutionChannelType, ResetAck},
         ordering_state_computer::OrderingStateComputer,
@@ -119,6 +119,7 @@ pub fn prepare_commit_phase_with_block_store_state_computer(
         commit_result_tx,
         block_store_state_computer.clone(),
         execution_phase_reset_tx,
+        storage.clone(),
     ));
 
     let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
@@ -4,12 +4,12 @@
 use crate::{
     block_storage::BlockStore,
     counters,
-    error::{error_kind, DbError},
-    experimental::{
+    decoupled_execution::{
         commit_phase::{CommitChannelType, CommitPhase},
         execution_phase::{ExecutionChannelType, ExecutionPhase, ResetAck},
         ordering_state_computer::OrderingStateComputer,
     },
+    error::{error_kind, DbError},
     liveness::{
         leader_reputation::{ActiveInactiveHeuristic, DijetsDBBackend, LeaderReputation},
         proposal_generator::ProposalGenerator,
@@ -44,7 +44,7 @@ use dijets_types::{
     on_chain_config::{OnChainConfigPayload, ValidatorSet},
 };
 use futures::{channel::oneshot, select, SinkExt, StreamExt};
-use network::protocols::network::Event;
+use network::{protocols::network::Event, reputation::Misbehavior};
 use safety_rules::SafetyRulesManager;
 use std::{
     cmp::Ordering,
@@ -323,6 +323,7 @@ impl EpochManager {
             execution_phase_tx,
             self.commit_state_computer.clone(),
             execution_phase_reset_tx,
+            self.storage.clone(),
         ));
 
         info!(epoch = epoch, "Create BlockStore");
@@ -395,6 +396,7 @@ impl EpochManager {
             self.config.sync_only,
             self.back_pressure.clone(),
             self.config.back_pressure_limit,
+            self.config.two_chain,
         );
 
         Ok((round_manager, execution_phase, commit_phase))
@@ -490,6 +492,7 @@ impl EpochManager {
                 self.txn_manager.clone(),
                 self.storage.clone(),
                 self.config.sync_only,
+                self.config.two_chain,
             )
         };
 
@@ -560,6 +563,22 @@ impl EpochManager {
             let verified_event = unverified_event
                 .clone()
                 .verify(&self.epoch_state().verifier)
+                .map_err(|err| {
+                    // Other failures, e.g. unknown authors, also happen to honest peers around
+                    // epoch changes
+                    if err.is_invalid_signature() {
+                        if let Err(e) = self
+                            .network_sender
+                            .report_misbehavior(peer_id, Misbehavior::InvalidSignature)
+                        {
+                            warn!(
+                                remote_peer = peer_id,
+                                error = ?e, "Failed to report invalid signature"
+                            );
+                        }
+                    }
+                    err
+                })
                 .context("[EpochManager] Verify event")
                 .map_err(|err| {
                     error!(
@@ -586,6 +605,9 @@ impl EpochManager {
             ConsensusMsg::ProposalMsg(_)
             | ConsensusMsg::SyncInfo(_)
             | ConsensusMsg::VoteMsg(_)
+            | ConsensusMsg::TwoChainProposalMsg(_)
+            | ConsensusMsg::TwoChainSyncInfo(_)
+            | ConsensusMsg::TwoChainVoteMsg(_)
             | ConsensusMsg::CommitVoteMsg(_)
             | ConsensusMsg::CommitDecisionMsg(_) => {
                 let event: UnverifiedEvent = msg.into();
@@ -1,7 +1,8 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::experimental;
+use crate::decoupled_execution;
+use dijets_types::validator_verifier;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -18,8 +19,8 @@ pub struct StateSyncError {
     inner: anyhow::Error,
 }
 
-impl From<experimental::errors::Error> for StateSyncError {
-    fn from(e: experimental::errors::Error) -> Self {
+impl From<decoupled_execution::errors::Error> for StateSyncError {
+    fn from(e: decoupled_execution::errors::Error) -> Self {
         StateSyncError { inner: e.into() }
     }
 }
@@ -44,6 +45,19 @@ pub struct VerifyError {
     inner: anyhow::Error,
 }
 
+impl VerifyError {
+    /// Whether the verification failed on an invalid signature, as opposed to e.g. an unknown
+    /// author or a malformed message.
+    pub fn is_invalid_signature(&self) -> bool {
+        self.inner.chain().any(|e| {
+            matches!(
+                e.downcast_ref::<validator_verifier::VerifyError>(),
+                Some(validator_verifier::VerifyError::InvalidSignature)
+            )
+        })
+    }
+}
+
 pub fn error_kind(e: &anyhow::Error) -> &'static str {
     if e.downcast_ref::<executor_types::Error>().is_some() {
         return "Execution";
@@ -71,8 +85,9 @@ pub fn error_kind(e: &anyhow::Error) -> &'static str {
 
 #[cfg(test)]
 mod tests {
-    use crate::error::{error_kind, StateSyncError};
-    use anyhow::Context;
+    use crate::error::{error_kind, StateSyncError, VerifyError};
+    use anyhow::{format_err, Context};
+    use dijets_types::validator_verifier;
 
     #[test]
     fn conversion_and_downcast() {
@@ -83,4 +98,18 @@ mod tests {
         let upper: anyhow::Result<()> = Err(typed_error).context("Context!");
         assert_eq!(error_kind(&upper.unwrap_err()), "Execution");
     }
+
+    #[test]
+    fn invalid_signature() {
+        let error: anyhow::Result<()> =
+            Err(validator_verifier::VerifyError::InvalidSignature).context("Failed to verify Vote");
+        assert!(VerifyError::from(error.unwrap_err()).is_invalid_signature());
+
+        let error: anyhow::Result<()> =
+            Err(validator_verifier::VerifyError::UnknownAuthor).context("Failed to verify Vote");
+        assert!(!VerifyError::from(error.unwrap_err()).is_invalid_signature());
+        assert!(
+            !VerifyError::from(format_err!("VoteMsg has different epoch")).is_invalid_signature()
+        );
+    }
 }
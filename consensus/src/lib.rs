@@ -15,9 +15,9 @@
 mod block_storage;
 mod consensusdb;
 mod counters;
+mod decoupled_execution;
 mod epoch_manager;
 mod error;
-mod experimental;
 mod liveness;
 mod logging;
 mod metrics_safety_rules;
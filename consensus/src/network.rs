@@ -12,6 +12,7 @@ use channel::{self, dijets_channel, message_queues::QueueStyle};
 use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, MAX_BLOCKS_PER_REQUEST},
     common::Author,
+    experimental::commit_decision::CommitDecision,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
 };
@@ -150,10 +151,10 @@ impl NetworkSender {
     /// internal(to provide back pressure), it does not indicate the message is delivered or sent
     /// out. It does not give indication about when the message is delivered to the recipients,
     /// as well as there is no indication about the network failures.
-    pub async fn send_vote(&self, vote_msg: VoteMsg, recipients: Vec<Author>) {
+    pub async fn send_vote(&self, vote_msg: VoteMsg, recipients: Vec<Author>, two_chain: bool) {
         let mut network_sender = self.network_sender.clone();
         let mut self_sender = self.self_sender.clone();
-        let msg = ConsensusMsg::VoteMsg(Box::new(vote_msg));
+        let msg = ConsensusMsg::vote_msg(vote_msg, two_chain);
         for peer in recipients {
             if self.author == peer {
                 let self_msg = Event::Message(self.author, msg.clone());
@@ -174,8 +175,8 @@ impl NetworkSender {
     /// Sends the given sync info to the given author.
     /// The future is fulfilled as soon as the message is added to the internal network channel
     /// (does not indicate whether the message is delivered or sent out).
-    pub fn send_sync_info(&self, sync_info: SyncInfo, recipient: Author) {
-        let msg = ConsensusMsg::SyncInfo(Box::new(sync_info));
+    pub fn send_sync_info(&self, sync_info: SyncInfo, recipient: Author, two_chain: bool) {
+        let msg = ConsensusMsg::sync_info(sync_info, two_chain);
         let mut network_sender = self.network_sender.clone();
         if let Err(e) = network_sender.send_to(recipient, msg) {
             warn!(
@@ -187,6 +188,21 @@ impl NetworkSender {
         }
     }
 
+    /// Sends the given commit decision to the given author, typically a validator still
+    /// collecting the signatures of blocks we've already committed.
+    /// The future is fulfilled as soon as the message is added to the internal network channel
+    /// (does not indicate whether the message is delivered or sent out).
+    pub fn send_commit_decision(&self, commit_decision: CommitDecision, recipient: Author) {
+        let msg = ConsensusMsg::CommitDecisionMsg(Box::new(commit_decision));
+        let mut network_sender = self.network_sender.clone();
+        if let Err(e) = network_sender.send_to(recipient, msg) {
+            warn!(
+                remote_peer = recipient,
+                error = ?e, "Failed to send a commit decision to peer",
+            );
+        }
+    }
+
     pub async fn notify_epoch_change(&mut self, proof: EpochChangeProof) {
         let msg = ConsensusMsg::EpochChangeProof(Box::new(proof));
         let self_msg = Event::Message(self.author, msg);
@@ -12,6 +12,7 @@ use consensus_types::{
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
+    two_chain_layout,
     vote_msg::VoteMsg,
 };
 use dijets_infallible::RwLock;
@@ -27,6 +28,7 @@ use network::{
         rpc::error::RpcError,
         wire::handshake::v1::SupportedProtocols,
     },
+    reputation::Misbehavior,
     ProtocolId,
 };
 use serde::{Deserialize, Serialize};
@@ -59,6 +61,47 @@ pub enum ConsensusMsg {
     /// than 2f + 1 signatures on the commit proposal. This part is not on the critical path, but
     /// it can save slow machines to quickly confirm the execution result.
     CommitDecisionMsg(Box<CommitDecision>),
+    /// ProposalMsg in the layout of the 2-chain mode, which carries the 2-chain timeout
+    /// certificate of its SyncInfo.
+    #[serde(with = "two_chain_layout::proposal_msg")]
+    TwoChainProposalMsg(Box<ProposalMsg>),
+    /// SyncInfo in the layout of the 2-chain mode, which carries the 2-chain timeout certificate.
+    #[serde(with = "two_chain_layout::sync_info")]
+    TwoChainSyncInfo(Box<SyncInfo>),
+    /// VoteMsg in the layout of the 2-chain mode, which carries the 2-chain timeout of its Vote
+    /// and the 2-chain timeout certificate of its SyncInfo.
+    #[serde(with = "two_chain_layout::vote_msg")]
+    TwoChainVoteMsg(Box<VoteMsg>),
+}
+
+impl ConsensusMsg {
+    /// Wraps a proposal in the layout of the consensus mode. Validators that don't run the
+    /// 2-chain mode can't decode the 2-chain layout, so it's only used with 2-chain enabled.
+    pub fn proposal_msg(proposal_msg: ProposalMsg, two_chain: bool) -> Self {
+        if two_chain {
+            ConsensusMsg::TwoChainProposalMsg(Box::new(proposal_msg))
+        } else {
+            ConsensusMsg::ProposalMsg(Box::new(proposal_msg))
+        }
+    }
+
+    /// Wraps a sync info in the layout of the consensus mode.
+    pub fn sync_info(sync_info: SyncInfo, two_chain: bool) -> Self {
+        if two_chain {
+            ConsensusMsg::TwoChainSyncInfo(Box::new(sync_info))
+        } else {
+            ConsensusMsg::SyncInfo(Box::new(sync_info))
+        }
+    }
+
+    /// Wraps a vote in the layout of the consensus mode.
+    pub fn vote_msg(vote_msg: VoteMsg, two_chain: bool) -> Self {
+        if two_chain {
+            ConsensusMsg::TwoChainVoteMsg(Box::new(vote_msg))
+        } else {
+            ConsensusMsg::VoteMsg(Box::new(vote_msg))
+        }
+    }
 }
 
 /// The interface from Network to Consensus layer.
@@ -170,6 +213,15 @@ impl ConsensusNetworkSender {
             .await
     }
 
+    /// Report a misbehaving peer to the network, which bans it once it misbehaves too often.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.network_sender.report_misbehavior(peer, misbehavior)
+    }
+
     /// Initialize a shared hashmap about connections metadata that is updated by the receiver.
     pub fn initialize(&mut self, connections: Arc<RwLock<HashMap<PeerId, SupportedProtocols>>>) {
         self.peers_protocols = Some(connections);
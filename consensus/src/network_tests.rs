@@ -295,8 +295,13 @@ impl NetworkPlayground {
     /// Return the round of a given message
     fn get_message_round(msg: ConsensusMsg) -> Option<u64> {
         match msg {
-            ConsensusMsg::ProposalMsg(proposal_msg) => Some(proposal_msg.proposal().round()),
-            ConsensusMsg::VoteMsg(vote_msg) => Some(vote_msg.vote().vote_data().proposed().round()),
+            ConsensusMsg::ProposalMsg(proposal_msg)
+            | ConsensusMsg::TwoChainProposalMsg(proposal_msg) => {
+                Some(proposal_msg.proposal().round())
+            }
+            ConsensusMsg::VoteMsg(vote_msg) | ConsensusMsg::TwoChainVoteMsg(vote_msg) => {
+                Some(vote_msg.vote().vote_data().proposed().round())
+            }
             _ => None,
         }
     }
@@ -308,12 +313,18 @@ impl NetworkPlayground {
 
     /// Returns true for proposal messages only.
     pub fn proposals_only(msg: &(Author, ConsensusMsg)) -> bool {
-        matches!(&msg.1, ConsensusMsg::ProposalMsg(_))
+        matches!(
+            &msg.1,
+            ConsensusMsg::ProposalMsg(_) | ConsensusMsg::TwoChainProposalMsg(_)
+        )
     }
 
     /// Returns true for vote messages only.
     pub fn votes_only(msg: &(Author, ConsensusMsg)) -> bool {
-        matches!(&msg.1, ConsensusMsg::VoteMsg(_))
+        matches!(
+            &msg.1,
+            ConsensusMsg::VoteMsg(_) | ConsensusMsg::TwoChainVoteMsg(_)
+        )
     }
 
     pub fn extend_author_to_twin_ids(&mut self, author: Author, twin_id: TwinId) {
@@ -601,7 +612,7 @@ mod tests {
         );
         timed_block_on(&mut runtime, async {
             nodes[0]
-                .send_vote(vote_msg.clone(), peers[2..5].to_vec())
+                .send_vote(vote_msg.clone(), peers[2..5].to_vec(), false)
                 .await;
             playground
                 .wait_for_messages(3, NetworkPlayground::take_all)
@@ -705,8 +716,8 @@ mod tests {
                 // make sure the network task is not blocked during RPC
                 // we limit the network notification queue size to 1 so if it's blocked,
                 // we can not process 2 votes and the test will timeout
-                node0.send_vote(vote_msg.clone(), vec![peer1]).await;
-                node0.send_vote(vote_msg.clone(), vec![peer1]).await;
+                node0.send_vote(vote_msg.clone(), vec![peer1], false).await;
+                node0.send_vote(vote_msg.clone(), vec![peer1], false).await;
                 playground
                     .wait_for_messages(2, NetworkPlayground::votes_only)
                     .await;
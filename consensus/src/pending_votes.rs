@@ -8,7 +8,8 @@
 //! Votes are automatically dropped when the structure goes out of scope.
 
 use consensus_types::{
-    common::Author, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate, vote::Vote,
+    common::Author, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
+    timeout_certificate::TimeoutCertificate, vote::Vote,
 };
 use dijets_crypto::{hash::CryptoHash, HashValue};
 use dijets_logger::prelude::*;
@@ -37,6 +38,8 @@ pub enum VoteReceptionResult {
     NewQuorumCertificate(Arc<QuorumCert>),
     /// The vote completes a new TimeoutCertificate
     NewTimeoutCertificate(Arc<TimeoutCertificate>),
+    /// The vote completes a new TwoChainTimeoutCertificate
+    New2ChainTimeoutCertificate(Arc<TwoChainTimeoutCertificate>),
     /// There might be some issues adding a vote
     ErrorAddingVote(VerifyError),
     /// The vote is not for the current round.
@@ -52,6 +55,9 @@ pub struct PendingVotes {
     /// Tracks all the signatures of the votes for the given round. In case we succeed to
     /// aggregate 2f+1 signatures a TimeoutCertificate is formed.
     maybe_partial_tc: Option<TimeoutCertificate>,
+    /// Tracks all the signed 2-chain timeouts of the votes for the given round. In case we
+    /// succeed to aggregate 2f+1 of them a TwoChainTimeoutCertificate is formed.
+    maybe_partial_2chain_tc: Option<TwoChainTimeoutCertificate>,
     /// Map of Author to vote. This is useful to discard multiple votes.
     author_to_vote: HashMap<Author, Vote>,
 }
@@ -62,12 +68,13 @@ impl PendingVotes {
         PendingVotes {
             li_digest_to_votes: HashMap::new(),
             maybe_partial_tc: None,
+            maybe_partial_2chain_tc: None,
             author_to_vote: HashMap::new(),
         }
     }
 
     /// Insert a vote and if the vote is valid, return a QuorumCertificate preferentially over a
-    /// TimeoutCertificate if either can can be formed
+    /// TimeoutCertificate (or a TwoChainTimeoutCertificate) if either can can be formed
     pub fn insert_vote(
         &mut self,
         vote: &Vote,
@@ -183,6 +190,38 @@ impl PendingVotes {
             }
         }
 
+        if let Some((timeout, signature)) = vote.two_chain_timeout() {
+            // if no partial 2-chain TC exist, create one
+            let partial_tc = self
+                .maybe_partial_2chain_tc
+                .get_or_insert_with(|| TwoChainTimeoutCertificate::new(timeout.clone()));
+
+            // add the signed timeout, the TC keeps the highest quorum cert it has seen
+            partial_tc.add(vote.author(), timeout.clone(), signature.clone());
+
+            // did the 2-chain TC reach a threshold?
+            match validator_verifier.check_voting_power(partial_tc.signers()) {
+                // A quorum of signature was reached, a new 2-chain TC was formed!
+                Ok(_) => {
+                    return VoteReceptionResult::New2ChainTimeoutCertificate(Arc::new(
+                        partial_tc.clone(),
+                    ))
+                }
+
+                // not enough votes
+                Err(VerifyError::TooLittleVotingPower { .. }) => (),
+
+                // error
+                Err(error) => {
+                    error!(
+                        "MUST_FIX: 2-chain timeout vote received could not be added: {}, vote: {}",
+                        error, vote
+                    );
+                    return VoteReceptionResult::ErrorAddingVote(error);
+                }
+            }
+        }
+
         //
         // 5. No QC (or TC) could be formed, return the QC's voting power
         //
@@ -210,6 +249,12 @@ impl fmt::Display for PendingVotes {
             .as_ref()
             .map(|partial_tc| partial_tc.signatures().keys().collect::<Vec<_>>());
 
+        // collect 2-chain timeout votes
+        let two_chain_timeout_votes = self
+            .maybe_partial_2chain_tc
+            .as_ref()
+            .map(|partial_tc| partial_tc.signers().collect::<Vec<_>>());
+
         // write
         write!(f, "PendingVotes: [")?;
 
@@ -221,6 +266,10 @@ impl fmt::Display for PendingVotes {
             write!(f, "{} timeout {:?}", authors.len(), authors)?;
         }
 
+        if let Some(authors) = two_chain_timeout_votes {
+            write!(f, "{} 2-chain timeout {:?}", authors.len(), authors)?;
+        }
+
         write!(f, "]")
     }
 }
@@ -232,7 +281,9 @@ impl fmt::Display for PendingVotes {
 #[cfg(test)]
 mod tests {
     use super::{PendingVotes, VoteReceptionResult};
-    use consensus_types::{vote::Vote, vote_data::VoteData};
+    use consensus_types::{
+        block::block_test_utils::certificate_for_genesis, vote::Vote, vote_data::VoteData,
+    };
     use dijets_crypto::HashValue;
     use dijets_types::{
         block_info::BlockInfo, ledger_info::LedgerInfo,
@@ -370,4 +421,52 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    /// Verify that signed 2-chain timeouts are properly aggregated to a 2-chain TC
+    fn test_2chain_tc_aggregation() {
+        ::dijets_logger::Logger::init_for_testing();
+
+        // set up 4 validators
+        let (signers, validator) = random_validator_verifier(4, Some(2), false);
+        let mut pending_votes = PendingVotes::new();
+
+        // submit a new vote from validator[0] -> VoteAdded
+        let li1 = random_ledger_info();
+        let vote1 = random_vote_data();
+        let mut vote1_author_0 = Vote::new(vote1, signers[0].author(), li1, &signers[0]);
+
+        assert_eq!(
+            pending_votes.insert_vote(&vote1_author_0, &validator),
+            VoteReceptionResult::VoteAdded(1)
+        );
+
+        // submit the same vote but enhanced with a 2-chain timeout -> VoteAdded
+        let timeout = vote1_author_0.generate_2chain_timeout(certificate_for_genesis());
+        let signature = timeout.sign(&signers[0]);
+        vote1_author_0.add_2chain_timeout(timeout, signature);
+
+        assert_eq!(
+            pending_votes.insert_vote(&vote1_author_0, &validator),
+            VoteReceptionResult::VoteAdded(1)
+        );
+
+        // another vote for a different block enhanced with a 2-chain timeout
+        // -> New2ChainTimeoutCertificate
+        let li2 = random_ledger_info();
+        let vote2 = random_vote_data();
+        let mut vote2_author_1 = Vote::new(vote2, signers[1].author(), li2, &signers[1]);
+        let timeout = vote2_author_1.generate_2chain_timeout(certificate_for_genesis());
+        let signature = timeout.sign(&signers[1]);
+        vote2_author_1.add_2chain_timeout(timeout, signature);
+        match pending_votes.insert_vote(&vote2_author_1, &validator) {
+            VoteReceptionResult::New2ChainTimeoutCertificate(tc) => {
+                assert!(validator.check_voting_power(tc.signers()).is_ok());
+                assert_eq!(tc.round(), 1);
+            }
+            _ => {
+                panic!("No 2-chain TC formed.");
+            }
+        };
+    }
 }
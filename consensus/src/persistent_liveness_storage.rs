@@ -4,7 +4,8 @@
 use crate::{consensusdb::ConsensusDB, epoch_manager::LivenessStorageData, error::DbError};
 use anyhow::{format_err, Context, Result};
 use consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate, vote::Vote,
+    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
+    timeout_certificate::TimeoutCertificate, two_chain_layout, vote::Vote,
 };
 use dijets_config::config::NodeConfig;
 use dijets_crypto::HashValue;
@@ -14,6 +15,7 @@ use dijets_types::{
     transaction::Version,
 };
 use executor_types::ExecutedTrees;
+use serde::{Deserialize, Serialize};
 use std::{cmp::max, collections::HashSet, sync::Arc};
 use storage_interface::DbReader;
 
@@ -32,6 +34,11 @@ pub trait PersistentLivenessStorage: Send + Sync {
     /// Persist consensus' state
     fn save_vote(&self, vote: &Vote) -> Result<()>;
 
+    /// Persist the ordering proof of a batch of blocks handed to the decoupled execution pipeline,
+    /// so that the same batch can be replayed after a restart. It's pruned together with the last
+    /// block of the batch once committed.
+    fn save_ordered_cert(&self, ordered_cert: LedgerInfoWithSignatures) -> Result<()>;
+
     /// Construct data that can be recovered from ledger
     fn recover_from_ledger(&self) -> LedgerRecoveryData;
 
@@ -42,6 +49,13 @@ pub trait PersistentLivenessStorage: Send + Sync {
     /// to jump to this round
     fn save_highest_timeout_cert(&self, highest_timeout_cert: TimeoutCertificate) -> Result<()>;
 
+    /// Persist the highest 2-chain timeout certificate, the counterpart of the highest timeout
+    /// certificate in the 2-chain mode
+    fn save_highest_2chain_timeout_cert(
+        &self,
+        highest_2chain_timeout_cert: TwoChainTimeoutCertificate,
+    ) -> Result<()>;
+
     /// Retrieve a epoch change proof for SafetyRules so it can instantiate its
     /// ValidatorVerifier.
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof>;
@@ -175,9 +189,12 @@ pub struct RecoveryData {
     blocks: Vec<Block>,
     quorum_certs: Vec<QuorumCert>,
     blocks_to_prune: Option<Vec<HashValue>>,
+    // Ordering proofs of the batches that were ordered but not committed yet, sorted by round.
+    ordered_certs: Vec<LedgerInfoWithSignatures>,
 
     // Liveness data
     highest_timeout_certificate: Option<TimeoutCertificate>,
+    highest_2chain_timeout_certificate: Option<TwoChainTimeoutCertificate>,
 }
 
 impl RecoveryData {
@@ -188,6 +205,8 @@ impl RecoveryData {
         root_metadata: RootMetadata,
         mut quorum_certs: Vec<QuorumCert>,
         highest_timeout_certificate: Option<TimeoutCertificate>,
+        highest_2chain_timeout_certificate: Option<TwoChainTimeoutCertificate>,
+        mut ordered_certs: Vec<LedgerInfoWithSignatures>,
     ) -> Result<Self> {
         let root = ledger_recovery_data
             .find_root(&mut blocks, &mut quorum_certs)
@@ -218,6 +237,12 @@ impl RecoveryData {
             &mut blocks,
             &mut quorum_certs,
         ));
+        // only the batches ordered on top of the root are still pending
+        ordered_certs.retain(|li| {
+            let block_id = li.ledger_info().consensus_block_id();
+            blocks.iter().any(|block| block.id() == block_id)
+        });
+        ordered_certs.sort_by_key(|li| li.ledger_info().round());
         let epoch = root.0.epoch();
         Ok(RecoveryData {
             last_vote: match last_vote {
@@ -229,10 +254,15 @@ impl RecoveryData {
             blocks,
             quorum_certs,
             blocks_to_prune,
+            ordered_certs,
             highest_timeout_certificate: match highest_timeout_certificate {
                 Some(tc) if tc.epoch() == epoch => Some(tc),
                 _ => None,
             },
+            highest_2chain_timeout_certificate: match highest_2chain_timeout_certificate {
+                Some(tc) if tc.epoch() == epoch => Some(tc),
+                _ => None,
+            },
         })
     }
 
@@ -259,10 +289,18 @@ impl RecoveryData {
             .expect("blocks_to_prune already taken")
     }
 
+    pub fn take_ordered_certs(&mut self) -> Vec<LedgerInfoWithSignatures> {
+        std::mem::take(&mut self.ordered_certs)
+    }
+
     pub fn highest_timeout_certificate(&self) -> Option<TimeoutCertificate> {
         self.highest_timeout_certificate.clone()
     }
 
+    pub fn highest_2chain_timeout_certificate(&self) -> Option<TwoChainTimeoutCertificate> {
+        self.highest_2chain_timeout_certificate.clone()
+    }
+
     fn find_blocks_to_prune(
         root_id: HashValue,
         blocks: &mut Vec<Block>,
@@ -287,6 +325,22 @@ impl RecoveryData {
     }
 }
 
+/// The last vote is persisted in the 2-chain layout, so that a 2-chain timeout vote can be sent
+/// again after a restart.
+#[derive(Deserialize, Serialize)]
+struct PersistedVote(#[serde(with = "two_chain_layout::vote")] Vote);
+
+/// Decodes the last vote, which was persisted in the original layout of `Vote` before the 2-chain
+/// mode. A vote that can't be decoded is dropped: safety rules keep the last voted round, so it's
+/// only needed to send the same vote again.
+fn decode_last_vote(bytes: &[u8]) -> Option<Vote> {
+    bcs::from_bytes::<PersistedVote>(bytes)
+        .map(|vote| vote.0)
+        .or_else(|_| bcs::from_bytes::<Vote>(bytes))
+        .map_err(|e| warn!(error = ?e, "Unable to deserialize last vote msg, dropping it"))
+        .ok()
+}
+
 /// The proxy we use to persist data in dijets db storage service via grpc.
 pub struct StorageWriteProxy {
     db: Arc<ConsensusDB>,
@@ -316,7 +370,13 @@ impl PersistentLivenessStorage for StorageWriteProxy {
     }
 
     fn save_vote(&self, vote: &Vote) -> Result<()> {
-        Ok(self.db.save_vote(bcs::to_bytes(vote)?)?)
+        Ok(self
+            .db
+            .save_vote(bcs::to_bytes(&PersistedVote(vote.clone()))?)?)
+    }
+
+    fn save_ordered_cert(&self, ordered_cert: LedgerInfoWithSignatures) -> Result<()> {
+        Ok(self.db.save_ordered_cert(ordered_cert)?)
     }
 
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
@@ -336,15 +396,20 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .get_data()
             .expect("unable to recover consensus data");
 
-        let last_vote = raw_data.0.map(|vote_data| {
-            bcs::from_bytes(&vote_data[..]).expect("unable to deserialize last vote msg")
-        });
+        let last_vote = raw_data
+            .0
+            .and_then(|vote_data| decode_last_vote(&vote_data[..]));
 
         let highest_timeout_certificate = raw_data.1.map(|ts| {
             bcs::from_bytes(&ts[..]).expect("unable to deserialize highest timeout certificate")
         });
-        let blocks = raw_data.2;
-        let quorum_certs: Vec<_> = raw_data.3;
+        let highest_2chain_timeout_certificate = raw_data.2.map(|ts| {
+            bcs::from_bytes(&ts[..])
+                .expect("unable to deserialize highest 2-chain timeout certificate")
+        });
+        let blocks = raw_data.3;
+        let quorum_certs: Vec<_> = raw_data.4;
+        let ordered_certs = raw_data.5;
         let blocks_repr: Vec<String> = blocks.iter().map(|b| format!("\n\t{}", b)).collect();
         info!(
             "The following blocks were restored from ConsensusDB : {}",
@@ -358,6 +423,14 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             "The following quorum certs were restored from ConsensusDB: {}",
             qc_repr.concat()
         );
+        let ordered_repr: Vec<String> = ordered_certs
+            .iter()
+            .map(|li| format!("\n\t{}", li))
+            .collect();
+        info!(
+            "The following ordered certs were restored from ConsensusDB: {}",
+            ordered_repr.concat()
+        );
 
         // find the block corresponding to storage latest ledger info
         let startup_info = self
@@ -382,6 +455,8 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             ),
             quorum_certs,
             highest_timeout_certificate,
+            highest_2chain_timeout_certificate,
+            ordered_certs,
         ) {
             Ok(mut initial_data) => {
                 (self as &dyn PersistentLivenessStorage)
@@ -397,10 +472,16 @@ impl PersistentLivenessStorage for StorageWriteProxy {
                         .delete_highest_timeout_certificate()
                         .expect("unable to cleanup highest timeout cert");
                 }
+                if initial_data.highest_2chain_timeout_certificate.is_none() {
+                    self.db
+                        .delete_highest_2chain_timeout_certificate()
+                        .expect("unable to cleanup highest 2-chain timeout cert");
+                }
                 info!(
-                    "Starting up the consensus state machine with recovery data - [last_vote {}], [highest timeout certificate: {}]",
+                    "Starting up the consensus state machine with recovery data - [last_vote {}], [highest timeout certificate: {}], [highest 2-chain timeout certificate: {}]",
                     initial_data.last_vote.as_ref().map_or("None".to_string(), |v| v.to_string()),
                     initial_data.highest_timeout_certificate.as_ref().map_or("None".to_string(), |v| v.to_string()),
+                    initial_data.highest_2chain_timeout_certificate.as_ref().map_or("None".to_string(), |v| v.to_string()),
                 );
 
                 LivenessStorageData::RecoveryData(initial_data)
@@ -418,6 +499,17 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .save_highest_timeout_certificate(bcs::to_bytes(&highest_timeout_cert)?)?)
     }
 
+    fn save_highest_2chain_timeout_cert(
+        &self,
+        highest_2chain_timeout_cert: TwoChainTimeoutCertificate,
+    ) -> Result<()> {
+        Ok(self
+            .db
+            .save_highest_2chain_timeout_certificate(bcs::to_bytes(
+                &highest_2chain_timeout_cert,
+            )?)?)
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let (_, proofs, _) = self
             .dijets_db
@@ -431,3 +523,44 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         self.dijets_db.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_types::{block::block_test_utils::certificate_for_genesis, vote_data::VoteData};
+    use dijets_types::{
+        block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+    };
+
+    fn vote(signer: &ValidatorSigner) -> Vote {
+        Vote::new(
+            VoteData::new(BlockInfo::random(1), BlockInfo::random(0)),
+            signer.author(),
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            signer,
+        )
+    }
+
+    #[test]
+    fn test_decode_last_vote() {
+        let signer = ValidatorSigner::random(None);
+        let mut vote = vote(&signer);
+
+        // a vote persisted before the 2-chain mode
+        let bytes = bcs::to_bytes(&vote).unwrap();
+        assert_eq!(decode_last_vote(&bytes), Some(vote.clone()));
+
+        let bytes = bcs::to_bytes(&PersistedVote(vote.clone())).unwrap();
+        assert_eq!(decode_last_vote(&bytes), Some(vote.clone()));
+
+        let timeout = vote.generate_2chain_timeout(certificate_for_genesis());
+        let signature = timeout.sign(&signer);
+        vote.add_2chain_timeout(timeout, signature);
+        let bytes = bcs::to_bytes(&PersistedVote(vote.clone())).unwrap();
+        let decoded = decode_last_vote(&bytes).unwrap();
+        assert_eq!(decoded, vote);
+        assert!(decoded.two_chain_timeout().is_some());
+
+        assert_eq!(decode_last_vote(&[1, 2, 3]), None);
+    }
+}
@@ -30,6 +30,7 @@ use consensus_types::{
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
     sync_info::SyncInfo,
+    timeout_2chain::TwoChainTimeoutCertificate,
     timeout_certificate::TimeoutCertificate,
     vote::Vote,
     vote_msg::VoteMsg,
@@ -98,9 +99,15 @@ impl UnverifiedEvent {
 impl From<ConsensusMsg> for UnverifiedEvent {
     fn from(value: ConsensusMsg) -> Self {
         match value {
-            ConsensusMsg::ProposalMsg(m) => UnverifiedEvent::ProposalMsg(m),
-            ConsensusMsg::VoteMsg(m) => UnverifiedEvent::VoteMsg(m),
-            ConsensusMsg::SyncInfo(m) => UnverifiedEvent::SyncInfo(m),
+            ConsensusMsg::ProposalMsg(m) | ConsensusMsg::TwoChainProposalMsg(m) => {
+                UnverifiedEvent::ProposalMsg(m)
+            }
+            ConsensusMsg::VoteMsg(m) | ConsensusMsg::TwoChainVoteMsg(m) => {
+                UnverifiedEvent::VoteMsg(m)
+            }
+            ConsensusMsg::SyncInfo(m) | ConsensusMsg::TwoChainSyncInfo(m) => {
+                UnverifiedEvent::SyncInfo(m)
+            }
             ConsensusMsg::CommitVoteMsg(m) => UnverifiedEvent::CommitVote(m),
             ConsensusMsg::CommitDecisionMsg(m) => UnverifiedEvent::CommitDecision(m),
             _ => unreachable!("Unexpected conversion"),
@@ -216,6 +223,8 @@ pub struct RoundManager {
     back_pressure: Arc<AtomicU64>,
     decoupled_execution: bool,
     back_pressure_limit: u64,
+    // Vote and timeout following the 2-chain safety rules instead of the 3-chain ones.
+    two_chain: bool,
 }
 
 impl RoundManager {
@@ -230,6 +239,7 @@ impl RoundManager {
         txn_manager: Arc<dyn TxnManager>,
         storage: Arc<dyn PersistentLivenessStorage>,
         sync_only: bool,
+        two_chain: bool,
     ) -> Self {
         // when decoupled execution is false,
         // the counter is still static.
@@ -250,6 +260,7 @@ impl RoundManager {
             back_pressure: Arc::new(AtomicU64::new(0)), // dummy value
             decoupled_execution: false,
             back_pressure_limit: 1, // arbitrary dummy value
+            two_chain,
         }
     }
 
@@ -266,6 +277,7 @@ impl RoundManager {
         sync_only: bool,
         back_pressure: Arc<AtomicU64>,
         back_pressure_limit: u64,
+        two_chain: bool,
     ) -> Self {
         Self {
             epoch_state,
@@ -281,6 +293,7 @@ impl RoundManager {
             back_pressure,
             decoupled_execution: true,
             back_pressure_limit,
+            two_chain,
         }
     }
 
@@ -322,8 +335,10 @@ impl RoundManager {
             .proposer_election
             .is_valid_proposer(self.proposal_generator.author(), new_round_event.round)
         {
-            let proposal_msg =
-                ConsensusMsg::ProposalMsg(Box::new(self.generate_proposal(new_round_event).await?));
+            let proposal_msg = ConsensusMsg::proposal_msg(
+                self.generate_proposal(new_round_event).await?,
+                self.two_chain,
+            );
             let mut network = self.network.clone();
             network.broadcast(proposal_msg).await;
             counters::PROPOSALS_COUNT.inc();
@@ -399,7 +414,8 @@ impl RoundManager {
                 self.new_log(LogEvent::HelpPeerSync).remote_peer(author),
                 "Remote peer has stale state {}, send it back {}", sync_info, local_sync_info,
             );
-            self.network.send_sync_info(local_sync_info.clone(), author);
+            self.network
+                .send_sync_info(local_sync_info.clone(), author, self.two_chain);
         }
         if sync_info.has_newer_certificates(&local_sync_info) {
             debug!(
@@ -503,8 +519,9 @@ impl RoundManager {
         }
     }
 
-    /// The replica broadcasts a "timeout vote message", which includes the round signature, which
-    /// can be aggregated to a TimeoutCertificate.
+    /// The replica broadcasts a "timeout vote message", which includes the round signature (or the
+    /// signed 2-chain timeout), which can be aggregated to a TimeoutCertificate (or
+    /// TwoChainTimeoutCertificate).
     /// The timeout vote message can be one of the following three options:
     /// 1) In case a validator has previously voted in this round, it repeats the same vote and sign
     /// a timeout.
@@ -518,9 +535,10 @@ impl RoundManager {
 
         if self.sync_only() {
             self.network
-                .broadcast(ConsensusMsg::SyncInfo(Box::new(
+                .broadcast(ConsensusMsg::sync_info(
                     self.block_store.sync_info(),
-                )))
+                    self.two_chain,
+                ))
                 .await;
             bail!("[RoundManager] sync_only flag is set, broadcasting SyncInfo");
         }
@@ -541,20 +559,35 @@ impl RoundManager {
         };
 
         if !timeout_vote.is_timeout() {
-            let timeout = timeout_vote.timeout();
-            let signature = self
-                .safety_rules
-                .lock()
-                .sign_timeout(&timeout)
-                .context("[RoundManager] SafetyRules signs timeout")?;
-            timeout_vote.add_timeout_signature(signature);
+            if self.two_chain {
+                let timeout = timeout_vote.generate_2chain_timeout(
+                    self.block_store.highest_quorum_cert().as_ref().clone(),
+                );
+                let signature = self
+                    .safety_rules
+                    .lock()
+                    .sign_timeout_with_qc(
+                        &timeout,
+                        self.block_store.highest_2chain_timeout_cert().as_deref(),
+                    )
+                    .context("[RoundManager] SafetyRules signs 2-chain timeout")?;
+                timeout_vote.add_2chain_timeout(timeout, signature);
+            } else {
+                let timeout = timeout_vote.timeout();
+                let signature = self
+                    .safety_rules
+                    .lock()
+                    .sign_timeout(&timeout)
+                    .context("[RoundManager] SafetyRules signs timeout")?;
+                timeout_vote.add_timeout_signature(signature);
+            }
         }
 
         self.round_state.record_vote(timeout_vote.clone());
-        let timeout_vote_msg = ConsensusMsg::VoteMsg(Box::new(VoteMsg::new(
-            timeout_vote,
-            self.block_store.sync_info(),
-        )));
+        let timeout_vote_msg = ConsensusMsg::vote_msg(
+            VoteMsg::new(timeout_vote, self.block_store.sync_info()),
+            self.two_chain,
+        );
         self.network.broadcast(timeout_vote_msg).await;
         error!(
             round = round,
@@ -623,7 +656,9 @@ impl RoundManager {
 
         self.round_state.record_vote(vote.clone());
         let vote_msg = VoteMsg::new(vote, self.block_store.sync_info());
-        self.network.send_vote(vote_msg, vec![recipients]).await;
+        self.network
+            .send_vote(vote_msg, vec![recipients], self.two_chain)
+            .await;
         Ok(())
     }
 
@@ -665,16 +700,22 @@ impl RoundManager {
         );
 
         let maybe_signed_vote_proposal = executed_block.maybe_signed_vote_proposal();
-        let vote = self
-            .safety_rules
-            .lock()
-            .construct_and_sign_vote(&maybe_signed_vote_proposal)
-            .context(format!(
-                "[RoundManager] SafetyRules {}Rejected{} {}",
-                Fg(Red),
-                Fg(Reset),
-                executed_block.block()
-            ))?;
+        let vote_result = if self.two_chain {
+            self.safety_rules.lock().construct_and_sign_vote_two_chain(
+                &maybe_signed_vote_proposal,
+                self.block_store.highest_2chain_timeout_cert().as_deref(),
+            )
+        } else {
+            self.safety_rules
+                .lock()
+                .construct_and_sign_vote(&maybe_signed_vote_proposal)
+        };
+        let vote = vote_result.context(format!(
+            "[RoundManager] SafetyRules {}Rejected{} {}",
+            Fg(Red),
+            Fg(Reset),
+            executed_block.block()
+        ))?;
         observe_block(executed_block.block().timestamp_usecs(), BlockStage::VOTED);
 
         self.storage
@@ -758,6 +799,9 @@ impl RoundManager {
                 self.new_qc_aggregated(qc, vote.author()).await
             }
             VoteReceptionResult::NewTimeoutCertificate(tc) => self.new_tc_aggregated(tc).await,
+            VoteReceptionResult::New2ChainTimeoutCertificate(tc) => {
+                self.new_2chain_tc_aggregated(tc, vote.author()).await
+            }
             _ => Ok(()),
         }
     }
@@ -789,6 +833,25 @@ impl RoundManager {
         result
     }
 
+    async fn new_2chain_tc_aggregated(
+        &mut self,
+        tc: Arc<TwoChainTimeoutCertificate>,
+        preferred_peer: Author,
+    ) -> anyhow::Result<()> {
+        // The TC might carry a higher QC than ours, which the next proposal has to extend.
+        let result = self
+            .block_store
+            .insert_quorum_cert(
+                tc.highest_quorum_cert(),
+                &mut self.create_block_retriever(preferred_peer),
+            )
+            .await
+            .and_then(|_| self.block_store.insert_2chain_timeout_certificate(tc))
+            .context("[RoundManager] Failed to process a newly aggregated 2-chain TC");
+        self.process_certificates().await?;
+        result
+    }
+
     /// Retrieve a n chained blocks from the block store starting from
     /// an initial parent id, returning with <n (as many as possible) if
     /// id or its ancestors can not be found.
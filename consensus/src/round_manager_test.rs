@@ -72,6 +72,7 @@ pub struct NodeSetup {
     commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
     _state_sync_receiver: mpsc::UnboundedReceiver<Payload>,
     id: usize,
+    two_chain: bool,
 }
 
 impl NodeSetup {
@@ -90,6 +91,15 @@ impl NodeSetup {
         playground: &mut NetworkPlayground,
         executor: Handle,
         num_nodes: usize,
+    ) -> Vec<Self> {
+        Self::create_nodes_with_two_chain(playground, executor, num_nodes, false)
+    }
+
+    fn create_nodes_with_two_chain(
+        playground: &mut NetworkPlayground,
+        executor: Handle,
+        num_nodes: usize,
+        two_chain: bool,
     ) -> Vec<Self> {
         let (signers, validators) = random_validator_verifier(num_nodes, None, false);
         let proposer_author = signers[0].author();
@@ -135,6 +145,7 @@ impl NodeSetup {
                 initial_data,
                 safety_rules_manager,
                 id,
+                two_chain,
             ));
         }
         nodes
@@ -149,6 +160,7 @@ impl NodeSetup {
         initial_data: RecoveryData,
         safety_rules_manager: SafetyRulesManager,
         id: usize,
+        two_chain: bool,
     ) -> Self {
         let epoch_state = EpochState {
             epoch: 1,
@@ -220,6 +232,7 @@ impl NodeSetup {
             Arc::new(MockTransactionManager::new(None)),
             storage.clone(),
             false,
+            two_chain,
         );
         block_on(round_manager.start(last_vote_sent));
         Self {
@@ -233,6 +246,7 @@ impl NodeSetup {
             commit_cb_receiver,
             _state_sync_receiver,
             id,
+            two_chain,
         }
     }
 
@@ -250,13 +264,14 @@ impl NodeSetup {
             recover_data,
             self.safety_rules_manager,
             self.id,
+            self.two_chain,
         )
     }
 
     pub async fn next_proposal(&mut self) -> ProposalMsg {
         match self.all_events.next().await.unwrap() {
             Event::Message(_, msg) => match msg {
-                ConsensusMsg::ProposalMsg(p) => *p,
+                ConsensusMsg::ProposalMsg(p) | ConsensusMsg::TwoChainProposalMsg(p) => *p,
                 msg => panic!("Unexpected Consensus Message: {:?}", msg),
             },
             _ => panic!("Unexpected Network Event"),
@@ -266,7 +281,7 @@ impl NodeSetup {
     pub async fn next_vote(&mut self) -> VoteMsg {
         match self.all_events.next().await.unwrap() {
             Event::Message(_, msg) => match msg {
-                ConsensusMsg::VoteMsg(v) => *v,
+                ConsensusMsg::VoteMsg(v) | ConsensusMsg::TwoChainVoteMsg(v) => *v,
                 msg => panic!("Unexpected Consensus Message: {:?}", msg),
             },
             _ => panic!("Unexpected Network Event"),
@@ -276,7 +291,7 @@ impl NodeSetup {
     pub async fn next_sync_info(&mut self) -> SyncInfo {
         match self.all_events.next().await.unwrap() {
             Event::Message(_, msg) => match msg {
-                ConsensusMsg::SyncInfo(s) => *s,
+                ConsensusMsg::SyncInfo(s) | ConsensusMsg::TwoChainSyncInfo(s) => *s,
                 msg => panic!("Unexpected Consensus Message: {:?}", msg),
             },
             _ => panic!("Unexpected Network Event"),
@@ -756,6 +771,116 @@ fn vote_resent_on_timeout() {
     });
 }
 
+#[test]
+/// In 2-chain mode the vote for a block certifying its direct parent commits the parent.
+fn two_chain_commit_on_quorum_cert() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut nodes =
+        NodeSetup::create_nodes_with_two_chain(&mut playground, runtime.handle().clone(), 1, true);
+    let node = &mut nodes[0];
+    timed_block_on(&mut runtime, async {
+        // round 1: certify b1
+        let proposal_msg = node.next_proposal().await;
+        let b1_id = proposal_msg.proposal().id();
+        node.round_manager
+            .process_proposal_msg(proposal_msg)
+            .await
+            .unwrap();
+        let vote_msg = node.next_vote().await;
+        node.round_manager.process_vote_msg(vote_msg).await.unwrap();
+
+        // round 2: the vote for b2 carries the commit of b1
+        let proposal_msg = node.next_proposal().await;
+        assert_eq!(proposal_msg.proposal().round(), 2);
+        node.round_manager
+            .process_proposal_msg(proposal_msg)
+            .await
+            .unwrap();
+        let vote_msg = node.next_vote().await;
+        assert_eq!(vote_msg.vote().ledger_info().commit_info().id(), b1_id);
+        node.round_manager.process_vote_msg(vote_msg).await.unwrap();
+
+        // the QC for b2 commits b1
+        let commit = node.commit_cb_receiver.next().await.unwrap();
+        assert_eq!(commit.ledger_info().commit_info().id(), b1_id);
+    });
+}
+
+#[test]
+/// In 2-chain mode the timeout votes carry signed 2-chain timeouts which are aggregated to a
+/// 2-chain TC, allowing the next round to start and its proposal to be voted for.
+fn new_round_on_2chain_timeout_certificate() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut nodes =
+        NodeSetup::create_nodes_with_two_chain(&mut playground, runtime.handle().clone(), 2, true);
+    runtime.spawn(playground.start());
+
+    timed_block_on(&mut runtime, async {
+        // only node 0 votes for the proposal of round 1, node 1 is going to vote for a NIL block
+        let proposal_msg = nodes[0].next_proposal().await;
+        nodes[1].next_proposal().await;
+        nodes[0]
+            .round_manager
+            .process_proposal_msg(proposal_msg)
+            .await
+            .unwrap();
+        nodes[0].next_vote().await;
+
+        // both nodes time out in round 1
+        for node in &mut nodes {
+            node.round_manager
+                .process_local_timeout(1)
+                .await
+                .unwrap_err();
+        }
+
+        // node 0 gathers the timeout votes for different blocks: no QC but a 2-chain TC
+        for _ in 0..2 {
+            let vote_msg = nodes[0].next_vote().await;
+            let (timeout, _) = vote_msg.vote().two_chain_timeout().unwrap();
+            assert_eq!(timeout.round(), 1);
+            assert_eq!(timeout.hqc_round(), 0);
+            assert!(vote_msg.vote().timeout_signature().is_none());
+            nodes[0]
+                .round_manager
+                .process_vote_msg(vote_msg)
+                .await
+                .unwrap();
+        }
+
+        // round 2 is started by the 2-chain TC
+        let proposal_msg = nodes[0].next_proposal().await;
+        assert_eq!(proposal_msg.proposal().round(), 2);
+        assert_eq!(
+            proposal_msg
+                .proposal()
+                .quorum_cert()
+                .certified_block()
+                .round(),
+            0
+        );
+        assert_eq!(
+            proposal_msg
+                .sync_info()
+                .highest_2chain_timeout_cert()
+                .unwrap()
+                .round(),
+            1
+        );
+
+        // the proposal extending the TC is safe to vote for
+        nodes[0]
+            .round_manager
+            .process_proposal_msg(proposal_msg)
+            .await
+            .unwrap();
+        let vote_msg = nodes[0].next_vote().await;
+        assert_eq!(vote_msg.vote().vote_data().proposed().round(), 2);
+    });
+}
+
 #[test]
 fn sync_info_sent_on_stale_sync_info() {
     let mut runtime = consensus_runtime();
@@ -9,7 +9,8 @@ use crate::{
 };
 use anyhow::Result;
 use consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_certificate::TimeoutCertificate, vote::Vote,
+    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate,
+    timeout_certificate::TimeoutCertificate, vote::Vote,
 };
 use dijets_crypto::HashValue;
 use dijets_infallible::Mutex;
@@ -29,10 +30,12 @@ pub struct MockSharedStorage {
     pub block: Mutex<HashMap<HashValue, Block>>,
     pub qc: Mutex<HashMap<HashValue, QuorumCert>>,
     pub lis: Mutex<HashMap<u64, LedgerInfoWithSignatures>>,
+    pub ordered_certs: Mutex<HashMap<HashValue, LedgerInfoWithSignatures>>,
     pub last_vote: Mutex<Option<Vote>>,
 
     // Liveness state
     pub highest_timeout_certificate: Mutex<Option<TimeoutCertificate>>,
+    pub highest_2chain_timeout_certificate: Mutex<Option<TwoChainTimeoutCertificate>>,
     pub validator_set: ValidatorSet,
 }
 
@@ -42,8 +45,10 @@ impl MockSharedStorage {
             block: Mutex::new(HashMap::new()),
             qc: Mutex::new(HashMap::new()),
             lis: Mutex::new(HashMap::new()),
+            ordered_certs: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
             highest_timeout_certificate: Mutex::new(None),
+            highest_2chain_timeout_certificate: Mutex::new(None),
             validator_set,
         }
     }
@@ -119,6 +124,14 @@ impl MockStorage {
             .into_iter()
             .map(|(_, v)| v)
             .collect();
+        let ordered_certs = self
+            .shared_storage
+            .ordered_certs
+            .lock()
+            .clone()
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect();
         blocks.sort_by_key(Block::round);
         RecoveryData::new(
             self.shared_storage.last_vote.lock().clone(),
@@ -130,6 +143,11 @@ impl MockStorage {
                 .highest_timeout_certificate
                 .lock()
                 .clone(),
+            self.shared_storage
+                .highest_2chain_timeout_certificate
+                .lock()
+                .clone(),
+            ordered_certs,
         )
     }
 
@@ -178,6 +196,7 @@ impl PersistentLivenessStorage for MockStorage {
         for id in block_id {
             self.shared_storage.block.lock().remove(&id);
             self.shared_storage.qc.lock().remove(&id);
+            self.shared_storage.ordered_certs.lock().remove(&id);
         }
         if let Err(e) = self.verify_consistency() {
             panic!("invalid db after prune tree: {}", e);
@@ -193,6 +212,14 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_ordered_cert(&self, ordered_cert: LedgerInfoWithSignatures) -> Result<()> {
+        self.shared_storage.ordered_certs.lock().insert(
+            ordered_cert.ledger_info().consensus_block_id(),
+            ordered_cert,
+        );
+        Ok(())
+    }
+
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
         self.get_ledger_recovery_data()
     }
@@ -215,6 +242,17 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_highest_2chain_timeout_cert(
+        &self,
+        highest_2chain_timeout_certificate: TwoChainTimeoutCertificate,
+    ) -> Result<()> {
+        self.shared_storage
+            .highest_2chain_timeout_certificate
+            .lock()
+            .replace(highest_2chain_timeout_certificate);
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let lis = self
             .shared_storage
@@ -261,6 +299,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_ordered_cert(&self, _: LedgerInfoWithSignatures) -> Result<()> {
+        Ok(())
+    }
+
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
         LedgerRecoveryData::new(LedgerInfoWithSignatures::new(
             LedgerInfo::mock_genesis(None),
@@ -276,6 +318,8 @@ impl PersistentLivenessStorage for EmptyStorage {
             RootMetadata::new_empty(),
             vec![],
             None,
+            None,
+            vec![],
         ) {
             Ok(recovery_data) => LivenessStorageData::RecoveryData(recovery_data),
             Err(e) => {
@@ -289,6 +333,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_highest_2chain_timeout_cert(&self, _: TwoChainTimeoutCertificate) -> Result<()> {
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
         unimplemented!()
     }
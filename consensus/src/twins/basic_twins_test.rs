@@ -33,6 +33,7 @@ fn basic_start_test() {
         &mut playground,
         RotatingProposer,
         None,
+        false,
     );
     let genesis = Block::make_genesis_block_from_ledger_info(&nodes[0].storage.get_ledger_info());
     timed_block_on(&mut runtime, async {
@@ -85,6 +86,7 @@ fn drop_config_test() {
         &mut playground,
         FixedProposer,
         None,
+        false,
     );
 
     // 4 honest nodes
@@ -141,6 +143,7 @@ fn twins_vote_dedup_test() {
         &mut playground,
         RotatingProposer,
         None,
+        false,
     );
 
     // 4 honest nodes
@@ -208,6 +211,7 @@ fn twins_proposer_test() {
         &mut playground,
         RoundProposer(HashMap::new()),
         Some(round_proposers),
+        false,
     );
 
     // 4 honest nodes
@@ -291,6 +295,7 @@ fn twins_commit_test() {
         &mut playground,
         RoundProposer(HashMap::new()),
         Some(round_proposers),
+        false,
     );
     runtime.spawn(playground.start());
 
@@ -310,3 +315,61 @@ fn twins_commit_test() {
         }
     });
 }
+
+#[test]
+/// This test checks that with the 2-chain protocol a block is committed as soon as its direct
+/// child gets certified.
+///
+/// Setup:
+///
+/// 4 honest nodes, and 0 twins, all running the 2-chain protocol
+///
+/// Test:
+///
+/// The proposal of round 3 carries the QC of the round 2 block, whose ledger info commits
+/// the round 1 block. Check that every node commits the round 1 block first.
+///
+/// Run the test:
+/// cargo xtest -p consensus two_chain_commit_test -- --nocapture
+fn two_chain_commit_test() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let num_nodes = 4;
+    let num_twins = 0;
+    let mut nodes = SMRNode::start_num_nodes_with_twins(
+        num_nodes,
+        num_twins,
+        &mut playground,
+        RotatingProposer,
+        None,
+        true,
+    );
+
+    timed_block_on(&mut runtime, async {
+        let mut round_3_proposal = None;
+        while round_3_proposal.is_none() {
+            let msg = playground
+                .wait_for_messages(1, NetworkPlayground::proposals_only)
+                .await;
+            match &msg[0].1 {
+                ConsensusMsg::TwoChainProposalMsg(proposal) if proposal.proposal().round() == 3 => {
+                    round_3_proposal = Some(proposal.clone());
+                }
+                ConsensusMsg::TwoChainProposalMsg(_) => (),
+                _ => panic!("Unexpected message found"),
+            }
+        }
+        let round_3_proposal = round_3_proposal.unwrap();
+        let quorum_cert = round_3_proposal.proposal().quorum_cert();
+        assert_eq!(quorum_cert.certified_block().round(), 2);
+        assert_eq!(quorum_cert.commit_info().round(), 1);
+
+        for node in &mut nodes {
+            let node_commit = node.commit_cb_receiver.next().await.unwrap();
+            assert_eq!(
+                node_commit.ledger_info().commit_info(),
+                quorum_cert.commit_info()
+            );
+        }
+    });
+}
@@ -140,6 +140,7 @@ impl SMRNode {
         playground: &mut NetworkPlayground,
         proposer_type: ConsensusProposerType,
         round_proposers_idx: Option<HashMap<Round, usize>>,
+        two_chain: bool,
     ) -> Vec<Self> {
         assert!(num_nodes >= num_twins);
         let ValidatorSwarm {
@@ -213,6 +214,7 @@ impl SMRNode {
                 .waypoint = Some(waypoint);
             config.base.waypoint = WaypointConfig::FromConfig(waypoint);
             config.consensus.proposer_type = proposer_type.clone();
+            config.consensus.two_chain = two_chain;
             config.consensus.safety_rules.verify_vote_proposal_signature = false;
             // Disable timeout in twins test to avoid flakiness
             config.consensus.round_initial_timeout_ms = 2_000_000;
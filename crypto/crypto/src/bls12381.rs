@@ -0,0 +1,550 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the BLS signature scheme over the BLS12-381 curve, as defined
+//! in the [BLS signatures draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04).
+//!
+//! We use the "minimal public key size" variant: public keys are 48 bytes compressed points of
+//! G1 and signatures are 96 bytes compressed points of G2, along with the proof-of-possession
+//! scheme to defend against rogue key attacks. Signatures on the same message can be aggregated
+//! into a single signature, which verifies against the aggregate of the public keys of the
+//! signers. Aggregation is only safe for public keys whose proof of possession was verified.
+//!
+//! # Examples
+//!
+//! ```
+//! use dijets_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use dijets_crypto::{
+//!     bls12381::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+//! let private_keys: Vec<_> = (0..3).map(|_| BLS12381PrivateKey::generate(&mut rng)).collect();
+//! let public_keys: Vec<BLS12381PublicKey> = private_keys.iter().map(|key| key.into()).collect();
+//!
+//! // Check the proofs of possession of the public keys before aggregating their signatures
+//! for (private_key, public_key) in private_keys.iter().zip(&public_keys) {
+//!     let pop = BLS12381ProofOfPossession::create(private_key);
+//!     assert!(pop.verify(public_key).is_ok());
+//! }
+//!
+//! let signatures: Vec<_> = private_keys.iter().map(|key| key.sign(&message)).collect();
+//! let aggregate = BLS12381Signature::aggregate(&signatures.iter().collect::<Vec<_>>()).unwrap();
+//! assert!(aggregate
+//!     .verify_aggregate(&message, &public_keys.iter().collect::<Vec<_>>())
+//!     .is_ok());
+//! ```
+//! **Note**: The above example generates a private key using a private function intended only for
+//! testing purposes. Production code should find an alternate means for secure key generation.
+
+use crate::{
+    hash::{CryptoHash, CryptoHasher},
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use blst::BLST_ERROR;
+use core::convert::TryFrom;
+use dijets_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use serde::Serialize;
+use std::fmt;
+
+pub use blst;
+
+/// The length of the BLS12381PrivateKey
+pub const BLS12381_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the BLS12381PublicKey, a compressed point of G1
+pub const BLS12381_PUBLIC_KEY_LENGTH: usize = 48;
+/// The length of the BLS12381Signature, a compressed point of G2
+pub const BLS12381_SIGNATURE_LENGTH: usize = 96;
+
+/// The domain separation tag of signatures, as defined in the proof-of-possession ciphersuite of
+/// the BLS signatures draft.
+const DST_BLS_SIG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// The domain separation tag of proofs of possession.
+const DST_BLS_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS12-381 private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct BLS12381PrivateKey(blst::min_pk::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(BLS12381PrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for BLS12381PrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        BLS12381PrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A BLS12-381 public key
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct BLS12381PublicKey(blst::min_pk::PublicKey);
+
+/// A BLS12-381 signature, or an aggregate of signatures on the same message
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct BLS12381Signature(blst::min_pk::Signature);
+
+/// A proof of possession of the private key of a BLS12-381 public key, i.e. a signature of the
+/// public key under a dedicated domain separation tag. It must be verified before the public key
+/// is aggregated with others, otherwise a rogue key could cancel out the others.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct BLS12381ProofOfPossession(blst::min_pk::Signature);
+
+/// The bytes a struct is signed as: its domain separation seed followed by its BCS serialization.
+fn signing_message<T: CryptoHash + Serialize>(
+    message: &T,
+) -> std::result::Result<Vec<u8>, CryptoMaterialError> {
+    let mut bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
+    bcs::serialize_into(&mut bytes, &message)
+        .map_err(|_| CryptoMaterialError::SerializationError)?;
+    Ok(bytes)
+}
+
+fn check_blst_result(result: BLST_ERROR, error: &str) -> Result<()> {
+    if result == BLST_ERROR::BLST_SUCCESS {
+        Ok(())
+    } else {
+        Err(anyhow!("{}: {:?}", error, result))
+    }
+}
+
+impl BLS12381PrivateKey {
+    /// The length of the BLS12381PrivateKey
+    pub const LENGTH: usize = BLS12381_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a BLS12381PrivateKey.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PRIVATE_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> BLS12381Signature {
+        BLS12381Signature(self.0.sign(message, DST_BLS_SIG, &[]))
+    }
+}
+
+impl BLS12381PublicKey {
+    /// Serialize a BLS12381PublicKey, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PUBLIC_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Aggregates public keys into a single public key, which verifies the aggregate of their
+    /// signatures on a message. The proofs of possession of the public keys must have been
+    /// verified beforehand.
+    pub fn aggregate(public_keys: &[&BLS12381PublicKey]) -> Result<BLS12381PublicKey> {
+        let public_keys: Vec<_> = public_keys.iter().map(|key| &key.0).collect();
+        blst::min_pk::AggregatePublicKey::aggregate(&public_keys, false)
+            .map(|aggregate| BLS12381PublicKey(aggregate.to_public_key()))
+            .map_err(|e| anyhow!("Unable to aggregate public keys: {:?}", e))
+    }
+}
+
+impl BLS12381Signature {
+    /// The length of the BLS12381Signature
+    pub const LENGTH: usize = BLS12381_SIGNATURE_LENGTH;
+
+    /// Serialize a BLS12381Signature, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Aggregates signatures on the same message into a single signature.
+    pub fn aggregate(signatures: &[&BLS12381Signature]) -> Result<BLS12381Signature> {
+        let signatures: Vec<_> = signatures.iter().map(|signature| &signature.0).collect();
+        blst::min_pk::AggregateSignature::aggregate(&signatures, false)
+            .map(|aggregate| BLS12381Signature(aggregate.to_signature()))
+            .map_err(|e| anyhow!("Unable to aggregate signatures: {:?}", e))
+    }
+
+    /// Verifies an aggregate signature on `message` against the public keys of its signers. The
+    /// proofs of possession of the public keys must have been verified beforehand.
+    pub fn verify_aggregate<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_keys: &[&BLS12381PublicKey],
+    ) -> Result<()> {
+        let bytes = signing_message(message)?;
+        self.verify_aggregate_arbitrary_msg(&bytes, public_keys)
+    }
+
+    /// Verifies an aggregate signature on an arbitrary &[u8] `message` against the public keys of
+    /// its signers.
+    pub fn verify_aggregate_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_keys: &[&BLS12381PublicKey],
+    ) -> Result<()> {
+        if public_keys.is_empty() {
+            return Err(anyhow!("BLS12-381 aggregate signature without signers"));
+        }
+        let public_keys: Vec<_> = public_keys.iter().map(|key| &key.0).collect();
+        // The signature and the public keys were validated during deserialization.
+        let result = self
+            .0
+            .fast_aggregate_verify(false, message, DST_BLS_SIG, &public_keys);
+        check_blst_result(result, "BLS12-381 aggregate signature verification failed")
+    }
+
+    /// return a signature of an empty message under a fixed key (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        BLS12381PrivateKey::genesis().sign_arbitrary_message(&[])
+    }
+}
+
+impl BLS12381ProofOfPossession {
+    /// Creates the proof of possession of `private_key`.
+    pub fn create(private_key: &BLS12381PrivateKey) -> BLS12381ProofOfPossession {
+        let public_key = BLS12381PublicKey::from(private_key);
+        BLS12381ProofOfPossession(private_key.0.sign(&public_key.to_bytes(), DST_BLS_POP, &[]))
+    }
+
+    /// Verifies that this is the proof of possession of the private key of `public_key`.
+    pub fn verify(&self, public_key: &BLS12381PublicKey) -> Result<()> {
+        let result = self.0.verify(
+            false,
+            &public_key.to_bytes(),
+            DST_BLS_POP,
+            &[],
+            &public_key.0,
+            false,
+        );
+        check_blst_result(result, "BLS12-381 proof of possession verification failed")
+    }
+
+    /// Serialize a BLS12381ProofOfPossession, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for BLS12381PrivateKey {
+    type PublicKeyMaterial = BLS12381PublicKey;
+}
+
+impl SigningKey for BLS12381PrivateKey {
+    type VerifyingKeyMaterial = BLS12381PublicKey;
+    type SignatureMaterial = BLS12381Signature;
+
+    fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> BLS12381Signature {
+        let bytes =
+            signing_message(message).expect("Serialization of signable material should not fail.");
+        BLS12381PrivateKey::sign_arbitrary_message(self, bytes.as_ref())
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> BLS12381Signature {
+        BLS12381PrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for BLS12381PrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        // The key generation of the BLS signatures draft needs at least 32 bytes of input
+        // keying material.
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        BLS12381PrivateKey(
+            blst::min_pk::SecretKey::key_gen(&ikm, &[])
+                .expect("Key generation from 32 bytes should not fail."),
+        )
+    }
+}
+
+impl PartialEq<Self> for BLS12381PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for BLS12381PrivateKey {}
+
+impl TryFrom<&[u8]> for BLS12381PrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a BLS12381PrivateKey. This method will also check for key validity, i.e.
+    /// that the key is a non-zero scalar lower than the order of the curve.
+    fn try_from(bytes: &[u8]) -> std::result::Result<BLS12381PrivateKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::SecretKey::from_bytes(bytes)
+            .map(BLS12381PrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for BLS12381PrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for BLS12381PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for BLS12381PrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; BLS12381_PRIVATE_KEY_LENGTH];
+        buf[BLS12381_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+// Implementing From<&PrivateKey<...>> allows to derive a public key in a more elegant fashion
+impl From<&BLS12381PrivateKey> for BLS12381PublicKey {
+    fn from(private_key: &BLS12381PrivateKey) -> Self {
+        BLS12381PublicKey(private_key.0.sk_to_pk())
+    }
+}
+
+// We deduce PublicKey from this
+impl PublicKey for BLS12381PublicKey {
+    type PrivateKeyMaterial = BLS12381PrivateKey;
+}
+
+impl std::hash::Hash for BLS12381PublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for BLS12381PublicKey {
+    fn eq(&self, other: &BLS12381PublicKey) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for BLS12381PublicKey {}
+
+// We deduce VerifyingKey from pointing to the signature material
+// we get the ability to do `pubkey.validate(msg, signature)`
+impl VerifyingKey for BLS12381PublicKey {
+    type SigningKeyMaterial = BLS12381PrivateKey;
+    type SignatureMaterial = BLS12381Signature;
+}
+
+impl fmt::Display for BLS12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for BLS12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BLS12381PublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for BLS12381PublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a BLS12381PublicKey from its compressed form. This method will also check for
+    /// key validity, i.e. that the point is in the prime order subgroup of G1 and isn't the
+    /// identity.
+    fn try_from(bytes: &[u8]) -> std::result::Result<BLS12381PublicKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let public_key = blst::min_pk::PublicKey::from_bytes(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        public_key
+            .validate()
+            .map_err(|_| CryptoMaterialError::SmallSubgroupError)?;
+        Ok(BLS12381PublicKey(public_key))
+    }
+}
+
+impl Length for BLS12381PublicKey {
+    fn length(&self) -> usize {
+        BLS12381_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for BLS12381PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for BLS12381Signature {
+    type VerifyingKeyMaterial = BLS12381PublicKey;
+    type SigningKeyMaterial = BLS12381PrivateKey;
+
+    /// Verifies that the provided signature is valid for the provided message, going beyond the
+    /// [BLS signatures draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04) by
+    /// signing the domain separated serialization of the message.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &BLS12381PublicKey,
+    ) -> Result<()> {
+        let bytes = signing_message(message)?;
+        Self::verify_arbitrary_msg(self, &bytes, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
+    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &BLS12381PublicKey) -> Result<()> {
+        // The signature and the public key were validated during deserialization.
+        let result = self
+            .0
+            .verify(false, message, DST_BLS_SIG, &[], &public_key.0, false);
+        check_blst_result(result, "BLS12-381 signature verification failed")
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Length for BLS12381Signature {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for BLS12381Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for BLS12381Signature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for BLS12381Signature {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a BLS12381Signature from its compressed form. This method will also check
+    /// that the point is in the prime order subgroup of G2 and isn't the identity.
+    fn try_from(bytes: &[u8]) -> std::result::Result<BLS12381Signature, CryptoMaterialError> {
+        if bytes.len() != BLS12381_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::Signature::sig_validate(bytes, true)
+            .map(BLS12381Signature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for BLS12381Signature {
+    fn eq(&self, other: &BLS12381Signature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for BLS12381Signature {}
+
+impl fmt::Display for BLS12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for BLS12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BLS12381Signature({})", self)
+    }
+}
+
+impl Length for BLS12381ProofOfPossession {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for BLS12381ProofOfPossession {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for BLS12381ProofOfPossession {
+    type Error = CryptoMaterialError;
+
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<BLS12381ProofOfPossession, CryptoMaterialError> {
+        BLS12381Signature::try_from(bytes).map(|signature| BLS12381ProofOfPossession(signature.0))
+    }
+}
+
+impl PartialEq for BLS12381ProofOfPossession {
+    fn eq(&self, other: &BLS12381ProofOfPossession) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for BLS12381ProofOfPossession {}
+
+impl fmt::Display for BLS12381ProofOfPossession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for BLS12381ProofOfPossession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BLS12381ProofOfPossession({})", self)
+    }
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+
+/// Produces a uniformly random BLS12-381 keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy() -> impl Strategy<Value = KeyPair<BLS12381PrivateKey, BLS12381PublicKey>> {
+    test_utils::uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for BLS12381PublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        crate::test_utils::uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+            .prop_map(|v| v.public_key)
+            .boxed()
+    }
+}
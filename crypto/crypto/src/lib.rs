@@ -7,6 +7,7 @@
 #![cfg_attr(mirai, allow(incomplete_features), feature(const_generics))]
 
 //! A library supplying various cryptographic primitives
+pub mod bls12381;
 pub mod compat;
 pub mod ed25519;
 pub mod error;
@@ -14,6 +15,7 @@ pub mod hash;
 pub mod hkdf;
 pub mod multi_ed25519;
 pub mod noise;
+pub mod secp256k1;
 pub mod test_utils;
 pub mod traits;
 pub mod validatable;
@@ -712,6 +712,40 @@ impl NoiseSession {
         // return a subslice of the buffer representing the decrypted plaintext
         Ok(buffer)
     }
+
+    /// replaces the key used to encrypt messages to the other peer (`REKEY()` in the noise
+    /// specification). The other peer must call `rekey_read` before decrypting the next message.
+    pub fn rekey_write(&mut self) -> Result<(), NoiseError> {
+        if !self.valid {
+            return Err(NoiseError::SessionClosed);
+        }
+        self.write_key = rekey(&self.write_key)?;
+        Ok(())
+    }
+
+    /// replaces the key used to decrypt messages from the other peer, following a call to
+    /// `rekey_write` on the other peer's session.
+    pub fn rekey_read(&mut self) -> Result<(), NoiseError> {
+        if !self.valid {
+            return Err(NoiseError::SessionClosed);
+        }
+        self.read_key = rekey(&self.read_key)?;
+        Ok(())
+    }
+}
+
+/// `REKEY(k)` from the noise specification: the new key is the first 32 bytes of the encryption
+/// of 32 zero bytes with the maximum nonce (which is never used to encrypt a message).
+fn rekey(key: &[u8]) -> Result<Vec<u8>, NoiseError> {
+    let aead = Aes256Gcm::new(GenericArray::from_slice(key));
+    let mut nonce = [0u8; 4].to_vec();
+    nonce.extend_from_slice(&u64::MAX.to_be_bytes());
+    let nonce = GenericArray::from_slice(&nonce);
+
+    let mut new_key = vec![0u8; 32];
+    aead.encrypt_in_place_detached(nonce, b"", &mut new_key)
+        .map_err(|_| NoiseError::Encrypt)?;
+    Ok(new_key)
 }
 
 impl std::fmt::Debug for NoiseSession {
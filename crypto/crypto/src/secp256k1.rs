@@ -0,0 +1,610 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the ECDSA signature scheme over the secp256k1 curve as
+//! defined in [SEC 1](https://www.secg.org/sec1-v2.pdf) and
+//! [SEC 2](https://www.secg.org/sec2-v2.pdf).
+//!
+//! Messages are signed through their SHA3-256 digest: a struct is signed through its
+//! [`CryptoHash`], and an arbitrary message through the SHA3-256 of its bytes, so that both agree
+//! on the domain-separated serialization of a struct.
+//!
+//! Signatures are normalized to their low-S form when signing, and signature verification
+//! rejects high-S signatures, so that a third party cannot produce another valid signature from
+//! a given one. Public keys are serialized in their 33 bytes compressed form.
+//!
+//! # Examples
+//!
+//! ```
+//! use dijets_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use dijets_crypto::{
+//!     secp256k1::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+//! let private_key = Secp256k1PrivateKey::generate(&mut rng);
+//! let public_key: Secp256k1PublicKey = (&private_key).into();
+//! let signature = private_key.sign(&message);
+//! assert!(signature.verify(&message, &public_key).is_ok());
+//!
+//! let recoverable_signature = private_key.sign_recoverable(&message);
+//! assert_eq!(recoverable_signature.recover(&message).unwrap(), public_key);
+//! ```
+//! **Note**: The above example generates a private key using a private function intended only for
+//! testing purposes. Production code should find an alternate means for secure key generation.
+
+use crate::{
+    hash::{CryptoHash, HashValue},
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use core::convert::TryFrom;
+use dijets_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use mirai_annotations::*;
+use serde::Serialize;
+use std::{cmp::Ordering, fmt};
+
+pub use libsecp256k1;
+
+/// The length of the Secp256k1PrivateKey
+pub const SECP256K1_PRIVATE_KEY_LENGTH: usize = libsecp256k1::util::SECRET_KEY_SIZE;
+/// The length of the Secp256k1PublicKey, in its compressed form
+pub const SECP256K1_PUBLIC_KEY_LENGTH: usize = libsecp256k1::util::COMPRESSED_PUBLIC_KEY_SIZE;
+/// The length of the Secp256k1Signature
+pub const SECP256K1_SIGNATURE_LENGTH: usize = libsecp256k1::util::SIGNATURE_SIZE;
+/// The length of the Secp256k1RecoverableSignature
+pub const SECP256K1_RECOVERABLE_SIGNATURE_LENGTH: usize = SECP256K1_SIGNATURE_LENGTH + 1;
+
+/// Half of the order of secp256k1, rounded down, as defined in
+/// [SEC 2](https://www.secg.org/sec2-v2.pdf). The S component of a low-S signature is at most
+/// this value.
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// A Secp256k1 private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Secp256k1PrivateKey(libsecp256k1::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Secp256k1PrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Secp256k1PrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        Secp256k1PrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A Secp256k1 public key
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1PublicKey(libsecp256k1::PublicKey);
+
+#[cfg(mirai)]
+use crate::tags::ValidatedPublicKeyTag;
+#[cfg(not(mirai))]
+struct ValidatedPublicKeyTag {}
+
+/// A Secp256k1 ECDSA signature, in its low-S form
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1Signature(libsecp256k1::Signature);
+
+/// A Secp256k1 ECDSA signature along with the recovery ID of the public key which made it
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1RecoverableSignature(Secp256k1Signature, libsecp256k1::RecoveryId);
+
+impl Secp256k1PrivateKey {
+    /// The length of the Secp256k1PrivateKey
+    pub const LENGTH: usize = SECP256K1_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Secp256k1PrivateKey.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_PRIVATE_KEY_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Signs an object, returning a signature which the public key can be recovered from.
+    pub fn sign_recoverable<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Secp256k1RecoverableSignature {
+        self.sign_digest(&message.hash())
+    }
+
+    /// Signs the SHA3-256 of a non-hash input message, returning a signature which the public
+    /// key can be recovered from. For testing only.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn sign_recoverable_arbitrary_message(
+        &self,
+        message: &[u8],
+    ) -> Secp256k1RecoverableSignature {
+        self.sign_digest(&HashValue::sha3_256_of(message))
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_digest(&self, digest: &HashValue) -> Secp256k1RecoverableSignature {
+        let message = libsecp256k1::Message::parse(digest.as_ref());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &self.0);
+        // libsecp256k1 already produces low-S signatures, this is only a safety net.
+        let mut signature = Secp256k1Signature(signature);
+        let recovery_id = if signature.normalize_s() {
+            libsecp256k1::RecoveryId::parse(recovery_id.serialize() ^ 1)
+                .expect("Flipping the parity bit of a recovery id should not fail.")
+        } else {
+            recovery_id
+        };
+        Secp256k1RecoverableSignature(signature, recovery_id)
+    }
+}
+
+impl Secp256k1PublicKey {
+    /// Serialize a Secp256k1PublicKey, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_PUBLIC_KEY_LENGTH] {
+        self.0.serialize_compressed()
+    }
+
+    /// Deserialize a Secp256k1PublicKey from its 65 bytes uncompressed form, as output by most
+    /// HSMs.
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
+        if bytes.len() != libsecp256k1::util::FULL_PUBLIC_KEY_SIZE {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::PublicKey::parse_slice(bytes, Some(libsecp256k1::PublicKeyFormat::Full))
+            .map(Secp256k1PublicKey)
+            .map_err(|_| CryptoMaterialError::PointNotOnCurveError)
+    }
+
+    /// Serialize a Secp256k1PublicKey in its 65 bytes uncompressed form.
+    pub fn to_uncompressed_bytes(&self) -> [u8; libsecp256k1::util::FULL_PUBLIC_KEY_SIZE] {
+        self.0.serialize()
+    }
+}
+
+impl Secp256k1Signature {
+    /// The length of the Secp256k1Signature
+    pub const LENGTH: usize = SECP256K1_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256k1Signature, as the concatenation of its big-endian R and S components.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_SIGNATURE_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Deserialize a Secp256k1Signature without any validation checks (malleability)
+    /// apart from expected size and R and S being lower than the order of the curve.
+    pub(crate) fn from_bytes_unchecked(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1Signature, CryptoMaterialError> {
+        let bytes = <&[u8; SECP256K1_SIGNATURE_LENGTH]>::try_from(bytes)
+            .map_err(|_| CryptoMaterialError::WrongLengthError)?;
+        libsecp256k1::Signature::parse_standard(bytes)
+            .map(Secp256k1Signature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+
+    /// Deserialize a Secp256k1Signature, normalizing it to its low-S form. This is meant to
+    /// ingest the signatures of signers which don't normalize them, such as some HSMs.
+    pub fn from_bytes_normalized(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
+        let mut signature = Self::from_bytes_unchecked(bytes)?;
+        signature.normalize_s();
+        Ok(signature)
+    }
+
+    /// return an all-zero signature (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Self::from_bytes_unchecked(&[0u8; Self::LENGTH]).unwrap()
+    }
+
+    /// Normalizes the signature to its low-S form, returning whether it was a high-S one.
+    fn normalize_s(&mut self) -> bool {
+        if check_s_low(&self.to_bytes()[32..]) {
+            return false;
+        }
+        self.0.normalize_s();
+        true
+    }
+
+    /// Check for correct size and third-party based signature malleability issues.
+    /// This method is required to ensure that given a valid signature for some message under some
+    /// key, an attacker cannot produce another valid signature for the same message and key.
+    ///
+    /// An ECDSA signature (R, S) is equally valid as (R, -S), so we only accept signatures with
+    /// an S component lower than or equal to half the order of the curve, as
+    /// [BIP 62](https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki) does.
+    pub fn check_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        if bytes.len() != SECP256K1_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        if !check_s_low(&bytes[32..]) {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+impl Secp256k1RecoverableSignature {
+    /// Returns the signature, without the recovery ID.
+    pub fn signature(&self) -> &Secp256k1Signature {
+        &self.0
+    }
+
+    /// Serialize a Secp256k1RecoverableSignature, as its signature followed by its recovery ID.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_RECOVERABLE_SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SECP256K1_RECOVERABLE_SIGNATURE_LENGTH];
+        bytes[..SECP256K1_SIGNATURE_LENGTH].copy_from_slice(&self.0.to_bytes());
+        bytes[SECP256K1_SIGNATURE_LENGTH] = self.1.serialize();
+        bytes
+    }
+
+    /// Recovers the public key which signed `message`.
+    pub fn recover<T: CryptoHash + Serialize>(&self, message: &T) -> Result<Secp256k1PublicKey> {
+        self.recover_digest(&message.hash())
+    }
+
+    /// Recovers the public key which signed the SHA3-256 of an arbitrary `message`.
+    pub fn recover_arbitrary_msg(&self, message: &[u8]) -> Result<Secp256k1PublicKey> {
+        self.recover_digest(&HashValue::sha3_256_of(message))
+    }
+
+    fn recover_digest(&self, digest: &HashValue) -> Result<Secp256k1PublicKey> {
+        let message = libsecp256k1::Message::parse(digest.as_ref());
+        libsecp256k1::recover(&message, &(self.0).0, &self.1)
+            .map(Secp256k1PublicKey)
+            .map_err(|e| anyhow!("Unable to recover public key: {:?}", e))
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Secp256k1PrivateKey {
+    type PublicKeyMaterial = Secp256k1PublicKey;
+}
+
+impl SigningKey for Secp256k1PrivateKey {
+    type VerifyingKeyMaterial = Secp256k1PublicKey;
+    type SignatureMaterial = Secp256k1Signature;
+
+    fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> Secp256k1Signature {
+        self.sign_recoverable(message).0
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256k1Signature {
+        self.sign_recoverable_arbitrary_message(message).0
+    }
+}
+
+impl Uniform for Secp256k1PrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        // Rejection sampling, the odds of sampling an invalid scalar are negligible
+        loop {
+            let mut bytes = [0u8; SECP256K1_PRIVATE_KEY_LENGTH];
+            rng.fill_bytes(&mut bytes);
+            if let Ok(secret_key) = libsecp256k1::SecretKey::parse(&bytes) {
+                return Secp256k1PrivateKey(secret_key);
+            }
+        }
+    }
+}
+
+impl PartialEq<Self> for Secp256k1PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256k1PrivateKey {}
+
+impl TryFrom<&[u8]> for Secp256k1PrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1PrivateKey. This method will also check for key validity, i.e.
+    /// that the key is a non-zero scalar lower than the order of the curve.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1PrivateKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::SecretKey::parse_slice(bytes)
+            .map(Secp256k1PrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256k1PrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Secp256k1PrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; SECP256K1_PRIVATE_KEY_LENGTH];
+        buf[SECP256K1_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+// Implementing From<&PrivateKey<...>> allows to derive a public key in a more elegant fashion
+impl From<&Secp256k1PrivateKey> for Secp256k1PublicKey {
+    fn from(private_key: &Secp256k1PrivateKey) -> Self {
+        Secp256k1PublicKey(libsecp256k1::PublicKey::from_secret_key(&private_key.0))
+    }
+}
+
+// We deduce PublicKey from this
+impl PublicKey for Secp256k1PublicKey {
+    type PrivateKeyMaterial = Secp256k1PrivateKey;
+}
+
+impl std::hash::Hash for Secp256k1PublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256k1PublicKey {
+    fn eq(&self, other: &Secp256k1PublicKey) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1PublicKey {}
+
+// We deduce VerifyingKey from pointing to the signature material
+// we get the ability to do `pubkey.validate(msg, signature)`
+impl VerifyingKey for Secp256k1PublicKey {
+    type SigningKeyMaterial = Secp256k1PrivateKey;
+    type SignatureMaterial = Secp256k1Signature;
+}
+
+impl fmt::Display for Secp256k1PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1PublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1PublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1PublicKey from its compressed form. This method will also check
+    /// for key validity, i.e. that the point lies on the curve. As secp256k1 has a cofactor of 1,
+    /// there are no small subgroup points to reject.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1PublicKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let public_key = libsecp256k1::PublicKey::parse_slice(
+            bytes,
+            Some(libsecp256k1::PublicKeyFormat::Compressed),
+        )
+        .map(Secp256k1PublicKey)
+        .map_err(|_| CryptoMaterialError::PointNotOnCurveError)?;
+        add_tag!(&public_key, ValidatedPublicKeyTag); // This key has gone through validity checks.
+        Ok(public_key)
+    }
+}
+
+impl Length for Secp256k1PublicKey {
+    fn length(&self) -> usize {
+        SECP256K1_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256k1Signature {
+    type VerifyingKeyMaterial = Secp256k1PublicKey;
+    type SigningKeyMaterial = Secp256k1PrivateKey;
+
+    /// Verifies that the provided signature is valid for the `CryptoHash` of the provided
+    /// message.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256k1PublicKey,
+    ) -> Result<()> {
+        // Public keys should be validated to be on the curve.
+        precondition!(has_tag!(public_key, ValidatedPublicKeyTag));
+        self.verify_digest(&message.hash(), public_key)
+    }
+
+    /// Checks that `self` is valid for the SHA3-256 of an arbitrary &[u8] `message` using
+    /// `public_key`.
+    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Secp256k1PublicKey) -> Result<()> {
+        // Public keys should be validated to be on the curve.
+        precondition!(has_tag!(public_key, ValidatedPublicKeyTag));
+        self.verify_digest(&HashValue::sha3_256_of(message), public_key)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Secp256k1Signature {
+    fn verify_digest(&self, digest: &HashValue, public_key: &Secp256k1PublicKey) -> Result<()> {
+        Secp256k1Signature::check_malleability(&self.to_bytes())?;
+
+        let message = libsecp256k1::Message::parse(digest.as_ref());
+        if libsecp256k1::verify(&message, &self.0, &public_key.0) {
+            Ok(())
+        } else {
+            Err(anyhow!("Secp256k1 signature verification failed"))
+        }
+    }
+}
+
+impl Length for Secp256k1Signature {
+    fn length(&self) -> usize {
+        SECP256K1_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256k1Signature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1Signature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1Signature, CryptoMaterialError> {
+        Secp256k1Signature::check_malleability(bytes)?;
+        Secp256k1Signature::from_bytes_unchecked(bytes)
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256k1Signature {
+    fn eq(&self, other: &Secp256k1Signature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1Signature {}
+
+impl fmt::Display for Secp256k1Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1Signature({})", self)
+    }
+}
+
+impl Length for Secp256k1RecoverableSignature {
+    fn length(&self) -> usize {
+        SECP256K1_RECOVERABLE_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1RecoverableSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1RecoverableSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1RecoverableSignature, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_RECOVERABLE_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let signature = Secp256k1Signature::try_from(&bytes[..SECP256K1_SIGNATURE_LENGTH])?;
+        let recovery_id = libsecp256k1::RecoveryId::parse(bytes[SECP256K1_SIGNATURE_LENGTH])
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        Ok(Secp256k1RecoverableSignature(signature, recovery_id))
+    }
+}
+
+impl PartialEq for Secp256k1RecoverableSignature {
+    fn eq(&self, other: &Secp256k1RecoverableSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1RecoverableSignature {}
+
+impl fmt::Display for Secp256k1RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1RecoverableSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1RecoverableSignature({})", self)
+    }
+}
+
+/// Check if S <= n / 2, with `s` in big-endian.
+fn check_s_low(s: &[u8]) -> bool {
+    for i in 0..32 {
+        match s[i].cmp(&HALF_ORDER[i]) {
+            Ordering::Less => return true,
+            Ordering::Greater => return false,
+            _ => {}
+        }
+    }
+    // At this stage S == n / 2, which is still a low S.
+    true
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+
+/// Produces a uniformly random secp256k1 keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy() -> impl Strategy<Value = KeyPair<Secp256k1PrivateKey, Secp256k1PublicKey>>
+{
+    test_utils::uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Secp256k1PublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        crate::test_utils::uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+            .prop_map(|v| v.public_key)
+            .boxed()
+    }
+}
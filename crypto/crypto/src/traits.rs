@@ -289,7 +289,11 @@ pub trait Genesis: PrivateKey {
 pub(crate) mod private {
     pub trait Sealed {}
 
-    // Implement for the ed25519, multi-ed25519 signatures
+    // Implement for the bls12381, ed25519, multi-ed25519, secp256k1 signatures
+    impl Sealed for crate::bls12381::BLS12381PrivateKey {}
+    impl Sealed for crate::bls12381::BLS12381PublicKey {}
+    impl Sealed for crate::bls12381::BLS12381Signature {}
+
     impl Sealed for crate::ed25519::Ed25519PrivateKey {}
     impl Sealed for crate::ed25519::Ed25519PublicKey {}
     impl Sealed for crate::ed25519::Ed25519Signature {}
@@ -297,4 +301,8 @@ pub(crate) mod private {
     impl Sealed for crate::multi_ed25519::MultiEd25519PrivateKey {}
     impl Sealed for crate::multi_ed25519::MultiEd25519PublicKey {}
     impl Sealed for crate::multi_ed25519::MultiEd25519Signature {}
+
+    impl Sealed for crate::secp256k1::Secp256k1PrivateKey {}
+    impl Sealed for crate::secp256k1::Secp256k1PublicKey {}
+    impl Sealed for crate::secp256k1::Secp256k1Signature {}
 }
@@ -5,15 +5,64 @@ use rand::{rngs::StdRng, SeedableRng};
 use std::convert::TryFrom;
 
 use crate::{
+    bls12381::{
+        BLS12381PrivateKey, BLS12381ProofOfPossession, BLS12381PublicKey, BLS12381Signature,
+        BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
+    },
     ed25519::{
         Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_PRIVATE_KEY_LENGTH,
         ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
     },
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey, MultiEd25519Signature},
+    secp256k1::{
+        Secp256k1PrivateKey, Secp256k1PublicKey, Secp256k1RecoverableSignature, Secp256k1Signature,
+        SECP256K1_PRIVATE_KEY_LENGTH, SECP256K1_PUBLIC_KEY_LENGTH,
+        SECP256K1_RECOVERABLE_SIGNATURE_LENGTH, SECP256K1_SIGNATURE_LENGTH,
+    },
     test_utils::{TestDijetsCrypto, TEST_SEED},
     Signature, SigningKey, Uniform,
 };
 
+#[test]
+fn bls12381_bcs_material() {
+    use std::borrow::Cow;
+
+    let mut rng = StdRng::from_seed(TEST_SEED);
+    let private_key = BLS12381PrivateKey::generate(&mut rng);
+    let public_key = BLS12381PublicKey::from(&private_key);
+
+    let serialized_public_key = bcs::to_bytes(&Cow::Borrowed(&public_key)).unwrap();
+    // Expected size should be 1 byte due to BCS length prefix + 48 bytes for the compressed key
+    assert_eq!(serialized_public_key.len(), 1 + BLS12381_PUBLIC_KEY_LENGTH);
+
+    // Ensure public key serialization - deserialization is stable and deterministic
+    let deserialized_public_key: BLS12381PublicKey =
+        bcs::from_bytes(&serialized_public_key).unwrap();
+    assert_eq!(deserialized_public_key, public_key);
+
+    let pop = BLS12381ProofOfPossession::create(&private_key);
+    let serialized_pop = bcs::to_bytes(&Cow::Borrowed(&pop)).unwrap();
+    assert_eq!(serialized_pop.len(), 1 + BLS12381_SIGNATURE_LENGTH);
+    let deserialized_pop: BLS12381ProofOfPossession = bcs::from_bytes(&serialized_pop).unwrap();
+    assert!(deserialized_pop.verify(&public_key).is_ok());
+
+    let message = TestDijetsCrypto("Hello, World".to_string());
+    let signature: BLS12381Signature = private_key.sign(&message);
+
+    let serialized_signature = bcs::to_bytes(&Cow::Borrowed(&signature)).unwrap();
+    // Expected size should be 1 byte due to BCS length prefix + 96 bytes for the compressed
+    // signature
+    assert_eq!(serialized_signature.len(), 1 + BLS12381_SIGNATURE_LENGTH);
+
+    // Ensure signature serialization - deserialization is stable and deterministic
+    let deserialized_signature: BLS12381Signature = bcs::from_bytes(&serialized_signature).unwrap();
+    assert_eq!(deserialized_signature, signature);
+
+    // Verify signature
+    let verified_signature = signature.verify(&message, &public_key);
+    assert!(verified_signature.is_ok())
+}
+
 #[test]
 fn ed25519_bcs_material() {
     use std::borrow::Cow;
@@ -108,3 +157,53 @@ fn multi_ed25519_bcs_material() {
         .verify(&message, &multi_public_key_7of10)
         .is_ok());
 }
+
+#[test]
+fn secp256k1_bcs_material() {
+    use std::borrow::Cow;
+
+    let private_key =
+        Secp256k1PrivateKey::try_from([1u8; SECP256K1_PRIVATE_KEY_LENGTH].as_ref()).unwrap();
+    let public_key = Secp256k1PublicKey::from(&private_key);
+
+    let serialized_public_key = bcs::to_bytes(&Cow::Borrowed(&public_key)).unwrap();
+    // Expected size should be 1 byte due to BCS length prefix + 33 bytes for the compressed key
+    assert_eq!(serialized_public_key.len(), 1 + SECP256K1_PUBLIC_KEY_LENGTH);
+
+    // Ensure public key serialization - deserialization is stable and deterministic
+    let deserialized_public_key: Secp256k1PublicKey =
+        bcs::from_bytes(&serialized_public_key).unwrap();
+    assert_eq!(deserialized_public_key, public_key);
+
+    let message = TestDijetsCrypto("Hello, World".to_string());
+    let signature: Secp256k1Signature = private_key.sign(&message);
+
+    let serialized_signature = bcs::to_bytes(&Cow::Borrowed(&signature)).unwrap();
+    // Expected size should be 1 byte due to BCS length prefix + 64 bytes for the raw signature bytes
+    assert_eq!(serialized_signature.len(), 1 + SECP256K1_SIGNATURE_LENGTH);
+
+    // Ensure signature serialization - deserialization is stable and deterministic
+    let deserialized_signature: Secp256k1Signature =
+        bcs::from_bytes(&serialized_signature).unwrap();
+    assert_eq!(deserialized_signature, signature);
+
+    // Verify signature
+    let verified_signature = signature.verify(&message, &public_key);
+    assert!(verified_signature.is_ok());
+
+    let recoverable_signature = private_key.sign_recoverable(&message);
+    let serialized_signature = bcs::to_bytes(&Cow::Borrowed(&recoverable_signature)).unwrap();
+    // Expected size should be 1 byte due to BCS length prefix + 65 bytes for the signature and
+    // recovery id
+    assert_eq!(
+        serialized_signature.len(),
+        1 + SECP256K1_RECOVERABLE_SIGNATURE_LENGTH
+    );
+    let deserialized_signature: Secp256k1RecoverableSignature =
+        bcs::from_bytes(&serialized_signature).unwrap();
+    assert_eq!(deserialized_signature, recoverable_signature);
+    assert_eq!(
+        deserialized_signature.recover(&message).unwrap(),
+        public_key
+    );
+}
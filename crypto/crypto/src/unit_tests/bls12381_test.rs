@@ -0,0 +1,206 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate as dijets_crypto;
+use crate::{
+    bls12381::{
+        BLS12381PrivateKey, BLS12381ProofOfPossession, BLS12381PublicKey, BLS12381Signature,
+        BLS12381_PRIVATE_KEY_LENGTH, BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy, KeyPair},
+    traits::*,
+};
+
+use core::convert::TryFrom;
+use dijets_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use proptest::{collection::vec, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(CryptoHasher, BCSCryptoHash, Serialize, Deserialize)]
+struct CryptoHashable(pub usize);
+
+fn keypairs_strategy(
+    max_size: usize,
+) -> impl Strategy<Value = Vec<KeyPair<BLS12381PrivateKey, BLS12381PublicKey>>> {
+    vec(
+        uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>(),
+        1..max_size,
+    )
+}
+
+proptest! {
+    #[test]
+    fn test_keys_encode(keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()) {
+        {
+            let encoded = keypair.private_key.to_encoded_string().unwrap();
+            // Hex encoding of a 32-bytes key is 64 (2 x 32) characters.
+            prop_assert_eq!(2 * BLS12381_PRIVATE_KEY_LENGTH, encoded.len());
+            let decoded = BLS12381PrivateKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.private_key), decoded.ok());
+        }
+        {
+            let encoded = keypair.public_key.to_encoded_string().unwrap();
+            // Hex encoding of a 48-bytes compressed key is 96 (2 x 48) characters.
+            prop_assert_eq!(2 * BLS12381_PUBLIC_KEY_LENGTH, encoded.len());
+            let decoded = BLS12381PublicKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.public_key), decoded.ok());
+        }
+    }
+
+    #[test]
+    fn test_keys_custom_serialisation(
+        keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+    ) {
+        {
+            let serialized: &[u8] = &(keypair.private_key.to_bytes());
+            prop_assert_eq!(BLS12381_PRIVATE_KEY_LENGTH, serialized.len());
+            let deserialized = BLS12381PrivateKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.private_key), deserialized.ok());
+        }
+        {
+            let serialized: &[u8] = &(keypair.public_key.to_bytes());
+            prop_assert_eq!(BLS12381_PUBLIC_KEY_LENGTH, serialized.len());
+            let deserialized = BLS12381PublicKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.public_key), deserialized.ok());
+        }
+    }
+
+    #[test]
+    fn test_pub_key_deserialization(bits in any::<[u8; 32]>()){
+        // Random bytes are very unlikely to be a valid point.
+        let mut bytes = [0u8; BLS12381_PUBLIC_KEY_LENGTH];
+        bytes[..32].copy_from_slice(&bits);
+        prop_assert!(BLS12381PublicKey::try_from(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_signature_verification_custom_serialisation(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(BLS12381_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = BLS12381Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&message, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_arbitrary(
+        // this should be > 64 bits to go over the length of a default hash
+        msg in vec(proptest::num::u8::ANY, 1..128),
+        keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_arbitrary_message(&msg);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(BLS12381_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = BLS12381Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify_arbitrary_msg(&msg, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_struct(
+        x in any::<usize>(),
+        keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+    ) {
+        let hashable = CryptoHashable(x);
+        let signature = keypair.private_key.sign(&hashable);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(BLS12381_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = BLS12381Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&hashable, &keypair.public_key).is_ok());
+        prop_assert!(deserialized.verify(&CryptoHashable(x.wrapping_add(1)), &keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_proof_of_possession(
+        keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>(),
+        other_keypair in uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>()
+    ) {
+        let pop = BLS12381ProofOfPossession::create(&keypair.private_key);
+        prop_assert!(pop.verify(&keypair.public_key).is_ok());
+        prop_assert!(pop.verify(&other_keypair.public_key).is_err());
+
+        let serialized: &[u8] = &(pop.to_bytes());
+        prop_assert_eq!(BLS12381_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = BLS12381ProofOfPossession::try_from(serialized).unwrap();
+        prop_assert_eq!(&deserialized, &pop);
+
+        // A signature of the public key isn't a proof of possession, as it's domain separated.
+        let signature = keypair.private_key.sign_arbitrary_message(&keypair.public_key.to_bytes());
+        let not_pop = BLS12381ProofOfPossession::try_from(&signature.to_bytes()[..]).unwrap();
+        prop_assert!(not_pop.verify(&keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_signature(
+        message in random_serializable_struct(),
+        keypairs in keypairs_strategy(10)
+    ) {
+        let signatures: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| keypair.private_key.sign(&message))
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|keypair| &keypair.public_key).collect();
+        let aggregate = BLS12381Signature::aggregate(&signatures.iter().collect::<Vec<_>>()).unwrap();
+        prop_assert!(aggregate.verify_aggregate(&message, &public_keys).is_ok());
+
+        // The aggregate signature verifies against the aggregate public key
+        let aggregate_public_key = BLS12381PublicKey::aggregate(&public_keys).unwrap();
+        prop_assert!(aggregate.verify(&message, &aggregate_public_key).is_ok());
+
+        // The aggregate signature survives serialization
+        let serialized: &[u8] = &(aggregate.to_bytes());
+        let deserialized = BLS12381Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify_aggregate(&message, &public_keys).is_ok());
+
+        // Missing signers fail verification
+        if public_keys.len() > 1 {
+            prop_assert!(aggregate.verify_aggregate(&message, &public_keys[1..]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_aggregate_signature_wrong_message(
+        message in random_serializable_struct(),
+        other_message in random_serializable_struct(),
+        keypairs in keypairs_strategy(10)
+    ) {
+        prop_assume!(message.0 != other_message.0);
+        let mut signatures: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| keypair.private_key.sign(&message))
+            .collect();
+        // One signer signs another message
+        signatures[0] = keypairs[0].private_key.sign(&other_message);
+        let public_keys: Vec<_> = keypairs.iter().map(|keypair| &keypair.public_key).collect();
+        let aggregate = BLS12381Signature::aggregate(&signatures.iter().collect::<Vec<_>>()).unwrap();
+        prop_assert!(aggregate.verify_aggregate(&message, &public_keys).is_err());
+    }
+}
+
+#[test]
+fn test_aggregate_nothing() {
+    assert!(BLS12381Signature::aggregate(&[]).is_err());
+    assert!(BLS12381PublicKey::aggregate(&[]).is_err());
+    assert!(BLS12381Signature::dummy_signature()
+        .verify_aggregate_arbitrary_msg(&[], &[])
+        .is_err());
+}
+
+#[test]
+fn test_identity_rejected() {
+    // The compressed identity points of G1 and G2 have the compression and infinity bits set.
+    let mut public_key = [0u8; BLS12381_PUBLIC_KEY_LENGTH];
+    public_key[0] = 0xc0;
+    assert!(BLS12381PublicKey::try_from(&public_key[..]).is_err());
+
+    let mut signature = [0u8; BLS12381_SIGNATURE_LENGTH];
+    signature[0] = 0xc0;
+    assert!(BLS12381Signature::try_from(&signature[..]).is_err());
+
+    assert_eq!(
+        BLS12381PrivateKey::try_from(&[0u8; BLS12381_PRIVATE_KEY_LENGTH][..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bcs_test;
+mod bls12381_test;
 mod compat_test;
 mod cross_test;
 mod cryptohasher;
@@ -10,3 +11,5 @@ mod hash_test;
 mod hkdf_test;
 mod multi_ed25519_test;
 mod noise_test;
+mod secp256k1_test;
+mod x25519_test;
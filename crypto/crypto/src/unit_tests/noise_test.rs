@@ -4,7 +4,9 @@
 use std::{fs::File, io::BufReader, path::PathBuf};
 
 use crate::{
-    noise::{handshake_init_msg_len, handshake_resp_msg_len, NoiseConfig, MAX_SIZE_NOISE_MSG},
+    noise::{
+        handshake_init_msg_len, handshake_resp_msg_len, NoiseConfig, NoiseError, MAX_SIZE_NOISE_MSG,
+    },
     test_utils::TEST_SEED,
     x25519, Uniform as _,
 };
@@ -12,6 +14,58 @@ use crate::{
 use rand::SeedableRng;
 use serde::*;
 
+#[test]
+fn rekey() {
+    // setup peers
+    let mut rng = ::rand::rngs::StdRng::from_seed(TEST_SEED);
+    let initiator_private = x25519::PrivateKey::generate(&mut rng);
+    let responder_private = x25519::PrivateKey::generate(&mut rng);
+    let responder_public = responder_private.public_key();
+    let initiator = NoiseConfig::new(initiator_private);
+    let responder = NoiseConfig::new(responder_private);
+
+    // handshake
+    let mut first_message = vec![0u8; handshake_init_msg_len(0)];
+    let initiator_state = initiator
+        .initiate_connection(&mut rng, b"", responder_public, None, &mut first_message)
+        .unwrap();
+    let mut second_message = vec![0u8; handshake_resp_msg_len(0)];
+    let (_, mut responder_session) = responder
+        .respond_to_client_and_finalize(&mut rng, b"", &first_message, None, &mut second_message)
+        .unwrap();
+    let (_, mut initiator_session) = initiator
+        .finalize_connection(initiator_state, &second_message)
+        .unwrap();
+
+    // both sides rekey in lockstep
+    for _ in 0..10 {
+        initiator_session.rekey_write().unwrap();
+        responder_session.rekey_read().unwrap();
+
+        let mut message = b"payload".to_vec();
+        let auth_tag = initiator_session
+            .write_message_in_place(&mut message)
+            .unwrap();
+        message.extend_from_slice(&auth_tag);
+        let received_message = responder_session
+            .read_message_in_place(&mut message)
+            .unwrap();
+        assert_eq!(received_message, b"payload");
+    }
+
+    // a message encrypted with the new key can't be decrypted with the old one
+    initiator_session.rekey_write().unwrap();
+    let mut message = b"payload".to_vec();
+    let auth_tag = initiator_session
+        .write_message_in_place(&mut message)
+        .unwrap();
+    message.extend_from_slice(&auth_tag);
+    assert!(matches!(
+        responder_session.read_message_in_place(&mut message),
+        Err(NoiseError::Decrypt)
+    ));
+}
+
 #[test]
 fn simple_handshake() {
     // setup peers
@@ -0,0 +1,271 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate as dijets_crypto;
+use crate::{
+    hash::{CryptoHash, CryptoHasher, HashValue},
+    secp256k1::{
+        Secp256k1PrivateKey, Secp256k1PublicKey, Secp256k1RecoverableSignature, Secp256k1Signature,
+        SECP256K1_PRIVATE_KEY_LENGTH, SECP256K1_PUBLIC_KEY_LENGTH,
+        SECP256K1_RECOVERABLE_SIGNATURE_LENGTH, SECP256K1_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy},
+    traits::*,
+};
+
+use core::convert::TryFrom;
+use dijets_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use proptest::{collection::vec, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(CryptoHasher, BCSCryptoHash, Serialize, Deserialize)]
+struct CryptoHashable(pub usize);
+
+/// The order of secp256k1, in big-endian.
+const ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+// Computes n - s, with s in big-endian and lower than n.
+fn negate_s(s: &[u8]) -> [u8; 32] {
+    let mut negated = [0u8; 32];
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut diff = i16::from(ORDER[i]) - i16::from(s[i]) - borrow;
+        borrow = if diff < 0 {
+            diff += 256;
+            1
+        } else {
+            0
+        };
+        negated[i] = diff as u8;
+    }
+    negated
+}
+
+proptest! {
+    #[test]
+    fn test_keys_encode(keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()) {
+        {
+            let encoded = keypair.private_key.to_encoded_string().unwrap();
+            // Hex encoding of a 32-bytes key is 64 (2 x 32) characters.
+            prop_assert_eq!(2 * SECP256K1_PRIVATE_KEY_LENGTH, encoded.len());
+            let decoded = Secp256k1PrivateKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.private_key), decoded.ok());
+        }
+        {
+            let encoded = keypair.public_key.to_encoded_string().unwrap();
+            // Hex encoding of a 33-bytes compressed key is 66 (2 x 33) characters.
+            prop_assert_eq!(2 * SECP256K1_PUBLIC_KEY_LENGTH, encoded.len());
+            let decoded = Secp256k1PublicKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.public_key), decoded.ok());
+        }
+    }
+
+    #[test]
+    fn test_keys_custom_serialisation(
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        {
+            let serialized: &[u8] = &(keypair.private_key.to_bytes());
+            prop_assert_eq!(SECP256K1_PRIVATE_KEY_LENGTH, serialized.len());
+            let deserialized = Secp256k1PrivateKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.private_key), deserialized.ok());
+        }
+        {
+            let serialized: &[u8] = &(keypair.public_key.to_bytes());
+            prop_assert_eq!(SECP256K1_PUBLIC_KEY_LENGTH, serialized.len());
+            let deserialized = Secp256k1PublicKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.public_key.clone()), deserialized.ok());
+        }
+        {
+            let serialized: &[u8] = &(keypair.public_key.to_uncompressed_bytes());
+            let deserialized = Secp256k1PublicKey::from_uncompressed_bytes(serialized);
+            prop_assert_eq!(Some(keypair.public_key), deserialized.ok());
+        }
+    }
+
+    #[test]
+    fn test_pub_key_deserialization(bits in any::<[u8; 33]>()){
+        let expected = libsecp256k1::PublicKey::parse_compressed(&bits);
+        let pub_key = Secp256k1PublicKey::try_from(&bits[..]);
+        let check = matches!((expected, pub_key),
+            (Ok(_), Ok(_)) // we agree with libsecp256k1,
+            | (Err(_), Err(CryptoMaterialError::PointNotOnCurveError)) // we agree on point decompression failures,
+        );
+        prop_assert!(check);
+    }
+
+    #[test]
+    fn test_signature_verification_custom_serialisation(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256K1_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256k1Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&message, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_arbitrary(
+        // this should be > 64 bits to go over the length of a default hash
+        msg in vec(proptest::num::u8::ANY, 1..128),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_arbitrary_message(&msg);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256K1_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256k1Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify_arbitrary_msg(&msg, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_struct(
+        x in any::<usize>(),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let hashable = CryptoHashable(x);
+        let signature = keypair.private_key.sign(&hashable);
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256K1_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256k1Signature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&hashable, &keypair.public_key).is_ok());
+
+        // Signing a struct is signing the SHA3-256 of its domain separated serialization.
+        let mut bytes = <CryptoHashable as CryptoHash>::Hasher::seed().to_vec();
+        bcs::serialize_into(&mut bytes, &hashable).unwrap();
+        prop_assert_eq!(HashValue::sha3_256_of(&bytes), hashable.hash());
+        prop_assert!(deserialized.verify_arbitrary_msg(&bytes, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_wrong_key(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>(),
+        other_keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        prop_assume!(keypair.public_key != other_keypair.public_key);
+        let signature = keypair.private_key.sign(&message);
+        prop_assert!(signature.verify(&message, &other_keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_public_key_recovery(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_recoverable(&message);
+        prop_assert_eq!(signature.recover(&message).unwrap(), keypair.public_key.clone());
+        prop_assert!(signature.signature().verify(&message, &keypair.public_key).is_ok());
+
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256K1_RECOVERABLE_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256k1RecoverableSignature::try_from(serialized).unwrap();
+        prop_assert_eq!(&deserialized, &signature);
+        prop_assert_eq!(deserialized.recover(&message).unwrap(), keypair.public_key);
+    }
+
+    #[test]
+    fn test_public_key_recovery_from_arbitrary(
+        msg in vec(proptest::num::u8::ANY, 1..128),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_recoverable_arbitrary_message(&msg);
+        prop_assert_eq!(signature.recover_arbitrary_msg(&msg).unwrap(), keypair.public_key);
+    }
+
+    // Check for low S.
+    #[test]
+    fn test_signature_malleability(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message);
+        let mut serialized = signature.to_bytes();
+
+        // (R, n - S) is as valid as (R, S) for ECDSA.
+        let high_s = negate_s(&serialized[32..]);
+        serialized[32..].copy_from_slice(&high_s);
+
+        // Check that the high-S signature passes verification in libsecp256k1.
+        let libsecp_message = libsecp256k1::Message::parse(message.hash().as_ref());
+        let libsecp_sig = libsecp256k1::Signature::parse_standard(&serialized).unwrap();
+        let libsecp_public_key =
+            libsecp256k1::PublicKey::parse_compressed(&keypair.public_key.to_bytes()).unwrap();
+        prop_assert!(libsecp256k1::verify(&libsecp_message, &libsecp_sig, &libsecp_public_key));
+
+        let serialized_malleable: &[u8] = &serialized;
+        // try_from will fail on malleable signatures. We detect malleable signatures
+        // during deserialization.
+        prop_assert_eq!(
+            Secp256k1Signature::try_from(serialized_malleable),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+
+        // We expect from_bytes_unchecked deserialization to succeed, but verification to reject
+        // the high-S signature.
+        let sig_unchecked = Secp256k1Signature::from_bytes_unchecked(&serialized).unwrap();
+        prop_assert!(sig_unchecked.verify(&message, &keypair.public_key).is_err());
+
+        // Normalizing the high-S signature gives back the original one.
+        let normalized = Secp256k1Signature::from_bytes_normalized(&serialized).unwrap();
+        prop_assert_eq!(&normalized, &signature);
+        prop_assert!(normalized.verify(&message, &keypair.public_key).is_ok());
+    }
+}
+
+#[test]
+fn test_private_key_validity() {
+    // Zero isn't a valid scalar
+    let zero = [0u8; SECP256K1_PRIVATE_KEY_LENGTH];
+    assert_eq!(
+        Secp256k1PrivateKey::try_from(&zero[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+    // Nor is the order of the curve
+    assert_eq!(
+        Secp256k1PrivateKey::try_from(&ORDER[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+    assert_eq!(
+        Secp256k1PrivateKey::try_from(&ORDER[1..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+
+    let genesis = Secp256k1PrivateKey::genesis();
+    let public_key: Secp256k1PublicKey = (&genesis).into();
+    // The genesis public key is the generator of the curve
+    assert_eq!(
+        public_key.to_string(),
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+    );
+}
+
+#[test]
+fn test_half_order_boundary() {
+    // S = (n - 1) / 2 is the highest low S, S = (n + 1) / 2 the lowest high S.
+    let mut half_order = negate_s(&[0u8; 32]);
+    half_order[31] -= 1;
+    let mut carry = 0u8;
+    for byte in half_order.iter_mut() {
+        let next_carry = *byte & 1;
+        *byte = (*byte >> 1) | (carry << 7);
+        carry = next_carry;
+    }
+
+    let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+    bytes[31] = 1;
+    bytes[32..].copy_from_slice(&half_order);
+    assert!(Secp256k1Signature::check_malleability(&bytes).is_ok());
+
+    let high_s = negate_s(&half_order);
+    bytes[32..].copy_from_slice(&high_s);
+    assert_eq!(
+        Secp256k1Signature::check_malleability(&bytes),
+        Err(CryptoMaterialError::CanonicalRepresentationError)
+    );
+    assert!(Secp256k1Signature::try_from(&bytes[..]).is_err());
+}
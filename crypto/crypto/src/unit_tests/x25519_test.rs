@@ -0,0 +1,25 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    test_utils::{random_serializable_struct, TestDijetsCrypto},
+    x25519,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn xeddsa_sign_verify(
+        keypair in x25519::keypair_strategy(),
+        other_keypair in x25519::keypair_strategy(),
+        message in random_serializable_struct(),
+    ) {
+        let signature = keypair.private_key.sign(&message);
+        prop_assert!(keypair.public_key.verify_signature(&message, &signature).is_ok());
+
+        // the signature doesn't verify under another key, or for another message
+        prop_assert!(other_keypair.public_key.verify_signature(&message, &signature).is_err());
+        let other_message = TestDijetsCrypto(format!("{}!", message.0));
+        prop_assert!(keypair.public_key.verify_signature(&other_message, &signature).is_err());
+    }
+}
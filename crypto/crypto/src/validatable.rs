@@ -222,12 +222,101 @@ impl Validate for Ed25519PublicKey {
     }
 }
 
+//
+// Implement for Secp256k1
+//
+
+use crate::secp256k1::{Secp256k1PublicKey, SECP256K1_PUBLIC_KEY_LENGTH};
+
+/// An unvalidated `Secp256k1PublicKey`
+#[derive(Debug, Clone, Eq)]
+pub struct UnvalidatedSecp256k1PublicKey([u8; SECP256K1_PUBLIC_KEY_LENGTH]);
+
+impl UnvalidatedSecp256k1PublicKey {
+    /// Return key as bytes
+    pub fn to_bytes(&self) -> [u8; SECP256K1_PUBLIC_KEY_LENGTH] {
+        self.0
+    }
+}
+
+impl Serialize for UnvalidatedSecp256k1PublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            let encoded = ::hex::encode(&self.0);
+            serializer.serialize_str(&encoded)
+        } else {
+            // See comment in deserialize_key.
+            serializer.serialize_newtype_struct(
+                "Secp256k1PublicKey",
+                serde_bytes::Bytes::new(self.0.as_ref()),
+            )
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for UnvalidatedSecp256k1PublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        if deserializer.is_human_readable() {
+            let encoded_key = <String>::deserialize(deserializer)?;
+            let bytes_out = ::hex::decode(encoded_key).map_err(D::Error::custom)?;
+            <[u8; SECP256K1_PUBLIC_KEY_LENGTH]>::try_from(bytes_out.as_ref())
+                .map(UnvalidatedSecp256k1PublicKey)
+                .map_err(D::Error::custom)
+        } else {
+            // In order to preserve the Serde data model and help analysis tools,
+            // make sure to wrap our value in a container with the same name
+            // as the original type.
+            #[derive(Deserialize)]
+            #[serde(rename = "Secp256k1PublicKey")]
+            struct Value<'a>(&'a [u8]);
+
+            let value = Value::deserialize(deserializer)?;
+            <[u8; SECP256K1_PUBLIC_KEY_LENGTH]>::try_from(value.0)
+                .map(UnvalidatedSecp256k1PublicKey)
+                .map_err(D::Error::custom)
+        }
+    }
+}
+
+impl Hash for UnvalidatedSecp256k1PublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(&self.0)
+    }
+}
+
+impl PartialEq for UnvalidatedSecp256k1PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Validate for Secp256k1PublicKey {
+    type Unvalidated = UnvalidatedSecp256k1PublicKey;
+
+    fn validate(unvalidated: &Self::Unvalidated) -> Result<Self> {
+        Self::try_from(unvalidated.0.as_ref()).map_err(Into::into)
+    }
+
+    fn to_unvalidated(&self) -> Self::Unvalidated {
+        UnvalidatedSecp256k1PublicKey(self.to_bytes())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+        secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey},
         test_utils::uniform_keypair_strategy,
-        validatable::{UnvalidatedEd25519PublicKey, Validate},
+        validatable::{UnvalidatedEd25519PublicKey, UnvalidatedSecp256k1PublicKey, Validate},
     };
     use proptest::prelude::*;
     use std::{
@@ -275,6 +364,61 @@ mod test {
             }
 
 
+            // Ensure Hash is implemented the same
+            let valid_hash = {
+                let mut hasher = DefaultHasher::new();
+                valid.hash(&mut hasher);
+                hasher.finish()
+            };
+
+            let unvalidated_hash = {
+                let mut hasher = DefaultHasher::new();
+                unvalidated.hash(&mut hasher);
+                hasher.finish()
+            };
+
+            prop_assert_eq!(valid_hash, unvalidated_hash);
+        }
+
+        #[test]
+        fn unvalidated_secp256k1_public_key_equivalence(
+            keypair in uniform_keypair_strategy::<Secp256k1PrivateKey, Secp256k1PublicKey>()
+        ) {
+            let valid = keypair.public_key;
+            let unvalidated = valid.to_unvalidated();
+
+            prop_assert_eq!(&unvalidated, &UnvalidatedSecp256k1PublicKey(valid.to_bytes()));
+            prop_assert_eq!(&valid, &Secp256k1PublicKey::validate(&unvalidated).unwrap());
+
+            // Ensure Serialize and Deserialize are implemented the same
+
+            // BCS - A non-human-readable format
+            {
+                let serialized_valid = bcs::to_bytes(&valid).unwrap();
+                let serialized_unvalidated = bcs::to_bytes(&unvalidated).unwrap();
+                prop_assert_eq!(&serialized_valid, &serialized_unvalidated);
+
+                let deserialized_valid_from_unvalidated: Secp256k1PublicKey = bcs::from_bytes(&serialized_unvalidated).unwrap();
+                let deserialized_unvalidated_from_valid: UnvalidatedSecp256k1PublicKey = bcs::from_bytes(&serialized_valid).unwrap();
+
+                prop_assert_eq!(&valid, &deserialized_valid_from_unvalidated);
+                prop_assert_eq!(&unvalidated, &deserialized_unvalidated_from_valid);
+            }
+
+            // JSON A human-readable format
+            {
+                let serialized_valid = serde_json::to_string(&valid).unwrap();
+                let serialized_unvalidated = serde_json::to_string(&unvalidated).unwrap();
+                prop_assert_eq!(&serialized_valid, &serialized_unvalidated);
+
+                let deserialized_valid_from_unvalidated: Secp256k1PublicKey = serde_json::from_str(&serialized_unvalidated).unwrap();
+                let deserialized_unvalidated_from_valid: UnvalidatedSecp256k1PublicKey = serde_json::from_str(&serialized_valid).unwrap();
+
+                prop_assert_eq!(&valid, &deserialized_valid_from_unvalidated);
+                prop_assert_eq!(&unvalidated, &deserialized_unvalidated_from_valid);
+            }
+
+
             // Ensure Hash is implemented the same
             let valid_hash = {
                 let mut hasher = DefaultHasher::new();
@@ -29,13 +29,28 @@
 //! # }
 //! ```
 //!
+//! X25519 keys can also sign messages following
+//! [XEdDSA](https://signal.org/docs/specifications/xeddsa/), so that a network identity key can
+//! vouch for data relayed by other peers. The signatures are Ed25519 signatures, verified under
+//! the Ed25519 public key the X25519 public key converts to.
+//!
 
 use crate::{
-    traits::{self, CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt},
+    ed25519::{Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_LENGTH},
+    hash::{CryptoHash, CryptoHasher},
+    traits::{
+        self, CryptoMaterialError, Signature, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+    },
     x25519,
 };
+use anyhow::{anyhow, Result};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE, montgomery::MontgomeryPoint, scalar::Scalar,
+};
 use dijets_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
 use rand::{CryptoRng, RngCore};
+use serde::Serialize;
+use sha2::{Digest, Sha512};
 use std::convert::{TryFrom, TryInto};
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -65,6 +80,13 @@ pub const PUBLIC_KEY_SIZE: usize = 32;
 /// Size of a X25519 shared secret
 pub const SHARED_SECRET_SIZE: usize = 32;
 
+/// Prefix of the hash deriving the nonce of an XEdDSA signature, which domain separates it from
+/// the hash of the signed message
+const XEDDSA_NONCE_PREFIX: [u8; 32] = [
+    0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
 /// This type should be used to deserialize a received private key
 #[derive(DeserializeKey, SilentDisplay, SilentDebug, SerializeKey)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Clone))]
@@ -96,6 +118,45 @@ impl PrivateKey {
         shared_secret.as_bytes().to_owned()
     }
 
+    /// Signs `message` following XEdDSA. The nonce is derived from the key and the message rather
+    /// than from random bytes, as for Ed25519.
+    pub fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> Ed25519Signature {
+        let message = signing_message(message);
+
+        // The Edwards key pair of the X25519 scalar, whose public key has a positive sign so that
+        // it can be recovered from the X25519 public key
+        let scalar = Scalar::from_bits(self.0.to_bytes());
+        let public_key = (&scalar * &ED25519_BASEPOINT_TABLE).compress();
+        let scalar = Scalar::from_bytes_mod_order(self.0.to_bytes());
+        let (scalar, mut public_key) = if public_key.as_bytes()[31] & 0x80 != 0 {
+            (-scalar, public_key.to_bytes())
+        } else {
+            (scalar, public_key.to_bytes())
+        };
+        public_key[31] &= 0x7f;
+
+        let nonce = Scalar::from_hash(
+            Sha512::new()
+                .chain(&XEDDSA_NONCE_PREFIX)
+                .chain(scalar.as_bytes())
+                .chain(&message),
+        );
+        let commitment = (&nonce * &ED25519_BASEPOINT_TABLE).compress();
+        let challenge = Scalar::from_hash(
+            Sha512::new()
+                .chain(commitment.as_bytes())
+                .chain(&public_key)
+                .chain(&message),
+        );
+        let response = nonce + challenge * scalar;
+
+        let mut signature = [0u8; ED25519_SIGNATURE_LENGTH];
+        signature[..32].copy_from_slice(commitment.as_bytes());
+        signature[32..].copy_from_slice(response.as_bytes());
+        Ed25519Signature::try_from(&signature[..])
+            .expect("XEdDSA signatures are valid Ed25519 signatures")
+    }
+
     /// Deserialize an X25119 PrivateKey given the sha512 pre-image of a hash
     /// whose least significant half is a canonical X25519 scalar, following
     /// the XEdDSA approach.
@@ -131,6 +192,20 @@ impl PublicKey {
         &self.0
     }
 
+    /// Verifies an XEdDSA `signature` of `message` under this key.
+    pub fn verify_signature<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        signature: &Ed25519Signature,
+    ) -> Result<()> {
+        let public_key = MontgomeryPoint(self.0)
+            .to_edwards(0)
+            .ok_or_else(|| anyhow!("X25519 public key has no Ed25519 equivalent"))?
+            .compress();
+        let public_key = Ed25519PublicKey::try_from(&public_key.as_bytes()[..])?;
+        signature.verify_arbitrary_msg(&signing_message(message), &public_key)
+    }
+
     /// Deserialize an X25119 PublicKey from its representation as an
     /// Ed25519PublicKey, following the XEdDSA approach. This is meant to
     /// compensate for the poor key storage capabilities of key management
@@ -148,6 +223,15 @@ impl PublicKey {
     }
 }
 
+/// The bytes signed for `message`, as for Ed25519 signatures.
+fn signing_message<T: CryptoHash + Serialize>(message: &T) -> Vec<u8> {
+    let mut bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
+    bcs::serialize_into(&mut bytes, &message)
+        .map_err(|_| CryptoMaterialError::SerializationError)
+        .expect("Serialization of signable material should not fail.");
+    bytes
+}
+
 //
 // Traits implementations
 // ======================
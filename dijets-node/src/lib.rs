@@ -261,6 +261,8 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         metric_server::start_server(public_metric_host, public_metrics_port, true)
     });
 
+    DijetsVM::set_concurrency_level_once(node_config.execution.concurrency_level);
+
     let mut instant = Instant::now();
     let (dijets_db, db_rw) = DbReaderWriter::wrap(
         DijetsDB::open(
@@ -392,7 +394,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     let state_sync_bootstrapper = StateSyncBootstrapper::bootstrap(
         state_sync_network_handles,
         state_sync_to_mempool_sender,
-        Arc::clone(&db_rw.reader),
+        db_rw.clone(),
         chunk_executor,
         node_config,
         genesis_waypoint,
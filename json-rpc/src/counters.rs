@@ -82,3 +82,26 @@ pub static METHOD_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of tokens allowed and throttled per refill interval by the rate limiter
+pub static RATE_LIMIT_QUOTA: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "dijets_client_service_rate_limit_quota",
+        "Number of tokens allowed and throttled per client per second by the JSON RPC rate limiter",
+        &[
+            "key_type", // what the quota is keyed on: "ip", "api_key"
+            "result"    // "allowed", "throttled"
+        ]
+    )
+    .unwrap()
+});
+
+/// Cumulative number of HTTP requests rejected by the rate limiter
+pub static RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_client_service_rate_limited_requests_count",
+        "Cumulative number of HTTP requests rejected by the JSON RPC rate limiter",
+        &["key_type"] // ip / api_key
+    )
+    .unwrap()
+});
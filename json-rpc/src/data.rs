@@ -6,16 +6,24 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionListView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MetadataView, SimulatedTransactionView, StateProofView,
+        TransactionListView, TransactionView, TransactionWithProofView, TransactionsWithProofsView,
     },
 };
 use anyhow::Result;
+use dijets_crypto::HashValue;
+use dijets_state_view::StateView;
 use dijets_types::{
-    account_address::AccountAddress, account_config::dijets_root_address,
-    account_state::AccountState, chain_id::ChainId, event::EventKey,
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::dijets_root_address,
+    account_state::AccountState,
+    chain_id::ChainId,
+    event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
+    transaction::{SignedTransaction, Transaction, TransactionStatus},
 };
+use dijets_vm::DijetsVM;
 use resource_viewer::{AnnotatedMoveStruct, MoveValueAnnotator};
 use std::{
     collections::BTreeMap,
@@ -166,6 +174,42 @@ pub fn get_account_transactions_with_proofs(
     )?)
 }
 
+/// Returns the transaction with the given hash
+pub fn get_transaction_by_hash(
+    db: &dyn MoveDbReader,
+    hash: HashValue,
+    include_events: bool,
+    ledger_version: u64,
+) -> Result<Option<TransactionView>, JsonRpcError> {
+    let tx = db
+        .get_transaction_by_hash(hash, ledger_version, include_events)?
+        .map(|tx| {
+            TransactionView::try_from_tx_and_events(
+                tx.version,
+                tx.transaction,
+                tx.proof.transaction_info,
+                tx.events.unwrap_or_default(),
+            )
+        })
+        .transpose()?;
+    Ok(tx)
+}
+
+/// Returns the transaction with the given hash along with a proof of its inclusion in the
+/// ledger at `ledger_version`
+pub fn get_transaction_by_hash_with_proof(
+    db: &dyn MoveDbReader,
+    hash: HashValue,
+    include_events: bool,
+    ledger_version: u64,
+) -> Result<Option<TransactionWithProofView>, JsonRpcError> {
+    let tx = db
+        .get_transaction_by_hash(hash, ledger_version, include_events)?
+        .map(|tx| TransactionWithProofView::try_from(&tx))
+        .transpose()?;
+    Ok(tx)
+}
+
 /// Returns events by given access path
 pub fn get_events(
     db: &dyn MoveDbReader,
@@ -317,3 +361,49 @@ pub fn get_resources(
     }
     Ok(resources)
 }
+
+/// Executes `txn` on top of the state at `ledger_version` without committing it. The signature
+/// of the transaction is not checked.
+pub fn simulate_transaction(
+    db: &dyn MoveDbReader,
+    ledger_version: u64,
+    txn: SignedTransaction,
+) -> Result<SimulatedTransactionView, JsonRpcError> {
+    let state_view = VersionedStateView::new(db, ledger_version);
+    let (_vm_status, output) = DijetsVM::simulate_signed_transaction(&txn, &state_view);
+    if let TransactionStatus::Discard(status_code) = output.status() {
+        return Err(JsonRpcError::vm_status(*status_code));
+    }
+    Ok(SimulatedTransactionView::try_from_tx_and_output(
+        ledger_version + 1,
+        Transaction::UserTransaction(txn),
+        output,
+    )?)
+}
+
+/// A read-only `StateView` of the account states at a given version
+struct VersionedStateView<'a> {
+    db: &'a dyn MoveDbReader,
+    version: u64,
+}
+
+impl<'a> VersionedStateView<'a> {
+    fn new(db: &'a dyn MoveDbReader, version: u64) -> Self {
+        Self { db, version }
+    }
+}
+
+impl<'a> StateView for VersionedStateView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        Ok(
+            match get_account_state(self.db, access_path.address, self.version)? {
+                Some(account_state) => account_state.get(&access_path.path).cloned(),
+                None => None,
+            },
+        )
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
@@ -10,6 +10,7 @@
 //!
 //! Module organization:
 //! ├── methods.rs        # contains all available JSON RPC method handlers
+//! ├── rate_limit.rs     # per-client rate limiting of JSON RPC requests
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
 //! ├── tests.rs          # tests
 
@@ -19,6 +20,7 @@ pub mod util;
 mod counters;
 pub mod data;
 mod methods;
+mod rate_limit;
 pub mod runtime;
 
 pub use dijets_json_rpc_types::{errors, response, views};
@@ -8,8 +8,8 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionListView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MetadataView, SimulatedTransactionView, StateProofView,
+        TransactionListView, TransactionView, TransactionWithProofView, TransactionsWithProofsView,
     },
 };
 use anyhow::Result;
@@ -19,8 +19,9 @@ use dijets_json_rpc_types::request::{
     GetAccountTransactionsParams, GetAccountTransactionsWithProofsParams,
     GetAccumulatorConsistencyProofParams, GetCurrenciesParams, GetEventByVersionWithProof,
     GetEventsParams, GetEventsWithProofsParams, GetMetadataParams, GetNetworkStatusParams,
-    GetResourcesParams, GetStateProofParams, GetTransactionsParams,
-    GetTransactionsWithProofsParams, MethodRequest, SubmitParams,
+    GetResourcesParams, GetStateProofParams, GetTransactionByHashParams,
+    GetTransactionByHashWithProofParams, GetTransactionsParams, GetTransactionsWithProofsParams,
+    MethodRequest, SimulateTransactionParams, SubmitParams,
 };
 use dijets_mempool::{MempoolClientSender, SubmissionStatus};
 use dijets_types::{
@@ -160,6 +161,9 @@ impl<'a> Handler<'a> {
             MethodRequest::GetAccountTransactions(params) => {
                 serde_json::to_value(self.get_account_transactions(params).await?)?
             }
+            MethodRequest::GetTransactionByHash(params) => {
+                serde_json::to_value(self.get_transaction_by_hash(params).await?)?
+            }
             MethodRequest::GetEvents(params) => {
                 serde_json::to_value(self.get_events(params).await?)?
             }
@@ -187,12 +191,18 @@ impl<'a> Handler<'a> {
             MethodRequest::GetAccountTransactionsWithProofs(params) => {
                 serde_json::to_value(self.get_account_transactions_with_proofs(params).await?)?
             }
+            MethodRequest::GetTransactionByHashWithProof(params) => {
+                serde_json::to_value(self.get_transaction_by_hash_with_proof(params).await?)?
+            }
             MethodRequest::GetEventsWithProofs(params) => {
                 serde_json::to_value(self.get_events_with_proofs(params).await?)?
             }
             MethodRequest::GetEventByVersionWithProof(params) => {
                 serde_json::to_value(self.get_event_by_version_with_proof(params).await?)?
             }
+            MethodRequest::SimulateTransaction(params) => {
+                serde_json::to_value(self.simulate_transaction(params).await?)?
+            }
         };
         Ok(response)
     }
@@ -339,6 +349,43 @@ impl<'a> Handler<'a> {
         )
     }
 
+    /// Returns the transaction with the given hash
+    async fn get_transaction_by_hash(
+        &self,
+        params: GetTransactionByHashParams,
+    ) -> Result<Option<TransactionView>, JsonRpcError> {
+        let GetTransactionByHashParams {
+            hash,
+            include_events,
+        } = params;
+        data::get_transaction_by_hash(
+            self.service.db.borrow(),
+            hash,
+            include_events,
+            self.version(),
+        )
+    }
+
+    /// Returns the transaction with the given hash along with a proof of its inclusion
+    async fn get_transaction_by_hash_with_proof(
+        &self,
+        params: GetTransactionByHashWithProofParams,
+    ) -> Result<Option<TransactionWithProofView>, JsonRpcError> {
+        let GetTransactionByHashWithProofParams {
+            hash,
+            include_events,
+            ledger_version,
+        } = params;
+        let ledger_version = self.version_param(ledger_version, "ledger_version")?;
+
+        data::get_transaction_by_hash_with_proof(
+            self.service.db.borrow(),
+            hash,
+            include_events,
+            ledger_version,
+        )
+    }
+
     /// Returns events by given access path
     async fn get_events(&self, params: GetEventsParams) -> Result<Vec<EventView>, JsonRpcError> {
         let GetEventsParams { key, start, limit } = params;
@@ -445,4 +492,13 @@ impl<'a> Handler<'a> {
             version,
         )
     }
+
+    /// Executes the transaction against the latest ledger state without committing it, so
+    /// clients can learn its outcome and gas usage before submitting it
+    async fn simulate_transaction(
+        &self,
+        params: SimulateTransactionParams,
+    ) -> Result<SimulatedTransactionView, JsonRpcError> {
+        data::simulate_transaction(self.service.db.borrow(), self.version(), params.data)
+    }
 }
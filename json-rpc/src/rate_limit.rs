@@ -0,0 +1,220 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-client rate limiting of JSON-RPC requests
+//!
+//! Clients are identified by an API key (passed in the `Authorization` header) if they present one
+//! of the configured keys, otherwise by their remote IP address (or /64 prefix for IPv6, as a
+//! single host usually gets a whole /64). Each identity gets its own token bucket, and every
+//! request deducts the cost of its method(s) from it. Buckets of idle clients are dropped
+//! periodically.
+
+use crate::counters;
+use dijets_config::config::JsonRpcRateLimitConfig;
+use dijets_infallible::Mutex;
+use dijets_rate_limiter::rate_limit::TokenBucketRateLimiter;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+use warp::{
+    http::{header, StatusCode},
+    Reply,
+};
+
+const LABEL_IP: &str = "ip";
+const LABEL_API_KEY: &str = "api_key";
+/// How often the buckets of idle clients are dropped
+const GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) struct RpcRateLimiter {
+    enabled: bool,
+    ip_limiter: TokenBucketRateLimiter<IpAddr>,
+    api_key_limiter: TokenBucketRateLimiter<String>,
+    api_keys: HashSet<String>,
+    default_method_cost: usize,
+    method_costs: HashMap<String, usize>,
+    last_garbage_collection: Mutex<Instant>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(config: &JsonRpcRateLimitConfig) -> Self {
+        if !config.enabled {
+            return Self::open();
+        }
+
+        Self {
+            enabled: true,
+            ip_limiter: TokenBucketRateLimiter::new(
+                LABEL_IP,
+                "json-rpc".to_string(),
+                config.initial_bucket_fill_percentage,
+                config.ip_bucket_size,
+                config.ip_bucket_rate,
+                Some(counters::RATE_LIMIT_QUOTA.clone()),
+            ),
+            api_key_limiter: TokenBucketRateLimiter::new(
+                LABEL_API_KEY,
+                "json-rpc".to_string(),
+                config.initial_bucket_fill_percentage,
+                config.api_key_bucket_size,
+                config.api_key_bucket_rate,
+                Some(counters::RATE_LIMIT_QUOTA.clone()),
+            ),
+            api_keys: config.api_keys.iter().cloned().collect(),
+            default_method_cost: config.default_method_cost,
+            method_costs: config.method_costs.clone(),
+            last_garbage_collection: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// A rate limiter which lets every request through
+    pub fn open() -> Self {
+        Self {
+            enabled: false,
+            ip_limiter: TokenBucketRateLimiter::open(LABEL_IP),
+            api_key_limiter: TokenBucketRateLimiter::open(LABEL_API_KEY),
+            api_keys: HashSet::new(),
+            default_method_cost: 0,
+            method_costs: HashMap::new(),
+            last_garbage_collection: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Deducts the cost of `request` (a single request or a batch) from the client's quota
+    pub fn check(
+        &self,
+        remote_addr: Option<SocketAddr>,
+        authorization: Option<&str>,
+        request: &Value,
+    ) -> Result<(), RateLimited> {
+        if !self.enabled {
+            return Ok(());
+        }
+        self.maybe_garbage_collect();
+
+        let cost = self.request_cost(request);
+        let (label, result) = if let Some(api_key) = self.api_key(authorization) {
+            let bucket = self.api_key_limiter.bucket(api_key.to_string());
+            let result = bucket.lock().acquire_all_tokens(cost);
+            (LABEL_API_KEY, result)
+        } else if let Some(remote_addr) = remote_addr {
+            let bucket = self.ip_limiter.bucket(ip_bucket_key(remote_addr.ip()));
+            let result = bucket.lock().acquire_all_tokens(cost);
+            (LABEL_IP, result)
+        } else {
+            // Nothing to key the quota on, e.g. requests which don't come over TCP
+            return Ok(());
+        };
+
+        result.map_err(|ready_at| {
+            counters::RATE_LIMITED_REQUESTS
+                .with_label_values(&[label])
+                .inc();
+            RateLimited {
+                retry_after: ready_at
+                    .map(|ready_at| ready_at.saturating_duration_since(Instant::now())),
+            }
+        })
+    }
+
+    /// Drops the IP buckets which aren't in use and have refilled completely, at most once per
+    /// `GARBAGE_COLLECTION_INTERVAL`, so that the buckets don't pile up with every client ever
+    /// seen. API keys are not collected, as there is a bucket per configured key at most.
+    fn maybe_garbage_collect(&self) {
+        let now = Instant::now();
+        {
+            let mut last_garbage_collection = self.last_garbage_collection.lock();
+            if now.duration_since(*last_garbage_collection) < GARBAGE_COLLECTION_INTERVAL {
+                return;
+            }
+            *last_garbage_collection = now;
+        }
+        self.ip_limiter.garbage_collect_full_buckets();
+    }
+
+    /// Returns the API key from an `Authorization` header, if it's one of the configured keys.
+    /// Both `Bearer <key>` and a bare `<key>` are accepted.
+    fn api_key<'a>(&self, authorization: Option<&'a str>) -> Option<&'a str> {
+        let authorization = authorization?.trim();
+        let api_key = authorization
+            .strip_prefix("Bearer ")
+            .unwrap_or(authorization)
+            .trim();
+        if self.api_keys.contains(api_key) {
+            Some(api_key)
+        } else {
+            None
+        }
+    }
+
+    fn request_cost(&self, request: &Value) -> usize {
+        match request {
+            Value::Array(requests) => requests
+                .iter()
+                .map(|request| self.method_cost(request))
+                .fold(0, usize::saturating_add),
+            request => self.method_cost(request),
+        }
+    }
+
+    fn method_cost(&self, request: &Value) -> usize {
+        request
+            .get("method")
+            .and_then(Value::as_str)
+            .and_then(|method| self.method_costs.get(method))
+            .copied()
+            .unwrap_or(self.default_method_cost)
+    }
+}
+
+/// Returns the key of the IP bucket of a client: its IPv4 address, or the /64 prefix of its IPv6
+/// address. IPv4-mapped IPv6 addresses (e.g. from a dual-stack listener) are keyed as IPv4.
+pub(crate) fn ip_bucket_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => ip,
+        IpAddr::V6(ipv6) => {
+            let segments = ipv6.segments();
+            match ipv6.to_ipv4() {
+                Some(ipv4) if segments[5] == 0xffff => IpAddr::V4(ipv4),
+                _ => IpAddr::V6(Ipv6Addr::new(
+                    segments[0],
+                    segments[1],
+                    segments[2],
+                    segments[3],
+                    0,
+                    0,
+                    0,
+                    0,
+                )),
+            }
+        }
+    }
+}
+
+/// Rejection of a request by the `RpcRateLimiter`
+#[derive(Debug)]
+pub(crate) struct RateLimited {
+    /// How long until the request can be let through, `None` if the request costs more than the
+    /// client's whole quota.
+    retry_after: Option<Duration>,
+}
+
+impl Reply for RateLimited {
+    fn into_response(self) -> warp::reply::Response {
+        let mut response =
+            warp::reply::with_status("rate limit exceeded", StatusCode::TOO_MANY_REQUESTS)
+                .into_response();
+        if let Some(retry_after) = self.retry_after {
+            // Retry-After is in whole seconds, round up so a retry is never too early
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                header::HeaderValue::from_str(&secs.max(1).to_string()).unwrap(),
+            );
+        }
+        response
+    }
+}
@@ -5,12 +5,13 @@ use crate::{
     counters,
     errors::is_internal_error,
     methods::{Handler, JsonRpcService},
+    rate_limit::RpcRateLimiter,
     response::{JsonRpcResponse, X_DIJETS_CHAIN_ID, X_DIJETS_TIMESTAMP_USEC_ID, X_DIJETS_VERSION_ID},
     stream_rpc,
     util::{sdk_info_from_user_agent, SdkInfo},
 };
 use anyhow::{ensure, Result};
-use dijets_config::config::{NodeConfig, RoleType, StreamConfig};
+use dijets_config::config::{JsonRpcRateLimitConfig, NodeConfig, RoleType, StreamConfig};
 use dijets_json_rpc_types::Method;
 use dijets_logger::{debug, Schema};
 use dijets_mempool::MempoolClientSender;
@@ -107,6 +108,7 @@ pub fn bootstrap(
     role: RoleType,
     chain_id: ChainId,
     stream_config: &StreamConfig,
+    rate_limit_config: &JsonRpcRateLimitConfig,
 ) -> Runtime {
     let runtime = Builder::new_multi_thread()
         .thread_name("json-rpc")
@@ -122,6 +124,7 @@ pub fn bootstrap(
         batch_size_limit,
         page_size_limit,
     );
+    let rate_limiter = Arc::new(RpcRateLimiter::new(rate_limit_config));
 
     let base_route = warp::any()
         .and(warp::post())
@@ -130,7 +133,10 @@ pub fn bootstrap(
         .and(warp::body::json())
         .and(warp::any().map(move || service.clone()))
         .and(warp::filters::header::optional::<String>("user-agent"))
-        .and_then(rpc_endpoint)
+        .and(warp::addr::remote())
+        .and(warp::filters::header::optional::<String>("authorization"))
+        .and(warp::any().map(move || rate_limiter.clone()))
+        .and_then(rate_limited_rpc_endpoint)
         .with(warp::log::custom(|info| {
             debug!(HttpRequestLog {
                 remote_addr: info.remote_addr(),
@@ -154,7 +160,7 @@ pub fn bootstrap(
             warp::cors()
                 .allow_any_origin()
                 .allow_methods(vec!["POST"])
-                .allow_headers(vec![header::CONTENT_TYPE]),
+                .allow_headers(vec![header::CONTENT_TYPE, header::AUTHORIZATION]),
         );
 
     // For now we still allow user to use "/", but user should start to move to "/v1" soon
@@ -224,6 +230,7 @@ pub fn bootstrap_from_config(
         config.base.role,
         chain_id,
         &config.json_rpc.stream_rpc,
+        &config.json_rpc.rate_limit,
     )
 }
 
@@ -257,6 +264,21 @@ pub fn check_latest_ledger_info_timestamp(
     Ok(())
 }
 
+/// Checks the client's quota before handing the request over to `rpc_endpoint`
+async fn rate_limited_rpc_endpoint(
+    data: Value,
+    service: JsonRpcService,
+    user_agent: Option<String>,
+    remote_addr: Option<SocketAddr>,
+    authorization: Option<String>,
+    rate_limiter: Arc<RpcRateLimiter>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    if let Err(rate_limited) = rate_limiter.check(remote_addr, authorization.as_deref(), &data) {
+        return Ok(rate_limited.into_response());
+    }
+    rpc_endpoint(data, service, user_agent).await
+}
+
 /// JSON RPC entry point
 /// Handles all incoming rpc requests
 pub(crate) async fn rpc_endpoint(
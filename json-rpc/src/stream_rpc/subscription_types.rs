@@ -18,13 +18,17 @@
 //!       returned. Otherwise if the parameters are valid and a subscription could be created, simply
 //!       return `Ok(())`
 //!
-//! 2. `next(&self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ParamType>;`
+//! 2. `next(&mut self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ParamType>;`
 //!     Within this method, use existing state set up in the `init` function to fetch the next batch of data.
+//!     Subscriptions which filter data may also update their state here, to skip over data which
+//!     was fetched but not returned.
 //!     The size of the batch should be determined by `helper.client.config.fetch_size`.
 //!     If the function returns an array of data, each item will be serialized one by one and sent to the client.
 //!     The `on_send` method (below) will be called for each item in the array.
 //!     If the function returns an empty array, the thread will sleep for `client.config.poll_interval_ms`,
 //!     increasing exponentially up to a ceiling, and will reset any time the function returns data.
+//!     A subscription which skipped over data without returning any (e.g. as it was all filtered
+//!     out) should report it with `is_catching_up`, so it is polled again right away instead.
 //!
 //! 3. `on_send(&mut self, item: &ParamType);`
 //!     If the `next` function returns an array of items, after each the item is serialized and sent to the client
@@ -142,9 +146,15 @@ where
 {
     fn init(&mut self, helper: &SubscriptionHelper, params: &ParamType)
         -> Result<(), JsonRpcError>;
-    fn next(&self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ReturnType>;
+    fn next(&mut self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ReturnType>;
     fn on_send(&mut self, item: Option<&ReturnType>);
 
+    /// Whether the last call to `next` went through data even though it returned nothing, so
+    /// there may be more data to go through right away
+    fn is_catching_up(&self) -> bool {
+        false
+    }
+
     fn run(
         mut self,
         mut helper: SubscriptionHelper,
@@ -167,7 +177,12 @@ where
 
                 if items.is_empty() {
                     self.on_send(None);
-                    helper.sleep_wiggled().await;
+                    if self.is_catching_up() {
+                        helper.reset_backoff();
+                        tokio::task::yield_now().await;
+                    } else {
+                        helper.sleep_wiggled().await;
+                    }
                     continue;
                 }
 
@@ -252,7 +267,7 @@ mod tests {
         }
 
         fn next(
-            &self,
+            &mut self,
             _helper: &SubscriptionHelper,
             params: &SubscribeTestParams,
         ) -> Vec<TestView> {
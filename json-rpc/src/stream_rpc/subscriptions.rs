@@ -5,17 +5,68 @@ use crate::{
     data::{get_events, get_transactions},
     errors::JsonRpcError,
     stream_rpc::subscription_types::{Subscription, SubscriptionHelper},
-    views::{EventView, TransactionView},
+    views::{AccountView, EventView, TransactionView},
 };
+use anyhow::Result;
 use dijets_json_rpc_types::stream::request::{
-    SubscribeToEventsParams, SubscribeToTransactionsParams,
+    SubscribeToAccountChangesParams, SubscribeToEventsParams, SubscribeToTransactionsParams,
+    TransactionFilter,
 };
 use dijets_logger::warn;
-use std::borrow::Borrow;
+use dijets_types::{account_state::AccountState, account_state_blob::AccountStateBlob};
+use std::{borrow::Borrow, convert::TryFrom};
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct TransactionsSubscription {
+    /// The next version to fetch: everything below it has either been sent or filtered out
     pub(crate) latest_version: u64,
+    /// Whether the last batch was fetched but entirely filtered out
+    pub(crate) filtered_out: bool,
+}
+
+impl TransactionsSubscription {
+    /// Fetches the next batch of transactions and drops those not matching `filter`.
+    /// Returns the number of transactions fetched along with the matching ones.
+    fn fetch_filtered(
+        &self,
+        helper: &SubscriptionHelper,
+        include_events: bool,
+        filter: &TransactionFilter,
+    ) -> Result<(u64, Vec<TransactionView>)> {
+        let ledger_version = helper.db.get_latest_version()?;
+        if self.latest_version > ledger_version {
+            return Ok((0, vec![]));
+        }
+        let fetch_events = include_events || filter.requires_events();
+        let txs = helper.db.get_transactions(
+            self.latest_version,
+            helper.client.config.fetch_size,
+            ledger_version,
+            fetch_events,
+        )?;
+
+        let num_fetched = txs.transactions.len();
+        let event_lists = txs.events.unwrap_or_else(|| vec![Vec::new(); num_fetched]);
+        let views = txs
+            .transactions
+            .into_iter()
+            .zip(txs.proof.transaction_infos)
+            .zip(event_lists)
+            .enumerate()
+            .filter(|(_, ((tx, info), events))| filter.matches(tx, info, events))
+            .map(|(idx, ((tx, info), events))| {
+                let events = if include_events { events } else { vec![] };
+                TransactionView::try_from_tx_and_events(
+                    self.latest_version + idx as u64,
+                    tx,
+                    info,
+                    events,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok((num_fetched as u64, views))
+    }
 }
 
 impl Subscription<SubscribeToTransactionsParams, TransactionView> for TransactionsSubscription {
@@ -29,18 +80,44 @@ impl Subscription<SubscribeToTransactionsParams, TransactionView> for Transactio
     }
 
     fn next(
-        &self,
+        &mut self,
         helper: &SubscriptionHelper,
         params: &SubscribeToTransactionsParams,
     ) -> Vec<TransactionView> {
-        match get_transactions(
-            helper.db.borrow(),
-            helper.db.get_latest_version().unwrap_or(0),
-            self.latest_version,
-            helper.client.config.fetch_size,
-            params.include_events.unwrap_or(false),
-        ) {
-            Ok(transactions) => transactions.0,
+        let include_events = params.include_events.unwrap_or(false);
+        let filter = match &params.filter {
+            Some(filter) => filter,
+            None => {
+                return match get_transactions(
+                    helper.db.borrow(),
+                    helper.db.get_latest_version().unwrap_or(0),
+                    self.latest_version,
+                    helper.client.config.fetch_size,
+                    include_events,
+                ) {
+                    Ok(transactions) => {
+                        self.latest_version += transactions.0.len() as u64;
+                        transactions.0
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Client#{} Could not fetch transactions: {}",
+                            helper.client.id, e
+                        );
+                        vec![]
+                    }
+                };
+            }
+        };
+
+        self.filtered_out = false;
+        match self.fetch_filtered(helper, include_events, filter) {
+            Ok((num_fetched, transactions)) => {
+                // Skip over the whole batch, so filtered out transactions aren't fetched again
+                self.latest_version += num_fetched;
+                self.filtered_out = num_fetched > 0 && transactions.is_empty();
+                transactions
+            }
             Err(e) => {
                 warn!(
                     "Client#{} Could not fetch transactions: {}",
@@ -51,10 +128,12 @@ impl Subscription<SubscribeToTransactionsParams, TransactionView> for Transactio
         }
     }
 
-    fn on_send(&mut self, tx: Option<&TransactionView>) {
-        if tx.is_some() {
-            self.latest_version += 1;
-        }
+    fn on_send(&mut self, _tx: Option<&TransactionView>) {}
+
+    /// The next batch is fetched right away if the last one was entirely filtered out, so that
+    /// sparse filters don't slow down catching up with the ledger
+    fn is_catching_up(&self) -> bool {
+        self.filtered_out
     }
 }
 
@@ -74,7 +153,7 @@ impl Subscription<SubscribeToEventsParams, EventView> for EventsSubscription {
     }
 
     fn next(
-        &self,
+        &mut self,
         helper: &SubscriptionHelper,
         params: &SubscribeToEventsParams,
     ) -> Vec<EventView> {
@@ -99,3 +178,81 @@ impl Subscription<SubscribeToEventsParams, EventView> for EventsSubscription {
         }
     }
 }
+
+#[derive(Clone, Debug, Default)]
+pub struct AccountChangesSubscription {
+    /// The last version the account state was checked at
+    pub(crate) checked_version: Option<u64>,
+    /// The account state last sent to the client
+    pub(crate) last_state: Option<AccountStateBlob>,
+}
+
+impl Subscription<SubscribeToAccountChangesParams, AccountView> for AccountChangesSubscription {
+    fn init(
+        &mut self,
+        _helper: &SubscriptionHelper,
+        _params: &SubscribeToAccountChangesParams,
+    ) -> Result<(), JsonRpcError> {
+        Ok(())
+    }
+
+    /// Sends the account when it is first seen, and then whenever its state differs from the one
+    /// last sent. Changes happening between two polls are coalesced into a single update.
+    fn next(
+        &mut self,
+        helper: &SubscriptionHelper,
+        params: &SubscribeToAccountChangesParams,
+    ) -> Vec<AccountView> {
+        let version = match helper.db.get_latest_version() {
+            Ok(version) => version,
+            Err(e) => {
+                warn!(
+                    "Client#{} Could not fetch latest version: {}",
+                    helper.client.id, e
+                );
+                return vec![];
+            }
+        };
+        if self.checked_version == Some(version) {
+            return vec![];
+        }
+
+        let blob = match helper
+            .db
+            .get_account_state_with_proof_by_version(params.address, version)
+        {
+            Ok((blob, _proof)) => blob,
+            Err(e) => {
+                warn!(
+                    "Client#{} Could not fetch account state: {}",
+                    helper.client.id, e
+                );
+                return vec![];
+            }
+        };
+        self.checked_version = Some(version);
+        if blob == self.last_state {
+            return vec![];
+        }
+        self.last_state = blob;
+
+        let account = self.last_state.as_ref().map(|blob| {
+            AccountState::try_from(blob).and_then(|account_state| {
+                AccountView::try_from_account_state(params.address, account_state, version)
+            })
+        });
+        match account {
+            Some(Ok(account)) => vec![account],
+            Some(Err(e)) => {
+                warn!(
+                    "Client#{} Could not convert account state: {}",
+                    helper.client.id, e
+                );
+                vec![]
+            }
+            None => vec![],
+        }
+    }
+
+    fn on_send(&mut self, _account: Option<&AccountView>) {}
+}
@@ -0,0 +1,174 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+use dijets_json_rpc_types::stream::request::{
+    ScriptFunctionFilter, TransactionFilter, VMStatusFilter,
+};
+use dijets_transaction_builder::stdlib::encode_peer_to_peer_with_metadata_script_function;
+use dijets_types::{
+    account_address::AccountAddress,
+    account_config::xus_tag,
+    contract_event::ContractEvent,
+    event::EventKey,
+    test_helpers::transaction_test_helpers::get_test_signed_txn,
+    transaction::{Transaction, TransactionInfo, TransactionPayload},
+    vm_status::{AbortLocation, KeptVMStatus},
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+};
+
+fn user_txn(payload: Option<TransactionPayload>) -> Transaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    Transaction::UserTransaction(get_test_signed_txn(
+        AccountAddress::random(),
+        0,
+        &private_key,
+        private_key.public_key(),
+        payload,
+    ))
+}
+
+fn txn_info(status: KeptVMStatus) -> TransactionInfo {
+    TransactionInfo::new(
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        status,
+    )
+}
+
+fn event(type_tag: TypeTag) -> ContractEvent {
+    ContractEvent::new(
+        EventKey::new_from_address(&AccountAddress::random(), 0),
+        0,
+        type_tag,
+        vec![],
+    )
+}
+
+fn struct_tag(module: &str, name: &str) -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+#[test]
+fn test_script_function_filter() {
+    let payload = encode_peer_to_peer_with_metadata_script_function(
+        xus_tag(),
+        AccountAddress::random(),
+        1,
+        vec![],
+        vec![],
+    );
+    let (module, function) = match &payload {
+        TransactionPayload::ScriptFunction(script_function) => (
+            script_function.module().clone(),
+            script_function.function().to_string(),
+        ),
+        _ => panic!("expected a script function payload"),
+    };
+    let script_function_txn = user_txn(Some(payload));
+    let script_txn = user_txn(None);
+    let info = txn_info(KeptVMStatus::Executed);
+    let filter = |module_name: &str, function: Option<&str>| TransactionFilter {
+        script_function: Some(ScriptFunctionFilter {
+            address: *module.address(),
+            module: module_name.to_string(),
+            function: function.map(str::to_string),
+        }),
+        ..TransactionFilter::default()
+    };
+
+    // the module alone, or along with the function, matches
+    let module_name = module.name().as_str();
+    assert!(filter(module_name, None).matches(&script_function_txn, &info, &[]));
+    assert!(filter(module_name, Some(&function)).matches(&script_function_txn, &info, &[]));
+
+    // other modules, functions or payloads don't
+    assert!(!filter(module_name, Some("other_function")).matches(&script_function_txn, &info, &[]));
+    assert!(!filter("OtherModule", None).matches(&script_function_txn, &info, &[]));
+    assert!(!filter(module_name, None).matches(&script_txn, &info, &[]));
+}
+
+#[test]
+fn test_event_type_filter() {
+    let tag = struct_tag("DijetsAccount", "SentPaymentEvent");
+    let filter = TransactionFilter {
+        event_type: Some(tag.clone()),
+        ..TransactionFilter::default()
+    };
+    assert!(filter.requires_events());
+    assert!(!TransactionFilter::default().requires_events());
+
+    let txn = user_txn(None);
+    let info = txn_info(KeptVMStatus::Executed);
+    let other_tag = struct_tag("DijetsAccount", "ReceivedPaymentEvent");
+
+    // any event of the type matches
+    assert!(filter.matches(
+        &txn,
+        &info,
+        &[
+            event(TypeTag::Struct(other_tag.clone())),
+            event(TypeTag::Struct(tag))
+        ],
+    ));
+
+    // no event, or events of other types, don't
+    assert!(!filter.matches(&txn, &info, &[]));
+    assert!(!filter.matches(
+        &txn,
+        &info,
+        &[event(TypeTag::Struct(other_tag)), event(TypeTag::Bool)],
+    ));
+}
+
+#[test]
+fn test_vm_status_filter() {
+    let statuses = vec![
+        (VMStatusFilter::Executed, KeptVMStatus::Executed),
+        (VMStatusFilter::OutOfGas, KeptVMStatus::OutOfGas),
+        (
+            VMStatusFilter::MoveAbort,
+            KeptVMStatus::MoveAbort(AbortLocation::Script, 1),
+        ),
+        (
+            VMStatusFilter::ExecutionFailure,
+            KeptVMStatus::ExecutionFailure {
+                location: AbortLocation::Script,
+                function: 0,
+                code_offset: 0,
+            },
+        ),
+        (
+            VMStatusFilter::MiscellaneousError,
+            KeptVMStatus::MiscellaneousError,
+        ),
+    ];
+    let txn = user_txn(None);
+
+    // each filter only matches its own status
+    for (vm_status, _) in &statuses {
+        let filter = TransactionFilter {
+            vm_status: Some(*vm_status),
+            ..TransactionFilter::default()
+        };
+        for (expected_vm_status, status) in &statuses {
+            assert_eq!(
+                filter.matches(&txn, &txn_info(status.clone()), &[]),
+                vm_status == expected_vm_status,
+                "{:?} filter on {:?}",
+                vm_status,
+                status
+            );
+        }
+    }
+}
@@ -1,6 +1,9 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+mod filters;
+
 #[cfg(test)]
 pub(crate) mod util;
 
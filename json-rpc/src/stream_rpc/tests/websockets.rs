@@ -8,7 +8,11 @@ use crate::{
     },
     tests::utils::create_db_and_runtime,
 };
-use dijets_json_rpc_types::stream::response::StreamJsonRpcResponse;
+use dijets_json_rpc_types::{
+    stream::response::StreamJsonRpcResponse,
+    views::{AccountView, TransactionDataView, TransactionView},
+};
+use dijets_types::transaction::Transaction;
 use futures::{SinkExt, StreamExt};
 use reqwest::Url;
 use serde_json::json;
@@ -120,6 +124,118 @@ async fn test_websocket_fetching_data() {
     }
 }
 
+#[tokio::test]
+async fn test_websocket_filtered_transactions() {
+    let (mock_db, config) = ws_test_setup(5, 10, 100, 1000).await;
+
+    let sender = mock_db
+        .all_txns
+        .iter()
+        .find_map(|(tx, _)| match tx {
+            Transaction::UserTransaction(user_txn) => Some(user_txn.sender()),
+            _ => None,
+        })
+        .expect("mock db has user transactions");
+    let expected_versions: Vec<_> = mock_db
+        .all_txns
+        .iter()
+        .enumerate()
+        .filter(|(_, (tx, _))| {
+            matches!(tx, Transaction::UserTransaction(user_txn) if user_txn.sender() == sender)
+        })
+        .map(|(version, _)| version as u64)
+        .collect();
+
+    let name = "subscribe_to_transactions: filter by sender";
+    let (mut ws_client, _cm) = connect_to_ws(mock_db.clone(), &config, None).await;
+    let request = json!({"id": "client-generated-id", "method": "subscribe_to_transactions", "params": {"starting_version": 0, "filter": {"sender": sender}}, "jsonrpc": "2.0"});
+    ws_client.send_text(request.to_string()).await;
+    verify_ok(next_message(&mut ws_client, name).await, name);
+
+    for expected_version in expected_versions {
+        let msg = next_message(&mut ws_client, name).await;
+        let resp: StreamJsonRpcResponse =
+            serde_json::from_str(msg.to_str().expect("response")).unwrap();
+        let tx: TransactionView = serde_json::from_value(resp.result.unwrap()).unwrap();
+        assert_eq!(tx.version, expected_version);
+        assert!(
+            matches!(tx.transaction, TransactionDataView::UserTransaction { sender: s, .. } if s == sender)
+        );
+    }
+    close_ws(ws_client, name).await;
+
+    let name = "subscribe_to_transactions: unknown filter";
+    let (mut ws_client, _cm) = connect_to_ws(mock_db.clone(), &config, None).await;
+    let request = json!({"id": "client-generated-id", "method": "subscribe_to_transactions", "params": {"starting_version": 0, "filter": {"not_a_filter": 0}}, "jsonrpc": "2.0"});
+    ws_client.send_text(request.to_string()).await;
+    let expected = json!({"jsonrpc":"2.0","id":"client-generated-id","error":{"code":-32602,"message":"Invalid params for method 'subscribe_to_transactions'","data":null}}).to_string();
+    let result = next_message(&mut ws_client, name).await;
+    assert_eq!(result.to_str().unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_websocket_filtered_transactions_catch_up() {
+    // Fetch a single transaction at a time, with a poll interval longer than `next_message` waits
+    let (mock_db, config) = ws_test_setup(1, 10, 1000, 10000).await;
+
+    let sender = mock_db
+        .all_txns
+        .iter()
+        .rev()
+        .find_map(|(tx, _)| match tx {
+            Transaction::UserTransaction(user_txn) => Some(user_txn.sender()),
+            _ => None,
+        })
+        .expect("mock db has user transactions");
+    let expected_versions: Vec<_> = mock_db
+        .all_txns
+        .iter()
+        .enumerate()
+        .filter(|(_, (tx, _))| {
+            matches!(tx, Transaction::UserTransaction(user_txn) if user_txn.sender() == sender)
+        })
+        .map(|(version, _)| version as u64)
+        .collect();
+
+    // Batches which are entirely filtered out don't make the subscription back off
+    let name = "subscribe_to_transactions: catch up through filtered out batches";
+    let (mut ws_client, _cm) = connect_to_ws(mock_db.clone(), &config, None).await;
+    let request = json!({"id": "client-generated-id", "method": "subscribe_to_transactions", "params": {"starting_version": 0, "filter": {"sender": sender}}, "jsonrpc": "2.0"});
+    ws_client.send_text(request.to_string()).await;
+    verify_ok(next_message(&mut ws_client, name).await, name);
+
+    for expected_version in expected_versions {
+        let msg = next_message(&mut ws_client, name).await;
+        let resp: StreamJsonRpcResponse =
+            serde_json::from_str(msg.to_str().expect("response")).unwrap();
+        let tx: TransactionView = serde_json::from_value(resp.result.unwrap()).unwrap();
+        assert_eq!(tx.version, expected_version);
+    }
+    close_ws(ws_client, name).await;
+}
+
+#[tokio::test]
+async fn test_websocket_account_changes() {
+    let (mock_db, config) = ws_test_setup(5, 10, 100, 1000).await;
+    let address = *mock_db.all_accounts.keys().next().unwrap();
+
+    let name = "subscribe_to_account_changes: get account";
+    let (mut ws_client, cm) = connect_to_ws(mock_db.clone(), &config, None).await;
+    let request = json!({"id": "client-generated-id", "method": "subscribe_to_account_changes", "params": {"address": address}, "jsonrpc": "2.0"});
+    ws_client.send_text(request.to_string()).await;
+    verify_ok(next_message(&mut ws_client, name).await, name);
+    assert_eq!(num_tasks(&get_latest_client(&cm)), 1);
+
+    let msg = next_message(&mut ws_client, name).await;
+    let resp: StreamJsonRpcResponse =
+        serde_json::from_str(msg.to_str().expect("response")).unwrap();
+    let account: AccountView = serde_json::from_value(resp.result.unwrap()).unwrap();
+    assert_eq!(account.address, address);
+    assert_eq!(account.version, Some(mock_db.version));
+
+    close_ws(ws_client, name).await;
+}
+
 #[tokio::test]
 async fn test_multiple_subscriptions_and_response() {
     let (mock_db, config) = ws_test_setup(5, 10, 100, 1000).await;
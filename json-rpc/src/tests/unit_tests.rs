@@ -3,6 +3,7 @@
 
 use crate::{
     errors::ServerCode,
+    rate_limit::RpcRateLimiter,
     runtime::check_latest_ledger_info_timestamp,
     tests::utils::{
         create_database_client_and_runtime, create_db_and_runtime, mock_db, test_bootstrap,
@@ -12,12 +13,19 @@ use crate::{
     views::VMStatusView,
 };
 use dijets_client::{views::TransactionDataView, BlockingClient, MethodRequest};
-use dijets_config::{config::DEFAULT_CONTENT_LENGTH_LIMIT, utils};
+use dijets_config::{
+    config::{
+        JsonRpcRateLimitConfig, RoleType, StreamConfig, DEFAULT_BATCH_SIZE_LIMIT,
+        DEFAULT_CONTENT_LENGTH_LIMIT, DEFAULT_PAGE_SIZE_LIMIT,
+    },
+    utils,
+};
 use dijets_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
 use dijets_metrics::get_all_metrics;
+use dijets_transaction_builder::stdlib::encode_peer_to_peer_with_metadata_script_function;
 use dijets_types::{
     account_address::AccountAddress,
-    account_config::AccountResource,
+    account_config::{dijets_root_address, xus_tag, AccountResource},
     account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     chain_id::ChainId,
@@ -25,14 +33,15 @@ use dijets_types::{
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     proof::{SparseMerkleProof, TransactionAccumulatorProof, TransactionInfoWithProof},
-    test_helpers::transaction_test_helpers::get_test_signed_txn,
-    transaction::{Transaction, TransactionInfo, TransactionPayload},
+    test_helpers::transaction_test_helpers::{get_test_signed_txn, get_test_unchecked_txn},
+    transaction::{Transaction, TransactionInfo, TransactionPayload, TransactionWithProof},
     vm_status::StatusCode,
 };
 use futures::{channel::mpsc::channel, StreamExt};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use std::{
     cmp::{max, min},
+    collections::HashMap,
     convert::TryFrom,
     ops::Sub,
     sync::Arc,
@@ -1111,6 +1120,145 @@ fn test_transaction_submission() {
     assert_eq!(status_code, StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST);
 }
 
+#[test]
+fn test_rate_limit() {
+    let (mp_sender, _mp_events) = channel(1);
+    let port = utils::get_available_port();
+    let address = format!("127.0.0.1:{}", port);
+    let mut method_costs = HashMap::new();
+    method_costs.insert("get_currencies".to_string(), 3);
+    let rate_limit_config = JsonRpcRateLimitConfig {
+        enabled: true,
+        ip_bucket_size: 3,
+        ip_bucket_rate: 1,
+        api_key_bucket_size: 100,
+        api_key_bucket_rate: 100,
+        api_keys: vec!["secret".to_string()],
+        method_costs,
+        ..JsonRpcRateLimitConfig::default()
+    };
+    let _runtime = crate::bootstrap(
+        address.parse().unwrap(),
+        DEFAULT_BATCH_SIZE_LIMIT,
+        DEFAULT_PAGE_SIZE_LIMIT,
+        DEFAULT_CONTENT_LENGTH_LIMIT,
+        &None,
+        &None,
+        Arc::new(mock_db()),
+        mp_sender,
+        RoleType::Validator,
+        ChainId::test(),
+        &StreamConfig::default(),
+        &rate_limit_config,
+    );
+    let url = format!("http://{}/v1", address);
+    let client = reqwest::blocking::Client::new();
+    let get_metadata = json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1});
+    let get_currencies =
+        json!({"jsonrpc": "2.0", "method": "get_currencies", "params": [], "id": 1});
+
+    // a batch costs the sum of its requests, which can never fit in the bucket
+    let resp = client
+        .post(&url)
+        .json(&json!([get_metadata, get_currencies]))
+        .send()
+        .unwrap();
+    assert_eq!(resp.status(), 429);
+    assert!(resp.headers().get("retry-after").is_none());
+
+    // the bucket allows (at least) 3 requests of cost 1 before throttling the IP, more may get
+    // through if the bucket is refilled in the meantime
+    let mut allowed = 0;
+    let resp = loop {
+        let resp = client.post(&url).json(&get_metadata).send().unwrap();
+        if resp.status() != 200 {
+            break resp;
+        }
+        allowed += 1;
+        assert!(allowed < 10, "requests are not rate limited");
+    };
+    assert!(allowed >= 3);
+    assert_eq!(resp.status(), 429);
+    let retry_after: u64 = resp.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after >= 1);
+
+    // known API keys have their own quota
+    for _ in 0..10 {
+        let resp = client
+            .post(&url)
+            .header("authorization", "Bearer secret")
+            .json(&get_currencies)
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+    }
+}
+
+#[test]
+fn test_rate_limit_ipv6_prefix() {
+    let rate_limiter = RpcRateLimiter::new(&JsonRpcRateLimitConfig {
+        enabled: true,
+        ip_bucket_size: 1,
+        ip_bucket_rate: 1,
+        ..JsonRpcRateLimitConfig::default()
+    });
+    let request = json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1});
+    let check = |addr: &str| rate_limiter.check(Some(addr.parse().unwrap()), None, &request);
+
+    // IPv6 clients share a bucket per /64
+    check("[2001:db8::1]:1000").unwrap();
+    check("[2001:db8::2]:1000").unwrap_err();
+    check("[2001:db8:0:1::1]:1000").unwrap();
+
+    // IPv4 clients have a bucket per address, even when they are IPv4-mapped
+    check("[::ffff:10.0.0.1]:1000").unwrap();
+    check("10.0.0.1:1000").unwrap_err();
+    check("[::ffff:10.0.0.2]:1000").unwrap();
+}
+
+#[test]
+fn test_simulate_transaction() {
+    let (_mock_db, _runtime, url, _) = create_db_and_runtime();
+    let client = BlockingClient::new(url);
+
+    // simulating a transaction from an account which does not exist is rejected
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let sender = AccountAddress::new([0; AccountAddress::LENGTH]);
+    let txn = get_test_signed_txn(sender, 0, &privkey, privkey.public_key(), None);
+    let error = client.simulate_transaction(&txn).unwrap_err();
+    let error = error.json_rpc_error().unwrap();
+    assert_eq!(error.code, ServerCode::VmValidationError as i16);
+    let status_code: StatusCode = error.as_status_code().unwrap();
+    assert_eq!(status_code, StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST);
+
+    // the signature is not checked, and aborts are reported with their explanation
+    let sender = dijets_root_address();
+    let payload =
+        encode_peer_to_peer_with_metadata_script_function(xus_tag(), sender, 0, vec![], vec![]);
+    let txn = get_test_unchecked_txn(
+        sender,
+        0,
+        &privkey,
+        vm_genesis::GENESIS_KEYPAIR.1.clone(),
+        Some(payload),
+    );
+    let view = client.simulate_transaction(&txn).unwrap().into_inner();
+    assert_eq!(view.hash, Transaction::UserTransaction(txn).hash());
+    assert!(matches!(
+        view.vm_status,
+        VMStatusView::MoveAbort {
+            explanation: Some(_),
+            ..
+        }
+    ));
+    assert!(view.gas_used > 0);
+    assert!(!view.write_set.is_empty());
+}
+
 #[test]
 fn test_get_account() {
     let (mock_db, client, _runtime) = create_database_client_and_runtime();
@@ -1431,6 +1579,50 @@ fn test_get_account_transactions() {
         assert_eq!(tx_views.len() as u64, total);
     }
 }
+
+#[test]
+fn test_get_transaction_by_hash() {
+    let (mock_db, client, _runtime) = create_database_client_and_runtime();
+
+    for (version, (tx, _)) in mock_db.all_txns.iter().enumerate() {
+        let hash = tx.hash();
+
+        let tx_view = client
+            .get_transaction_by_hash(hash, true)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        assert_eq!(tx_view.version, version as u64);
+        assert_eq!(tx_view.hash, hash);
+
+        let tx_with_proof_view = client
+            .get_transaction_by_hash_with_proof(hash, false, None)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        let tx_with_proof = TransactionWithProof::try_from(&tx_with_proof_view).unwrap();
+        assert_eq!(tx_with_proof.version, version as u64);
+        assert_eq!(&tx_with_proof.transaction, tx);
+        assert_eq!(
+            tx_with_proof.proof.transaction_info.transaction_hash(),
+            hash
+        );
+        assert!(tx_with_proof.events.is_none());
+    }
+
+    // a hash which matches no transaction returns nothing
+    let unknown_hash = HashValue::random();
+    let tx_view = client
+        .get_transaction_by_hash(unknown_hash, false)
+        .unwrap()
+        .into_inner();
+    assert!(tx_view.is_none());
+    let tx_with_proof_view = client
+        .get_transaction_by_hash_with_proof(unknown_hash, false, None)
+        .unwrap()
+        .into_inner();
+    assert!(tx_with_proof_view.is_none());
+}
 #[test]
 // Check that if version and ledger_version parameters are None, then the server returns the latest
 // known state.
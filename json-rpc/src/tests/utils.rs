@@ -4,14 +4,14 @@
 use anyhow::{format_err, Error, Result};
 use dijets_config::{
     config::{
-        RoleType, StreamConfig, DEFAULT_BATCH_SIZE_LIMIT, DEFAULT_CONTENT_LENGTH_LIMIT,
-        DEFAULT_PAGE_SIZE_LIMIT, DEFAULT_STREAM_RPC_MAX_POLL_INTERVAL_MS,
-        DEFAULT_STREAM_RPC_POLL_INTERVAL_MS, DEFAULT_STREAM_RPC_SEND_QUEUE_SIZE,
-        DEFAULT_STREAM_RPC_SUBSCRIPTION_FETCH_SIZE,
+        JsonRpcRateLimitConfig, RoleType, StreamConfig, DEFAULT_BATCH_SIZE_LIMIT,
+        DEFAULT_CONTENT_LENGTH_LIMIT, DEFAULT_PAGE_SIZE_LIMIT,
+        DEFAULT_STREAM_RPC_MAX_POLL_INTERVAL_MS, DEFAULT_STREAM_RPC_POLL_INTERVAL_MS,
+        DEFAULT_STREAM_RPC_SEND_QUEUE_SIZE, DEFAULT_STREAM_RPC_SUBSCRIPTION_FETCH_SIZE,
     },
     utils,
 };
-use dijets_crypto::HashValue;
+use dijets_crypto::{hash::CryptoHash, HashValue};
 use dijets_mempool::{MempoolClientSender, SubmissionStatus};
 use dijets_types::{
     account_address::AccountAddress,
@@ -88,6 +88,7 @@ pub fn test_bootstrap(
         RoleType::Validator,
         ChainId::test(),
         &stream_config,
+        &JsonRpcRateLimitConfig::default(),
     )
 }
 
@@ -104,6 +105,42 @@ pub struct MockDijetsDB {
     pub timestamps: Vec<u64>,
 }
 
+impl MockDijetsDB {
+    fn get_transaction_with_proof(
+        &self,
+        version: u64,
+        include_events: bool,
+        transaction_hash: HashValue,
+    ) -> TransactionWithProof {
+        let (tx, status) = &self.all_txns[version as usize];
+        TransactionWithProof {
+            version,
+            transaction: tx.clone(),
+            events: if include_events {
+                let events = self
+                    .events
+                    .iter()
+                    .filter(|(ev, _)| *ev == version)
+                    .map(|(_, e)| e.clone())
+                    .collect();
+                Some(events)
+            } else {
+                None
+            },
+            proof: TransactionInfoWithProof::new(
+                TransactionAccumulatorProof::new(vec![]),
+                TransactionInfo::new(
+                    transaction_hash,
+                    Default::default(),
+                    Default::default(),
+                    0,
+                    status.clone(),
+                ),
+            ),
+        }
+    }
+}
+
 impl DbReader for MockDijetsDB {
     fn get_latest_account_state(
         &self,
@@ -173,38 +210,27 @@ impl DbReader for MockDijetsDB {
                     false
                 }
             })
-            .map(|(v, (tx, status))| {
-                let txn_with_proof = TransactionWithProof {
-                    version: v as u64,
-                    transaction: tx.clone(),
-                    events: if include_events {
-                        let events = self
-                            .events
-                            .iter()
-                            .filter(|(ev, _)| *ev == v as u64)
-                            .map(|(_, e)| e.clone())
-                            .collect();
-                        Some(events)
-                    } else {
-                        None
-                    },
-                    proof: TransactionInfoWithProof::new(
-                        TransactionAccumulatorProof::new(vec![]),
-                        TransactionInfo::new(
-                            Default::default(),
-                            Default::default(),
-                            Default::default(),
-                            0,
-                            status.clone(),
-                        ),
-                    ),
-                };
-                Ok(txn_with_proof)
+            .map(|(v, _)| {
+                self.get_transaction_with_proof(v as u64, include_events, Default::default())
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Vec<_>>();
         Ok(AccountTransactionsWithProof::new(txns_with_proofs))
     }
 
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        Ok(self
+            .all_txns
+            .iter()
+            .take(ledger_version as usize + 1)
+            .position(|(tx, _)| tx.hash() == hash)
+            .map(|v| self.get_transaction_with_proof(v as u64, fetch_events, hash)))
+    }
+
     fn get_transactions(
         &self,
         start_version: u64,
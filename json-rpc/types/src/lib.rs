@@ -44,6 +44,7 @@ pub enum Method {
     GetTransactions,
     GetAccountTransaction,
     GetAccountTransactions,
+    GetTransactionByHash,
     GetEvents,
     GetCurrencies,
     GetNetworkStatus,
@@ -57,8 +58,10 @@ pub enum Method {
     GetAccountStateWithProof,
     GetTransactionsWithProofs,
     GetAccountTransactionsWithProofs,
+    GetTransactionByHashWithProof,
     GetEventsWithProofs,
     GetEventByVersionWithProof,
+    SimulateTransaction,
 }
 
 impl Method {
@@ -70,6 +73,7 @@ impl Method {
             Method::GetTransactions => "get_transactions",
             Method::GetAccountTransaction => "get_account_transaction",
             Method::GetAccountTransactions => "get_account_transactions",
+            Method::GetTransactionByHash => "get_transaction_by_hash",
             Method::GetEvents => "get_events",
             Method::GetCurrencies => "get_currencies",
             Method::GetNetworkStatus => "get_network_status",
@@ -79,8 +83,10 @@ impl Method {
             Method::GetAccountStateWithProof => "get_account_state_with_proof",
             Method::GetTransactionsWithProofs => "get_transactions_with_proofs",
             Method::GetAccountTransactionsWithProofs => "get_account_transactions_with_proofs",
+            Method::GetTransactionByHashWithProof => "get_transaction_by_hash_with_proof",
             Method::GetEventsWithProofs => "get_events_with_proofs",
             Method::GetEventByVersionWithProof => "get_event_by_version_with_proof",
+            Method::SimulateTransaction => "simulate_transaction",
         }
     }
 }
@@ -3,6 +3,7 @@
 
 use super::{Id, JsonRpcVersion, Method};
 use crate::{errors::JsonRpcError, views::BytesView};
+use dijets_crypto::HashValue;
 use dijets_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
@@ -69,6 +70,7 @@ pub enum MethodRequest {
     GetTransactions(GetTransactionsParams),
     GetAccountTransaction(GetAccountTransactionParams),
     GetAccountTransactions(GetAccountTransactionsParams),
+    GetTransactionByHash(GetTransactionByHashParams),
     GetEvents(GetEventsParams),
     GetCurrencies(GetCurrenciesParams),
     GetNetworkStatus(GetNetworkStatusParams),
@@ -82,8 +84,10 @@ pub enum MethodRequest {
     GetAccountStateWithProof(GetAccountStateWithProofParams),
     GetTransactionsWithProofs(GetTransactionsWithProofsParams),
     GetAccountTransactionsWithProofs(GetAccountTransactionsWithProofsParams),
+    GetTransactionByHashWithProof(GetTransactionByHashWithProofParams),
     GetEventsWithProofs(GetEventsWithProofsParams),
     GetEventByVersionWithProof(GetEventByVersionWithProof),
+    SimulateTransaction(SimulateTransactionParams),
 }
 
 impl MethodRequest {
@@ -101,6 +105,9 @@ impl MethodRequest {
             Method::GetAccountTransactions => {
                 MethodRequest::GetAccountTransactions(serde_json::from_value(value)?)
             }
+            Method::GetTransactionByHash => {
+                MethodRequest::GetTransactionByHash(serde_json::from_value(value)?)
+            }
             Method::GetEvents => MethodRequest::GetEvents(serde_json::from_value(value)?),
             Method::GetCurrencies => MethodRequest::GetCurrencies(serde_json::from_value(value)?),
             Method::GetNetworkStatus => {
@@ -120,12 +127,18 @@ impl MethodRequest {
             Method::GetAccountTransactionsWithProofs => {
                 MethodRequest::GetAccountTransactionsWithProofs(serde_json::from_value(value)?)
             }
+            Method::GetTransactionByHashWithProof => {
+                MethodRequest::GetTransactionByHashWithProof(serde_json::from_value(value)?)
+            }
             Method::GetEventsWithProofs => {
                 MethodRequest::GetEventsWithProofs(serde_json::from_value(value)?)
             }
             Method::GetEventByVersionWithProof => {
                 MethodRequest::GetEventByVersionWithProof(serde_json::from_value(value)?)
             }
+            Method::SimulateTransaction => {
+                MethodRequest::SimulateTransaction(serde_json::from_value(value)?)
+            }
         };
 
         Ok(method_request)
@@ -139,6 +152,7 @@ impl MethodRequest {
             MethodRequest::GetTransactions(_) => Method::GetTransactions,
             MethodRequest::GetAccountTransaction(_) => Method::GetAccountTransaction,
             MethodRequest::GetAccountTransactions(_) => Method::GetAccountTransactions,
+            MethodRequest::GetTransactionByHash(_) => Method::GetTransactionByHash,
             MethodRequest::GetEvents(_) => Method::GetEvents,
             MethodRequest::GetCurrencies(_) => Method::GetCurrencies,
             MethodRequest::GetNetworkStatus(_) => Method::GetNetworkStatus,
@@ -152,8 +166,12 @@ impl MethodRequest {
             MethodRequest::GetAccountTransactionsWithProofs(_) => {
                 Method::GetAccountTransactionsWithProofs
             }
+            MethodRequest::GetTransactionByHashWithProof(_) => {
+                Method::GetTransactionByHashWithProof
+            }
             MethodRequest::GetEventsWithProofs(_) => Method::GetEventsWithProofs,
             MethodRequest::GetEventByVersionWithProof(_) => Method::GetEventByVersionWithProof,
+            MethodRequest::SimulateTransaction(_) => Method::SimulateTransaction,
         }
     }
 }
@@ -243,6 +261,12 @@ pub struct GetAccountTransactionsParams {
     pub include_events: bool,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionByHashParams {
+    pub hash: HashValue,
+    pub include_events: bool,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GetEventsParams {
     pub key: EventKey,
@@ -369,6 +393,14 @@ pub struct GetAccountTransactionsWithProofsParams {
     pub ledger_version: Option<u64>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetTransactionByHashWithProofParams {
+    pub hash: HashValue,
+    pub include_events: bool,
+    #[serde(default)]
+    pub ledger_version: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GetEventsWithProofsParams {
     pub key: EventKey,
@@ -383,6 +415,13 @@ pub struct GetEventByVersionWithProof {
     pub version: Option<u64>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SimulateTransactionParams {
+    #[serde(serialize_with = "serialize_signed_transaction")]
+    #[serde(deserialize_with = "deserialize_signed_transaction")]
+    pub data: SignedTransaction,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
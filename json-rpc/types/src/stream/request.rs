@@ -2,7 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{errors::JsonRpcError, request::RawJsonRpcRequest, Id, JsonRpcVersion};
-use dijets_types::event::EventKey;
+use dijets_types::{
+    account_address::AccountAddress,
+    contract_event::ContractEvent,
+    event::EventKey,
+    transaction::{Transaction, TransactionInfo, TransactionPayload},
+    vm_status::KeptVMStatus,
+};
+use move_core_types::language_storage::{StructTag, TypeTag};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -68,12 +75,13 @@ impl FromStr for StreamJsonRpcRequest {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "method", content = "params")]
 pub enum StreamMethodRequest {
     SubscribeToTransactions(SubscribeToTransactionsParams),
     SubscribeToEvents(SubscribeToEventsParams),
+    SubscribeToAccountChanges(SubscribeToAccountChangesParams),
     Unsubscribe,
 }
 
@@ -93,6 +101,9 @@ impl StreamMethodRequest {
             StreamMethod::SubscribeToEvents => {
                 StreamMethodRequest::SubscribeToEvents(serde_json::from_value(value)?)
             }
+            StreamMethod::SubscribeToAccountChanges => {
+                StreamMethodRequest::SubscribeToAccountChanges(serde_json::from_value(value)?)
+            }
             StreamMethod::Unsubscribe => StreamMethodRequest::Unsubscribe,
         };
 
@@ -105,6 +116,9 @@ impl StreamMethodRequest {
                 StreamMethod::SubscribeToTransactions
             }
             StreamMethodRequest::SubscribeToEvents(_) => StreamMethod::SubscribeToEvents,
+            StreamMethodRequest::SubscribeToAccountChanges(_) => {
+                StreamMethod::SubscribeToAccountChanges
+            }
             StreamMethodRequest::Unsubscribe => StreamMethod::Unsubscribe,
         }
     }
@@ -115,6 +129,7 @@ impl StreamMethodRequest {
 pub enum StreamMethod {
     SubscribeToTransactions,
     SubscribeToEvents,
+    SubscribeToAccountChanges,
     Unsubscribe,
 }
 
@@ -123,6 +138,7 @@ impl StreamMethod {
         match self {
             StreamMethod::SubscribeToTransactions => "subscribe_to_transactions",
             StreamMethod::SubscribeToEvents => "subscribe_to_events",
+            StreamMethod::SubscribeToAccountChanges => "subscribe_to_account_changes",
             StreamMethod::Unsubscribe => "unsubscribe",
         }
     }
@@ -134,8 +150,135 @@ pub struct SubscribeToEventsParams {
     pub event_seq_num: u64,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SubscribeToTransactionsParams {
     pub starting_version: u64,
     pub include_events: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<TransactionFilter>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct SubscribeToAccountChangesParams {
+    pub address: AccountAddress,
+}
+
+/// Server-side filter for `subscribe_to_transactions`: only transactions matching all of the set
+/// criteria are sent to the client.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionFilter {
+    /// Only user transactions sent by this account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<AccountAddress>,
+    /// Only user transactions calling a matching script function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_function: Option<ScriptFunctionFilter>,
+    /// Only transactions which emitted at least one event of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<StructTag>,
+    /// Only transactions which were kept with this VM status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_status: Option<VMStatusFilter>,
+}
+
+impl TransactionFilter {
+    /// Whether the filter needs the events of a transaction to be able to match it
+    pub fn requires_events(&self) -> bool {
+        self.event_type.is_some()
+    }
+
+    pub fn matches(
+        &self,
+        txn: &Transaction,
+        txn_info: &TransactionInfo,
+        events: &[ContractEvent],
+    ) -> bool {
+        if self.sender.is_some() || self.script_function.is_some() {
+            let user_txn = match txn {
+                Transaction::UserTransaction(user_txn) => user_txn,
+                _ => return false,
+            };
+            if let Some(sender) = self.sender {
+                if user_txn.sender() != sender {
+                    return false;
+                }
+            }
+            if let Some(script_function) = &self.script_function {
+                if !script_function.matches(user_txn.payload()) {
+                    return false;
+                }
+            }
+        }
+        if let Some(event_type) = &self.event_type {
+            let has_event = events.iter().any(|event| match event.type_tag() {
+                TypeTag::Struct(tag) => tag == event_type,
+                _ => false,
+            });
+            if !has_event {
+                return false;
+            }
+        }
+        if let Some(vm_status) = self.vm_status {
+            if !vm_status.matches(txn_info.status()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Matches script functions by module, and optionally by function name
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptFunctionFilter {
+    pub address: AccountAddress,
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
+impl ScriptFunctionFilter {
+    fn matches(&self, payload: &TransactionPayload) -> bool {
+        match payload {
+            TransactionPayload::ScriptFunction(script_function) => {
+                let module = script_function.module();
+                module.address() == &self.address
+                    && module.name().as_str() == self.module
+                    && self.function.as_ref().map_or(true, |function| {
+                        script_function.function().as_str() == function
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VMStatusFilter {
+    Executed,
+    OutOfGas,
+    MoveAbort,
+    ExecutionFailure,
+    MiscellaneousError,
+}
+
+impl VMStatusFilter {
+    fn matches(&self, status: &KeptVMStatus) -> bool {
+        matches!(
+            (self, status),
+            (VMStatusFilter::Executed, KeptVMStatus::Executed)
+                | (VMStatusFilter::OutOfGas, KeptVMStatus::OutOfGas)
+                | (VMStatusFilter::MoveAbort, KeptVMStatus::MoveAbort(..))
+                | (
+                    VMStatusFilter::ExecutionFailure,
+                    KeptVMStatus::ExecutionFailure { .. }
+                )
+                | (
+                    VMStatusFilter::MiscellaneousError,
+                    KeptVMStatus::MiscellaneousError
+                )
+        )
+    }
 }
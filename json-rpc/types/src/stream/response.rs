@@ -4,7 +4,7 @@
 use crate::{
     errors::JsonRpcError,
     stream::request::StreamMethod,
-    views::{EventView, TransactionView},
+    views::{AccountView, EventView, TransactionView},
     Id, JsonRpcVersion,
 };
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,7 @@ use std::str::FromStr;
 pub enum StreamJsonRpcResponseView {
     Transaction(TransactionView),
     Event(EventView),
+    Account(AccountView),
     SubscribeResult(SubscribeResult),
     UnsubscribeResult(UnsubscribeResult),
 }
@@ -36,6 +37,9 @@ impl StreamJsonRpcResponseView {
                 Self::Transaction(serde_json::from_value(value)?)
             }
             StreamMethod::SubscribeToEvents => Self::Event(serde_json::from_value(value)?),
+            StreamMethod::SubscribeToAccountChanges => {
+                Self::Account(serde_json::from_value(value)?)
+            }
             StreamMethod::Unsubscribe => Self::UnsubscribeResult(serde_json::from_value(value)?),
         })
     }
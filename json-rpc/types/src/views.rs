@@ -5,6 +5,7 @@ use anyhow::{ensure, format_err, Error, Result};
 use dijets_crypto::hash::{CryptoHash, HashValue};
 use dijets_transaction_builder::{error_explain, stdlib::ScriptCall};
 use dijets_types::{
+    access_path::AccessPath,
     account_config::{
         AccountResource, AccountRole, AdminTransactionEvent, BalanceResource, BaseUrlRotationEvent,
         BurnEvent, CancelBurnEvent, ComplianceKeyRotationEvent, CreateAccountEvent,
@@ -24,9 +25,11 @@ use dijets_types::{
     state_proof::StateProof,
     transaction::{
         AccountTransactionsWithProof, Script, ScriptFunction, Transaction, TransactionArgument,
-        TransactionInfo, TransactionListWithProof, TransactionPayload,
+        TransactionInfo, TransactionListWithProof, TransactionOutput, TransactionPayload,
+        TransactionStatus, TransactionWithProof,
     },
     vm_status::KeptVMStatus,
+    write_set::WriteOp,
 };
 use hex::FromHex;
 use move_core_types::{
@@ -840,6 +843,84 @@ impl TransactionView {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SimulatedTransactionView {
+    pub transaction: TransactionDataView,
+    pub hash: HashValue,
+    pub events: Vec<EventView>,
+    pub write_set: Vec<WriteSetChangeView>,
+    pub vm_status: VMStatusView,
+    pub gas_used: u64,
+}
+
+impl SimulatedTransactionView {
+    /// `version` is the version the transaction would have been committed at; it is only used
+    /// as the transaction version of the emitted events.
+    pub fn try_from_tx_and_output(
+        version: u64,
+        tx: Transaction,
+        output: TransactionOutput,
+    ) -> Result<Self> {
+        let status = match output.status() {
+            TransactionStatus::Keep(status) => status,
+            status => {
+                return Err(format_err!(
+                    "expected a kept transaction status, received {:?}",
+                    status
+                ))
+            }
+        };
+        let events = output
+            .events()
+            .iter()
+            .map(|event| EventView::try_from((version, event.clone())))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SimulatedTransactionView {
+            hash: tx.hash(),
+            transaction: TransactionDataView::from(tx),
+            events,
+            write_set: output
+                .write_set()
+                .iter()
+                .map(WriteSetChangeView::from)
+                .collect(),
+            vm_status: VMStatusView::from(status),
+            gas_used: output.gas_used(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum WriteSetChangeView {
+    Value {
+        address: AccountAddress,
+        path: BytesView,
+        value_size: u64,
+    },
+    Deletion {
+        address: AccountAddress,
+        path: BytesView,
+    },
+}
+
+impl From<&(AccessPath, WriteOp)> for WriteSetChangeView {
+    fn from((access_path, op): &(AccessPath, WriteOp)) -> Self {
+        let address = access_path.address;
+        let path = access_path.path.as_slice().into();
+        match op {
+            WriteOp::Value(value) => WriteSetChangeView::Value {
+                address,
+                path,
+                value_size: value.len() as u64,
+            },
+            WriteOp::Deletion => WriteSetChangeView::Deletion { address, path },
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct TransactionListView(pub Vec<TransactionView>);
 
@@ -1017,6 +1098,29 @@ impl TryFrom<&TransactionsProofsView> for TransactionListProof {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionWithProofView {
+    pub serialized_txn_with_proof: BytesView,
+}
+
+impl TryFrom<&TransactionWithProof> for TransactionWithProofView {
+    type Error = Error;
+
+    fn try_from(txn: &TransactionWithProof) -> Result<Self, Self::Error> {
+        Ok(Self {
+            serialized_txn_with_proof: BytesView::new(bcs::to_bytes(txn)?),
+        })
+    }
+}
+
+impl TryFrom<&TransactionWithProofView> for TransactionWithProof {
+    type Error = Error;
+
+    fn try_from(view: &TransactionWithProofView) -> Result<Self, Self::Error> {
+        Ok(bcs::from_bytes(view.serialized_txn_with_proof.as_ref())?)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountTransactionsWithProofView {
     pub serialized_txns_with_proofs: Vec<BytesView>,
@@ -9,9 +9,12 @@ use std::{
     hash::Hash,
 };
 
+mod speculative;
 #[cfg(test)]
 mod unit_tests;
 
+pub use speculative::{Incarnation, SpeculativeMVHashMap, SpeculativeVersion, TxnIndex};
+
 /// A structure that holds placeholders for each write to the database
 //
 //  The structure is created by one thread creating the scheduling, and
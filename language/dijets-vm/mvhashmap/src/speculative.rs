@@ -0,0 +1,111 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crossbeam_utils::CachePadded;
+use dashmap::DashMap;
+use std::{collections::btree_map::BTreeMap, hash::Hash, sync::Arc};
+
+/// Index of a transaction in the block.
+pub type TxnIndex = usize;
+/// Number of times a transaction has been (re-)executed.
+pub type Incarnation = usize;
+/// The version of a write: the transaction that produced it and the incarnation it came from.
+pub type SpeculativeVersion = (TxnIndex, Incarnation);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Flag {
+    Done,
+    Estimate,
+}
+
+// The value written by a transaction incarnation. Once the writing incarnation gets aborted the
+// entry is kept but flagged as an estimate, so that readers know they depend on a transaction
+// that will most likely write the same location again.
+struct SpeculativeCell<V> {
+    flag: Flag,
+    incarnation: Incarnation,
+    data: Arc<V>,
+}
+
+impl<V> SpeculativeCell<V> {
+    fn new(incarnation: Incarnation, data: V) -> Self {
+        Self {
+            flag: Flag::Done,
+            incarnation,
+            data: Arc::new(data),
+        }
+    }
+}
+
+/// A multi-version data structure where the set of written keys does not need to be known
+/// upfront, used by speculative (Block-STM style) parallel execution.
+///
+/// Unlike [`MVHashMap`](crate::MVHashMap), where every entry is written exactly once, an entry
+/// here can be rewritten by every incarnation of a transaction, flagged as an estimate once the
+/// writing incarnation is aborted, and removed when a re-execution no longer writes the key.
+pub struct SpeculativeMVHashMap<K, V> {
+    data: DashMap<K, BTreeMap<TxnIndex, CachePadded<SpeculativeCell<V>>>>,
+}
+
+impl<K: Hash + Clone + Eq, V> SpeculativeMVHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            data: DashMap::new(),
+        }
+    }
+
+    /// Write `data` to `key` on behalf of the incarnation given by `version`. Replaces any value
+    /// previously written by an earlier incarnation of the same transaction.
+    pub fn write(&self, key: &K, version: SpeculativeVersion, data: V) {
+        let (txn_idx, incarnation) = version;
+        let mut map = self.data.entry(key.clone()).or_insert_with(BTreeMap::new);
+        let prev = map.insert(
+            txn_idx,
+            CachePadded::new(SpeculativeCell::new(incarnation, data)),
+        );
+
+        // Incarnations of the same transaction are never executed concurrently, and the
+        // incarnation number only grows.
+        assert!(prev.map_or(true, |cell| cell.incarnation < incarnation));
+    }
+
+    /// Flag the value written by transaction `txn_idx` at `key` as an estimate. This must only be
+    /// called after the writing incarnation got aborted.
+    pub fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
+        let mut map = self.data.get_mut(key).expect("Path must exist");
+        map.get_mut(&txn_idx).expect("Entry by txn must exist").flag = Flag::Estimate;
+    }
+
+    /// Remove the value written by transaction `txn_idx` at `key`, after a re-execution of the
+    /// transaction no longer writes it.
+    pub fn delete(&self, key: &K, txn_idx: TxnIndex) {
+        let mut map = self.data.get_mut(key).expect("Path must exist");
+        map.remove(&txn_idx);
+    }
+
+    /// Get the value of `key` visible to transaction `txn_idx`, i.e. the one written by the
+    /// highest transaction with a lower index.
+    /// Returns Ok((version, value)) if such a value was written by a previous transaction.
+    /// Returns Err(None) if no previous transaction wrote `key`, so the value must be read from
+    /// storage.
+    /// Returns Err(Some(txn_idx)) if the closest write is an estimate of transaction `txn_idx`,
+    /// which `txn_idx` then depends on.
+    pub fn read(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> Result<(SpeculativeVersion, Arc<V>), Option<TxnIndex>> {
+        let map = self.data.get(key).ok_or(None)?;
+        let (idx, cell) = map.range(0..txn_idx).next_back().ok_or(None)?;
+        match cell.flag {
+            Flag::Estimate => Err(Some(*idx)),
+            Flag::Done => Ok(((*idx, cell.incarnation), cell.data.clone())),
+        }
+    }
+}
+
+impl<K: Hash + Clone + Eq, V> Default for SpeculativeMVHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -56,3 +56,55 @@ fn create_write_read_placeholder_struct() {
     let r1 = mvtbl.read(&ap2, 25);
     assert_eq!(Ok(&Some(vec![0, 0, 0])), r1);
 }
+
+#[test]
+fn speculative_write_estimate_delete() {
+    let ap1 = b"/foo/b".to_vec();
+    let ap2 = b"/foo/c".to_vec();
+
+    let mvtbl: SpeculativeMVHashMap<Vec<u8>, Vec<u8>> = SpeculativeMVHashMap::new();
+
+    // Reads of unwritten keys should go to the DB.
+    assert_eq!(Err(None), mvtbl.read(&ap1, 5).map(|(v, _)| v));
+
+    mvtbl.write(&ap1, (10, 0), vec![0, 0, 0]);
+
+    // Reads at a version return the previous versions, not this version.
+    assert_eq!(Err(None), mvtbl.read(&ap1, 10).map(|(v, _)| v));
+    let (version, value) = mvtbl.read(&ap1, 15).unwrap();
+    assert_eq!((10, 0), version);
+    assert_eq!(vec![0, 0, 0], *value);
+
+    // An aborted write turns into a dependency for higher transactions.
+    mvtbl.mark_estimate(&ap1, 10);
+    assert_eq!(Err(Some(10)), mvtbl.read(&ap1, 15).map(|(v, _)| v));
+
+    // The next incarnation replaces the estimate.
+    mvtbl.write(&ap1, (10, 1), vec![1, 1, 1]);
+    let (version, value) = mvtbl.read(&ap1, 15).unwrap();
+    assert_eq!((10, 1), version);
+    assert_eq!(vec![1, 1, 1], *value);
+
+    // Higher reads see the closest lower write.
+    mvtbl.write(&ap1, (12, 0), vec![2, 2, 2]);
+    assert_eq!((12, 0), mvtbl.read(&ap1, 15).unwrap().0);
+    assert_eq!((10, 1), mvtbl.read(&ap1, 12).unwrap().0);
+
+    // Deleted writes are no longer visible.
+    mvtbl.delete(&ap1, 12);
+    assert_eq!((10, 1), mvtbl.read(&ap1, 15).unwrap().0);
+
+    // Keys are independent.
+    mvtbl.write(&ap2, (3, 0), vec![3]);
+    assert_eq!((3, 0), mvtbl.read(&ap2, 15).unwrap().0);
+    assert_eq!((10, 1), mvtbl.read(&ap1, 15).unwrap().0);
+}
+
+#[test]
+#[should_panic]
+fn speculative_write_older_incarnation() {
+    let ap = b"/foo/b".to_vec();
+    let mvtbl: SpeculativeMVHashMap<Vec<u8>, Vec<u8>> = SpeculativeMVHashMap::new();
+    mvtbl.write(&ap, (1, 1), vec![1]);
+    mvtbl.write(&ap, (1, 0), vec![0]);
+}
@@ -0,0 +1,13 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The block contains a write to a module path. Code is not multi-versioned, so the block
+    /// needs to be executed sequentially instead.
+    ModulePathReadWrite,
+    /// Execution of a transaction aborted with an error that must be propagated to the caller.
+    UserError(E),
+}
+
+pub type Result<T, E> = ::std::result::Result<T, Error<E>>;
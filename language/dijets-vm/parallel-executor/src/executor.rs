@@ -0,0 +1,309 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    errors::*,
+    scheduler::{Scheduler, SchedulerTask, TaskGuard},
+    task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
+    txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
+};
+use dijets_infallible::Mutex;
+use mvhashmap::{SpeculativeMVHashMap, SpeculativeVersion, TxnIndex};
+use std::{
+    collections::HashSet,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A view of the multi-version data structure for a given transaction, which records every read
+/// so that the transaction can be validated after its execution.
+pub struct MVHashMapView<'a, K, V> {
+    versioned_map: &'a SpeculativeMVHashMap<K, V>,
+    txn_idx: TxnIndex,
+    scheduler: &'a Scheduler,
+    captured_reads: Mutex<Vec<ReadDescriptor<K>>>,
+}
+
+impl<'a, K: ModulePath + PartialOrd + Send + Clone + Hash + Eq, V: Send + Sync>
+    MVHashMapView<'a, K, V>
+{
+    /// Captures a read from the VM execution. Returns `None` if no lower transaction wrote
+    /// `key`, in which case the value must be read from storage.
+    pub fn read(&self, key: &K) -> Option<Arc<V>> {
+        // Code is not multi-versioned: blocks writing modules are rejected after execution.
+        if key.is_module_path() {
+            return None;
+        }
+
+        loop {
+            match self.versioned_map.read(key, self.txn_idx) {
+                Ok((version, v)) => {
+                    self.captured_reads
+                        .lock()
+                        .push(ReadDescriptor::from_version(key.clone(), version));
+                    return Some(v);
+                }
+                Err(None) => {
+                    self.captured_reads
+                        .lock()
+                        .push(ReadDescriptor::from_storage(key.clone()));
+                    return None;
+                }
+                Err(Some(dep_idx)) => {
+                    // Don't start execution of a transaction that reads an estimate: wait for
+                    // the dependency to be executed again and retry the read.
+                    if let Some(dep_condvar) =
+                        self.scheduler.wait_for_dependency(self.txn_idx, dep_idx)
+                    {
+                        let (lock, cvar) = &*dep_condvar;
+                        let mut dep_resolved = lock.lock().expect("Lock poisoned");
+                        while !*dep_resolved {
+                            dep_resolved = cvar.wait(dep_resolved).expect("Lock poisoned");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The index of the transaction this view is for.
+    pub fn txn_idx(&self) -> TxnIndex {
+        self.txn_idx
+    }
+
+    fn take_reads(&self) -> Vec<ReadDescriptor<K>> {
+        std::mem::take(&mut self.captured_reads.lock())
+    }
+}
+
+pub struct ParallelTransactionExecutor<T: Transaction, E: ExecutorTask> {
+    num_cpus: usize,
+    phantom: PhantomData<(T, E)>,
+}
+
+impl<T, E> ParallelTransactionExecutor<T, E>
+where
+    T: Transaction,
+    E: ExecutorTask<T = T>,
+{
+    /// The caller needs to ensure that num_cpus > 0.
+    pub fn new() -> Self {
+        Self::with_num_cpus(num_cpus::get())
+    }
+
+    pub fn with_num_cpus(num_cpus: usize) -> Self {
+        assert!(
+            num_cpus > 0,
+            "Parallel execution requires at least one thread"
+        );
+        Self {
+            num_cpus,
+            phantom: PhantomData,
+        }
+    }
+
+    fn execute<'a>(
+        &self,
+        version: SpeculativeVersion,
+        guard: TaskGuard<'a>,
+        signature_verified_block: &[T],
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        versioned_data_cache: &SpeculativeMVHashMap<T::Key, T::Value>,
+        module_write_detected: &AtomicBool,
+        scheduler: &'a Scheduler,
+        executor: &E,
+    ) -> SchedulerTask<'a> {
+        let (idx_to_execute, incarnation) = version;
+        let txn = &signature_verified_block[idx_to_execute];
+
+        let view = MVHashMapView {
+            versioned_map: versioned_data_cache,
+            txn_idx: idx_to_execute,
+            scheduler,
+            captured_reads: Mutex::new(Vec::new()),
+        };
+        let execute_result = executor.execute_transaction(&view, txn);
+
+        let prev_write_keys = last_input_output.write_set(idx_to_execute);
+        let mut write_keys = HashSet::new();
+
+        // For tracking whether the recent execution wrote outside of the previous write set.
+        let mut updates_outside = false;
+        let mut apply_writes = |output: &E::Output| {
+            for (k, v) in output.get_writes().into_iter() {
+                if k.is_module_path() {
+                    module_write_detected.store(true, Ordering::Relaxed);
+                }
+                if !prev_write_keys.contains(&k) {
+                    updates_outside = true;
+                }
+                versioned_data_cache.write(&k, version, v);
+                write_keys.insert(k);
+            }
+        };
+
+        match &execute_result {
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                apply_writes(output)
+            }
+            // An aborted transaction doesn't write anything.
+            ExecutionStatus::Abort(_) => {}
+        };
+
+        // Remove entries from previous write set that were not overwritten.
+        for k in prev_write_keys.difference(&write_keys) {
+            versioned_data_cache.delete(k, idx_to_execute);
+        }
+
+        last_input_output.record(
+            idx_to_execute,
+            view.take_reads(),
+            execute_result,
+            write_keys,
+        );
+        scheduler.finish_execution(idx_to_execute, incarnation, updates_outside, guard)
+    }
+
+    fn validate<'a>(
+        &self,
+        version_to_validate: SpeculativeVersion,
+        guard: TaskGuard<'a>,
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        versioned_data_cache: &SpeculativeMVHashMap<T::Key, T::Value>,
+        scheduler: &'a Scheduler,
+    ) -> SchedulerTask<'a> {
+        let (idx_to_validate, incarnation) = version_to_validate;
+        let read_set = last_input_output
+            .read_set(idx_to_validate)
+            .expect("Prior read-set must be recorded");
+
+        let valid = read_set.iter().all(|r| {
+            match versioned_data_cache.read(r.path(), idx_to_validate) {
+                Ok((version, _)) => r.validate(Some(version)),
+                Err(None) => r.validate(None),
+                // Reading an estimate means the dependency got aborted since.
+                Err(Some(_)) => false,
+            }
+        });
+
+        let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
+
+        if aborted {
+            // Not valid and successfully aborted, mark the latest write-set as estimates.
+            for k in &last_input_output.write_set(idx_to_validate) {
+                versioned_data_cache.mark_estimate(k, idx_to_validate);
+            }
+
+            scheduler.finish_abort(idx_to_validate, incarnation, guard)
+        } else {
+            SchedulerTask::NoTask
+        }
+    }
+
+    fn work_task_with_scope(
+        &self,
+        executor_arguments: &E::Argument,
+        block: &[T],
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        versioned_data_cache: &SpeculativeMVHashMap<T::Key, T::Value>,
+        module_write_detected: &AtomicBool,
+        scheduler: &Scheduler,
+    ) {
+        // Every worker thread gets its own executor.
+        let executor = E::init(*executor_arguments);
+
+        let mut scheduler_task = SchedulerTask::NoTask;
+        loop {
+            scheduler_task = match scheduler_task {
+                SchedulerTask::ValidationTask(version_to_validate, guard) => self.validate(
+                    version_to_validate,
+                    guard,
+                    last_input_output,
+                    versioned_data_cache,
+                    scheduler,
+                ),
+                SchedulerTask::ExecutionTask(version_to_execute, guard) => self.execute(
+                    version_to_execute,
+                    guard,
+                    block,
+                    last_input_output,
+                    versioned_data_cache,
+                    module_write_detected,
+                    scheduler,
+                    &executor,
+                ),
+                SchedulerTask::NoTask => scheduler.next_task(),
+                SchedulerTask::Done => {
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn execute_transactions_parallel(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        let num_txns = signature_verified_block.len();
+        if num_txns == 0 {
+            return Ok(vec![]);
+        }
+
+        let versioned_data_cache = SpeculativeMVHashMap::new();
+        let last_input_output = TxnLastInputOutput::new(num_txns);
+        let module_write_detected = AtomicBool::new(false);
+        let scheduler = Scheduler::new(num_txns);
+
+        rayon::scope(|s| {
+            for _ in 0..self.num_cpus {
+                s.spawn(|_| {
+                    self.work_task_with_scope(
+                        &executor_initial_arguments,
+                        &signature_verified_block,
+                        &last_input_output,
+                        &versioned_data_cache,
+                        &module_write_detected,
+                        &scheduler,
+                    );
+                });
+            }
+        });
+
+        // Code is not multi-versioned, the results are only valid if no module got published.
+        if module_write_detected.load(Ordering::Relaxed) {
+            return Err(Error::ModulePathReadWrite);
+        }
+
+        // Every transaction has been executed and validated: the outputs are now those of a
+        // sequential execution, up to the first transaction that aborts or skips the rest.
+        let mut final_results = Vec::with_capacity(num_txns);
+        for idx in 0..num_txns {
+            match last_input_output.take_output(idx) {
+                ExecutionStatus::Success(t) => final_results.push(t),
+                ExecutionStatus::SkipRest(t) => {
+                    final_results.push(t);
+                    break;
+                }
+                ExecutionStatus::Abort(err) => return Err(Error::UserError(err)),
+            }
+        }
+        final_results.resize_with(num_txns, E::Output::skip_output);
+
+        Ok(final_results)
+    }
+}
+
+impl<T, E> Default for ParallelTransactionExecutor<T, E>
+where
+    T: Transaction,
+    E: ExecutorTask<T = T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! A parallel transaction executor following the Block-STM approach.
+//!
+//! Transactions of a block are executed speculatively and optimistically across threads against
+//! a [`SpeculativeMVHashMap`](mvhashmap::SpeculativeMVHashMap), which holds the writes of every
+//! transaction keyed by its index in the block. While executing, each transaction records the
+//! versions it read. Once executed, a transaction is validated by checking that its reads would
+//! still observe the same versions. Failed validation aborts the transaction: its writes are
+//! marked as estimates and it is scheduled for re-execution, which also causes every higher
+//! transaction to be validated again.
+//!
+//! A transaction reading an estimate written by a lower transaction waits for that transaction
+//! to finish its re-execution instead of proceeding with a value that is likely to be stale.
+//!
+//! Once every transaction has been executed and validated, the outputs are exactly those of a
+//! sequential execution of the block.
+
+pub mod errors;
+pub mod executor;
+mod scheduler;
+pub mod task;
+mod txn_last_input_output;
+
+#[cfg(test)]
+mod unit_tests;
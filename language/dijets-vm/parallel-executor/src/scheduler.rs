@@ -0,0 +1,437 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crossbeam_utils::CachePadded;
+use dijets_infallible::Mutex;
+use mvhashmap::{Incarnation, SpeculativeVersion, TxnIndex};
+use std::{
+    cmp::min,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex as StdMutex,
+    },
+};
+
+/// Condition variable a suspended transaction waits on until the transaction it depends on has
+/// been executed again.
+pub type DependencyCondvar = Arc<(StdMutex<bool>, Condvar)>;
+
+/// Keeps `num_active_tasks` up to date: the counter is incremented when a task is created and
+/// decremented once the task is finished and the guard is dropped.
+pub struct TaskGuard<'a> {
+    counter: &'a AtomicUsize,
+}
+
+impl<'a> TaskGuard<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self { counter }
+    }
+}
+
+impl Drop for TaskGuard<'_> {
+    fn drop(&mut self) {
+        assert!(self.counter.fetch_sub(1, Ordering::SeqCst) > 0);
+    }
+}
+
+/// A holder for potential task returned from the Scheduler. ExecutionTask and ValidationTask
+/// each contain a version of transaction that must be executed or validated, respectively.
+/// NoTask holds no task (similar to None if we wrapped tasks in Option), and Done implies that
+/// there are no more tasks and the scheduler is done.
+pub enum SchedulerTask<'a> {
+    ExecutionTask(SpeculativeVersion, TaskGuard<'a>),
+    ValidationTask(SpeculativeVersion, TaskGuard<'a>),
+    NoTask,
+    Done,
+}
+
+/////////////////////////////// Explanation for ExecutionStatus ///////////////////////////////
+/// All possible execution status for each transaction. In the explanation below, we abbreviate
+/// 'execution status' as 'status'. Each status contains the latest incarnation number,
+/// where incarnation = i means it is the i-th execution instance of the transaction.
+///
+/// 'ReadyToExecute' means that the corresponding incarnation should be executed and the scheduler
+/// must eventually create a corresponding execution task. The scheduler ensures that exactly one
+/// execution task gets created, changing the status to 'Executing' in the process.
+///
+/// 'Executing' status of an incarnation turns into 'Executed' if the execution task finishes, or
+/// into 'Suspended' if the incarnation reads a value that is flagged as an estimate.
+///
+/// 'Suspended' holds the condition variable the suspended thread waits on, and turns back into
+/// 'Executing' once the dependency has been executed again.
+///
+/// 'Executed' status of an incarnation turns into 'Aborting' if the incarnation fails
+/// validation. 'Aborting' then turns into 'ReadyToExecute' with an incremented incarnation.
+#[derive(Debug)]
+enum ExecutionStatus {
+    ReadyToExecute(Incarnation),
+    Executing(Incarnation),
+    Suspended(Incarnation, DependencyCondvar),
+    Executed(Incarnation),
+    Aborting(Incarnation),
+}
+
+pub struct Scheduler {
+    /// Number of transactions to execute, immutable.
+    num_txns: usize,
+
+    /// An index i maps to indices of other transactions that depend on transaction i, i.e. they
+    /// should be re-executed once transaction i's next incarnation finishes.
+    txn_dependency: Vec<CachePadded<Mutex<Vec<TxnIndex>>>>,
+    /// An index i maps to the most up-to-date status of transaction i.
+    txn_status: Vec<CachePadded<Mutex<ExecutionStatus>>>,
+
+    /// Next transaction to execute.
+    execution_idx: AtomicUsize,
+    /// Next transaction to validate.
+    validation_idx: AtomicUsize,
+    /// Number of times validation_idx was decreased.
+    decrease_cnt: AtomicUsize,
+
+    /// Number of ongoing validation and execution tasks.
+    num_active_tasks: AtomicUsize,
+
+    /// Shared marker that is set when a thread detects that all txns can be committed - no
+    /// thread can create new tasks from then on.
+    done_marker: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new(num_txns: usize) -> Self {
+        Self {
+            num_txns,
+            txn_dependency: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(Vec::new())))
+                .collect(),
+            txn_status: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(ExecutionStatus::ReadyToExecute(0))))
+                .collect(),
+            execution_idx: AtomicUsize::new(0),
+            validation_idx: AtomicUsize::new(0),
+            decrease_cnt: AtomicUsize::new(0),
+            num_active_tasks: AtomicUsize::new(0),
+            done_marker: AtomicBool::new(false),
+        }
+    }
+
+    /// Try to abort version = (txn_idx, incarnation), called upon validation failure.
+    /// When the invocation manages to update the status of the transaction, it changes
+    /// Executed(incarnation) => Aborting(incarnation), it returns true. Otherwise,
+    /// returns false. Since incarnation numbers never decrease, this also ensures
+    /// that the same version may not successfully abort more than once.
+    pub fn try_abort(&self, txn_idx: TxnIndex, incarnation: Incarnation) -> bool {
+        let mut status = self.txn_status[txn_idx].lock();
+        if let ExecutionStatus::Executed(stored_incarnation) = *status {
+            if stored_incarnation == incarnation {
+                *status = ExecutionStatus::Aborting(incarnation);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Return the next task for the thread.
+    pub fn next_task(&self) -> SchedulerTask<'_> {
+        loop {
+            if self.done() {
+                // No more tasks.
+                return SchedulerTask::Done;
+            }
+
+            let idx_to_validate = self.validation_idx.load(Ordering::SeqCst);
+            let idx_to_execute = self.execution_idx.load(Ordering::SeqCst);
+
+            if idx_to_validate < idx_to_execute {
+                if let Some((version_to_validate, guard)) = self.try_validate_next_version() {
+                    return SchedulerTask::ValidationTask(version_to_validate, guard);
+                }
+            } else if let Some((version_to_execute, guard)) = self.try_execute_next_version() {
+                return SchedulerTask::ExecutionTask(version_to_execute, guard);
+            }
+        }
+    }
+
+    /// When a txn depends on another txn, adds it to the dependency list of the other txn.
+    /// Returns Some(condvar) if the dependency was added and the caller must wait on the
+    /// condition variable, or None if the dependency was resolved in the meantime and the
+    /// caller should simply retry its read.
+    pub fn wait_for_dependency(
+        &self,
+        txn_idx: TxnIndex,
+        dep_txn_idx: TxnIndex,
+    ) -> Option<DependencyCondvar> {
+        // Create a condition variable associated with the dependency.
+        let dep_condvar = Arc::new((StdMutex::new(false), Condvar::new()));
+
+        let mut stored_deps = self.txn_dependency[dep_txn_idx].lock();
+
+        // Note: is_executed & suspend calls acquire (a different, status) mutex, while holding
+        // (dependency) mutex. This is the only place in scheduler where a thread may hold > 1
+        // mutexes. Thus, acquisitions always happen in the same order (here), may not deadlock.
+
+        if self.is_executed(dep_txn_idx).is_some() {
+            // Current status of dep_txn_idx is 'executed', so the dependency got resolved.
+            // To avoid zombie dependency (and losing liveness), must return here and
+            // not add a (stale) dependency.
+            return None;
+        }
+
+        self.suspend(txn_idx, dep_condvar.clone());
+
+        // Safe to add dependency here (still holding the lock) - finish_execution of txn
+        // dep_txn_idx is guaranteed to acquire the same lock later and clear the dependency.
+        stored_deps.push(txn_idx);
+
+        Some(dep_condvar)
+    }
+
+    /// After txn is executed, schedule its dependencies for re-execution.
+    /// If revalidate_suffix is true, decrease validation_idx to schedule all higher transactions
+    /// for (re-)validation. Otherwise, in some cases (if validation_idx not already lower),
+    /// return a validation task of the transaction to the caller (otherwise NoTask).
+    pub fn finish_execution<'a>(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        revalidate_suffix: bool,
+        guard: TaskGuard<'a>,
+    ) -> SchedulerTask<'a> {
+        self.set_executed_status(txn_idx, incarnation);
+
+        let txn_deps: Vec<TxnIndex> = {
+            let mut stored_deps = self.txn_dependency[txn_idx].lock();
+            // Holding the lock, take dependency vector.
+            std::mem::take(&mut stored_deps)
+        };
+
+        // Resume dependent transactions.
+        for dep_idx in txn_deps {
+            self.resume(dep_idx);
+        }
+
+        if self.validation_idx.load(Ordering::SeqCst) > txn_idx {
+            // Validation index is higher than txn_idx, so the transaction needs to be validated
+            // here rather than through the shared index.
+            if revalidate_suffix {
+                // The transaction execution required revalidating all higher txns (not
+                // only itself), currently happens when incarnation writes to a new path
+                // (w.r.t. the write-set of its previous completed incarnation).
+                self.decrease_validation_idx(txn_idx);
+            } else {
+                // Only transaction txn_idx requires validation. Return validation task
+                // back to the caller. No need to change active tasks (-1 +1= 0)
+                return SchedulerTask::ValidationTask((txn_idx, incarnation), guard);
+            }
+        }
+
+        SchedulerTask::NoTask
+    }
+
+    /// Finalize a failed validation of version (txn_idx, incarnation), after the caller
+    /// successfully aborted it. In some cases, may return a re-execution task back to the
+    /// caller (otherwise, NoTask).
+    pub fn finish_abort<'a>(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        guard: TaskGuard<'a>,
+    ) -> SchedulerTask<'a> {
+        self.set_aborted_status(txn_idx, incarnation);
+
+        // Schedule higher txns for validation, skipping txn_idx itself (needs to be
+        // re-executed first).
+        self.decrease_validation_idx(txn_idx + 1);
+
+        // txn_idx must be re-executed, and if execution_idx is lower, it will be.
+        if self.execution_idx.load(Ordering::SeqCst) > txn_idx {
+            // Optimization: execution_idx is higher than txn_idx, but decreasing it may
+            // lead to wasted work for all indices between txn_idx and execution_idx.
+            // Instead, attempt to create a new incarnation and return the corresponding
+            // re-execution task back to the caller. If incarnation fails, there is
+            // nothing to do, as another thread must have succeeded to incarnate and
+            // obtain the task for re-execution.
+            if let Some(new_incarnation) = self.try_incarnate(txn_idx) {
+                return SchedulerTask::ExecutionTask((txn_idx, new_incarnation), guard);
+            }
+        }
+
+        SchedulerTask::NoTask
+    }
+
+    /// Checks whether the done marker is set. The marker can only be set by 'check_done'.
+    fn done(&self) -> bool {
+        self.done_marker.load(Ordering::Acquire)
+    }
+}
+
+/// Private functions of the Scheduler
+impl Scheduler {
+    /// Decreases the validation index, recording the decrease for the completion check.
+    fn decrease_validation_idx(&self, target_idx: TxnIndex) {
+        if self.validation_idx.fetch_min(target_idx, Ordering::SeqCst) > target_idx {
+            self.decrease_cnt.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Try and incarnate a transaction. Only possible when the status is
+    /// ReadyToExecute(incarnation), in which case Some(incarnation) is returned and the
+    /// status is (atomically, due to the mutex) updated to Executing(incarnation).
+    /// An unsuccessful incarnation returns None.
+    fn try_incarnate(&self, txn_idx: TxnIndex) -> Option<Incarnation> {
+        if txn_idx >= self.num_txns {
+            return None;
+        }
+
+        let mut status = self.txn_status[txn_idx].lock();
+        if let ExecutionStatus::ReadyToExecute(incarnation) = *status {
+            *status = ExecutionStatus::Executing(incarnation);
+            Some(incarnation)
+        } else {
+            None
+        }
+    }
+
+    /// If the status of transaction is Executed(incarnation), returns Some(incarnation),
+    /// Useful to determine when a transaction can be validated, and to avoid a race in
+    /// dependency resolution.
+    /// If the status is not Executed, returns None.
+    fn is_executed(&self, txn_idx: TxnIndex) -> Option<Incarnation> {
+        let status = self.txn_status[txn_idx].lock();
+        if let ExecutionStatus::Executed(incarnation) = *status {
+            Some(incarnation)
+        } else {
+            None
+        }
+    }
+
+    /// Grab an index to try and validate next (by fetch-and-incrementing validation_idx).
+    /// - If the index is out of bounds, return None (and invoke a check of whether
+    /// all txns can be committed).
+    /// - If the transaction is ready for validation (EXECUTED state), return the version
+    /// to the caller together with a guard to be used for the corresponding ValidationTask.
+    /// - Otherwise, return None.
+    fn try_validate_next_version(&self) -> Option<(SpeculativeVersion, TaskGuard<'_>)> {
+        let idx_to_validate = self.validation_idx.load(Ordering::SeqCst);
+
+        if idx_to_validate >= self.num_txns {
+            self.check_done();
+            return None;
+        }
+
+        // Must create a guard before incrementing validation_idx.
+        let guard = TaskGuard::new(&self.num_active_tasks);
+        let idx_to_validate = self.validation_idx.fetch_add(1, Ordering::SeqCst);
+
+        // If incarnation was last executed, and thus ready for validation,
+        // return version and guard for validation task, otherwise None.
+        if idx_to_validate < self.num_txns {
+            if let Some(incarnation) = self.is_executed(idx_to_validate) {
+                return Some(((idx_to_validate, incarnation), guard));
+            }
+        }
+
+        None
+    }
+
+    /// Grab an index to try and execute next (by fetch-and-incrementing execution_idx).
+    /// - If the index is out of bounds, return None (and invoke a check of whether
+    /// all txns can be committed).
+    /// - If the transaction is ready for execution (ReadyToExecute state), attempt
+    /// to create the next incarnation (should happen exactly once), and if successful,
+    /// return the version to the caller together with a guard for the ExecutionTask.
+    /// - Otherwise, return None.
+    fn try_execute_next_version(&self) -> Option<(SpeculativeVersion, TaskGuard<'_>)> {
+        let idx_to_execute = self.execution_idx.load(Ordering::SeqCst);
+
+        if idx_to_execute >= self.num_txns {
+            self.check_done();
+            return None;
+        }
+
+        // Must create a guard before incrementing execution_idx.
+        let guard = TaskGuard::new(&self.num_active_tasks);
+        let idx_to_execute = self.execution_idx.fetch_add(1, Ordering::SeqCst);
+
+        // If successfully incarnated (changed status from ready to executing),
+        // return version and guard for execution task, otherwise None.
+        self.try_incarnate(idx_to_execute)
+            .map(|incarnation| ((idx_to_execute, incarnation), guard))
+    }
+
+    /// Put a transaction in a suspended state, with a condition variable that can be
+    /// used to wake it up after the dependency is resolved.
+    fn suspend(&self, txn_idx: TxnIndex, dep_condvar: DependencyCondvar) {
+        let mut status = self.txn_status[txn_idx].lock();
+
+        if let ExecutionStatus::Executing(incarnation) = *status {
+            *status = ExecutionStatus::Suspended(incarnation, dep_condvar);
+        } else {
+            unreachable!();
+        }
+    }
+
+    /// When a dependency is resolved, mark the transaction as Executing again and notify the
+    /// thread waiting on the condition variable.
+    fn resume(&self, txn_idx: TxnIndex) {
+        let mut status = self.txn_status[txn_idx].lock();
+        if let ExecutionStatus::Suspended(incarnation, dep_condvar) = &*status {
+            let dep_condvar = dep_condvar.clone();
+            *status = ExecutionStatus::Executing(*incarnation);
+
+            let (lock, cvar) = &*dep_condvar;
+            let mut dependency_resolved = lock.lock().expect("Lock poisoned");
+            *dependency_resolved = true;
+            cvar.notify_one();
+        } else {
+            unreachable!();
+        }
+    }
+
+    /// Set status of the transaction to Executed(incarnation).
+    fn set_executed_status(&self, txn_idx: TxnIndex, incarnation: Incarnation) {
+        let mut status = self.txn_status[txn_idx].lock();
+
+        // Only makes sense when the current status is 'Executing'.
+        debug_assert!(matches!(*status, ExecutionStatus::Executing(i) if i == incarnation));
+
+        *status = ExecutionStatus::Executed(incarnation);
+    }
+
+    /// After a successful abort, mark the transaction as ready for re-execution with an
+    /// incremented incarnation number.
+    fn set_aborted_status(&self, txn_idx: TxnIndex, incarnation: Incarnation) {
+        let mut status = self.txn_status[txn_idx].lock();
+
+        // Only makes sense when the current status is 'Aborting'.
+        debug_assert!(matches!(*status, ExecutionStatus::Aborting(i) if i == incarnation));
+
+        *status = ExecutionStatus::ReadyToExecute(incarnation + 1);
+    }
+
+    /// A lazy check of whether the scheduler execution is completed.
+    /// Updates the 'done_marker' so other threads can know by calling done().
+    ///
+    /// 1. After the STM execution has completed:
+    /// validation_idx >= num_txn, execution_idx >= num_txn, num_active_tasks == 0,
+    /// and decrease_cnt does not change - so it will be successfully detected.
+    /// 2. If done_marker is set, all of these must hold at the same time, in particular:
+    /// - all transactions have completed their last incarnation and passed validation,
+    /// - no ongoing or future validation or execution tasks.
+    fn check_done(&self) {
+        let observed_cnt = self.decrease_cnt.load(Ordering::SeqCst);
+
+        let val_idx = self.validation_idx.load(Ordering::SeqCst);
+        let exec_idx = self.execution_idx.load(Ordering::SeqCst);
+        let num_tasks = self.num_active_tasks.load(Ordering::SeqCst);
+        if min(exec_idx, val_idx) < self.num_txns || num_tasks > 0 {
+            // There is work remaining.
+            return;
+        }
+
+        // Re-read and make sure decrease_cnt hasn't changed.
+        if observed_cnt == self.decrease_cnt.load(Ordering::SeqCst) {
+            self.done_marker.store(true, Ordering::Release);
+        }
+    }
+}
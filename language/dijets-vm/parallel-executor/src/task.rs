@@ -0,0 +1,81 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::executor::MVHashMapView;
+use dijets_types::access_path::AccessPath;
+use move_core_types::language_storage::CODE_TAG;
+use std::{fmt::Debug, hash::Hash};
+
+/// The execution result of a transaction
+#[derive(Debug)]
+pub enum ExecutionStatus<T, E> {
+    /// Transaction was executed successfully.
+    Success(T),
+    /// Transaction hit a non-recoverable error during execution. Execution of the block halts
+    /// and the error is propagated back to the caller.
+    Abort(E),
+    /// Transaction was executed successfully, but all the trailing transactions in the block
+    /// must be skipped (e.g. after a reconfiguration).
+    SkipRest(T),
+}
+
+/// Trait for keys that may point to code, which cannot be multi-versioned.
+pub trait ModulePath {
+    fn is_module_path(&self) -> bool;
+}
+
+impl ModulePath for AccessPath {
+    fn is_module_path(&self) -> bool {
+        self.path.first() == Some(&CODE_TAG)
+    }
+}
+
+/// Trait that defines a transaction that could be executed in parallel.
+pub trait Transaction: Sync + Send + 'static {
+    type Key: PartialOrd + Send + Sync + Clone + Hash + Eq + ModulePath + Debug;
+    type Value: Send + Sync;
+}
+
+/// Trait for a single-threaded transaction executor. One instance is created per worker thread
+/// of the parallel executor.
+pub trait ExecutorTask: Sync {
+    /// Type of transaction and its associated key and value.
+    type T: Transaction;
+
+    /// The output of executing a transaction.
+    type Output: TransactionOutput<T = Self::T> + 'static;
+
+    /// Type of error when the executor fails to process a transaction and needs to abort.
+    type Error: Clone + Send + Sync + 'static;
+
+    /// Type to initialize the single thread transaction executor. Copy and Sync are required
+    /// because the argument is shared by all the worker threads.
+    type Argument: Sync + Copy;
+
+    /// Create an instance of the transaction executor.
+    fn init(args: Self::Argument) -> Self;
+
+    /// Execute a single transaction given the view of the current state.
+    fn execute_transaction(
+        &self,
+        view: &MVHashMapView<<Self::T as Transaction>::Key, <Self::T as Transaction>::Value>,
+        txn: &Self::T,
+    ) -> ExecutionStatus<Self::Output, Self::Error>;
+}
+
+/// Trait for the output of a transaction executed by an `ExecutorTask`.
+pub trait TransactionOutput: Send + Sync {
+    /// Type of transaction and its associated key and value.
+    type T: Transaction;
+
+    /// Get the side effects of executing a transaction.
+    fn get_writes(
+        &self,
+    ) -> Vec<(
+        <Self::T as Transaction>::Key,
+        <Self::T as Transaction>::Value,
+    )>;
+
+    /// The output of a transaction that was skipped because of a preceding `SkipRest`.
+    fn skip_output() -> Self;
+}
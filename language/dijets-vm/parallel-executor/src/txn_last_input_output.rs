@@ -0,0 +1,116 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::task::{ExecutionStatus, Transaction, TransactionOutput};
+use crossbeam_utils::CachePadded;
+use dijets_infallible::Mutex;
+use mvhashmap::{SpeculativeVersion, TxnIndex};
+use std::{collections::HashSet, hash::Hash, sync::Arc};
+
+type TxnInput<K> = Vec<ReadDescriptor<K>>;
+
+/// A read performed by a transaction and where its value came from: either the write of a
+/// lower transaction, or storage if no lower transaction wrote the key.
+#[derive(Clone)]
+pub struct ReadDescriptor<K> {
+    access_path: K,
+    version: Option<SpeculativeVersion>,
+}
+
+impl<K: Clone> ReadDescriptor<K> {
+    pub fn from_version(access_path: K, version: SpeculativeVersion) -> Self {
+        Self {
+            access_path,
+            version: Some(version),
+        }
+    }
+
+    pub fn from_storage(access_path: K) -> Self {
+        Self {
+            access_path,
+            version: None,
+        }
+    }
+
+    pub fn path(&self) -> &K {
+        &self.access_path
+    }
+
+    /// Checks whether a read of the same key now returning `version` (None if the value would
+    /// be read from storage) observes the same value.
+    pub fn validate(&self, version: Option<SpeculativeVersion>) -> bool {
+        self.version == version
+    }
+}
+
+struct TxnOutput<T: TransactionOutput, E> {
+    output_status: ExecutionStatus<T, E>,
+    write_keys: HashSet<<T::T as Transaction>::Key>,
+}
+
+/// Holds the reads and outputs of the latest executed incarnation of every transaction.
+pub struct TxnLastInputOutput<K, T: TransactionOutput, E> {
+    inputs: Vec<CachePadded<Mutex<Option<Arc<TxnInput<K>>>>>>,
+    outputs: Vec<CachePadded<Mutex<Option<Arc<TxnOutput<T, E>>>>>>,
+}
+
+impl<K, T, E> TxnLastInputOutput<K, T, E>
+where
+    K: Clone + Hash + Eq,
+    T: TransactionOutput,
+    T::T: Transaction<Key = K>,
+{
+    pub fn new(num_txns: usize) -> Self {
+        Self {
+            inputs: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(None)))
+                .collect(),
+            outputs: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(None)))
+                .collect(),
+        }
+    }
+
+    /// Records the reads and output of an execution of transaction `txn_idx`, replacing the
+    /// records of any earlier incarnation.
+    pub fn record(
+        &self,
+        txn_idx: TxnIndex,
+        input: TxnInput<K>,
+        output_status: ExecutionStatus<T, E>,
+        write_keys: HashSet<K>,
+    ) {
+        *self.inputs[txn_idx].lock() = Some(Arc::new(input));
+        *self.outputs[txn_idx].lock() = Some(Arc::new(TxnOutput {
+            output_status,
+            write_keys,
+        }));
+    }
+
+    /// Returns the reads of the latest execution of transaction `txn_idx`.
+    pub fn read_set(&self, txn_idx: TxnIndex) -> Option<Arc<TxnInput<K>>> {
+        self.inputs[txn_idx].lock().clone()
+    }
+
+    /// Returns the keys written by the latest execution of transaction `txn_idx`.
+    pub fn write_set(&self, txn_idx: TxnIndex) -> HashSet<K> {
+        self.outputs[txn_idx]
+            .lock()
+            .as_ref()
+            .map(|txn_output| txn_output.write_keys.clone())
+            .unwrap_or_default()
+    }
+
+    /// Takes the output of transaction `txn_idx`. Must only be called once all the worker
+    /// threads are done.
+    pub fn take_output(&self, txn_idx: TxnIndex) -> ExecutionStatus<T, E> {
+        let txn_output = self.outputs[txn_idx]
+            .lock()
+            .take()
+            .expect("Output must be recorded after execution");
+        match Arc::try_unwrap(txn_output) {
+            Ok(txn_output) => txn_output.output_status,
+            Err(_) => unreachable!("Output must not be shared after execution"),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod proptest_types;
+
+use crate::{errors::Error, executor::ParallelTransactionExecutor};
+use proptest::prelude::*;
+use proptest_types::{sequential_outputs, transaction_strategy, KeyType, MockTransaction, Task};
+
+fn run_and_assert(txns: Vec<MockTransaction<KeyType<u64>, u64>>, num_cpus: usize) {
+    let expected = sequential_outputs(&txns).map_err(Error::UserError);
+    let output = ParallelTransactionExecutor::<_, Task<_, _>>::with_num_cpus(num_cpus)
+        .execute_transactions_parallel((), txns);
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn empty_block() {
+    run_and_assert(vec![], 4);
+}
+
+#[test]
+fn chained_dependencies() {
+    // Every transaction reads what the previous one wrote.
+    let txns = (0..1000)
+        .map(|i| MockTransaction::Write {
+            reads: vec![KeyType(i, false)],
+            writes: vec![(KeyType(i + 1, false), i)],
+        })
+        .collect();
+    run_and_assert(txns, 8);
+}
+
+#[test]
+fn single_hot_key() {
+    let txns = (0..1000)
+        .map(|i| MockTransaction::Write {
+            reads: vec![KeyType(0, false)],
+            writes: vec![(KeyType(0, false), i)],
+        })
+        .collect();
+    run_and_assert(txns, 8);
+}
+
+#[test]
+fn skip_rest_and_abort() {
+    let write = |k| MockTransaction::Write {
+        reads: vec![KeyType(k, false)],
+        writes: vec![(KeyType(k, false), k)],
+    };
+    run_and_assert(vec![write(0), MockTransaction::SkipRest, write(1)], 4);
+    run_and_assert(
+        vec![write(0), MockTransaction::Abort, MockTransaction::Abort],
+        4,
+    );
+    // Transactions after a SkipRest are discarded, so their abort is not an error.
+    run_and_assert(
+        vec![write(0), MockTransaction::SkipRest, MockTransaction::Abort],
+        4,
+    );
+}
+
+#[test]
+fn module_write_falls_back() {
+    let txns = vec![
+        MockTransaction::Write {
+            reads: vec![],
+            writes: vec![(KeyType(0, false), 0)],
+        },
+        MockTransaction::Write {
+            reads: vec![KeyType(0, false)],
+            writes: vec![(KeyType(1, true), 1)],
+        },
+    ];
+    let output = ParallelTransactionExecutor::<_, Task<_, _>>::with_num_cpus(4)
+        .execute_transactions_parallel((), txns);
+    assert_eq!(output, Err(Error::ModulePathReadWrite));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn low_contention(txns in transaction_strategy(1000, 500)) {
+        run_and_assert(txns, 8);
+    }
+
+    #[test]
+    fn high_contention(txns in transaction_strategy(10, 500)) {
+        run_and_assert(txns, 8);
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    executor::MVHashMapView,
+    task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
+};
+use proptest::{collection::vec, prelude::*, strategy::Strategy};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData};
+
+/// Key used by the mock transactions. Keys with the flag set are treated as code.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd, Eq)]
+pub struct KeyType<K>(pub K, pub bool);
+
+impl<K> ModulePath for KeyType<K> {
+    fn is_module_path(&self) -> bool {
+        self.1
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MockTransaction<K, V> {
+    Write {
+        reads: Vec<K>,
+        writes: Vec<(K, V)>,
+    },
+    /// Skip the execution of trailing transactions.
+    SkipRest,
+    /// Abort the execution.
+    Abort,
+}
+
+impl<K, V> Transaction for MockTransaction<K, V>
+where
+    K: PartialOrd + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + 'static,
+{
+    type Key = K;
+    type Value = V;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MockOutput<K, V> {
+    writes: Vec<(K, V)>,
+    read_results: Vec<Option<V>>,
+    skipped: bool,
+}
+
+impl<K, V> TransactionOutput for MockOutput<K, V>
+where
+    K: PartialOrd + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + 'static,
+{
+    type T = MockTransaction<K, V>;
+
+    fn get_writes(&self) -> Vec<(K, V)> {
+        // Like a write set, only keep the last write of every key.
+        self.writes
+            .iter()
+            .cloned()
+            .collect::<HashMap<_, _>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn skip_output() -> Self {
+        Self {
+            writes: vec![],
+            read_results: vec![],
+            skipped: true,
+        }
+    }
+}
+
+pub struct Task<K, V>(PhantomData<(K, V)>);
+
+impl<K, V> ExecutorTask for Task<K, V>
+where
+    K: PartialOrd + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + 'static,
+{
+    type T = MockTransaction<K, V>;
+    type Output = MockOutput<K, V>;
+    type Error = usize;
+    type Argument = ();
+
+    fn init(_argument: Self::Argument) -> Self {
+        Self(PhantomData)
+    }
+
+    fn execute_transaction(
+        &self,
+        view: &MVHashMapView<K, V>,
+        txn: &Self::T,
+    ) -> ExecutionStatus<Self::Output, Self::Error> {
+        match txn {
+            MockTransaction::Write { reads, writes } => {
+                let read_results = reads
+                    .iter()
+                    .map(|k| view.read(k).map(|v| (*v).clone()))
+                    .collect();
+                ExecutionStatus::Success(MockOutput {
+                    writes: writes.clone(),
+                    read_results,
+                    skipped: false,
+                })
+            }
+            MockTransaction::SkipRest => ExecutionStatus::SkipRest(MockOutput {
+                writes: vec![],
+                read_results: vec![],
+                skipped: false,
+            }),
+            MockTransaction::Abort => ExecutionStatus::Abort(view.txn_idx()),
+        }
+    }
+}
+
+/// Computes the expected outputs of a block by executing it sequentially.
+pub fn sequential_outputs<K, V>(
+    txns: &[MockTransaction<K, V>],
+) -> Result<Vec<MockOutput<K, V>>, usize>
+where
+    K: PartialOrd + Send + Sync + Clone + Hash + Eq + ModulePath + Debug + 'static,
+    V: Send + Sync + Debug + Clone + 'static,
+{
+    let mut state: HashMap<K, V> = HashMap::new();
+    let mut outputs = vec![];
+    for (idx, txn) in txns.iter().enumerate() {
+        match txn {
+            MockTransaction::Write { reads, writes } => {
+                let read_results = reads.iter().map(|k| state.get(k).cloned()).collect();
+                state.extend(writes.iter().cloned());
+                outputs.push(MockOutput {
+                    writes: writes.clone(),
+                    read_results,
+                    skipped: false,
+                });
+            }
+            MockTransaction::SkipRest => {
+                outputs.push(MockOutput {
+                    writes: vec![],
+                    read_results: vec![],
+                    skipped: false,
+                });
+                break;
+            }
+            MockTransaction::Abort => return Err(idx),
+        }
+    }
+    outputs.resize_with(txns.len(), MockOutput::skip_output);
+    Ok(outputs)
+}
+
+/// Generates blocks of transactions that read and write a small set of keys, so that
+/// transactions conflict often.
+pub fn transaction_strategy(
+    num_keys: u64,
+    max_txns: usize,
+) -> impl Strategy<Value = Vec<MockTransaction<KeyType<u64>, u64>>> {
+    let key = (0..num_keys).prop_map(|k| KeyType(k, false));
+    let txn = prop_oneof![
+        50 => (vec(key.clone(), 0..10), vec((key, any::<u64>()), 0..5))
+            .prop_map(|(reads, writes)| MockTransaction::Write { reads, writes }),
+        1 => Just(MockTransaction::SkipRest),
+        1 => Just(MockTransaction::Abort),
+    ];
+    vec(txn, 1..max_txns)
+}
@@ -70,3 +70,13 @@ pub static TXN_GAS_USAGE: Lazy<Histogram> =
 pub static CRITICAL_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("dijets_vm_critical_errors", "Number of critical errors").unwrap()
 });
+
+/// Count the number of blocks that could not be executed in parallel and were re-executed
+/// sequentially (e.g. because they publish modules).
+pub static PARALLEL_EXECUTION_FALLBACK: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "dijets_vm_parallel_execution_fallback",
+        "Number of blocks re-executed sequentially after parallel execution"
+    )
+    .unwrap()
+});
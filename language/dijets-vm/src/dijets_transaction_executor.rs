@@ -4,13 +4,14 @@
 use crate::{
     counters::*,
     data_cache::StateViewCache,
-    dijets_transaction_validator::validate_signature_checked_transaction,
+    dijets_transaction_validator::{validate_signature_checked_transaction, validate_transaction},
     dijets_vm::{
         charge_global_write_gas_usage, convert_changeset_and_events, get_transaction_output,
         DijetsVMImpl, DijetsVMInternals,
     },
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
+    parallel_executor::ParallelDijetsVM,
     script_to_script_function,
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
@@ -24,8 +25,9 @@ use dijets_types::{
     block_metadata::BlockMetadata,
     on_chain_config::DIJETS_VERSION_3,
     transaction::{
-        ChangeSet, Module, SignatureCheckedTransaction, Transaction, TransactionArgument,
-        TransactionOutput, TransactionPayload, TransactionStatus, WriteSetPayload,
+        ChangeSet, Module, SignatureCheckedTransaction, SignedTransaction, Transaction,
+        TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
+        WriteSetPayload,
     },
     vm_status::{KeptVMStatus, StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
@@ -41,12 +43,15 @@ use move_core_types::{
 };
 use move_vm_runtime::session::Session;
 use move_vm_types::gas_schedule::GasStatus;
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::{
     collections::HashSet,
     convert::{AsMut, AsRef},
 };
 
+static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
+
 pub struct DijetsVM(DijetsVMImpl);
 
 impl DijetsVM {
@@ -54,6 +59,20 @@ impl DijetsVM {
         Self(DijetsVMImpl::new(state))
     }
 
+    /// Sets the number of threads used to execute a block. With a concurrency level above 1,
+    /// `execute_block` runs the `ParallelDijetsVM`. Only the first call has an effect.
+    pub fn set_concurrency_level_once(concurrency_level: usize) {
+        EXECUTION_CONCURRENCY_LEVEL.set(concurrency_level).ok();
+    }
+
+    /// Returns the number of threads used to execute a block, 1 (sequential) by default.
+    pub fn get_concurrency_level() -> usize {
+        match EXECUTION_CONCURRENCY_LEVEL.get() {
+            Some(concurrency_level) => *concurrency_level,
+            None => 1,
+        }
+    }
+
     pub fn internals(&self) -> DijetsVMInternals {
         DijetsVMInternals::new(&self.0)
     }
@@ -287,6 +306,18 @@ impl DijetsVM {
         storage: &S,
         txn: &SignatureCheckedTransaction,
         log_context: &AdapterLogSchema,
+    ) -> (VMStatus, TransactionOutput) {
+        self.execute_user_transaction_without_signature_check(storage, txn, log_context)
+    }
+
+    /// Executes a user transaction without checking its signature. Apart from
+    /// `execute_user_transaction`, only simulation runs it, as the output of a simulated
+    /// transaction is never committed.
+    fn execute_user_transaction_without_signature_check<S: MoveResolver>(
+        &self,
+        storage: &S,
+        txn: &SignedTransaction,
+        log_context: &AdapterLogSchema,
     ) -> (VMStatus, TransactionOutput) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
@@ -299,19 +330,13 @@ impl DijetsVM {
 
         // Revalidate the transaction.
         let mut session = self.0.new_session(storage);
-        let account_currency_symbol = match validate_signature_checked_transaction(
-            &self.0,
-            &mut session,
-            txn,
-            storage,
-            false,
-            log_context,
-        ) {
-            Ok((_, currency_code)) => currency_code,
-            Err(err) => {
-                return discard_error_vm_status(err);
-            }
-        };
+        let account_currency_symbol =
+            match validate_transaction(&self.0, &mut session, txn, storage, false, log_context) {
+                Ok((_, currency_code)) => currency_code,
+                Err(err) => {
+                    return discard_error_vm_status(err);
+                }
+            };
 
         let gas_schedule = unwrap_or_discard!(self.0.get_gas_schedule(log_context));
         let txn_data = TransactionMetadata::new(txn);
@@ -797,6 +822,24 @@ impl DijetsVM {
             }
         })
     }
+
+    /// Executes `txn` against `state_view` without checking its signature, so that signed and
+    /// unsigned transactions can be dry-run (e.g. to estimate gas usage or surface aborts). The
+    /// returned output is never applied to storage. The prologue still checks that the public
+    /// key in the authenticator matches the sender's authentication key.
+    pub fn simulate_signed_transaction(
+        txn: &SignedTransaction,
+        state_view: &dyn StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        if let TransactionPayload::WriteSet(_) = txn.payload() {
+            return discard_error_vm_status(VMStatus::Error(StatusCode::REJECTED_WRITE_SET));
+        }
+        let data_cache = StateViewCache::new(state_view);
+        let vm = DijetsVM::new(&data_cache);
+        let log_context = AdapterLogSchema::new(data_cache.id(), 0);
+        vm.execute_user_transaction_without_signature_check(&data_cache, txn, &log_context)
+    }
+
     /// Alternate form of 'execute_block' that keeps the vm_status before it goes into the
     /// `TransactionOutput`
     pub fn execute_block_and_keep_vm_status(
@@ -832,7 +875,7 @@ pub(crate) fn preprocess_transaction(txn: Transaction) -> PreprocessedTransactio
     }
 }
 
-fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
+pub(crate) fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
     let new_epoch_event_key = dijets_types::on_chain_config::new_epoch_event_key();
     vm_output
         .events()
@@ -869,6 +912,13 @@ impl VMExecutor for DijetsVM {
             ))
         });
 
+        let concurrency_level = Self::get_concurrency_level();
+        if concurrency_level > 1 {
+            let (output, _fallback_reason) =
+                ParallelDijetsVM::execute_block(transactions, state_view, concurrency_level)?;
+            return Ok(output);
+        }
+
         let output = Self::execute_block_and_keep_vm_status(transactions, state_view)?;
         Ok(output
             .into_iter()
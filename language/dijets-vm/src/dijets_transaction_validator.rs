@@ -121,11 +121,32 @@ fn get_account_role(sender: AccountAddress, remote_cache: &StateViewCache) -> Go
 
 pub(crate) fn validate_signature_checked_transaction<S: MoveResolver>(
     vm: &DijetsVMImpl,
-    mut session: &mut Session<S>,
+    session: &mut Session<S>,
     transaction: &SignatureCheckedTransaction,
     remote_cache: &S,
     allow_too_new: bool,
     log_context: &AdapterLogSchema,
+) -> Result<(u64, Identifier), VMStatus> {
+    validate_transaction(
+        vm,
+        session,
+        transaction,
+        remote_cache,
+        allow_too_new,
+        log_context,
+    )
+}
+
+/// Runs the checks and the prologue of `transaction` without checking its signature. Apart from
+/// `validate_signature_checked_transaction`, only simulation runs it, as the output of a
+/// simulated transaction is never committed.
+pub(crate) fn validate_transaction<S: MoveResolver>(
+    vm: &DijetsVMImpl,
+    mut session: &mut Session<S>,
+    transaction: &SignedTransaction,
+    remote_cache: &S,
+    allow_too_new: bool,
+    log_context: &AdapterLogSchema,
 ) -> Result<(u64, Identifier), VMStatus> {
     if transaction.is_multi_agent() && vm.get_dijets_version()? < DIJETS_VERSION_3 {
         // Multi agent is not allowed under this version
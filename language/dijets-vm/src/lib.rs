@@ -118,6 +118,7 @@ pub mod transaction_metadata;
 pub mod dijets_transaction_executor;
 pub mod dijets_transaction_validator;
 pub mod logging;
+pub mod parallel_executor;
 pub mod script_to_script_function;
 pub mod system_module_names;
 
@@ -126,7 +127,7 @@ mod unit_tests;
 
 pub use crate::{
     dijets_transaction_executor::DijetsVM, dijets_transaction_validator::DijetsVMValidator,
-    dijets_vm::convert_changeset_and_events,
+    dijets_vm::convert_changeset_and_events, parallel_executor::ParallelDijetsVM,
 };
 
 use dijets_state_view::StateView;
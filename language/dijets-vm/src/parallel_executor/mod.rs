@@ -0,0 +1,114 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod storage_wrapper;
+mod vm_wrapper;
+
+use crate::{
+    counters::PARALLEL_EXECUTION_FALLBACK,
+    dijets_transaction_executor::{preprocess_transaction, PreprocessedTransaction},
+    DijetsVM,
+};
+use dijets_parallel_executor::{
+    errors::Error,
+    executor::ParallelTransactionExecutor,
+    task::{Transaction as PTransaction, TransactionOutput as PTransactionOutput},
+};
+use dijets_state_view::StateView;
+use dijets_types::{
+    access_path::AccessPath,
+    transaction::{Transaction, TransactionOutput, TransactionStatus},
+    vm_status::VMStatus,
+    write_set::{WriteOp, WriteSet},
+};
+use rayon::prelude::*;
+use vm_wrapper::DijetsVMWrapper;
+
+impl PTransaction for PreprocessedTransaction {
+    type Key = AccessPath;
+    type Value = WriteOp;
+}
+
+// Wrapper to avoid orphan rule
+pub(crate) struct DijetsTransactionOutput(TransactionOutput);
+
+impl DijetsTransactionOutput {
+    pub fn new(output: TransactionOutput) -> Self {
+        Self(output)
+    }
+
+    pub fn into_output(self) -> TransactionOutput {
+        self.0
+    }
+}
+
+impl PTransactionOutput for DijetsTransactionOutput {
+    type T = PreprocessedTransaction;
+
+    fn get_writes(&self) -> Vec<(AccessPath, WriteOp)> {
+        self.0
+            .write_set()
+            .iter()
+            .map(|(ap, write_op)| (ap.clone(), write_op.clone()))
+            .collect()
+    }
+
+    /// Execution output for transactions that comes after SkipRest signal.
+    fn skip_output() -> Self {
+        Self(TransactionOutput::new(
+            WriteSet::default(),
+            vec![],
+            0,
+            TransactionStatus::Retry,
+        ))
+    }
+}
+
+/// Executes a block of transactions on `concurrency_level` threads, speculatively and
+/// optimistically, producing the same outputs as `DijetsVM`'s sequential execution.
+pub struct ParallelDijetsVM();
+
+impl ParallelDijetsVM {
+    /// Execute `transactions` in parallel. Blocks that publish modules cannot be executed in
+    /// parallel and are re-executed sequentially, in which case the error that caused the
+    /// fallback is returned alongside the outputs.
+    pub fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &dyn StateView,
+        concurrency_level: usize,
+    ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        // Verify the signatures of all the transactions in parallel.
+        // This is time consuming so don't wait and do the checking
+        // sequentially while executing the transactions.
+        let signature_verified_block: Vec<PreprocessedTransaction> = transactions
+            .par_iter()
+            .map(|txn| preprocess_transaction(txn.clone()))
+            .collect();
+
+        match ParallelTransactionExecutor::<PreprocessedTransaction, DijetsVMWrapper>::with_num_cpus(
+            concurrency_level,
+        )
+        .execute_transactions_parallel(state_view, signature_verified_block)
+        {
+            Ok(results) => Ok((
+                results
+                    .into_iter()
+                    .map(DijetsTransactionOutput::into_output)
+                    .collect(),
+                None,
+            )),
+            Err(err @ Error::ModulePathReadWrite) => {
+                PARALLEL_EXECUTION_FALLBACK.inc();
+                let output = DijetsVM::execute_block_and_keep_vm_status(transactions, state_view)?;
+                Ok((
+                    output
+                        .into_iter()
+                        .map(|(_vm_status, txn_output)| txn_output)
+                        .collect(),
+                    Some(err),
+                ))
+            }
+            Err(Error::UserError(err)) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::data_cache::RemoteStorage;
+use dijets_parallel_executor::executor::MVHashMapView;
+use dijets_state_view::{StateView, StateViewId};
+use dijets_types::{access_path::AccessPath, write_set::WriteOp};
+use move_binary_format::errors::VMError;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag},
+    resolver::{ModuleResolver, ResourceResolver},
+};
+
+/// A `StateView` for a transaction executed in parallel: values written by lower transactions
+/// of the block are read from the multi-version data structure, everything else from storage.
+pub(crate) struct VersionedView<'a, 'b> {
+    base_view: &'a dyn StateView,
+    hashmap_view: &'a MVHashMapView<'b, AccessPath, WriteOp>,
+}
+
+impl<'a, 'b> VersionedView<'a, 'b> {
+    pub fn new_view(
+        base_view: &'a dyn StateView,
+        hashmap_view: &'a MVHashMapView<'b, AccessPath, WriteOp>,
+    ) -> VersionedView<'a, 'b> {
+        VersionedView {
+            base_view,
+            hashmap_view,
+        }
+    }
+}
+
+impl<'a, 'b> StateView for VersionedView<'a, 'b> {
+    // Get some data either through the cache or the `StateView` on a cache miss.
+    fn get(&self, access_path: &AccessPath) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.hashmap_view.read(access_path) {
+            Some(write_op) => Ok(match &*write_op {
+                WriteOp::Value(blob) => Some(blob.clone()),
+                WriteOp::Deletion => None,
+            }),
+            None => self.base_view.get(access_path),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base_view.is_genesis()
+    }
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+}
+
+impl<'a, 'b> ModuleResolver for VersionedView<'a, 'b> {
+    type Error = VMError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        RemoteStorage::new(self).get_module(module_id)
+    }
+}
+
+impl<'a, 'b> ResourceResolver for VersionedView<'a, 'b> {
+    type Error = VMError;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        RemoteStorage::new(self).get_resource(address, tag)
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    data_cache::StateViewCache,
+    dijets_transaction_executor::{is_reconfiguration, PreprocessedTransaction},
+    logging::AdapterLogSchema,
+    parallel_executor::{storage_wrapper::VersionedView, DijetsTransactionOutput},
+    DijetsVM,
+};
+use dijets_logger::prelude::*;
+use dijets_parallel_executor::{
+    executor::MVHashMapView,
+    task::{ExecutionStatus, ExecutorTask},
+};
+use dijets_state_view::StateView;
+use dijets_types::{access_path::AccessPath, vm_status::VMStatus, write_set::WriteOp};
+
+pub(crate) struct DijetsVMWrapper<'a> {
+    vm: DijetsVM,
+    base_view: &'a dyn StateView,
+}
+
+impl<'a> ExecutorTask for DijetsVMWrapper<'a> {
+    type T = PreprocessedTransaction;
+    type Output = DijetsTransactionOutput;
+    type Error = VMStatus;
+    type Argument = &'a dyn StateView;
+
+    fn init(argument: &'a dyn StateView) -> Self {
+        let vm = DijetsVM::new(&StateViewCache::new(argument));
+
+        Self {
+            vm,
+            base_view: argument,
+        }
+    }
+
+    fn execute_transaction(
+        &self,
+        view: &MVHashMapView<AccessPath, WriteOp>,
+        txn: &PreprocessedTransaction,
+    ) -> ExecutionStatus<DijetsTransactionOutput, VMStatus> {
+        let log_context = AdapterLogSchema::new(self.base_view.id(), view.txn_idx());
+        let versioned_view = VersionedView::new_view(self.base_view, view);
+
+        match self
+            .vm
+            .execute_single_transaction(txn, &versioned_view, &log_context)
+        {
+            Ok((vm_status, output, sender)) => {
+                if output.status().is_discarded() {
+                    match sender {
+                        Some(s) => trace!(
+                            log_context,
+                            "Transaction discarded, sender: {}, error: {:?}",
+                            s,
+                            vm_status,
+                        ),
+                        None => {
+                            trace!(log_context, "Transaction malformed, error: {:?}", vm_status,)
+                        }
+                    };
+                }
+                if is_reconfiguration(&output) {
+                    info!(log_context, "Reconfiguration occurred: restart required",);
+                    ExecutionStatus::SkipRest(DijetsTransactionOutput::new(output))
+                } else {
+                    ExecutionStatus::Success(DijetsTransactionOutput::new(output))
+                }
+            }
+            Err(err) => ExecutionStatus::Abort(err),
+        }
+    }
+}
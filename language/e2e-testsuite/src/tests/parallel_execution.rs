@@ -0,0 +1,77 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_types::transaction::{SignedTransaction, Transaction, TransactionOutput};
+use dijets_vm::{DijetsVM, ParallelDijetsVM, VMExecutor};
+use language_e2e_tests::{
+    account::Account, common_transactions::peer_to_peer_txn, executor::FakeExecutor,
+};
+
+// Executes `txns` both sequentially and in parallel and checks that the outputs are identical.
+fn assert_parallel_matches_sequential(executor: &FakeExecutor, txns: Vec<SignedTransaction>) {
+    let block: Vec<Transaction> = txns.into_iter().map(Transaction::UserTransaction).collect();
+
+    let sequential_output = DijetsVM::execute_block(block.clone(), executor.get_state_view())
+        .expect("Sequential execution must succeed");
+    let (parallel_output, fallback) =
+        ParallelDijetsVM::execute_block(block, executor.get_state_view(), 4)
+            .expect("Parallel execution must succeed");
+
+    assert!(fallback.is_none());
+    assert_eq!(sequential_output.len(), parallel_output.len());
+    for (sequential, parallel) in sequential_output.iter().zip(parallel_output.iter()) {
+        assert_outputs_eq(sequential, parallel);
+    }
+}
+
+fn assert_outputs_eq(left: &TransactionOutput, right: &TransactionOutput) {
+    assert_eq!(left.write_set(), right.write_set());
+    assert_eq!(left.events(), right.events());
+    assert_eq!(left.gas_used(), right.gas_used());
+    assert_eq!(left.status(), right.status());
+}
+
+fn create_accounts(executor: &mut FakeExecutor, count: usize) -> Vec<Account> {
+    executor.create_accounts(count, 2_000_000, 10)
+}
+
+#[test]
+fn parallel_cyclic_transfers() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let accounts = create_accounts(&mut executor, 100);
+
+    // Every account pays the next one, so every transaction conflicts with its neighbours.
+    let count = accounts.len();
+    let txns = (0..count)
+        .map(|i| peer_to_peer_txn(&accounts[i], &accounts[(i + 1) % count], 10, 1_000))
+        .collect();
+    assert_parallel_matches_sequential(&executor, txns);
+}
+
+#[test]
+fn parallel_many_to_one_transfers() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let accounts = create_accounts(&mut executor, 100);
+
+    let receiver = &accounts[0];
+    let txns = accounts
+        .iter()
+        .skip(1)
+        .map(|sender| peer_to_peer_txn(sender, receiver, 10, 1_000))
+        .collect();
+    assert_parallel_matches_sequential(&executor, txns);
+}
+
+#[test]
+fn parallel_sequence_number_chain() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let accounts = create_accounts(&mut executor, 2);
+
+    // Consecutive transactions from a single sender, including a sequence number gap which gets
+    // the trailing transactions discarded.
+    let txns = (10..60)
+        .chain(70..80)
+        .map(|seq_num| peer_to_peer_txn(&accounts[0], &accounts[1], seq_num, 1_000))
+        .collect();
+    assert_parallel_matches_sequential(&executor, txns);
+}
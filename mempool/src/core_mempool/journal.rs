@@ -0,0 +1,155 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal of the transactions accepted into mempool, so that pending transactions
+//! survive node restarts.
+//!
+//! The journal is a sequence of length-prefixed, BCS-serialized `JournalEntry`s. Accepted
+//! transactions are appended as they come in, and removals are never written: instead the
+//! journal is periodically compacted by rewriting it with the current contents of mempool.
+//! Entries for transactions which were committed or expired in the meantime are discarded when
+//! the journal is replayed on startup.
+
+use crate::core_mempool::transaction::TimelineState;
+use anyhow::{anyhow, ensure, Context, Error, Result};
+use dijets_types::transaction::SignedTransaction;
+use move_core_types::gas_schedule::MAX_TRANSACTION_SIZE_IN_BYTES;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Upper bound on the size of a serialized entry: a raw transaction of the maximum size, along
+/// with its authenticator (at most a multi-ed25519 one with 32 keys and signatures) and the
+/// other fields of the entry. Larger length prefixes can only come from a corrupted journal.
+const MAX_ENTRY_SIZE: usize = MAX_TRANSACTION_SIZE_IN_BYTES as usize + 8 * 1024;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JournalEntry {
+    pub txn: SignedTransaction,
+    // System expiration time of the transaction, kept so that it isn't extended by a restart.
+    pub expiration_time: Duration,
+    pub timeline_state: TimelineState,
+}
+
+pub struct MempoolJournal {
+    path: PathBuf,
+    file: BufWriter<File>,
+}
+
+impl MempoolJournal {
+    /// Creates a journal at `path` holding exactly `entries`, replacing any existing journal.
+    pub fn create<'a>(
+        path: &Path,
+        entries: impl IntoIterator<Item = &'a JournalEntry>,
+    ) -> Result<Self> {
+        let mut journal = Self {
+            path: path.to_path_buf(),
+            file: BufWriter::new(Self::open(path)?),
+        };
+        journal.compact(entries)?;
+        Ok(journal)
+    }
+
+    /// Reads the entries of the journal at `path`. A missing journal is empty.
+    /// A truncated trailing entry, e.g. from a crash in the middle of a write, is ignored.
+    /// Reading stops at the first corrupted entry: the entries before it are returned along with
+    /// the corruption, and the rest of the journal is dropped when it is next compacted.
+    pub fn read(path: &Path) -> Result<(Vec<JournalEntry>, Option<Error>)> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((vec![], None)),
+            Err(e) => return Err(e).with_context(|| format!("failed to open {}", path.display())),
+        };
+        let mut reader = BufReader::new(file);
+        let mut entries = vec![];
+        loop {
+            match Self::read_entry(&mut reader) {
+                Ok(Some(entry)) => entries.push(entry),
+                Ok(None) => return Ok((entries, None)),
+                Err(e) => {
+                    let e = e.context(format!(
+                        "corrupted entry {} in {}",
+                        entries.len(),
+                        path.display()
+                    ));
+                    return Ok((entries, Some(e)));
+                }
+            }
+        }
+    }
+
+    /// Reads the next entry, or `None` at the end of the journal or of its last complete entry.
+    fn read_entry(reader: &mut impl Read) -> Result<Option<JournalEntry>> {
+        let mut len = [0u8; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_ENTRY_SIZE {
+            return Err(anyhow!(
+                "entry length {} exceeds the maximum of {}",
+                len,
+                MAX_ENTRY_SIZE
+            ));
+        }
+        let mut bytes = vec![0u8; len];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        Ok(Some(bcs::from_bytes(&bytes)?))
+    }
+
+    /// Appends `entry` to the journal.
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        Self::write_entry(&mut self.file, entry)?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// Atomically replaces the contents of the journal with `entries`.
+    pub fn compact<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = &'a JournalEntry>,
+    ) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        let mut tmp_file = BufWriter::new(File::create(&tmp_path)?);
+        for entry in entries {
+            Self::write_entry(&mut tmp_file, entry)?;
+        }
+        tmp_file.flush()?;
+        tmp_file.get_ref().sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.file = BufWriter::new(Self::open(&self.path)?);
+        Ok(())
+    }
+
+    fn open(path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open {}", path.display()))
+    }
+
+    fn write_entry(writer: &mut impl Write, entry: &JournalEntry) -> Result<()> {
+        let bytes = bcs::to_bytes(entry)?;
+        ensure!(
+            bytes.len() <= MAX_ENTRY_SIZE,
+            "entry length {} exceeds the maximum of {}",
+            bytes.len(),
+            MAX_ENTRY_SIZE
+        );
+        writer.write_all(&u32::try_from(bytes.len())?.to_le_bytes())?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        journal::{JournalEntry, MempoolJournal},
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
@@ -13,6 +14,7 @@ use crate::{
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
 };
+use anyhow::Result;
 use dijets_config::config::NodeConfig;
 use dijets_logger::prelude::*;
 use dijets_types::{
@@ -23,6 +25,7 @@ use dijets_types::{
 use std::{
     cmp::max,
     collections::HashSet,
+    path::Path,
     time::{Duration, SystemTime},
 };
 
@@ -36,6 +39,8 @@ pub struct Mempool {
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
     pub system_transaction_timeout: Duration,
+    // Optional on-disk journal of accepted transactions.
+    journal: Option<MempoolJournal>,
 }
 
 impl Mempool {
@@ -47,6 +52,7 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            journal: None,
         }
     }
 
@@ -107,6 +113,50 @@ impl Mempool {
         db_sequence_number: u64,
         timeline_state: TimelineState,
         governance_role: GovernanceRole,
+    ) -> MempoolStatus {
+        let expiration_time =
+            dijets_infallible::duration_since_epoch() + self.system_transaction_timeout;
+        self.add_txn_with_expiration_time(
+            txn,
+            expiration_time,
+            gas_amount,
+            ranking_score,
+            db_sequence_number,
+            timeline_state,
+            governance_role,
+        )
+    }
+
+    /// Re-adds a transaction read from the journal, keeping its original system expiration time.
+    pub(crate) fn restore_txn(
+        &mut self,
+        entry: JournalEntry,
+        ranking_score: u64,
+        db_sequence_number: u64,
+        governance_role: GovernanceRole,
+    ) -> MempoolStatus {
+        let gas_amount = entry.txn.max_gas_amount();
+        self.add_txn_with_expiration_time(
+            entry.txn,
+            entry.expiration_time,
+            gas_amount,
+            ranking_score,
+            db_sequence_number,
+            entry.timeline_state,
+            governance_role,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_txn_with_expiration_time(
+        &mut self,
+        txn: SignedTransaction,
+        expiration_time: Duration,
+        gas_amount: u64,
+        ranking_score: u64,
+        db_sequence_number: u64,
+        timeline_state: TimelineState,
+        governance_role: GovernanceRole,
     ) -> MempoolStatus {
         trace!(
             LogSchema::new(LogEntry::AddTxn)
@@ -128,13 +178,28 @@ impl Mempool {
             ));
         }
 
-        let expiration_time =
-            dijets_infallible::duration_since_epoch() + self.system_transaction_timeout;
         if timeline_state != TimelineState::NonQualified {
             self.metrics_cache
                 .insert((txn.sender(), txn.sequence_number()), SystemTime::now());
         }
 
+        // Resubmissions of a txn already in mempool are accepted without being inserted again, so
+        // they aren't journaled again either.
+        let journal_entry = if self.journal.is_some()
+            && self
+                .transactions
+                .get(&txn.sender(), txn.sequence_number())
+                .as_ref()
+                != Some(&txn)
+        {
+            Some(JournalEntry {
+                txn: txn.clone(),
+                expiration_time,
+                timeline_state,
+            })
+        } else {
+            None
+        };
         let txn_info = MempoolTransaction::new(
             txn,
             expiration_time,
@@ -144,7 +209,15 @@ impl Mempool {
             governance_role,
         );
 
-        self.transactions.insert(txn_info, sequence_number)
+        let status = self.transactions.insert(txn_info, sequence_number);
+        if let (Some(journal), Some(entry)) = (self.journal.as_mut(), journal_entry) {
+            if status.code == MempoolStatusCode::Accepted {
+                if let Err(e) = journal.append(&entry) {
+                    error!(LogSchema::new(LogEntry::MempoolJournal).error(&e));
+                }
+            }
+        }
+        status
     }
 
     /// Fetches next block of transactions for consensus.
@@ -265,6 +338,39 @@ impl Mempool {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
 
+    /// Starts journaling accepted transactions to `path`. The journal is initialized with the
+    /// current contents of mempool.
+    pub(crate) fn enable_journal(&mut self, path: &Path) -> Result<()> {
+        self.journal = Some(MempoolJournal::create(path, &self.journal_entries())?);
+        Ok(())
+    }
+
+    /// Rewrites the journal with the current contents of mempool, dropping the entries of
+    /// transactions which were since removed.
+    pub(crate) fn compact_journal(&mut self) {
+        let entries = self.journal_entries();
+        if let Some(journal) = self.journal.as_mut() {
+            if let Err(e) = journal.compact(&entries) {
+                error!(LogSchema::new(LogEntry::MempoolJournal).error(&e));
+            }
+        }
+    }
+
+    fn journal_entries(&self) -> Vec<JournalEntry> {
+        self.transactions
+            .iter_transactions()
+            .map(|txn| JournalEntry {
+                txn: txn.txn.clone(),
+                expiration_time: txn.expiration_time,
+                // timeline ids aren't stable across restarts, txns get new ones on replay
+                timeline_state: match txn.timeline_state {
+                    TimelineState::Ready(_) => TimelineState::NotReady,
+                    state => state,
+                },
+            })
+            .collect()
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
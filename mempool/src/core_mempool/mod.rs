@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
 mod transaction;
 mod transaction_store;
 mod ttl_cache;
 
+pub use self::{
+    index::TxnPointer,
+    journal::{JournalEntry, MempoolJournal},
+    mempool::Mempool as CoreMempool,
+    transaction::TimelineState,
+};
 #[cfg(test)]
-pub use self::ttl_cache::TtlCache;
-pub use self::{index::TxnPointer, mempool::Mempool as CoreMempool, transaction::TimelineState};
+pub use self::{transaction::MempoolTransaction, ttl_cache::TtlCache};
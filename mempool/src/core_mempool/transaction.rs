@@ -6,7 +6,7 @@ use dijets_types::{
     transaction::{GovernanceRole, SignedTransaction},
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{mem::size_of, time::Duration};
 
 #[derive(Clone)]
 pub struct MempoolTransaction {
@@ -17,6 +17,8 @@ pub struct MempoolTransaction {
     pub ranking_score: u64,
     pub timeline_state: TimelineState,
     pub governance_role: GovernanceRole,
+    // Estimated memory footprint of the transaction, used to bound mempool by size.
+    pub size_bytes: usize,
 }
 
 impl MempoolTransaction {
@@ -28,6 +30,7 @@ impl MempoolTransaction {
         timeline_state: TimelineState,
         governance_role: GovernanceRole,
     ) -> Self {
+        let size_bytes = txn.raw_txn_bytes_len() + size_of::<Self>();
         Self {
             txn,
             expiration_time,
@@ -35,6 +38,7 @@ impl MempoolTransaction {
             ranking_score,
             timeline_state,
             governance_role,
+            size_bytes,
         }
     }
     pub(crate) fn get_sequence_number(&self) -> u64 {
@@ -46,6 +50,9 @@ impl MempoolTransaction {
     pub(crate) fn get_gas_price(&self) -> u64 {
         self.txn.gas_unit_price()
     }
+    pub(crate) fn get_estimated_bytes(&self) -> usize {
+        self.size_bytes
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Hash, Serialize)]
@@ -42,10 +42,14 @@ pub struct TransactionStore {
     timeline_index: TimelineIndex,
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
+    // estimated total size in bytes of all transactions in `transactions`
+    size_bytes: usize,
 
     // configuration
     capacity: usize,
+    capacity_bytes: usize,
     capacity_per_user: usize,
+    capacity_per_user_bytes: usize,
 }
 
 impl TransactionStore {
@@ -62,10 +66,13 @@ impl TransactionStore {
             priority_index: PriorityIndex::new(),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
+            size_bytes: 0,
 
             // configuration
             capacity: config.capacity,
+            capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
+            capacity_per_user_bytes: config.capacity_per_user_bytes,
         }
     }
 
@@ -120,12 +127,11 @@ impl TransactionStore {
             }
         }
 
-        if self.check_is_full_after_eviction(&txn, current_sequence_number) {
-            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
-                "mempool size: {}, capacity: {}",
-                self.system_ttl_index.size(),
-                self.capacity,
-            ));
+        if let Some(exceeded_limit) =
+            self.check_is_full_after_eviction(&txn, current_sequence_number)
+        {
+            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull)
+                .with_message(exceeded_limit);
         }
 
         self.transactions
@@ -145,10 +151,25 @@ impl TransactionStore {
                     ),
                 );
             }
+            let txns_size_bytes: usize = txns
+                .values()
+                .map(MempoolTransaction::get_estimated_bytes)
+                .sum();
+            if txns_size_bytes + txn.get_estimated_bytes() > self.capacity_per_user_bytes {
+                return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
+                    format!(
+                        "txns size: {} bytes, txn size: {} bytes, capacity per user: {} bytes",
+                        txns_size_bytes,
+                        txn.get_estimated_bytes(),
+                        self.capacity_per_user_bytes,
+                    ),
+                );
+            }
 
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
+            self.size_bytes += txn.get_estimated_bytes();
             txns.insert(sequence_number, txn);
             self.track_indices();
         }
@@ -177,37 +198,66 @@ impl TransactionStore {
             counters::TIMELINE_INDEX_LABEL,
             self.timeline_index.size(),
         );
+        counters::core_mempool_size_bytes(self.size_bytes);
     }
 
-    /// Checks if Mempool is full.
+    /// Checks if Mempool is full, i.e. if there is no room for `txn` either by transaction count
+    /// or by size in bytes.
     /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
+    /// Returns a description of the exceeded limit if Mempool is still full after eviction.
     fn check_is_full_after_eviction(
         &mut self,
         txn: &MempoolTransaction,
         curr_sequence_number: u64,
-    ) -> bool {
-        if self.system_ttl_index.size() >= self.capacity
+    ) -> Option<String> {
+        let txn_bytes = txn.get_estimated_bytes();
+        if self.exceeded_limit(txn_bytes).is_some()
             && self.check_txn_ready(txn, curr_sequence_number)
         {
-            // try to free some space in Mempool from ParkingLot by evicting a non-ready txn
-            if let Some((address, sequence_number)) = self.parking_lot_index.get_poppable() {
-                if let Some(txn) = self
+            // try to free enough space in Mempool from ParkingLot by evicting non-ready txns
+            while self.exceeded_limit(txn_bytes).is_some() {
+                let (address, sequence_number) = match self.parking_lot_index.get_poppable() {
+                    Some(key) => key,
+                    None => break,
+                };
+                match self
                     .transactions
                     .get_mut(&address)
                     .and_then(|txns| txns.remove(&sequence_number))
                 {
-                    debug!(
-                        LogSchema::new(LogEntry::MempoolFullEvictedTxn).txns(TxnsLog::new_txn(
-                            txn.get_sender(),
-                            txn.get_sequence_number()
-                        ))
-                    );
-                    self.index_remove(&txn);
+                    Some(txn) => {
+                        debug!(
+                            LogSchema::new(LogEntry::MempoolFullEvictedTxn)
+                                .txns(TxnsLog::new_txn(address, sequence_number)),
+                            size_bytes = txn.get_estimated_bytes(),
+                        );
+                        self.index_remove(&txn);
+                    }
+                    None => break,
                 }
             }
         }
-        self.system_ttl_index.size() >= self.capacity
+        self.exceeded_limit(txn_bytes)
+    }
+
+    /// Returns a description of the Mempool capacity limit that inserting a transaction of
+    /// `txn_bytes` would exceed, if any.
+    fn exceeded_limit(&self, txn_bytes: usize) -> Option<String> {
+        if self.system_ttl_index.size() >= self.capacity {
+            Some(format!(
+                "mempool size: {}, capacity: {}",
+                self.system_ttl_index.size(),
+                self.capacity,
+            ))
+        } else if self.size_bytes + txn_bytes > self.capacity_bytes {
+            Some(format!(
+                "mempool size: {} bytes, txn size: {} bytes, capacity: {} bytes",
+                self.size_bytes, txn_bytes, self.capacity_bytes,
+            ))
+        } else {
+            None
+        }
     }
 
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
@@ -332,6 +382,7 @@ impl TransactionStore {
     /// Removes transaction from all indexes.
     fn index_remove(&mut self, txn: &MempoolTransaction) {
         counters::CORE_MEMPOOL_REMOVED_TXNS.inc();
+        self.size_bytes = self.size_bytes.saturating_sub(txn.get_estimated_bytes());
         self.system_ttl_index.remove(txn);
         self.expiration_time_index.remove(txn);
         self.priority_index.remove(txn);
@@ -468,6 +519,11 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Iterates over all transactions in the store, in no particular order.
+    pub(crate) fn iter_transactions(&self) -> impl Iterator<Item = &MempoolTransaction> {
+        self.transactions.values().flat_map(|txns| txns.values())
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
@@ -92,6 +92,19 @@ pub fn core_mempool_index_size(label: &'static str, size: usize) {
         .set(size as i64)
 }
 
+/// Gauge tracking the total size in bytes of the txns in core mempool
+static CORE_MEMPOOL_SIZE_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "dijets_core_mempool_size_bytes",
+        "Total size in bytes of the txns in core mempool"
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_size_bytes(size_bytes: usize) {
+    CORE_MEMPOOL_SIZE_BYTES.set(size_bytes as i64)
+}
+
 /// Counter tracking number of txns removed from core mempool
 pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
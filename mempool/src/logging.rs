@@ -149,6 +149,7 @@ pub enum LogEntry {
     UpstreamNetwork,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    MempoolJournal,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -279,3 +279,15 @@ pub(crate) async fn snapshot_job(mempool: Arc<Mutex<CoreMempool>>, snapshot_inte
         debug!(LogSchema::new(LogEntry::MempoolSnapshot).txns(snapshot));
     }
 }
+
+/// Periodically compacts the mempool journal, dropping the entries of transactions which have
+/// since left mempool.
+pub(crate) async fn journal_compaction_job(
+    mempool: Arc<Mutex<CoreMempool>>,
+    compaction_interval_secs: u64,
+) {
+    let mut interval = IntervalStream::new(interval(Duration::from_secs(compaction_interval_secs)));
+    while let Some(_interval) = interval.next().await {
+        mempool.lock().compact_journal();
+    }
+}
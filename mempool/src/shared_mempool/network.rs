@@ -11,7 +11,11 @@ use fail::fail_point;
 use network::{
     error::NetworkError,
     peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
-    protocols::network::{NetworkEvents, NetworkSender, NewNetworkSender},
+    protocols::{
+        network::{NetworkEvents, NetworkSender, NewNetworkSender},
+        wire::handshake::v1::SupportedProtocols,
+    },
+    reputation::Misbehavior,
     ProtocolId,
 };
 use serde::{Deserialize, Serialize};
@@ -39,6 +43,12 @@ pub enum MempoolSyncMsg {
 /// Protocol id for mempool direct-send calls.
 pub const MEMPOOL_DIRECT_SEND_PROTOCOL: &[u8] = b"/dijets/direct-send/0.1.0/mempool/0.1.0";
 
+/// Supported protocols in preferred order (from highest priority to lowest).
+pub const DIRECT_SEND: &[ProtocolId] = &[
+    ProtocolId::MempoolDirectSendCompressed,
+    ProtocolId::MempoolDirectSend,
+];
+
 /// The interface from Network to Mempool layer.
 ///
 /// `MempoolNetworkEvents` is a `Stream` of `PeerManagerNotification` where the
@@ -73,7 +83,7 @@ pub fn network_endpoint_config(
 ) {
     (
         vec![],
-        vec![ProtocolId::MempoolDirectSend],
+        DIRECT_SEND.to_vec(),
         QueueStyle::KLAST,
         max_broadcasts_per_peer,
         Some(&counters::PENDING_MEMPOOL_NETWORK_EVENTS),
@@ -92,15 +102,47 @@ impl NewNetworkSender for MempoolNetworkSender {
 }
 
 impl MempoolNetworkSender {
+    /// Sends a message over the uncompressed protocol, which every peer supports.
     pub fn send_to(
         &mut self,
         recipient: PeerId,
         message: MempoolSyncMsg,
+    ) -> Result<(), NetworkError> {
+        self.send_with_protocol(recipient, ProtocolId::MempoolDirectSend, message)
+    }
+
+    /// Sends a message over the most preferred protocol among the `supported_protocols` of the
+    /// peer, negotiated in the handshake.
+    pub fn send_to_with_protocols(
+        &mut self,
+        recipient: PeerId,
+        supported_protocols: &SupportedProtocols,
+        message: MempoolSyncMsg,
+    ) -> Result<(), NetworkError> {
+        let protocol = supported_protocols
+            .preferred(DIRECT_SEND)
+            .unwrap_or(ProtocolId::MempoolDirectSend);
+        self.send_with_protocol(recipient, protocol, message)
+    }
+
+    fn send_with_protocol(
+        &mut self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        message: MempoolSyncMsg,
     ) -> Result<(), NetworkError> {
         fail_point!("mempool::send_to", |_| {
             Err(anyhow::anyhow!("Injected error in mempool::send_to").into())
         });
-        let protocol = ProtocolId::MempoolDirectSend;
         self.inner.send_to(recipient, protocol, message)
     }
+
+    /// Reports a misbehaving peer to the network, which bans it once it misbehaves too often.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.inner.report_misbehavior(peer, misbehavior)
+    }
 }
@@ -16,7 +16,7 @@ use dijets_logger::prelude::*;
 use dijets_types::transaction::SignedTransaction;
 use itertools::Itertools;
 use netcore::transport::ConnectionOrigin;
-use network::transport::ConnectionMetadata;
+use network::{reputation::Misbehavior, transport::ConnectionMetadata};
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
 use std::{
@@ -86,6 +86,8 @@ pub struct BroadcastInfo {
     pub sent_batches: BTreeMap<BatchId, SystemTime>,
     // Broadcasts that have received a retry ack and are pending a resend.
     pub retry_batches: BTreeSet<BatchId>,
+    // Sent broadcasts whose ack timed out, already reported as misbehavior of the peer.
+    pub timed_out_batches: BTreeSet<BatchId>,
     // Whether broadcasting to this peer is in backoff mode, e.g. broadcasting at longer intervals.
     pub backoff_mode: bool,
 }
@@ -95,6 +97,7 @@ impl BroadcastInfo {
         Self {
             sent_batches: BTreeMap::new(),
             retry_batches: BTreeSet::new(),
+            timed_out_batches: BTreeSet::new(),
             backoff_mode: false,
         }
     }
@@ -228,6 +231,11 @@ impl PeerManager {
                 .into_iter()
                 .filter(|(id, _batch)| !mempool.timeline_range(id.0, id.1).is_empty())
                 .collect::<BTreeMap<BatchId, SystemTime>>();
+            let sent_batches = &state.broadcast_info.sent_batches;
+            state
+                .broadcast_info
+                .timed_out_batches
+                .retain(|id| sent_batches.contains_key(id));
 
             // Check for batch to rebroadcast:
             // 1. Batch that did not receive ACK in configured window of time
@@ -293,9 +301,26 @@ impl PeerManager {
             .expect("[shared mempool] missing network sender")
             .clone();
 
+        // The peer didn't ACK the batch in time, which is only reported once per batch as it is
+        // rebroadcast until the peer ACKs it
+        if metric_label == Some(counters::EXPIRED_BROADCAST_LABEL)
+            && state.broadcast_info.timed_out_batches.insert(batch_id)
+        {
+            if let Err(e) = network_sender.report_misbehavior(peer.peer_id(), Misbehavior::Timeout)
+            {
+                warn!(LogSchema::event_log(
+                    LogEntry::BroadcastTransaction,
+                    LogEvent::NetworkSendFail
+                )
+                .peer(&peer)
+                .error(&e.into()));
+            }
+        }
+
         let num_txns = transactions.len();
-        if let Err(e) = network_sender.send_to(
+        if let Err(e) = network_sender.send_to_with_protocols(
             peer.peer_id(),
+            &state.metadata.application_protocols,
             MempoolSyncMsg::BroadcastTransactionsRequest {
                 request_id: bcs::to_bytes(&batch_id).expect("failed BCS serialization of batch ID"),
                 transactions,
@@ -3,10 +3,12 @@
 
 use crate::{
     core_mempool::CoreMempool,
+    logging::{LogEntry, LogSchema},
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        coordinator::{coordinator, gc_coordinator, journal_compaction_job, snapshot_job},
         peer_manager::PeerManager,
+        tasks,
         types::{SharedMempool, SharedMempoolNotification},
     },
     CommitNotification, ConsensusRequest, SubmissionStatus,
@@ -15,6 +17,7 @@ use anyhow::Result;
 use channel::dijets_channel;
 use dijets_config::{config::NodeConfig, network_id::NodeNetworkId};
 use dijets_infallible::{Mutex, RwLock};
+use dijets_logger::prelude::*;
 use dijets_types::{on_chain_config::OnChainConfigPayload, transaction::SignedTransaction};
 use futures::channel::{
     mpsc::{self, Receiver, UnboundedSender},
@@ -30,6 +33,7 @@ use vm_validator::vm_validator::{TransactionValidation, VMValidator};
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+///   - journal_compaction_task (task that periodically compacts the mempool journal, if enabled).
 pub(crate) fn start_shared_mempool<V>(
     executor: &Handle,
     config: &NodeConfig,
@@ -49,6 +53,19 @@ pub(crate) fn start_shared_mempool<V>(
 {
     let peer_manager = Arc::new(PeerManager::new(config.base.role, config.mempool.clone()));
 
+    let journal_path = config.mempool.journal_path();
+    if let Some(journal_path) = &journal_path {
+        match tasks::restore_from_journal(&mempool, journal_path, db.as_ref(), &validator) {
+            Ok(restored) => info!(
+                LogSchema::new(LogEntry::MempoolJournal),
+                "restored {} transactions from mempool journal {}",
+                restored,
+                journal_path.display()
+            ),
+            Err(e) => error!(LogSchema::new(LogEntry::MempoolJournal).error(&e)),
+        }
+    }
+
     let mut all_network_events = vec![];
     let mut network_senders = HashMap::new();
     for (network_id, network_sender, network_events) in mempool_network_handles.into_iter() {
@@ -81,6 +98,13 @@ pub(crate) fn start_shared_mempool<V>(
         config.mempool.system_transaction_gc_interval_ms,
     ));
 
+    if journal_path.is_some() {
+        executor.spawn(journal_compaction_job(
+            mempool.clone(),
+            config.mempool.journal_compaction_interval_secs,
+        ));
+    }
+
     executor.spawn(snapshot_job(
         mempool,
         config.mempool.mempool_snapshot_interval_secs,
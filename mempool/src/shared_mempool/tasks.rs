@@ -4,7 +4,7 @@
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{CoreMempool, MempoolJournal, TimelineState, TxnPointer},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::MempoolSyncMsg,
@@ -32,9 +32,11 @@ use short_hex_str::AsShortHexStr;
 use std::{
     cmp,
     collections::HashSet,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
+use storage_interface::DbReader;
 use tokio::runtime::Handle;
 use vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
 
@@ -335,6 +337,74 @@ fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<Pe
     }
 }
 
+// ============================== //
+//     mempool journal tasks      //
+// ============================== //
+
+/// Replays the mempool journal at `path` into `mempool`, then starts journaling to it.
+/// Journaled transactions are re-validated like newly submitted ones, and transactions which
+/// expired or were committed while the node was down are discarded. A journal which can't be
+/// read in full is replayed up to its first corrupted entry, and then replaced.
+/// Returns the number of restored transactions.
+pub(crate) fn restore_from_journal<V>(
+    mempool: &Mutex<CoreMempool>,
+    path: &Path,
+    db: &dyn DbReader,
+    validator: &RwLock<V>,
+) -> Result<usize>
+where
+    V: TransactionValidation,
+{
+    let entries = match MempoolJournal::read(path) {
+        Ok((entries, corruption)) => {
+            if let Some(e) = corruption {
+                error!(LogSchema::new(LogEntry::MempoolJournal).error(&e));
+            }
+            entries
+        }
+        Err(e) => {
+            error!(LogSchema::new(LogEntry::MempoolJournal).error(&e));
+            vec![]
+        }
+    };
+    let now = dijets_infallible::duration_since_epoch();
+    let mut restored = 0;
+    let mut mempool = mempool.lock();
+    for entry in entries {
+        if entry.expiration_time <= now
+            || Duration::from_secs(entry.txn.expiration_timestamp_secs()) <= now
+        {
+            continue;
+        }
+        let sequence_number = match get_account_sequence_number(db, entry.txn.sender()) {
+            Ok(sequence_number) if entry.txn.sequence_number() >= sequence_number => {
+                sequence_number
+            }
+            Ok(_) => continue,
+            Err(e) => {
+                error!(LogSchema::new(LogEntry::DBError).error(&e));
+                counters::DB_ERROR.inc();
+                continue;
+            }
+        };
+        let validation_result = match validator.read().validate_transaction(entry.txn.clone()) {
+            Ok(validation_result) if validation_result.status().is_none() => validation_result,
+            _ => continue,
+        };
+        let status = mempool.restore_txn(
+            entry,
+            validation_result.score(),
+            sequence_number,
+            validation_result.governance_role(),
+        );
+        if status.code == MempoolStatusCode::Accepted {
+            restored += 1;
+        }
+    }
+    mempool.enable_journal(path)?;
+    Ok(restored)
+}
+
 // ================================= //
 // intra-node communication handlers //
 // ================================= //
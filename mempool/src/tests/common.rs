@@ -54,29 +54,34 @@ impl TestTransaction {
         &self,
         exp_timestamp_secs: u64,
     ) -> SignedTransaction {
-        self.make_signed_transaction_impl(100, exp_timestamp_secs)
+        self.make_signed_transaction_impl(100, exp_timestamp_secs, vec![])
     }
 
     pub(crate) fn make_signed_transaction_with_max_gas_amount(
         &self,
         max_gas_amount: u64,
     ) -> SignedTransaction {
-        self.make_signed_transaction_impl(max_gas_amount, u64::max_value())
+        self.make_signed_transaction_impl(max_gas_amount, u64::max_value(), vec![])
+    }
+
+    pub(crate) fn make_signed_transaction_with_code(&self, code: Vec<u8>) -> SignedTransaction {
+        self.make_signed_transaction_impl(100, u64::max_value(), code)
     }
 
     pub(crate) fn make_signed_transaction(&self) -> SignedTransaction {
-        self.make_signed_transaction_impl(100, u64::max_value())
+        self.make_signed_transaction_impl(100, u64::max_value(), vec![])
     }
 
     fn make_signed_transaction_impl(
         &self,
         max_gas_amount: u64,
         exp_timestamp_secs: u64,
+        code: Vec<u8>,
     ) -> SignedTransaction {
         let raw_txn = RawTransaction::new_script(
             TestTransaction::get_address(self.address),
             self.sequence_number,
-            Script::new(vec![], vec![], vec![]),
+            Script::new(code, vec![], vec![]),
             max_gas_amount,
             self.gas_price,
             XUS_NAME.to_owned(),
@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TtlCache},
+    core_mempool::{CoreMempool, MempoolTransaction, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
         TestTransaction,
     },
 };
 use dijets_config::config::NodeConfig;
-use dijets_types::transaction::{GovernanceRole, SignedTransaction};
+use dijets_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{GovernanceRole, SignedTransaction},
+};
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
@@ -365,6 +368,104 @@ fn test_parking_lot_eviction() {
     assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
 }
 
+fn estimated_bytes(txn: &SignedTransaction) -> usize {
+    MempoolTransaction::new(
+        txn.clone(),
+        Duration::from_secs(0),
+        0,
+        0,
+        TimelineState::NotReady,
+        GovernanceRole::NonGovernanceRole,
+    )
+    .get_estimated_bytes()
+}
+
+fn add_signed_txn_with_status(pool: &mut CoreMempool, txn: SignedTransaction) -> MempoolStatus {
+    pool.add_txn(
+        txn.clone(),
+        0,
+        txn.gas_unit_price(),
+        0,
+        TimelineState::NotReady,
+        GovernanceRole::NonGovernanceRole,
+    )
+}
+
+#[test]
+fn test_capacity_bytes() {
+    let txns: Vec<_> = (0..3)
+        .map(|seq| TestTransaction::new(1, seq, 1).make_signed_transaction())
+        .collect();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity_bytes = 2 * estimated_bytes(&txns[0]);
+    let mut pool = CoreMempool::new(&config);
+
+    // Error on exceeding limit.
+    add_signed_txn(&mut pool, txns[0].clone()).unwrap();
+    add_signed_txn(&mut pool, txns[1].clone()).unwrap();
+    let status = add_signed_txn_with_status(&mut pool, txns[2].clone());
+    assert_eq!(status.code, MempoolStatusCode::MempoolIsFull);
+    assert!(status.message.contains("bytes"));
+
+    // Commit transaction and free space.
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    add_signed_txn(&mut pool, txns[2].clone()).unwrap();
+}
+
+#[test]
+fn test_capacity_per_user_bytes() {
+    let txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity_per_user_bytes = 2 * estimated_bytes(&txn);
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+    let status = add_signed_txn_with_status(
+        &mut pool,
+        TestTransaction::new(1, 2, 1).make_signed_transaction(),
+    );
+    assert_eq!(status.code, MempoolStatusCode::TooManyTransactions);
+
+    // Other accounts are not affected.
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+}
+
+#[test]
+fn test_parking_lot_eviction_by_bytes() {
+    let txn_bytes = estimated_bytes(&TestTransaction::new(1, 0, 1).make_signed_transaction());
+    let mut config = NodeConfig::random();
+    config.mempool.capacity_bytes = 5 * txn_bytes;
+    let mut pool = CoreMempool::new(&config);
+    // Add transactions with the following sequence numbers to Mempool.
+    for seq in &[0, 1, 2, 9, 10] {
+        add_txn(&mut pool, TestTransaction::new(1, *seq, 1)).unwrap();
+    }
+
+    // Mempool is full. Insert a txn which needs the space of two parked txns.
+    let big_txn = TestTransaction::new(0, 0, 1).make_signed_transaction_with_code(vec![0; 100]);
+    let big_txn_bytes = estimated_bytes(&big_txn);
+    assert!(big_txn_bytes > txn_bytes && big_txn_bytes <= 2 * txn_bytes);
+    add_signed_txn(&mut pool, big_txn).unwrap();
+    assert_eq!(pool.get_parking_lot_size(), 0);
+
+    // Make sure that we have correct txns in Mempool.
+    let mut txns: Vec<_> = pool
+        .get_block(5, HashSet::new())
+        .iter()
+        .map(SignedTransaction::sequence_number)
+        .collect();
+    txns.sort_unstable();
+    assert_eq!(txns, vec![0, 0, 1, 2]);
+
+    // Make sure we can't insert any new transactions, cause parking lot supposed to be empty by now.
+    let status = add_signed_txn_with_status(
+        &mut pool,
+        TestTransaction::new(0, 1, 1).make_signed_transaction(),
+    );
+    assert_eq!(status.code, MempoolStatusCode::MempoolIsFull);
+}
+
 #[test]
 fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     let mut config = NodeConfig::random();
@@ -2,15 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    core_mempool::{CoreMempool, MempoolJournal},
     mocks::MockSharedMempool,
+    shared_mempool::tasks::restore_from_journal,
     tests::common::{batch_add_signed_txn, TestTransaction},
     CommitNotification, CommittedTransaction, ConsensusRequest,
 };
+use dijets_config::config::NodeConfig;
+use dijets_infallible::{Mutex, RwLock};
+use dijets_temppath::TempPath;
 use futures::{
     channel::{mpsc, oneshot},
     executor::block_on,
     sink::SinkExt,
 };
+use std::{collections::HashSet, fs::OpenOptions, io::Write};
+use storage_interface::mock::MockDbReader;
+use vm_validator::mocks::mock_vm_validator::MockVMValidator;
 
 #[test]
 fn test_consensus_events_rejected_txns() {
@@ -96,3 +104,115 @@ fn test_state_sync_events_committed_txns() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline.get(0).unwrap(), &kept_txn);
 }
+
+fn num_journal_entries(journal_path: &TempPath) -> usize {
+    MempoolJournal::read(journal_path.path()).unwrap().0.len()
+}
+
+#[test]
+fn test_mempool_journal() {
+    let journal_path = TempPath::new();
+    let config = NodeConfig::random();
+
+    let kept_txns = vec![
+        TestTransaction::new(0, 0, 1).make_signed_transaction(),
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+    ];
+    let expired_txn = TestTransaction::new(2, 0, 1).make_signed_transaction_with_expiration_time(0);
+    let committed_txn = TestTransaction::new(3, 0, 1).make_signed_transaction();
+
+    let mut pool = CoreMempool::new(&config);
+    pool.enable_journal(journal_path.path()).unwrap();
+    let mut txns = kept_txns.clone();
+    txns.push(expired_txn);
+    txns.push(committed_txn.clone());
+    assert!(batch_add_signed_txn(&mut pool, txns).is_ok());
+    assert_eq!(num_journal_entries(&journal_path), 4);
+
+    // Resubmitted txns aren't journaled again.
+    assert!(batch_add_signed_txn(&mut pool, kept_txns.clone()).is_ok());
+    assert_eq!(num_journal_entries(&journal_path), 4);
+
+    // Compaction drops the txns which left mempool.
+    pool.remove_transaction(&committed_txn.sender(), 0, false);
+    pool.compact_journal();
+    assert_eq!(num_journal_entries(&journal_path), 3);
+
+    // Expired txns aren't restored.
+    let restored_pool = Mutex::new(CoreMempool::new(&config));
+    let restored = restore_from_journal(
+        &restored_pool,
+        journal_path.path(),
+        &MockDbReader,
+        &RwLock::new(MockVMValidator),
+    )
+    .unwrap();
+    assert_eq!(restored, kept_txns.len());
+    let block: HashSet<_> = restored_pool
+        .lock()
+        .get_block(10, HashSet::new())
+        .into_iter()
+        .collect();
+    assert_eq!(block, kept_txns.into_iter().collect());
+
+    // The journal is rewritten with the restored txns.
+    assert_eq!(num_journal_entries(&journal_path), 2);
+}
+
+#[test]
+fn test_mempool_journal_corrupted() {
+    let journal_path = TempPath::new();
+    let config = NodeConfig::random();
+    let kept_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+
+    let mut pool = CoreMempool::new(&config);
+    pool.enable_journal(journal_path.path()).unwrap();
+    assert!(batch_add_signed_txn(&mut pool, vec![kept_txn.clone()]).is_ok());
+
+    // A length prefix larger than any entry is corruption, and isn't allocated.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(journal_path.path())
+        .unwrap();
+    file.write_all(&u32::MAX.to_le_bytes()).unwrap();
+    file.write_all(&[0; 16]).unwrap();
+    let (entries, corruption) = MempoolJournal::read(journal_path.path()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(corruption.is_some());
+
+    // The entries before the corruption are restored, and the journal is rewritten without it.
+    let restored_pool = Mutex::new(CoreMempool::new(&config));
+    let restored = restore_from_journal(
+        &restored_pool,
+        journal_path.path(),
+        &MockDbReader,
+        &RwLock::new(MockVMValidator),
+    )
+    .unwrap();
+    assert_eq!(restored, 1);
+    let (entries, corruption) = MempoolJournal::read(journal_path.path()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].txn, kept_txn);
+    assert!(corruption.is_none());
+
+    // So is a journal with an undecodable entry.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(journal_path.path())
+        .unwrap();
+    file.write_all(&4u32.to_le_bytes()).unwrap();
+    file.write_all(&[0xff; 4]).unwrap();
+    let restored_pool = Mutex::new(CoreMempool::new(&config));
+    let restored = restore_from_journal(
+        &restored_pool,
+        journal_path.path(),
+        &MockDbReader,
+        &RwLock::new(MockVMValidator),
+    )
+    .unwrap();
+    assert_eq!(restored, 1);
+    assert!(MempoolJournal::read(journal_path.path())
+        .unwrap()
+        .1
+        .is_none());
+}
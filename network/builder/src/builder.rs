@@ -12,19 +12,21 @@
 use channel::{self, message_queues::QueueStyle};
 use dijets_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RateLimitConfig, RoleType,
-        CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS,
-        MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS,
-        MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
+        DiscoveryMethod, Identity, NetworkConfig, NoiseRekeyConfig, Peer, PeerExchangeConfig,
+        PeerRole, PeerSet, RateLimitConfig, ReputationConfig, RoleType, CONNECTION_BACKOFF_BASE,
+        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
+        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
+        MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
-use dijets_crypto::x25519::PublicKey;
+use dijets_crypto::x25519::{self, PublicKey};
+use dijets_global_constants::VALIDATOR_NETWORK_KEY_IN_USE;
 use dijets_infallible::RwLock;
 use dijets_logger::prelude::*;
 use dijets_metrics::IntCounterVec;
 use dijets_network_address_encryption::Encryptor;
-use dijets_secure_storage::Storage;
+use dijets_secure_storage::{KVStorage, Storage};
 use dijets_time_service::TimeService;
 use dijets_types::{chain_id::ChainId, network_address::NetworkAddress};
 use network::{
@@ -37,10 +39,13 @@ use network::{
     protocols::{
         health_checker::{self, builder::HealthCheckerBuilder},
         network::{NewNetworkEvents, NewNetworkSender},
+        peer_exchange::{self, builder::PeerExchangeBuilder},
     },
     ProtocolId,
 };
-use network_discovery::{gen_simple_discovery_reconfig_subscription, DiscoveryChangeListener};
+use network_discovery::{
+    gen_simple_discovery_reconfig_subscription, DiscoveryChangeListener, SystemResolver,
+};
 use std::{
     clone::Clone,
     collections::{HashMap, HashSet},
@@ -69,6 +74,7 @@ pub struct NetworkBuilder {
     discovery_listeners: Option<Vec<DiscoveryChangeListener>>,
     connectivity_manager_builder: Option<ConnectivityManagerBuilder>,
     health_checker_builder: Option<HealthCheckerBuilder>,
+    peer_exchange_builder: Option<PeerExchangeBuilder>,
     peer_manager_builder: PeerManagerBuilder,
 
     // (StateSync) ReconfigSubscriptions required by internal Network components.
@@ -78,6 +84,7 @@ pub struct NetworkBuilder {
 impl NetworkBuilder {
     /// Return a new NetworkBuilder initialized with default configuration values.
     // TODO:  Remove `pub`.  NetworkBuilder should only be created thorugh `::create()`
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         chain_id: ChainId,
         trusted_peers: Arc<RwLock<PeerSet>>,
@@ -86,12 +93,15 @@ impl NetworkBuilder {
         listen_address: NetworkAddress,
         authentication_mode: AuthenticationMode,
         max_frame_size: usize,
+        max_message_size: usize,
         enable_proxy_protocol: bool,
+        noise_rekey_config: Option<NoiseRekeyConfig>,
         network_channel_size: usize,
         max_concurrent_network_reqs: usize,
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
+        reputation_config: Option<ReputationConfig>,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -105,10 +115,13 @@ impl NetworkBuilder {
             network_channel_size,
             max_concurrent_network_reqs,
             max_frame_size,
+            max_message_size,
             enable_proxy_protocol,
+            noise_rekey_config,
             inbound_connection_limit,
             inbound_rate_limit_config,
             outbound_rate_limit_config,
+            reputation_config,
         );
 
         NetworkBuilder {
@@ -119,6 +132,7 @@ impl NetworkBuilder {
             discovery_listeners: None,
             connectivity_manager_builder: None,
             health_checker_builder: None,
+            peer_exchange_builder: None,
             peer_manager_builder,
             reconfig_subscriptions: vec![],
         }
@@ -143,12 +157,15 @@ impl NetworkBuilder {
             listen_address,
             authentication_mode,
             MAX_FRAME_SIZE,
+            MAX_MESSAGE_SIZE,
             false, /* Disable proxy protocol */
+            None,  /* Disable noise rekeying */
             NETWORK_CHANNEL_SIZE,
             MAX_CONCURRENT_NETWORK_REQS,
             MAX_INBOUND_CONNECTIONS,
             None,
             None,
+            None, /* Disable peer reputation */
         );
 
         builder.add_connectivity_manager(
@@ -187,6 +204,7 @@ impl NetworkBuilder {
             config.network_id.clone(),
             peer_id,
         ));
+        record_network_key_in_use(config, &network_context, pubkey);
 
         let trusted_peers = Arc::new(RwLock::new(HashMap::new()));
 
@@ -198,12 +216,15 @@ impl NetworkBuilder {
             config.listen_address.clone(),
             authentication_mode,
             config.max_frame_size,
+            config.max_message_size,
             config.enable_proxy_protocol,
+            config.noise_rekey_config,
             config.network_channel_size,
             config.max_concurrent_network_reqs,
             config.max_inbound_connections,
             config.inbound_rate_limit_config,
             config.outbound_rate_limit_config,
+            config.reputation_config,
         );
 
         network_builder.add_connection_monitoring(
@@ -226,6 +247,13 @@ impl NetworkBuilder {
             config.mutual_authentication,
         );
 
+        // Peer exchange is only needed by nodes which can't rely on a trusted peer set
+        if let Some(peer_exchange_config) = config.peer_exchange_config {
+            if !config.mutual_authentication {
+                network_builder.add_peer_exchange(peer_exchange_config, config.identity_key());
+            }
+        }
+
         network_builder.discovery_listeners = Some(Vec::new());
         for discovery_method in config.discovery_methods() {
             network_builder.add_discovery_change_listener(
@@ -289,6 +317,14 @@ impl NetworkBuilder {
             );
         }
 
+        if let Some(peer_exchange_builder) = self.peer_exchange_builder.as_mut() {
+            peer_exchange_builder.start(executor);
+            debug!(
+                NetworkSchema::new(&self.network_context),
+                "{} Started peer exchange", self.network_context
+            );
+        }
+
         if let Some(discovery_listeners) = self.discovery_listeners.take() {
             discovery_listeners
                 .into_iter()
@@ -391,6 +427,22 @@ impl NetworkBuilder {
                 *interval_duration,
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Dns(config) => DiscoveryChangeListener::dns(
+                self.network_context.clone(),
+                conn_mgr_reqs_tx,
+                config,
+                Arc::new(
+                    SystemResolver::new(&config.name_servers)
+                        .expect("Failed to create DNS resolver"),
+                ),
+                self.time_service.clone(),
+            ),
+            DiscoveryMethod::Rest(config) => DiscoveryChangeListener::rest(
+                self.network_context.clone(),
+                conn_mgr_reqs_tx,
+                config,
+                self.time_service.clone(),
+            ),
             DiscoveryMethod::None => return,
         };
 
@@ -427,6 +479,34 @@ impl NetworkBuilder {
         self
     }
 
+    /// Add a PeerExchange to the network, feeding exchanged peers to the ConnectivityManager.
+    fn add_peer_exchange(
+        &mut self,
+        config: PeerExchangeConfig,
+        identity_key: x25519::PrivateKey,
+    ) -> &mut Self {
+        let conn_mgr_reqs_tx = self
+            .conn_mgr_reqs_tx()
+            .expect("ConnectivityManager must be installed for peer exchange");
+        let (px_network_tx, px_network_rx) =
+            self.add_protocol_handler(peer_exchange::network_endpoint_config());
+
+        self.peer_exchange_builder = Some(PeerExchangeBuilder::new(
+            self.network_context(),
+            identity_key,
+            self.time_service.clone(),
+            config,
+            px_network_tx,
+            px_network_rx,
+            conn_mgr_reqs_tx,
+        ));
+        debug!(
+            NetworkSchema::new(&self.network_context),
+            "{} Created peer exchange", self.network_context
+        );
+        self
+    }
+
     /// Adds a endpoints for the provided configuration.  Returns NetworkSender and NetworkEvent which
     /// can be attached to other components.
     pub fn add_protocol_handler<SenderT, EventT>(
@@ -497,3 +577,24 @@ fn merge_seeds(config: &NetworkConfig) -> PeerSet {
     );
     seeds
 }
+
+/// Records the validator network key loaded from storage, so that the key manager knows once the
+/// node no longer uses the previous key, i.e., once its addresses can be dropped from the
+/// ValidatorConfig.
+fn record_network_key_in_use(
+    config: &NetworkConfig,
+    network_context: &NetworkContext,
+    pubkey: PublicKey,
+) {
+    if let Identity::FromStorage(identity) = &config.identity {
+        if config.network_id.is_validator_network() {
+            let mut storage: Storage = (&identity.backend).into();
+            if let Err(e) = storage.set(VALIDATOR_NETWORK_KEY_IN_USE, pubkey) {
+                warn!(
+                    NetworkSchema::new(network_context),
+                    "Unable to record the network key in use: {}", e
+                );
+            }
+        }
+    }
+}
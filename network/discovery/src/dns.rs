@@ -0,0 +1,495 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer discovery from DNS records.
+//!
+//! Every host name is a single peer. Its addresses are its A/AAAA records, and its keys are given
+//! by TXT records of the form `dijets-key=<hex encoded x25519 key>`. The peer id is derived from
+//! the smallest of its keys, so that every node agrees on it while keys are being rotated.
+//!
+//! With `DnsRecordType::Ip`, the configured name is the only peer. With `DnsRecordType::Srv`, the
+//! configured name has SRV records and each of their targets is a peer.
+
+use crate::DiscoveryError;
+use dijets_config::config::{
+    DnsDiscoveryConfig, DnsRecordType, Peer, PeerRole, PeerSet, HANDSHAKE_VERSION,
+};
+use dijets_crypto::{x25519, ValidCryptoMaterialStringExt};
+use dijets_logger::prelude::*;
+use dijets_time_service::{Interval, TimeService, TimeServiceTrait};
+use dijets_types::{
+    account_address::from_identity_public_key, network_address::NetworkAddress, PeerId,
+};
+use futures::{
+    future::{BoxFuture, FutureExt},
+    Future, Stream,
+};
+use std::{
+    collections::HashSet,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use trust_dns_resolver::{
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+
+/// Prefix of the TXT records holding the keys of a peer
+pub const KEY_TXT_PREFIX: &str = "dijets-key=";
+
+/// The target and port of a SRV record
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrvRecord {
+    pub target: String,
+    pub port: u16,
+}
+
+/// The DNS lookups used by discovery, so that tests can stand in for a real resolver
+pub trait DnsResolver: Send + Sync {
+    fn lookup_ip(&self, name: String) -> BoxFuture<'static, io::Result<Vec<IpAddr>>>;
+
+    fn lookup_srv(&self, name: String) -> BoxFuture<'static, io::Result<Vec<SrvRecord>>>;
+
+    /// Returns every TXT record of `name`, with its character-strings concatenated
+    fn lookup_txt(&self, name: String) -> BoxFuture<'static, io::Result<Vec<String>>>;
+}
+
+/// A `DnsResolver` querying actual name servers
+#[derive(Clone)]
+pub struct SystemResolver(TokioAsyncResolver);
+
+impl SystemResolver {
+    /// Queries `name_servers` if any are given, otherwise the name servers of the system's
+    /// resolver configuration
+    pub fn new(name_servers: &[SocketAddr]) -> io::Result<Self> {
+        let resolver = if name_servers.is_empty() {
+            TokioAsyncResolver::tokio_from_system_conf()?
+        } else {
+            let mut group = NameServerConfigGroup::new();
+            for name_server in name_servers {
+                group.merge(NameServerConfigGroup::from_ips_clear(
+                    &[name_server.ip()],
+                    name_server.port(),
+                    true,
+                ));
+            }
+            let config = ResolverConfig::from_parts(None, vec![], group);
+            TokioAsyncResolver::tokio(config, ResolverOpts::default())?
+        };
+        Ok(SystemResolver(resolver))
+    }
+}
+
+impl DnsResolver for SystemResolver {
+    fn lookup_ip(&self, name: String) -> BoxFuture<'static, io::Result<Vec<IpAddr>>> {
+        let resolver = self.0.clone();
+        async move { Ok(resolver.lookup_ip(name.as_str()).await?.iter().collect()) }.boxed()
+    }
+
+    fn lookup_srv(&self, name: String) -> BoxFuture<'static, io::Result<Vec<SrvRecord>>> {
+        let resolver = self.0.clone();
+        async move {
+            let records = resolver.srv_lookup(name.as_str()).await?;
+            Ok(records
+                .iter()
+                .map(|record| SrvRecord {
+                    target: record.target().to_utf8(),
+                    port: record.port(),
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn lookup_txt(&self, name: String) -> BoxFuture<'static, io::Result<Vec<String>>> {
+        let resolver = self.0.clone();
+        async move {
+            let records = resolver.txt_lookup(name.as_str()).await?;
+            Ok(records
+                .iter()
+                .map(|record| {
+                    record
+                        .txt_data()
+                        .iter()
+                        .map(|data| String::from_utf8_lossy(data))
+                        .collect()
+                })
+                .collect())
+        }
+        .boxed()
+    }
+}
+
+pub struct DnsStream {
+    name: String,
+    record_type: DnsRecordType,
+    role: PeerRole,
+    resolver: Arc<dyn DnsResolver>,
+    interval: Pin<Box<Interval>>,
+    lookup: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        config: &DnsDiscoveryConfig,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            name: config.name.clone(),
+            record_type: config.record_type,
+            role: config.role,
+            resolver,
+            interval: Box::pin(time_service.interval(Duration::from_millis(config.interval_ms))),
+            lookup: None,
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay before starting the next lookup
+        if self.lookup.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let lookup = resolve_peers(
+                self.resolver.clone(),
+                self.name.clone(),
+                self.record_type,
+                self.role,
+            );
+            self.lookup = Some(lookup.boxed());
+        }
+
+        let result = match self.lookup.as_mut() {
+            Some(lookup) => futures::ready!(lookup.as_mut().poll(cx)),
+            None => unreachable!("A lookup was just started"),
+        };
+        self.lookup = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+async fn resolve_peers(
+    resolver: Arc<dyn DnsResolver>,
+    name: String,
+    record_type: DnsRecordType,
+    role: PeerRole,
+) -> Result<PeerSet, DiscoveryError> {
+    let mut peers = PeerSet::new();
+    match record_type {
+        DnsRecordType::Ip { port } => {
+            let (peer_id, peer) = resolve_peer(resolver.as_ref(), &name, port, role).await?;
+            peers.insert(peer_id, peer);
+        }
+        DnsRecordType::Srv => {
+            let records = resolver
+                .lookup_srv(name)
+                .await
+                .map_err(DiscoveryError::IO)?;
+            let mut last_error = None;
+            for record in records {
+                // A single misconfigured host shouldn't hide the rest of them
+                match resolve_peer(resolver.as_ref(), &record.target, record.port, role).await {
+                    Ok((peer_id, peer)) => {
+                        peers.insert(peer_id, peer);
+                    }
+                    Err(error) => {
+                        warn!(
+                            "Skipping DNS discovery target {}: {:?}",
+                            record.target, error
+                        );
+                        last_error = Some(error);
+                    }
+                }
+            }
+            // Don't drop every discovered peer if resolution is failing altogether
+            if let (true, Some(error)) = (peers.is_empty(), last_error) {
+                return Err(error);
+            }
+        }
+    }
+    Ok(peers)
+}
+
+async fn resolve_peer(
+    resolver: &dyn DnsResolver,
+    name: &str,
+    port: u16,
+    role: PeerRole,
+) -> Result<(PeerId, Peer), DiscoveryError> {
+    let records = resolver
+        .lookup_txt(name.to_string())
+        .await
+        .map_err(DiscoveryError::IO)?;
+    let keys = parse_keys(name, &records)?;
+    let ips = resolver
+        .lookup_ip(name.to_string())
+        .await
+        .map_err(DiscoveryError::IO)?;
+    if ips.is_empty() {
+        return Err(DiscoveryError::Parsing(format!(
+            "No addresses for {}",
+            name
+        )));
+    }
+
+    let peer_id = from_identity_public_key(*keys.iter().min().expect("Keys are not empty"));
+    let addresses = ips
+        .into_iter()
+        .flat_map(|ip| {
+            keys.iter().map(move |key| {
+                NetworkAddress::from(SocketAddr::new(ip, port))
+                    .append_prod_protos(*key, HANDSHAKE_VERSION)
+            })
+        })
+        .collect();
+    Ok((peer_id, Peer::new(addresses, keys, role)))
+}
+
+/// Parses the keys out of the `KEY_TXT_PREFIX` TXT records, ignoring any other TXT record
+fn parse_keys(
+    name: &str,
+    records: &[String],
+) -> Result<HashSet<x25519::PublicKey>, DiscoveryError> {
+    let keys = records
+        .iter()
+        .filter_map(|record| record.strip_prefix(KEY_TXT_PREFIX))
+        .map(|key| {
+            x25519::PublicKey::from_encoded_string(key.trim()).map_err(|err| {
+                DiscoveryError::Parsing(format!("Invalid key for {}: {}", name, err))
+            })
+        })
+        .collect::<Result<HashSet<_>, _>>()?;
+    if keys.is_empty() {
+        return Err(DiscoveryError::Parsing(format!(
+            "No {} TXT records for {}",
+            KEY_TXT_PREFIX, name
+        )));
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use dijets_config::network_id::NetworkContext;
+    use dijets_crypto::{test_utils::TEST_SEED, Uniform};
+    use dijets_infallible::Mutex;
+    use futures::StreamExt;
+    use network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+
+    const NAME: &str = "fullnodes.dijets.test";
+    const PORT: u16 = 6182;
+
+    /// A stand-in resolver answering from in-memory records
+    #[derive(Default)]
+    struct MockResolver {
+        ips: Mutex<HashMap<String, Vec<IpAddr>>>,
+        srv: Mutex<HashMap<String, Vec<SrvRecord>>>,
+        txt: Mutex<HashMap<String, Vec<String>>>,
+    }
+
+    fn lookup<T: Clone + Send + 'static>(
+        records: &Mutex<HashMap<String, Vec<T>>>,
+        name: &str,
+    ) -> BoxFuture<'static, io::Result<Vec<T>>> {
+        let result = records.lock().get(name).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No records for {}", name))
+        });
+        futures::future::ready(result).boxed()
+    }
+
+    impl DnsResolver for MockResolver {
+        fn lookup_ip(&self, name: String) -> BoxFuture<'static, io::Result<Vec<IpAddr>>> {
+            lookup(&self.ips, &name)
+        }
+
+        fn lookup_srv(&self, name: String) -> BoxFuture<'static, io::Result<Vec<SrvRecord>>> {
+            lookup(&self.srv, &name)
+        }
+
+        fn lookup_txt(&self, name: String) -> BoxFuture<'static, io::Result<Vec<String>>> {
+            lookup(&self.txt, &name)
+        }
+    }
+
+    impl MockResolver {
+        /// Publishes a host `name` with the given addresses and keys
+        fn add_host(&self, name: &str, ips: &[&str], keys: &[x25519::PublicKey]) {
+            self.ips.lock().insert(
+                name.to_string(),
+                ips.iter().map(|ip| ip.parse().unwrap()).collect(),
+            );
+            let mut records = vec!["v=spf1 -all".to_string()];
+            records.extend(
+                keys.iter()
+                    .map(|key| format!("{}{}", KEY_TXT_PREFIX, key.to_encoded_string().unwrap())),
+            );
+            self.txt.lock().insert(name.to_string(), records);
+        }
+    }
+
+    fn keys(count: usize) -> Vec<x25519::PublicKey> {
+        let mut rng = StdRng::from_seed(TEST_SEED);
+        (0..count)
+            .map(|_| x25519::PrivateKey::generate(&mut rng).public_key())
+            .collect()
+    }
+
+    fn expected_peer(ips: &[&str], port: u16, keys: &[x25519::PublicKey]) -> (PeerId, Peer) {
+        let peer_id = from_identity_public_key(*keys.iter().min().unwrap());
+        let addresses = ips
+            .iter()
+            .flat_map(|ip| {
+                keys.iter().map(move |key| {
+                    NetworkAddress::from(SocketAddr::new(ip.parse().unwrap(), port))
+                        .append_prod_protos(*key, HANDSHAKE_VERSION)
+                })
+            })
+            .collect();
+        let peer = Peer::new(
+            addresses,
+            keys.iter().copied().collect(),
+            PeerRole::Upstream,
+        );
+        (peer_id, peer)
+    }
+
+    fn config(record_type: DnsRecordType) -> DnsDiscoveryConfig {
+        DnsDiscoveryConfig {
+            name: NAME.to_string(),
+            record_type,
+            role: PeerRole::Upstream,
+            interval_ms: 5,
+            name_servers: vec![],
+        }
+    }
+
+    fn dns_stream(resolver: Arc<MockResolver>, record_type: DnsRecordType) -> DnsStream {
+        DnsStream::new(&config(record_type), resolver, TimeService::real())
+    }
+
+    #[tokio::test]
+    async fn test_ip_records() {
+        let resolver = Arc::new(MockResolver::default());
+        let keys = keys(2);
+        let ips = ["10.0.0.1", "2001:db8::1"];
+        resolver.add_host(NAME, &ips, &keys);
+
+        let mut stream = dns_stream(resolver.clone(), DnsRecordType::Ip { port: PORT });
+        let peers = stream.next().await.unwrap().unwrap();
+        let (peer_id, peer) = expected_peer(&ips, PORT, &keys);
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers.get(&peer_id), Some(&peer));
+
+        // Records changing are picked up by the next lookup
+        resolver.add_host(NAME, &ips[..1], &keys[..1]);
+        let peers = stream.next().await.unwrap().unwrap();
+        let (peer_id, peer) = expected_peer(&ips[..1], PORT, &keys[..1]);
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers.get(&peer_id), Some(&peer));
+    }
+
+    #[tokio::test]
+    async fn test_ip_records_without_keys() {
+        let resolver = Arc::new(MockResolver::default());
+        resolver.add_host(NAME, &["10.0.0.1"], &[]);
+
+        let mut stream = dns_stream(resolver.clone(), DnsRecordType::Ip { port: PORT });
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(DiscoveryError::Parsing(_)))
+        ));
+
+        resolver
+            .txt
+            .lock()
+            .insert(NAME.to_string(), vec![format!("{}zz", KEY_TXT_PREFIX)]);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(DiscoveryError::Parsing(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_srv_records() {
+        let resolver = Arc::new(MockResolver::default());
+        let keys = keys(2);
+        resolver.add_host("a.dijets.test", &["10.0.0.1"], &keys[..1]);
+        resolver.add_host("b.dijets.test", &["10.0.0.2"], &keys[1..]);
+        // A target without any keys is skipped
+        resolver.add_host("c.dijets.test", &["10.0.0.3"], &[]);
+        resolver.srv.lock().insert(
+            NAME.to_string(),
+            vec![
+                SrvRecord {
+                    target: "a.dijets.test".to_string(),
+                    port: 6180,
+                },
+                SrvRecord {
+                    target: "b.dijets.test".to_string(),
+                    port: 6181,
+                },
+                SrvRecord {
+                    target: "c.dijets.test".to_string(),
+                    port: 6182,
+                },
+            ],
+        );
+
+        let mut stream = dns_stream(resolver.clone(), DnsRecordType::Srv);
+        let peers = stream.next().await.unwrap().unwrap();
+        let expected: PeerSet = vec![
+            expected_peer(&["10.0.0.1"], 6180, &keys[..1]),
+            expected_peer(&["10.0.0.2"], 6181, &keys[1..]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(peers, expected);
+
+        // If no target resolves, the update fails rather than removing every peer
+        resolver.txt.lock().clear();
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(DiscoveryError::IO(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dns_listener() {
+        let resolver = Arc::new(MockResolver::default());
+        let keys = keys(1);
+        resolver.add_host(NAME, &["10.0.0.1"], &keys);
+
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) =
+            channel::new(1, &network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS);
+        let listener = DiscoveryChangeListener::dns(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            &config(DnsRecordType::Ip { port: PORT }),
+            resolver,
+            TimeService::real(),
+        );
+        tokio::task::spawn(Box::pin(listener).run());
+
+        if let Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Dns, peers)) =
+            conn_mgr_reqs_rx.next().await
+        {
+            let expected: PeerSet = vec![expected_peer(&["10.0.0.1"], PORT, &keys)]
+                .into_iter()
+                .collect();
+            assert_eq!(peers, expected)
+        } else {
+            panic!("No message sent by discovery")
+        }
+    }
+}
@@ -1,9 +1,15 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::DISCOVERY_COUNTS, file::FileStream, validator_set::ValidatorSetStream};
+use crate::{
+    counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream, rest::RestStream,
+    validator_set::ValidatorSetStream,
+};
 use channel::{dijets_channel, dijets_channel::Receiver};
-use dijets_config::{config::PeerSet, network_id::NetworkContext};
+use dijets_config::{
+    config::{DnsDiscoveryConfig, PeerSet, RestDiscoveryConfig},
+    network_id::NetworkContext,
+};
 use dijets_crypto::x25519;
 use dijets_logger::prelude::*;
 use dijets_network_address_encryption::Encryptor;
@@ -27,13 +33,18 @@ use subscription_service::ReconfigSubscription;
 use tokio::runtime::Handle;
 
 mod counters;
+mod dns;
 mod file;
+mod rest;
 mod validator_set;
 
+pub use dns::{DnsResolver, SrvRecord, SystemResolver, KEY_TXT_PREFIX};
+
 #[derive(Debug)]
 pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
+    Http(String),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -47,6 +58,8 @@ pub struct DiscoveryChangeListener {
 enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
+    Dns(DnsStream),
+    Rest(RestStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -56,6 +69,8 @@ impl Stream for DiscoveryChangeStream {
         match self.get_mut() {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
+            Self::Rest(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -102,6 +117,38 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn dns(
+        network_context: Arc<NetworkContext>,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        config: &DnsDiscoveryConfig,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream =
+            DiscoveryChangeStream::Dns(DnsStream::new(config, resolver, time_service));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
+    pub fn rest(
+        network_context: Arc<NetworkContext>,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        config: &RestDiscoveryConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Rest(RestStream::new(config, time_service));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Rest,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         executor.spawn(Box::pin(self).run());
     }
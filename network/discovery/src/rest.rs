@@ -0,0 +1,179 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer discovery from a `PeerSet` JSON document, periodically fetched over HTTP(S).
+
+use crate::DiscoveryError;
+use dijets_config::config::{PeerSet, RestDiscoveryConfig};
+use dijets_time_service::{Interval, TimeService, TimeServiceTrait};
+use futures::{
+    future::{BoxFuture, FutureExt},
+    Future, Stream,
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Timeout of a single request for the peer set
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct RestStream {
+    client: reqwest::Client,
+    url: String,
+    interval: Pin<Box<Interval>>,
+    request: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl RestStream {
+    pub(crate) fn new(config: &RestDiscoveryConfig, time_service: TimeService) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client");
+        RestStream {
+            client,
+            url: config.url.clone(),
+            interval: Box::pin(time_service.interval(Duration::from_millis(config.interval_ms))),
+            request: None,
+        }
+    }
+}
+
+impl Stream for RestStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay before sending the next request
+        if self.request.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let request = fetch_peers(self.client.clone(), self.url.clone());
+            self.request = Some(request.boxed());
+        }
+
+        let result = match self.request.as_mut() {
+            Some(request) => futures::ready!(request.as_mut().poll(cx)),
+            None => unreachable!("A request was just sent"),
+        };
+        self.request = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+async fn fetch_peers(client: reqwest::Client, url: String) -> Result<PeerSet, DiscoveryError> {
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|err| DiscoveryError::Http(err.to_string()))?;
+    response
+        .json()
+        .await
+        .map_err(|err| DiscoveryError::Parsing(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use dijets_config::{
+        config::{Peer, PeerRole},
+        network_id::NetworkContext,
+    };
+    use dijets_infallible::Mutex;
+    use dijets_types::{network_address::NetworkAddress, PeerId};
+    use futures::StreamExt;
+    use network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use std::{collections::HashSet, net::SocketAddr, str::FromStr, sync::Arc};
+    use warp::{http::StatusCode, Filter};
+
+    /// Serves `peers` as JSON on `/peers`, and invalid JSON on `/invalid`
+    fn start_server(peers: Arc<Mutex<PeerSet>>) -> SocketAddr {
+        let peers_route = warp::path("peers").map(move || warp::reply::json(&*peers.lock()));
+        let invalid_route = warp::path("invalid").map(|| "not a peer set");
+        let (address, server) =
+            warp::serve(peers_route.or(invalid_route)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::task::spawn(server);
+        address
+    }
+
+    fn rest_stream(address: SocketAddr, path: &str) -> RestStream {
+        let config = RestDiscoveryConfig {
+            url: format!("http://{}/{}", address, path),
+            interval_ms: 5,
+        };
+        RestStream::new(&config, TimeService::real())
+    }
+
+    fn peer_set() -> PeerSet {
+        let mut peers = PeerSet::new();
+        let addr = NetworkAddress::from_str("/ip4/1.2.3.4/tcp/6180/ln-noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/ln-handshake/0").unwrap();
+        peers.insert(
+            PeerId::random(),
+            Peer::new(vec![addr], HashSet::new(), PeerRole::Upstream),
+        );
+        peers
+    }
+
+    #[tokio::test]
+    async fn test_fetch_peers() {
+        let peers = Arc::new(Mutex::new(PeerSet::new()));
+        let address = start_server(peers.clone());
+        let mut stream = rest_stream(address, "peers");
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), PeerSet::new());
+
+        // Changes are picked up by the next request
+        let expected = peer_set();
+        *peers.lock() = expected.clone();
+        assert_eq!(stream.next().await.unwrap().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_errors() {
+        let address = start_server(Arc::new(Mutex::new(PeerSet::new())));
+
+        let mut stream = rest_stream(address, "missing");
+        match stream.next().await {
+            Some(Err(DiscoveryError::Http(error))) => {
+                assert!(error.contains(StatusCode::NOT_FOUND.as_str()))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let mut stream = rest_stream(address, "invalid");
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(DiscoveryError::Parsing(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rest_listener() {
+        let expected = peer_set();
+        let address = start_server(Arc::new(Mutex::new(expected.clone())));
+
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) =
+            channel::new(1, &network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS);
+        let listener = DiscoveryChangeListener::rest(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            &RestDiscoveryConfig {
+                url: format!("http://{}/peers", address),
+                interval_ms: 5,
+            },
+            TimeService::real(),
+        );
+        tokio::task::spawn(Box::pin(listener).run());
+
+        if let Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Rest, peers)) =
+            conn_mgr_reqs_rx.next().await
+        {
+            assert_eq!(peers, expected)
+        } else {
+            panic!("No message sent by discovery")
+        }
+    }
+}
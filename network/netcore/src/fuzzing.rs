@@ -0,0 +1,123 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//
+// Proxy Protocol Fuzzing
+// ======================
+//
+// This fuzzes the parsing of the proxy protocol headers sent by load balancers ahead of inbound
+// connections.
+//
+
+use crate::transport::proxy_protocol::{
+    read_header, IPV4_SIZE, PP2_SUBTYPE_AWS_VPCE_ID, PP2_TYPE_AWS, PPV1_SIGNATURE, PPV2_PROXY,
+    PPV2_SIGNATURE, TCP_IPV4,
+};
+use dijets_types::network_address::NetworkAddress;
+use futures::{executor::block_on, io::Cursor};
+use proptest::{collection::vec, prelude::*};
+
+//
+// Corpus generation
+// =================
+//
+
+/// A PPv1 header, for an IPv6 TCP connection.
+pub fn generate_v1_header() -> Vec<u8> {
+    b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n".to_vec()
+}
+
+/// A PPv2 header, for an IPv4 TCP connection from an AWS VPC endpoint.
+pub fn generate_v2_header() -> Vec<u8> {
+    let mut tlvs = vec![PP2_TYPE_AWS, 0x00, 0x17, PP2_SUBTYPE_AWS_VPCE_ID];
+    tlvs.extend_from_slice(b"vpce-08d2bf15fac5001c9");
+    v2_header_with_tlvs(&tlvs)
+}
+
+/// A PPv2 header for an IPv4 TCP connection, followed by the given TLV bytes.
+fn v2_header_with_tlvs(tlvs: &[u8]) -> Vec<u8> {
+    // The TLVs must fit in the 2 bytes address size
+    let tlvs = &tlvs[..tlvs.len().min((u16::MAX - IPV4_SIZE) as usize)];
+
+    let mut header = PPV2_SIGNATURE.to_vec();
+    header.push(PPV2_PROXY);
+    header.push(TCP_IPV4);
+    header.extend_from_slice(&(IPV4_SIZE + tlvs.len() as u16).to_be_bytes());
+    // Source and destination addresses, then ports
+    header.extend_from_slice(&[192, 0, 2, 1, 192, 0, 2, 2, 0xDC, 0x04, 0x01, 0xBB]);
+    header.extend_from_slice(tlvs);
+    header
+}
+
+//
+// Fuzzing
+// =======
+//
+
+/// Fuzz the parsing of a proxy protocol header of any version.
+pub fn fuzz_proxy_protocol(data: &[u8]) {
+    let original_addr = NetworkAddress::mock();
+    let mut stream = Cursor::new(data);
+    let _ = block_on(read_header(&original_addr, &mut stream));
+}
+
+/// Fuzz the parsing of a PPv1 header, skipping the check of its signature.
+pub fn fuzz_proxy_protocol_v1(data: &[u8]) {
+    let mut header = PPV1_SIGNATURE.to_vec();
+    header.extend_from_slice(data);
+    fuzz_proxy_protocol(&header);
+}
+
+/// Fuzz the parsing of the TLVs of a PPv2 header, skipping the parsing of the addresses.
+pub fn fuzz_proxy_protocol_tlvs(data: &[u8]) {
+    fuzz_proxy_protocol(&v2_header_with_tlvs(data));
+}
+
+//
+// Tests
+// =====
+//
+// To ensure fuzzers will not break, this test the fuzzers.
+//
+
+#[test]
+fn test_proxy_protocol_fuzzers() {
+    fuzz_proxy_protocol(&generate_v1_header());
+    fuzz_proxy_protocol_v1(&generate_v1_header()[PPV1_SIGNATURE.len()..]);
+    fuzz_proxy_protocol(&generate_v2_header());
+    fuzz_proxy_protocol_tlvs(&generate_v2_header()[28..]);
+    fuzz_proxy_protocol(&[]);
+}
+
+#[test]
+fn test_corpus_is_valid() {
+    let original_addr = NetworkAddress::mock();
+    for header in &[generate_v1_header(), generate_v2_header()] {
+        let mut stream = Cursor::new(header);
+        let header = block_on(read_header(&original_addr, &mut stream)).unwrap();
+        assert_ne!(header.source_addr, original_addr);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_proxy_protocol_fuzzer(data in vec(any::<u8>(), 0..256)) {
+        fuzz_proxy_protocol(&data);
+    }
+
+    #[test]
+    fn test_proxy_protocol_v1_fuzzer(data in vec(any::<u8>(), 0..128)) {
+        fuzz_proxy_protocol_v1(&data);
+    }
+
+    // Arbitrary bytes are rarely valid ASCII, so this reaches the parsing of the addresses
+    #[test]
+    fn test_proxy_protocol_v1_line_fuzzer(line in "(TCP4|TCP6|UNKNOWN)?[ -~]{0,100}\r\n") {
+        fuzz_proxy_protocol_v1(line.as_bytes());
+    }
+
+    #[test]
+    fn test_proxy_protocol_tlvs_fuzzer(data in vec(any::<u8>(), 0..512)) {
+        fuzz_proxy_protocol_tlvs(&data);
+    }
+}
@@ -8,4 +8,6 @@
 //! socket.
 
 pub mod framing;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
 pub mod transport;
@@ -5,26 +5,34 @@
 //! https://www.haproxy.org/download/1.8/doc/proxy-protocol.txt
 //!
 //! ## Limitations
-//! - Only supports ProxyProtocol V2
-//! - Rejects ProxyProtocol V1
 //! - Only supports IPv4 & IPv6 TCP mode
 //! - All other valid connections, will just drop the address information and use the proxy's one
-//! - Does not interpret TLVs
+//! - TLVs are only read from IPv4 & IPv6 TCP mode PPv2 headers, and their content isn't validated
+//!   (e.g. the CRC32C checksum isn't checked)
 //!
 //! ## Interpetations not in the spec
 //! - An address space that doesn't match the size expected is rejected e.g. too big for IPv4
 //! - Address space that's larger than the current supported requests is rejected
+//! - A TLV which doesn't fit in the address space is rejected
 
 use dijets_types::network_address::NetworkAddress;
 use futures::io::{AsyncRead, AsyncReadExt};
+use serde::Serialize;
 use std::{
     convert::TryInto,
     io,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str,
 };
 
+/// Unique signature required at the beginning of every PPv1 connection
+pub(crate) const PPV1_SIGNATURE: [u8; 6] = *b"PROXY ";
+
+/// Maximum length of a PPv1 header, including its signature and CRLF
+const PPV1_MAX_LENGTH: usize = 107;
+
 /// Unique signature required at the beginning of every PPv2 connection
-const PPV2_SIGNATURE: [u8; 12] = [
+pub(crate) const PPV2_SIGNATURE: [u8; 12] = [
     0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A,
 ];
 
@@ -32,11 +40,11 @@ const PPV2_SIGNATURE: [u8; 12] = [
 /// Command for healthchecks and local traffic
 const PPV2_LOCAL: u8 = 0x20;
 /// Command for proxy traffic
-const PPV2_PROXY: u8 = 0x21;
+pub(crate) const PPV2_PROXY: u8 = 0x21;
 
 // Protocol families
 const LOCAL_PROTOCOL: u8 = 0x00;
-const TCP_IPV4: u8 = 0x11;
+pub(crate) const TCP_IPV4: u8 = 0x11;
 const TCP_IPV6: u8 = 0x21;
 const TCP_UNIX: u8 = 0x31;
 const UDP_IPV4: u8 = 0x12;
@@ -44,17 +52,216 @@ const UDP_IPV6: u8 = 0x22;
 const UDP_UNIX: u8 = 0x32;
 
 // Address sizes
-const IPV4_SIZE: u16 = 12;
+pub(crate) const IPV4_SIZE: u16 = 12;
 const IPV6_SIZE: u16 = 36;
 
+// TLV types
+pub const PP2_TYPE_ALPN: u8 = 0x01;
+pub const PP2_TYPE_AUTHORITY: u8 = 0x02;
+pub const PP2_TYPE_CRC32C: u8 = 0x03;
+pub const PP2_TYPE_NOOP: u8 = 0x04;
+pub const PP2_TYPE_UNIQUE_ID: u8 = 0x05;
+pub const PP2_TYPE_SSL: u8 = 0x20;
+pub const PP2_TYPE_NETNS: u8 = 0x30;
+/// GCP Private Service Connect, the value is the 8 bytes connection ID
+pub const PP2_TYPE_GCP: u8 = 0xE0;
+/// AWS, the first byte of the value is the subtype
+pub const PP2_TYPE_AWS: u8 = 0xEA;
+/// AWS subtype of the VPC endpoint ID the connection came through
+pub const PP2_SUBTYPE_AWS_VPCE_ID: u8 = 0x01;
+
+/// Size of the header of a TLV: a type byte followed by a 2 bytes length
+const TLV_HEADER_SIZE: usize = 3;
+
+/// The information of a proxy protocol header
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProxyHeader {
+    /// Address of the client, or of the proxy if the header doesn't carry a supported one
+    pub source_addr: NetworkAddress,
+    /// TLVs of a PPv2 header, always empty for PPv1
+    pub tlvs: ProxyTlvs,
+}
+
+/// A Type-Length-Value field of a PPv2 header
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Tlv {
+    pub kind: u8,
+    pub value: Vec<u8>,
+}
+
+/// The TLVs of a PPv2 header, in the order they were received
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ProxyTlvs(Vec<Tlv>);
+
+impl ProxyTlvs {
+    pub fn new(tlvs: Vec<Tlv>) -> Self {
+        Self(tlvs)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tlv> {
+        self.0.iter()
+    }
+
+    /// Returns the value of the first TLV of type `kind`
+    pub fn get(&self, kind: u8) -> Option<&[u8]> {
+        self.0
+            .iter()
+            .find(|tlv| tlv.kind == kind)
+            .map(|tlv| tlv.value.as_slice())
+    }
+
+    /// The host name the client connected to, as sent in the TLS SNI extension
+    pub fn authority(&self) -> Option<&str> {
+        self.get(PP2_TYPE_AUTHORITY)
+            .and_then(|value| str::from_utf8(value).ok())
+    }
+
+    /// The ID the proxy assigned to the connection
+    pub fn unique_id(&self) -> Option<&[u8]> {
+        self.get(PP2_TYPE_UNIQUE_ID)
+    }
+
+    /// The ID of the AWS VPC endpoint the connection came through
+    pub fn aws_vpce_id(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|tlv| tlv.kind == PP2_TYPE_AWS)
+            .find_map(|tlv| match tlv.value.split_first() {
+                Some((&PP2_SUBTYPE_AWS_VPCE_ID, vpce_id)) => str::from_utf8(vpce_id).ok(),
+                _ => None,
+            })
+    }
+
+    /// The ID of the GCP Private Service Connect connection the connection came through
+    pub fn gcp_psc_connection_id(&self) -> Option<u64> {
+        self.get(PP2_TYPE_GCP)
+            .and_then(|value| value.try_into().ok())
+            .map(u64::from_be_bytes)
+    }
+}
+
 /// Read a proxy protocol event and unwrap the address information associated.
 pub async fn read_header<T: AsyncRead + std::marker::Unpin>(
     original_addr: &NetworkAddress,
     stream: &mut T,
-) -> io::Result<NetworkAddress> {
+) -> io::Result<ProxyHeader> {
+    // No header is shorter than the PPv1 signature, so this can't read past the header
+    let mut signature = [0u8; 6];
+    stream.read_exact(&mut signature).await?;
+
+    if signature == PPV1_SIGNATURE {
+        read_v1_header(original_addr, stream).await
+    } else {
+        read_v2_header(original_addr, signature, stream).await
+    }
+}
+
+/// Read the rest of a PPv1 header, following its signature.
+async fn read_v1_header<T: AsyncRead + std::marker::Unpin>(
+    original_addr: &NetworkAddress,
+    stream: &mut T,
+) -> io::Result<ProxyHeader> {
+    // The header has no length prefix, so read one byte at a time to not read past its CRLF
+    let mut header = PPV1_SIGNATURE.to_vec();
+    let mut byte = [0u8; 1];
+    while !header.ends_with(b"\r\n") {
+        if header.len() >= PPV1_MAX_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ProxyProtocol: V1 header too long",
+            ));
+        }
+        stream.read_exact(&mut byte).await?;
+        header.push(byte[0]);
+    }
+
+    let line = str::from_utf8(&header[PPV1_SIGNATURE.len()..header.len() - 2]).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "ProxyProtocol: V1 header isn't valid ASCII",
+        )
+    })?;
+
+    Ok(ProxyHeader {
+        source_addr: parse_v1_address(original_addr, line)?,
+        tlvs: ProxyTlvs::default(),
+    })
+}
+
+/// Parse the source address of a PPv1 header, e.g. `TCP4 192.0.2.1 192.0.2.2 56324 443`.
+fn parse_v1_address(original_addr: &NetworkAddress, line: &str) -> io::Result<NetworkAddress> {
+    let malformed = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "ProxyProtocol: Malformed V1 header",
+        )
+    };
+
+    let mut fields = line.split(' ');
+    let is_ipv4 = match fields.next() {
+        Some("TCP4") => true,
+        Some("TCP6") => false,
+        // The rest of the line must be ignored as per spec
+        Some("UNKNOWN") => return Ok(original_addr.clone()),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ProxyProtocol: Unsupported V1 protocol",
+            ))
+        }
+    };
+
+    let (src_addr, dst_addr, src_port, dst_port) = match (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) {
+        (Some(src_addr), Some(dst_addr), Some(src_port), Some(dst_port), None) => {
+            (src_addr, dst_addr, src_port, dst_port)
+        }
+        _ => return Err(malformed()),
+    };
+
+    let parse_addr = |addr: &str| -> io::Result<IpAddr> {
+        let ip_addr = if is_ipv4 {
+            addr.parse::<Ipv4Addr>().map(IpAddr::V4)
+        } else {
+            addr.parse::<Ipv6Addr>().map(IpAddr::V6)
+        };
+        ip_addr.map_err(|_| malformed())
+    };
+    let parse_port = |port: &str| -> io::Result<u16> {
+        // Ports are plain decimal numbers, without a sign
+        if port.is_empty() || !port.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(malformed());
+        }
+        port.parse().map_err(|_| malformed())
+    };
+
+    let src_addr = parse_addr(src_addr)?;
+    parse_addr(dst_addr)?;
+    let src_port = parse_port(src_port)?;
+    parse_port(dst_port)?;
+
+    Ok(NetworkAddress::from(SocketAddr::new(src_addr, src_port)))
+}
+
+/// Read the rest of a PPv2 header, following the first bytes of its signature.
+async fn read_v2_header<T: AsyncRead + std::marker::Unpin>(
+    original_addr: &NetworkAddress,
+    signature: [u8; 6],
+    stream: &mut T,
+) -> io::Result<ProxyHeader> {
     // This is small enough that it should not be fragmented by TCP
     let mut header = [0u8; 16];
-    stream.read_exact(&mut header).await?;
+    header[0..6].copy_from_slice(&signature);
+    stream.read_exact(&mut header[6..]).await?;
 
     // If it's not proxy protocol, let's stop
     if header[0..12] != PPV2_SIGNATURE {
@@ -84,12 +291,12 @@ pub async fn read_header<T: AsyncRead + std::marker::Unpin>(
     let mut address_bytes: Vec<u8> = vec![0; address_size as usize];
     stream.read_exact(&mut address_bytes).await?;
 
-    let source_address = match family_and_protocol {
+    let (source_address, tlvs) = match family_and_protocol {
         // TODO: Support UDP in the future
         LOCAL_PROTOCOL | UDP_IPV4 | UDP_IPV6 | TCP_UNIX | UDP_UNIX => {
             // UNSPEC, UDP, and UNIX Steam/datagram
             // Accept connection but ignore address info as per spec
-            original_addr.clone()
+            (original_addr.clone(), ProxyTlvs::default())
         }
         TCP_IPV4 => {
             // This is not mentioned in the spec, but if it doesn't match we might not read correctly
@@ -103,7 +310,8 @@ pub async fn read_header<T: AsyncRead + std::marker::Unpin>(
             let src_addr = u32::from_be_bytes(address_bytes[0..4].try_into().unwrap());
             let src_port = u16::from_be_bytes(address_bytes[8..10].try_into().unwrap());
             let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::from(src_addr)), src_port);
-            NetworkAddress::from(socket_addr)
+            let tlvs = parse_tlvs(&address_bytes[IPV4_SIZE as usize..])?;
+            (NetworkAddress::from(socket_addr), tlvs)
         }
         TCP_IPV6 => {
             // This is not mentioned in the spec, but if it doesn't match we might not read correctly
@@ -118,7 +326,8 @@ pub async fn read_header<T: AsyncRead + std::marker::Unpin>(
             let src_port = u16::from_be_bytes(address_bytes[32..34].try_into().unwrap());
 
             let socket_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::from(src_addr)), src_port);
-            NetworkAddress::from(socket_addr)
+            let tlvs = parse_tlvs(&address_bytes[IPV6_SIZE as usize..])?;
+            (NetworkAddress::from(socket_addr), tlvs)
         }
         _ => {
             return Err(io::Error::new(
@@ -128,7 +337,41 @@ pub async fn read_header<T: AsyncRead + std::marker::Unpin>(
         }
     };
 
-    Ok(source_address)
+    Ok(ProxyHeader {
+        source_addr: source_address,
+        tlvs,
+    })
+}
+
+/// Parse the TLVs following the addresses of a PPv2 header, skipping the NOOP ones.
+fn parse_tlvs(mut bytes: &[u8]) -> io::Result<ProxyTlvs> {
+    let mut tlvs = Vec::new();
+    while !bytes.is_empty() {
+        if bytes.len() < TLV_HEADER_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ProxyProtocol: Truncated TLV header",
+            ));
+        }
+        let kind = bytes[0];
+        let length = u16::from_be_bytes(bytes[1..3].try_into().unwrap()) as usize;
+        let value = bytes
+            .get(TLV_HEADER_SIZE..TLV_HEADER_SIZE + length)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "ProxyProtocol: TLV length doesn't match header size",
+                )
+            })?;
+        if kind != PP2_TYPE_NOOP {
+            tlvs.push(Tlv {
+                kind,
+                value: value.to_vec(),
+            });
+        }
+        bytes = &bytes[TLV_HEADER_SIZE + length..];
+    }
+    Ok(ProxyTlvs(tlvs))
 }
 
 #[cfg(test)]
@@ -190,7 +433,7 @@ mod test {
 
         // Verify the correct address is picked up
         let client = async move {
-            let addr = reader.await.expect("An address");
+            let addr = reader.await.expect("An address").source_addr;
             let addresses: Vec<_> = addr.to_socket_addrs().unwrap().collect();
             let socket_addr = addresses.first().unwrap();
             assert_eq!(IpAddr::V4(Ipv4Addr::from(0x0000000001)), socket_addr.ip());
@@ -221,7 +464,7 @@ mod test {
         let reader = read_header(&original_addr, &mut receiver);
         // Verify the correct address is picked up
         let client = async move {
-            let addr = reader.await.expect("An address");
+            let addr = reader.await.expect("An address").source_addr;
             let addresses: Vec<_> = addr.to_socket_addrs().unwrap().collect();
             let socket_addr = addresses.first().unwrap();
             assert_eq!(
@@ -278,8 +521,9 @@ mod test {
         let proxy_addr = original_addr.clone();
         // Verify no change in the address
         let client = async move {
-            let addr = reader.await.expect("An address");
-            assert_eq!(addr, proxy_addr.clone());
+            let header = reader.await.expect("An address");
+            assert_eq!(header.source_addr, proxy_addr.clone());
+            assert!(header.tlvs.is_empty());
         };
 
         block_on(join(server, client));
@@ -331,4 +575,165 @@ mod test {
 
         block_on(join(server, client));
     }
+
+    /// Reads a header from `input`, checking that the data following it isn't affected
+    fn read_test_header(input: Vec<u8>) -> io::Result<ProxyHeader> {
+        let (mut sender, mut receiver) = MemorySocket::new_pair();
+        let original_addr = NetworkAddress::mock();
+
+        let server = async move {
+            sender.write_all(&input).await.expect("Successful send");
+            sender.write_all(TEST_DATA).await.expect("Successful send");
+        };
+
+        let client = async move {
+            let header = read_header(&original_addr, &mut receiver).await;
+            if header.is_ok() {
+                let data: &mut [u8; 4] = &mut [0; 4];
+                receiver.read_exact(data).await.unwrap();
+                assert_eq!(TEST_DATA, data);
+            }
+            header
+        };
+
+        block_on(join(server, client)).1
+    }
+
+    fn socket_addr(addr: &str) -> NetworkAddress {
+        NetworkAddress::from(addr.parse::<SocketAddr>().unwrap())
+    }
+
+    #[test]
+    fn test_v1_proxy_protocol() {
+        let header = read_test_header(b"PROXY TCP4 192.0.2.1 192.0.2.2 56324 443\r\n".to_vec())
+            .expect("An address");
+        assert_eq!(header.source_addr, socket_addr("192.0.2.1:56324"));
+        assert!(header.tlvs.is_empty());
+
+        let header = read_test_header(b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n".to_vec())
+            .expect("An address");
+        assert_eq!(header.source_addr, socket_addr("[2001:db8::1]:56324"));
+
+        // The longest possible header
+        let header = read_test_header(
+            b"PROXY TCP6 ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff \
+              ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff 65535 65535\r\n"
+                .to_vec(),
+        )
+        .expect("An address");
+        assert_eq!(
+            header.source_addr,
+            socket_addr("[ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff]:65535")
+        );
+
+        // Unknown connections keep the proxy's address
+        let header = read_test_header(b"PROXY UNKNOWN\r\n".to_vec()).expect("An address");
+        assert_eq!(header.source_addr, NetworkAddress::mock());
+        let header = read_test_header(
+            b"PROXY UNKNOWN ffff:f...f:ffff ffff:f...f:ffff 65535 65535\r\n".to_vec(),
+        )
+        .expect("An address");
+        assert_eq!(header.source_addr, NetworkAddress::mock());
+    }
+
+    #[test]
+    fn test_v1_error_handling() {
+        let headers: &[&[u8]] = &[
+            // Unsupported protocols
+            b"PROXY TCP5 192.0.2.1 192.0.2.2 56324 443\r\n",
+            b"PROXY UDP4 192.0.2.1 192.0.2.2 56324 443\r\n",
+            // Addresses not matching the protocol
+            b"PROXY TCP4 2001:db8::1 2001:db8::2 56324 443\r\n",
+            b"PROXY TCP6 192.0.2.1 192.0.2.2 56324 443\r\n",
+            b"PROXY TCP4 192.0.2.1 2001:db8::2 56324 443\r\n",
+            // Missing, extra or invalid fields
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 56324\r\n",
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 56324 443 80\r\n",
+            b"PROXY TCP4  192.0.2.1 192.0.2.2 56324 443\r\n",
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 +56324 443\r\n",
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 65536 443\r\n",
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 56324 \r\n",
+            b"PROXY TCP4 192.0.2.\xff 192.0.2.2 56324 443\r\n",
+            // Missing CRLF
+            b"PROXY TCP4 192.0.2.1 192.0.2.2 56324 443\n",
+        ];
+        for header in headers {
+            read_test_header(header.to_vec()).expect_err("Expected error");
+        }
+
+        // Too long
+        let mut header = b"PROXY UNKNOWN ".to_vec();
+        header.resize(PPV1_MAX_LENGTH - 1, b'f');
+        header.extend_from_slice(b"\r\n");
+        read_test_header(header).expect_err("Expected error");
+
+        // Truncated
+        test_error_case(&[b"PROXY TCP4 192.0.2.1"]);
+    }
+
+    fn v2_header_with_tlvs(tlvs: &[u8]) -> Vec<u8> {
+        let mut header = PPV2_SIGNATURE.to_vec();
+        header.push(PPV2_PROXY);
+        header.push(TCP_IPV4);
+        header.extend_from_slice(&(IPV4_SIZE + tlvs.len() as u16).to_be_bytes());
+        header.extend_from_slice(IPV4_ADDR_1);
+        header.extend_from_slice(IPV4_ADDR_2);
+        header.extend_from_slice(PORT_80);
+        header.extend_from_slice(PORT_80);
+        header.extend_from_slice(tlvs);
+        header
+    }
+
+    #[test]
+    fn test_tlvs() {
+        let mut tlvs = vec![PP2_TYPE_AUTHORITY, 0x00, 0x0B];
+        tlvs.extend_from_slice(b"dijets.test");
+        tlvs.extend_from_slice(&[PP2_TYPE_NOOP, 0x00, 0x02, 0x00, 0x00]);
+        tlvs.extend_from_slice(&[PP2_TYPE_AWS, 0x00, 0x17, PP2_SUBTYPE_AWS_VPCE_ID]);
+        tlvs.extend_from_slice(b"vpce-08d2bf15fac5001c9");
+        tlvs.extend_from_slice(&[PP2_TYPE_GCP, 0x00, 0x08]);
+        tlvs.extend_from_slice(&0x0102_0304_0506_0708u64.to_be_bytes());
+        tlvs.extend_from_slice(&[PP2_TYPE_UNIQUE_ID, 0x00, 0x00]);
+
+        let header = read_test_header(v2_header_with_tlvs(&tlvs)).expect("An address");
+        assert_eq!(
+            header.source_addr,
+            NetworkAddress::from(SocketAddr::new(IpAddr::V4(Ipv4Addr::from(1)), 80))
+        );
+
+        // NOOP TLVs are skipped
+        let kinds: Vec<_> = header.tlvs.iter().map(|tlv| tlv.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PP2_TYPE_AUTHORITY,
+                PP2_TYPE_AWS,
+                PP2_TYPE_GCP,
+                PP2_TYPE_UNIQUE_ID
+            ]
+        );
+        assert_eq!(header.tlvs.authority(), Some("dijets.test"));
+        assert_eq!(header.tlvs.aws_vpce_id(), Some("vpce-08d2bf15fac5001c9"));
+        assert_eq!(
+            header.tlvs.gcp_psc_connection_id(),
+            Some(0x0102_0304_0506_0708)
+        );
+        assert_eq!(header.tlvs.unique_id(), Some(&[][..]));
+        assert_eq!(header.tlvs.get(PP2_TYPE_ALPN), None);
+
+        // Other AWS subtypes aren't mistaken for the VPC endpoint ID
+        let header = read_test_header(v2_header_with_tlvs(&[PP2_TYPE_AWS, 0x00, 0x02, 0x02, 0x00]))
+            .expect("An address");
+        assert_eq!(header.tlvs.aws_vpce_id(), None);
+    }
+
+    #[test]
+    fn test_tlv_error_handling() {
+        // Truncated TLV header
+        read_test_header(v2_header_with_tlvs(&[PP2_TYPE_ALPN, 0x00])).expect_err("Expected error");
+
+        // TLV value longer than the address space
+        read_test_header(v2_header_with_tlvs(&[PP2_TYPE_ALPN, 0x00, 0x02, 0x00]))
+            .expect_err("Expected error");
+    }
 }
@@ -116,13 +116,16 @@ pub struct ConnectivityManager<TBackoff> {
 }
 
 /// Different sources for peer addresses, ordered by priority (Onchain=highest,
-/// Config=lowest).
+/// PeerExchange=lowest).
 #[repr(u8)]
 #[derive(Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, NumVariants, Serialize)]
 pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
+    Dns,
+    Rest,
     Config,
+    PeerExchange,
 }
 
 impl fmt::Debug for DiscoverySource {
@@ -139,7 +142,10 @@ impl fmt::Display for DiscoverySource {
             match self {
                 DiscoverySource::OnChainValidatorSet => "OnChainValidatorSet",
                 DiscoverySource::File => "File",
+                DiscoverySource::Dns => "Dns",
+                DiscoverySource::Rest => "Rest",
                 DiscoverySource::Config => "Config",
+                DiscoverySource::PeerExchange => "PeerExchange",
             }
         )
     }
@@ -162,6 +168,12 @@ pub enum ConnectivityRequest {
 struct DiscoveredPeerSet(HashMap<PeerId, DiscoveredPeer>);
 
 impl DiscoveredPeerSet {
+    fn is_exchanged_only(&self, peer_id: &PeerId) -> bool {
+        self.0
+            .get(peer_id)
+            .map_or(false, DiscoveredPeer::is_exchanged_only)
+    }
+
     fn try_remove_empty(&mut self, peer_id: &PeerId) -> bool {
         match self.0.entry(*peer_id) {
             Entry::Occupied(entry) => {
@@ -182,7 +194,7 @@ impl DiscoveredPeerSet {
     pub fn to_eligible_peers(&self) -> PeerSet {
         self.0
             .iter()
-            .filter(|(_, peer)| peer.is_eligible())
+            .filter(|(_, peer)| peer.is_eligible() && !peer.is_exchanged_only())
             .map(|(peer_id, peer)| (*peer_id, peer.into()))
             .collect()
     }
@@ -205,6 +217,13 @@ impl DiscoveredPeer {
     pub fn is_eligible_to_be_dialed(&self) -> bool {
         self.is_eligible() && !self.addrs.is_empty()
     }
+
+    /// Peers only known from other peers through peer exchange can be dialed, but aren't trusted:
+    /// they're kept out of the eligible peers, so their inbound connections are handled like the
+    /// ones of unknown peers
+    pub fn is_exchanged_only(&self) -> bool {
+        self.keys.is_only_from(DiscoverySource::PeerExchange)
+    }
 }
 
 impl From<&DiscoveredPeer> for Peer {
@@ -364,7 +383,9 @@ where
         let stale_connections: Vec<_> = self
             .connected
             .iter()
-            .filter(|(peer_id, _)| !eligible.contains_key(peer_id))
+            .filter(|(peer_id, _)| {
+                !eligible.contains_key(peer_id) && !self.discovered_peers.is_exchanged_only(peer_id)
+            })
             .filter_map(|(peer_id, metadata)| {
                 // If we're using server only auth, we need to not evict unknown peers
                 // TODO: We should prevent `Unknown` from discovery sources
@@ -411,7 +432,9 @@ where
         let stale_dials: Vec<_> = self
             .dial_queue
             .keys()
-            .filter(|peer_id| !eligible.contains_key(peer_id))
+            .filter(|peer_id| {
+                !eligible.contains_key(peer_id) && !self.discovered_peers.is_exchanged_only(peer_id)
+            })
             .cloned()
             .collect();
 
@@ -448,7 +471,8 @@ where
                 peer.is_eligible_to_be_dialed() // The node is eligible to dial
                 && !self.connected.contains_key(peer_id) // The node is not already connected.
                 && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node.
-                && roles_to_dial.contains(&peer.role) // We can dial this role
+                && (roles_to_dial.contains(&peer.role) // We can dial this role
+                    || peer.is_exchanged_only()) // or the node is only known from peer exchange
             })
             .collect();
 
@@ -667,7 +691,8 @@ where
                 continue;
             }
 
-            // Create the new `DiscoveredPeer`, role is set when a `Peer` is first discovered
+            // Create the new `DiscoveredPeer`, role is set when a `Peer` is first discovered, or
+            // when it's discovered by a source giving it a role after peer exchange
             let peer = self
                 .discovered_peers
                 .0
@@ -677,6 +702,9 @@ where
                     addrs: Addresses::default(),
                     keys: PublicKeys::default(),
                 });
+            if peer.role == PeerRole::Unknown {
+                peer.role = discovered_peer.role;
+            }
             let mut peer_updated = false;
             // Update peer's pubkeys
             if peer.keys.update(src, discovered_peer.keys) {
@@ -929,6 +957,15 @@ impl PublicKeys {
         self.update(src, HashSet::new())
     }
 
+    /// Whether all the keys come from `src`
+    fn is_only_from(&self, src: DiscoverySource) -> bool {
+        let src_idx = src.as_usize();
+        self.0
+            .iter()
+            .enumerate()
+            .all(|(idx, pubkeys)| idx == src_idx || pubkeys.is_empty())
+    }
+
     fn union(&self) -> HashSet<x25519::PublicKey> {
         self.0.iter().flatten().copied().collect()
     }
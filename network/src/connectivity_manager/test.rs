@@ -805,3 +805,33 @@ fn basic_update_discovered_peers() {
     conn_mgr.handle_update_discovered_peers(DiscoverySource::Config, peers_empty.clone());
     assert_eq!(*trusted_peers.read(), peers_empty);
 }
+
+#[test]
+fn exchanged_peers_not_trusted() {
+    let (mock, mut conn_mgr) = TestHarness::new(HashMap::new());
+    let trusted_peers = mock.trusted_peers;
+
+    let (peer_id, peer, _, _) = test_peer(0);
+    let exchanged_peer = Peer::new(peer.addresses.clone(), HashSet::new(), PeerRole::Unknown);
+
+    // exchanged peers are dialed, but aren't trusted
+    conn_mgr.handle_update_discovered_peers(
+        DiscoverySource::PeerExchange,
+        hashmap! {peer_id => exchanged_peer},
+    );
+    assert!(trusted_peers.read().is_empty());
+    let to_dial = conn_mgr.choose_peers_to_dial();
+    assert_eq!(to_dial.len(), 1);
+    assert_eq!(to_dial[0].0, peer_id);
+
+    // once discovered by another source, the peer is trusted with the role given by that source
+    conn_mgr.handle_update_discovered_peers(DiscoverySource::Config, hashmap! {peer_id => peer});
+    assert_eq!(
+        trusted_peers.read().get(&peer_id).map(|peer| peer.role),
+        Some(PeerRole::Validator)
+    );
+
+    // and it isn't trusted anymore once only known from peer exchange again
+    conn_mgr.handle_update_discovered_peers(DiscoverySource::Config, PeerSet::new());
+    assert!(trusted_peers.read().is_empty());
+}
@@ -13,10 +13,15 @@ pub const INBOUND_RPC_TIMEOUT_MS: u64 = 10_000;
 pub const MAX_CONCURRENT_OUTBOUND_RPCS: u32 = 100;
 /// Limit on concurrent Inbound RPC requests before backpressure is applied
 pub const MAX_CONCURRENT_INBOUND_RPCS: u32 = 100;
+/// The timeout for receiving all the fragments of an inbound stream before it's discarded
+pub const INBOUND_STREAM_TIMEOUT_MS: u64 = 30_000;
+/// Limit on the size of a message of a compressed protocol once decompressed
+pub const MAX_DECOMPRESSED_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 
 // These are only used in tests
 // TODO: Fix this so the tests and the defaults in config are the same
 pub const NETWORK_CHANNEL_SIZE: usize = 1024;
 pub const MAX_FRAME_SIZE: usize = 8 * 1024 * 1024; /* 8 MiB */
+pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const MAX_CONCURRENT_NETWORK_REQS: usize = 100;
 pub const MAX_CONCURRENT_NETWORK_NOTIFS: usize = 100;
@@ -24,6 +24,10 @@ pub const SENT_LABEL: &str = "sent";
 pub const SUCCEEDED_LABEL: &str = "succeeded";
 pub const FAILED_LABEL: &str = "failed";
 
+// compression labels
+pub const COMPRESS_LABEL: &str = "compress";
+pub const DECOMPRESS_LABEL: &str = "decompress";
+
 pub static DIJETS_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "dijets_connections",
@@ -63,6 +67,42 @@ pub fn connections_rejected(
     ])
 }
 
+pub static DIJETS_NETWORK_MISBEHAVIOR_REPORTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_misbehavior_reports",
+        "Number of peer misbehaviors reported by applications",
+        &["role_type", "network_id", "peer_id", "misbehavior"]
+    )
+    .unwrap()
+});
+
+pub fn misbehavior_reports(network_context: &NetworkContext, misbehavior: &str) -> IntCounter {
+    DIJETS_NETWORK_MISBEHAVIOR_REPORTS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        misbehavior,
+    ])
+}
+
+pub static DIJETS_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_peer_bans",
+        "Number of peers banned, by the misbehavior which got them banned",
+        &["role_type", "network_id", "peer_id", "misbehavior"]
+    )
+    .unwrap()
+});
+
+pub fn peer_bans(network_context: &NetworkContext, misbehavior: &str) -> IntCounter {
+    DIJETS_NETWORK_PEER_BANS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        misbehavior,
+    ])
+}
+
 pub static DIJETS_NETWORK_PEER_CONNECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "dijets_network_peer_connected",
@@ -303,6 +343,77 @@ pub fn direct_send_bytes(
     ])
 }
 
+pub static DIJETS_NETWORK_STREAM_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_stream_messages",
+        "Number of messages streamed as multiple frames",
+        &["role_type", "network_id", "peer_id", "state"]
+    )
+    .unwrap()
+});
+
+pub fn stream_messages(network_context: &NetworkContext, state_label: &'static str) -> IntCounter {
+    DIJETS_NETWORK_STREAM_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        state_label,
+    ])
+}
+
+pub static DIJETS_NETWORK_PEER_EXCHANGE_RECORDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_peer_exchange_records",
+        "Number of peer records sent and received through peer exchange",
+        &["role_type", "network_id", "peer_id", "state"]
+    )
+    .unwrap()
+});
+
+pub fn peer_exchange_records(
+    network_context: &NetworkContext,
+    state_label: &'static str,
+) -> IntCounter {
+    DIJETS_NETWORK_PEER_EXCHANGE_RECORDS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        state_label,
+    ])
+}
+
+pub static DIJETS_NETWORK_UNCOMPRESSED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_uncompressed_bytes",
+        "Number of uncompressed bytes of messages of compressed protocols",
+        &["protocol_id", "operation"]
+    )
+    .unwrap()
+});
+
+pub static DIJETS_NETWORK_COMPRESSED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_compressed_bytes",
+        "Number of compressed bytes of messages of compressed protocols",
+        &["protocol_id", "operation"]
+    )
+    .unwrap()
+});
+
+pub fn compression_bytes(
+    protocol_id: ProtocolId,
+    operation: &str,
+    uncompressed_size: usize,
+    compressed_size: usize,
+) {
+    DIJETS_NETWORK_UNCOMPRESSED_BYTES
+        .with_label_values(&[protocol_id.as_str(), operation])
+        .inc_by(uncompressed_size as u64);
+    DIJETS_NETWORK_COMPRESSED_BYTES
+        .with_label_values(&[protocol_id.as_str(), operation])
+        .inc_by(compressed_size as u64);
+}
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -344,6 +455,16 @@ pub static PENDING_DISCOVERY_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(||
     .unwrap()
 });
 
+/// Counter of pending network events to Peer Exchange.
+pub static PENDING_PEER_EXCHANGE_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_network_pending_peer_exchange_events",
+        "Number of pending peer exchange events by state",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Counter of pending requests in Peer Manager
 pub static PENDING_PEER_MANAGER_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -19,6 +19,7 @@ pub mod noise;
 pub mod peer;
 pub mod peer_manager;
 pub mod protocols;
+pub mod reputation;
 pub mod transport;
 
 #[cfg(feature = "fuzzing")]
@@ -12,7 +12,7 @@
 
 use crate::noise::{error::NoiseHandshakeError, stream::NoiseStream};
 use dijets_config::{
-    config::{Peer, PeerRole, PeerSet},
+    config::{NoiseRekeyConfig, Peer, PeerRole, PeerSet},
     network_id::NetworkContext,
 };
 use dijets_crypto::{noise, x25519};
@@ -140,6 +140,8 @@ pub struct NoiseUpgrader {
     noise_config: noise::NoiseConfig,
     /// Handshake authentication can be either mutual or server-only authentication.
     auth_mode: HandshakeAuthMode,
+    /// When the established noise streams rekey their outbound messages, if ever.
+    rekey_config: Option<NoiseRekeyConfig>,
 }
 
 impl NoiseUpgrader {
@@ -153,9 +155,16 @@ impl NoiseUpgrader {
             network_context,
             noise_config: noise::NoiseConfig::new(key),
             auth_mode,
+            rekey_config: None,
         }
     }
 
+    /// Rekey the outbound messages of the established noise streams according to `rekey_config`.
+    pub fn with_rekey_config(mut self, rekey_config: Option<NoiseRekeyConfig>) -> Self {
+        self.rekey_config = rekey_config;
+        self
+    }
+
     /// Perform a protocol upgrade on an underlying connection. In addition perform the noise IK
     /// handshake to establish a noise stream and exchange static public keys. Upon success,
     /// returns the static public key of the remote as well as a NoiseStream.
@@ -284,7 +293,7 @@ impl NoiseUpgrader {
             .map_err(NoiseHandshakeError::ClientFinalizeFailed)?;
 
         // finalize the connection
-        Ok(NoiseStream::new(socket, session))
+        Ok(NoiseStream::new(socket, session, self.rekey_config))
     }
 
     /// Perform an inbound protocol upgrade on this connection.
@@ -438,7 +447,11 @@ impl NoiseUpgrader {
             self.network_context,
             remote_peer_short,
         );
-        Ok((NoiseStream::new(socket, session), remote_peer_id, peer_role))
+        Ok((
+            NoiseStream::new(socket, session, self.rekey_config),
+            remote_peer_id,
+            peer_role,
+        ))
     }
 
     fn authenticate_inbound(
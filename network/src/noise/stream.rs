@@ -6,6 +6,10 @@
 //! functions in this module enables encrypting and decrypting messages from a socket.
 //! Note that since noise is length-unaware, we have to prefix every noise message with its length
 //!
+//! If configured, a [`NoiseStream`] periodically replaces the key it encrypts messages with. It
+//! signals this to the remote with an empty frame (a zero length prefix) right before the first
+//! message encrypted with the new key.
+//!
 //! [handshake]: crate::noise::handshake
 
 use futures::{
@@ -17,8 +21,10 @@ use std::{
     io,
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
+use dijets_config::config::NoiseRekeyConfig;
 use dijets_crypto::{noise, x25519};
 use dijets_logger::prelude::*;
 
@@ -43,17 +49,24 @@ pub struct NoiseStream<TSocket> {
     read_state: ReadState,
     /// an enum used for progressively writing a noise payload
     write_state: WriteState,
+    /// when to rekey outbound messages, if ever
+    rekey_schedule: Option<RekeySchedule>,
 }
 
 impl<TSocket> NoiseStream<TSocket> {
     /// Create a NoiseStream from a socket and a noise post-handshake session
-    pub fn new(socket: TSocket, session: noise::NoiseSession) -> Self {
+    pub fn new(
+        socket: TSocket,
+        session: noise::NoiseSession,
+        rekey_config: Option<NoiseRekeyConfig>,
+    ) -> Self {
         Self {
             socket,
             session,
             buffers: Box::new(NoiseBuffers::new()),
             read_state: ReadState::Init,
             write_state: WriteState::Init,
+            rekey_schedule: rekey_config.map(RekeySchedule::new),
         }
     }
 
@@ -73,8 +86,12 @@ impl<TSocket> NoiseStream<TSocket> {
 enum ReadState {
     /// Initial State
     Init,
-    /// Read frame length
-    ReadFrameLen { buf: [u8; 2], offset: usize },
+    /// Read frame length, `rekeyed` indicates that the previous frame was a rekey
+    ReadFrameLen {
+        buf: [u8; 2],
+        offset: usize,
+        rekeyed: bool,
+    },
     /// Read encrypted frame
     ReadFrame { frame_len: u16, offset: usize },
     /// Copy decrypted frame to provided buffer
@@ -97,11 +114,13 @@ where
                     self.read_state = ReadState::ReadFrameLen {
                         buf: [0, 0],
                         offset: 0,
+                        rekeyed: false,
                     };
                 }
                 ReadState::ReadFrameLen {
                     ref mut buf,
                     ref mut offset,
+                    rekeyed,
                 } => {
                     match ready!(poll_read_u16frame_len(
                         &mut context,
@@ -112,8 +131,26 @@ where
                         Ok(Some(frame_len)) => {
                             // Empty Frame
                             if frame_len == 0 {
-                                // 0-length messages are not expected
-                                self.read_state = ReadState::Eof(Err(()));
+                                if rekeyed {
+                                    // A rekey must be followed by a message
+                                    self.read_state = ReadState::Eof(Err(()));
+                                } else {
+                                    // The remote rekeyed, the next message uses the new key
+                                    trace!("NoiseStream: rekeying inbound messages");
+                                    match self.session.rekey_read() {
+                                        Ok(()) => {
+                                            self.read_state = ReadState::ReadFrameLen {
+                                                buf: [0, 0],
+                                                offset: 0,
+                                                rekeyed: true,
+                                            };
+                                        }
+                                        Err(e) => {
+                                            error!(error = %e, "Rekey Error: {}", e);
+                                            self.read_state = ReadState::DecryptionError(e);
+                                        }
+                                    }
+                                }
                             } else {
                                 self.read_state = ReadState::ReadFrame {
                                     frame_len,
@@ -213,6 +250,12 @@ enum WriteState {
     Init,
     /// Buffer provided data
     BufferData { offset: usize },
+    /// Write an empty frame to signal a rekey to the remote
+    WriteRekey {
+        frame_len: u16,
+        buf: [u8; 2],
+        offset: usize,
+    },
     /// Write frame length to the wire
     WriteFrameLen {
         frame_len: u16,
@@ -270,10 +313,23 @@ where
                     };
 
                     if buf.is_none() || *offset == MAX_WRITE_BUFFER_LENGTH {
-                        match self
-                            .session
-                            .write_message_in_place(&mut self.buffers.write_buffer[..*offset])
-                        {
+                        let rekey = match self.rekey_schedule {
+                            Some(ref mut rekey_schedule) => rekey_schedule.record_message(),
+                            None => false,
+                        };
+                        let result = if rekey {
+                            trace!("NoiseStream: rekeying outbound messages");
+                            self.session.rekey_write()
+                        } else {
+                            Ok(())
+                        };
+                        let result = match result {
+                            Ok(()) => self
+                                .session
+                                .write_message_in_place(&mut self.buffers.write_buffer[..*offset]),
+                            Err(e) => Err(e),
+                        };
+                        match result {
                             Ok(authentication_tag) => {
                                 // append the authentication tag
                                 self.buffers.write_buffer[*offset..*offset + noise::AES_GCM_TAGLEN]
@@ -283,10 +339,18 @@ where
                                 let frame_len = frame_len
                                     .try_into()
                                     .expect("offset should be able to fit in u16");
-                                self.write_state = WriteState::WriteFrameLen {
-                                    frame_len,
-                                    buf: u16::to_be_bytes(frame_len),
-                                    offset: 0,
+                                self.write_state = if rekey {
+                                    WriteState::WriteRekey {
+                                        frame_len,
+                                        buf: [0, 0],
+                                        offset: 0,
+                                    }
+                                } else {
+                                    WriteState::WriteFrameLen {
+                                        frame_len,
+                                        buf: u16::to_be_bytes(frame_len),
+                                        offset: 0,
+                                    }
                                 };
                             }
                             Err(e) => {
@@ -305,6 +369,32 @@ where
                         return Poll::Ready(Ok(Some(bytes_buffered)));
                     }
                 }
+                WriteState::WriteRekey {
+                    frame_len,
+                    ref buf,
+                    ref mut offset,
+                } => {
+                    match ready!(poll_write_all(
+                        &mut context,
+                        Pin::new(&mut self.socket),
+                        buf,
+                        offset
+                    )) {
+                        Ok(()) => {
+                            self.write_state = WriteState::WriteFrameLen {
+                                frame_len,
+                                buf: u16::to_be_bytes(frame_len),
+                                offset: 0,
+                            };
+                        }
+                        Err(e) => {
+                            if e.kind() == io::ErrorKind::WriteZero {
+                                self.write_state = WriteState::Eof;
+                            }
+                            return Poll::Ready(Err(e));
+                        }
+                    }
+                }
                 WriteState::WriteFrameLen {
                     frame_len,
                     ref buf,
@@ -422,6 +512,45 @@ where
     }
 }
 
+//
+// Rekeying
+// --------
+//
+
+/// Tracks when a [`NoiseStream`] should replace the key of its outbound messages.
+#[derive(Debug)]
+struct RekeySchedule {
+    max_messages: u64,
+    interval: Duration,
+    /// number of messages encrypted with the current key
+    messages: u64,
+    /// when the current key was put in use
+    last_rekey: Instant,
+}
+
+impl RekeySchedule {
+    fn new(config: NoiseRekeyConfig) -> Self {
+        Self {
+            max_messages: config.max_messages,
+            interval: Duration::from_millis(config.interval_ms),
+            messages: 0,
+            last_rekey: Instant::now(),
+        }
+    }
+
+    /// Records a message about to be encrypted, returns true if the key must be replaced first.
+    fn record_message(&mut self) -> bool {
+        let rekey =
+            self.messages >= self.max_messages || self.last_rekey.elapsed() >= self.interval;
+        if rekey {
+            self.messages = 0;
+            self.last_rekey = Instant::now();
+        }
+        self.messages += 1;
+        rekey
+    }
+}
+
 //
 // NoiseBuffers
 // ------------
@@ -590,6 +719,18 @@ mod test {
         ((client, client_public), (server, server_public))
     }
 
+    /// helper to perform a noise handshake with two peers which rekey according to `rekey_config`
+    fn perform_handshake_with_rekey(
+        rekey_config: NoiseRekeyConfig,
+    ) -> (NoiseStream<MemorySocket>, NoiseStream<MemorySocket>) {
+        let ((client, _client_public), (server, server_public)) = build_peers();
+        perform_handshake(
+            client.with_rekey_config(Some(rekey_config)),
+            server_public,
+            server.with_rekey_config(Some(rekey_config)),
+        )
+    }
+
     /// helper to perform a noise handshake with two peers
     fn perform_handshake(
         client: NoiseUpgrader,
@@ -631,6 +772,75 @@ mod test {
         Ok(())
     }
 
+    /// helper to exchange many messages in both directions
+    fn exchange_messages(
+        client: &mut NoiseStream<MemorySocket>,
+        server: &mut NoiseStream<MemorySocket>,
+    ) {
+        for i in 0..100u8 {
+            let message = vec![i; 1 + i as usize * 1000];
+
+            block_on(client.write_all(&message)).unwrap();
+            block_on(client.flush()).unwrap();
+            let mut buf = vec![0; message.len()];
+            block_on(server.read_exact(&mut buf)).unwrap();
+            assert_eq!(buf, message);
+
+            block_on(server.write_all(&message)).unwrap();
+            block_on(server.flush()).unwrap();
+            let mut buf = vec![0; message.len()];
+            block_on(client.read_exact(&mut buf)).unwrap();
+            assert_eq!(buf, message);
+        }
+    }
+
+    #[test]
+    fn rekey_by_message_count() {
+        // rekey before every message but the first
+        let (mut client, mut server) = perform_handshake_with_rekey(NoiseRekeyConfig {
+            max_messages: 1,
+            interval_ms: u64::MAX,
+        });
+        exchange_messages(&mut client, &mut server);
+    }
+
+    #[test]
+    fn rekey_by_interval() {
+        // rekey before every message
+        let (mut client, mut server) = perform_handshake_with_rekey(NoiseRekeyConfig {
+            max_messages: u64::MAX,
+            interval_ms: 0,
+        });
+        exchange_messages(&mut client, &mut server);
+    }
+
+    #[test]
+    fn rekey_one_side() {
+        // only the client rekeys, the server must still read its messages
+        let ((client, _client_public), (server, server_public)) = build_peers();
+        let client = client.with_rekey_config(Some(NoiseRekeyConfig {
+            max_messages: 3,
+            interval_ms: u64::MAX,
+        }));
+        let (mut client, mut server) = perform_handshake(client, server_public, server);
+        exchange_messages(&mut client, &mut server);
+    }
+
+    // a rekey must be followed by a message, so a stream of empty frames is an error
+    #[test]
+    fn consecutive_rekeys() {
+        let mut fake_socket = ReadOnlyTestSocket::new(&[0u8, 0, 0, 0]);
+        fake_socket.set_trailing();
+        let noise_session = noise::NoiseSession::new_for_testing();
+        let mut peer = NoiseStream::new(fake_socket, noise_session, None);
+
+        block_on(async move {
+            let mut buffer = [0u8; 128];
+            let err = peer.read(&mut buffer).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        });
+    }
+
     // we used to time out when given a stream of all zeros, now we want an EOF
     #[test]
     fn dont_read_forever() {
@@ -642,7 +852,7 @@ mod test {
 
         // setup a NoiseStream with a dummy state
         let noise_session = noise::NoiseSession::new_for_testing();
-        let mut peer = NoiseStream::new(fake_socket, noise_session);
+        let mut peer = NoiseStream::new(fake_socket, noise_session, None);
 
         // make sure we error and we don't continuously read
         block_on(async move {
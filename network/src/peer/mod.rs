@@ -15,15 +15,17 @@
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
-    counters::{self, RECEIVED_LABEL, SENT_LABEL},
+    constants,
+    counters::{self, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL},
     logging::NetworkSchema,
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
         direct_send::Message,
         rpc::{InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
+        stream::{InboundStreamBuffer, OutboundStream, StreamError},
         wire::messaging::v1::{
             DirectSendMsg, ErrorCode, NetworkMessage, NetworkMessageSink, NetworkMessageStream,
-            Priority, ReadError, WriteError,
+            Priority, ReadError, StreamMessage, WriteError,
         },
     },
     transport::{self, Connection, ConnectionMetadata},
@@ -128,8 +130,12 @@ pub struct Peer<TSocket> {
     /// Flag to indicate if the actor is being shut down.
     state: State,
     /// The maximum size of an inbound or outbound request frame
-    /// Currently, requests are only a single frame
     max_frame_size: usize,
+    /// The maximum size of an inbound or outbound message. Messages larger
+    /// than a frame are streamed as multiple frames.
+    max_message_size: usize,
+    /// Reassembly buffer for inbound messages streamed by the remote peer.
+    inbound_stream: InboundStreamBuffer,
     /// Optional inbound rate limiter
     inbound_rate_limiter: Option<SharedBucket>,
     /// Optional outbound rate limiter
@@ -140,6 +146,7 @@ impl<TSocket> Peer<TSocket>
 where
     TSocket: AsyncRead + AsyncWrite + Send + 'static,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         network_context: Arc<NetworkContext>,
         executor: Handle,
@@ -152,6 +159,7 @@ where
         max_concurrent_inbound_rpcs: u32,
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
+        max_message_size: usize,
         inbound_rate_limiter: Option<SharedBucket>,
         outbound_rate_limiter: Option<SharedBucket>,
     ) -> Self {
@@ -184,6 +192,11 @@ where
             ),
             state: State::Connected,
             max_frame_size,
+            max_message_size,
+            inbound_stream: InboundStreamBuffer::new(
+                max_message_size,
+                Duration::from_millis(constants::INBOUND_STREAM_TIMEOUT_MS),
+            ),
             inbound_rate_limiter,
             outbound_rate_limiter,
         }
@@ -229,8 +242,15 @@ where
             self.connection_metadata.clone(),
             self.network_context.clone(),
             writer,
+            OutboundStream::new(self.max_frame_size, self.max_message_size),
         );
 
+        // Periodically discard inbound streams which the remote peer never completed.
+        let stream_ticker = self
+            .time_service
+            .interval(Duration::from_millis(constants::INBOUND_STREAM_TIMEOUT_MS));
+        tokio::pin!(stream_ticker);
+
         // Start main Peer event loop.
         let reason = loop {
             if let State::ShuttingDown(reason) = self.state {
@@ -283,6 +303,11 @@ where
                 // successfully or unsuccessfully completed request.
                 (request_id, maybe_completed_request) = self.outbound_rpcs.next_completed_request() => {
                     self.outbound_rpcs.handle_completed_request(request_id, maybe_completed_request);
+                },
+                _ = stream_ticker.select_next_some() => {
+                    if let Err(err) = self.inbound_stream.expire(self.time_service.now()) {
+                        self.handle_inbound_stream_error(err);
+                    }
                 }
             }
         };
@@ -299,12 +324,15 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    // Messages larger than a frame are split by `outbound_stream`, and all of their frames are
+    // written before the next message.
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: Arc<NetworkContext>,
         mut writer: NetworkMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
+        mut outbound_stream: OutboundStream,
     ) -> (
         channel::Sender<(
             NetworkMessage,
@@ -326,8 +354,32 @@ where
             loop {
                 futures::select! {
                     (message, ack_ch) = write_reqs_rx.select_next_some() => {
-                        if let Err(err) = writer
-                            .send(&message)
+                        let messages = match outbound_stream.fragment(message) {
+                            Ok(messages) => messages,
+                            Err(err) => {
+                                warn!(
+                                    NetworkSchema::new(&network_context)
+                                        .connection_metadata(&connection_metadata),
+                                    error = %err,
+                                    "{} Dropping message to peer: {}, error: {}",
+                                    network_context,
+                                    remote_peer_id.short_str(),
+                                    err
+                                );
+                                counters::stream_messages(&network_context, FAILED_LABEL).inc();
+                                continue;
+                            }
+                        };
+                        if messages.len() > 1 {
+                            counters::stream_messages(&network_context, SENT_LABEL).inc();
+                        }
+                        let send_all = async {
+                            for message in &messages {
+                                writer.feed(message).await?;
+                            }
+                            writer.flush().await
+                        };
+                        if let Err(err) = send_all
                             .map_ok(|_| ack_ch.send(Ok(())))
                             .await
                         {
@@ -438,6 +490,11 @@ where
             },
         };
 
+        self.handle_inbound_network_message(message);
+        Ok(())
+    }
+
+    fn handle_inbound_network_message(&mut self, message: NetworkMessage) {
         match message {
             NetworkMessage::DirectSendMsg(message) => self.handle_inbound_direct_send(message),
             NetworkMessage::Error(error_msg) => {
@@ -469,8 +526,37 @@ where
             NetworkMessage::RpcResponse(response) => {
                 self.outbound_rpcs.handle_inbound_response(response)
             }
+            NetworkMessage::Stream(message) => self.handle_inbound_stream(message),
         };
-        Ok(())
+    }
+
+    /// Handle an inbound stream header or fragment from the remote peer, and
+    /// handle the reassembled message once its stream is complete.
+    fn handle_inbound_stream(&mut self, message: StreamMessage) {
+        match self
+            .inbound_stream
+            .handle_message(message, self.time_service.now())
+        {
+            Ok(Some(message)) => {
+                counters::stream_messages(&self.network_context, RECEIVED_LABEL).inc();
+                self.handle_inbound_network_message(message);
+            }
+            Ok(None) => {}
+            Err(err) => self.handle_inbound_stream_error(err),
+        }
+    }
+
+    fn handle_inbound_stream_error(&self, err: StreamError) {
+        counters::stream_messages(&self.network_context, FAILED_LABEL).inc();
+        warn!(
+            NetworkSchema::new(&self.network_context)
+                .connection_metadata(&self.connection_metadata),
+            error = %err,
+            "{} Discarded inbound stream from peer: {}, error: {}",
+            self.network_context,
+            self.remote_peer_id().short_str(),
+            err
+        );
     }
 
     /// Handle an inbound DirectSendMsg from the remote peer. There's not much to
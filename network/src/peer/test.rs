@@ -4,7 +4,7 @@
 use crate::{
     constants::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
-        MAX_FRAME_SIZE, NETWORK_CHANNEL_SIZE,
+        MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
     peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
     peer_manager::TransportNotification,
@@ -15,7 +15,7 @@ use crate::{
             handshake::v1::MessagingProtocolVersion,
             messaging::v1::{
                 DirectSendMsg, NetworkMessage, NetworkMessageSink, NetworkMessageStream,
-                RpcRequest, RpcResponse,
+                RpcRequest, RpcResponse, StreamFragment, StreamMessage,
             },
         },
     },
@@ -88,6 +88,7 @@ fn build_test_peer(
         MAX_CONCURRENT_INBOUND_RPCS,
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
         None,
         None,
     );
@@ -336,6 +337,85 @@ fn peers_send_message_concurrent() {
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
 
+// Messages larger than a frame are streamed and reassembled by the remote Peer.
+#[test]
+fn peers_send_large_message() {
+    ::dijets_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (
+        (peer_a, mut peer_handle_a, _connection_notifs_rx_a, _peer_notifs_rx_a),
+        (peer_b, peer_handle_b, _connection_notifs_rx_b, mut peer_notifs_rx_b),
+    ) = build_test_connected_peers(rt.handle().clone(), TimeService::mock());
+
+    let test = async move {
+        let large_msg = Message {
+            protocol_id: PROTOCOL,
+            mdata: Bytes::from(vec![7; 2 * MAX_FRAME_SIZE + 1]),
+        };
+        let small_msg = Message {
+            protocol_id: PROTOCOL,
+            mdata: Bytes::from("hello world"),
+        };
+
+        // Peer A -> large_msg, small_msg -> Peer B
+        peer_handle_a.send_direct_send(large_msg.clone());
+        peer_handle_a.send_direct_send(small_msg.clone());
+
+        // Peer B receives both messages in order
+        let notif = peer_notifs_rx_b.next().await;
+        assert_eq!(notif, Some(PeerNotification::RecvMessage(large_msg)));
+        let notif = peer_notifs_rx_b.next().await;
+        assert_eq!(notif, Some(PeerNotification::RecvMessage(small_msg)));
+
+        drop(peer_handle_a);
+        drop(peer_handle_b);
+    };
+
+    rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
+}
+
+// A stream fragment without a stream header is discarded, and later messages
+// are still received.
+#[test]
+fn peer_recv_unexpected_stream_fragment() {
+    ::dijets_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (peer, _peer_handle, connection, _connection_notifs_rx, mut peer_notifs_rx) =
+        build_test_peer(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+        );
+
+    let fragment = NetworkMessage::Stream(StreamMessage::Fragment(StreamFragment {
+        stream_id: 0,
+        fragment_id: 1,
+        raw_data: Vec::from("hello world"),
+    }));
+    let send_msg = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: Vec::from("hello world"),
+    });
+    let recv_msg = PeerNotification::RecvMessage(Message {
+        protocol_id: PROTOCOL,
+        mdata: Bytes::from("hello world"),
+    });
+
+    let client = async move {
+        let mut connection = NetworkMessageSink::new(connection, MAX_FRAME_SIZE, None);
+        connection.send(&fragment).await.unwrap();
+        connection.send(&send_msg).await.unwrap();
+        connection.close().await.unwrap();
+    };
+
+    let server = async move {
+        let received = peer_notifs_rx.next().await.unwrap();
+        assert_eq!(recv_msg, received);
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 #[test]
 fn peer_recv_rpc() {
     ::dijets_logger::Logger::init_for_testing();
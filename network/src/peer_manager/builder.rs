@@ -10,12 +10,13 @@ use crate::{
         PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
     protocols::wire::handshake::v1::SupportedProtocols,
+    reputation::PeerReputation,
     transport::{self, Connection, DijetsNetTransport, DIJETS_TCP_TRANSPORT},
     ProtocolId,
 };
 use channel::{self, dijets_channel, message_queues::QueueStyle};
 use dijets_config::{
-    config::{PeerSet, RateLimitConfig, HANDSHAKE_VERSION},
+    config::{NoiseRekeyConfig, PeerSet, RateLimitConfig, ReputationConfig, HANDSHAKE_VERSION},
     network_id::NetworkContext,
 };
 use dijets_crypto::x25519;
@@ -55,6 +56,7 @@ struct TransportContext {
     authentication_mode: AuthenticationMode,
     trusted_peers: Arc<RwLock<PeerSet>>,
     enable_proxy_protocol: bool,
+    noise_rekey_config: Option<NoiseRekeyConfig>,
 }
 
 impl TransportContext {
@@ -65,6 +67,7 @@ impl TransportContext {
         authentication_mode: AuthenticationMode,
         trusted_peers: Arc<RwLock<PeerSet>>,
         enable_proxy_protocol: bool,
+        noise_rekey_config: Option<NoiseRekeyConfig>,
     ) -> Self {
         Self {
             chain_id,
@@ -73,6 +76,7 @@ impl TransportContext {
             authentication_mode,
             trusted_peers,
             enable_proxy_protocol,
+            noise_rekey_config,
         }
     }
 
@@ -112,12 +116,14 @@ struct PeerManagerContext {
     max_concurrent_network_reqs: usize,
     channel_size: usize,
     max_frame_size: usize,
+    max_message_size: usize,
     inbound_connection_limit: usize,
     inbound_rate_limit_config: Option<RateLimitConfig>,
     outbound_rate_limit_config: Option<RateLimitConfig>,
 }
 
 impl PeerManagerContext {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         pm_reqs_tx: dijets_channel::Sender<(PeerId, ProtocolId), PeerManagerRequest>,
         pm_reqs_rx: dijets_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
@@ -134,6 +140,7 @@ impl PeerManagerContext {
         max_concurrent_network_reqs: usize,
         channel_size: usize,
         max_frame_size: usize,
+        max_message_size: usize,
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
@@ -151,6 +158,7 @@ impl PeerManagerContext {
             max_concurrent_network_reqs,
             channel_size,
             max_frame_size,
+            max_message_size,
             inbound_connection_limit,
             inbound_rate_limit_config,
             outbound_rate_limit_config,
@@ -193,9 +201,11 @@ pub struct PeerManagerBuilder {
     peer_manager: Option<TransportPeerManager>,
     // ListenAddress will be updated when the PeerManager is built
     listen_address: NetworkAddress,
+    reputation: Arc<PeerReputation>,
 }
 
 impl PeerManagerBuilder {
+    #[allow(clippy::too_many_arguments)]
     pub fn create(
         chain_id: ChainId,
         network_context: Arc<NetworkContext>,
@@ -207,10 +217,13 @@ impl PeerManagerBuilder {
         channel_size: usize,
         max_concurrent_network_reqs: usize,
         max_frame_size: usize,
+        max_message_size: usize,
         enable_proxy_protocol: bool,
+        noise_rekey_config: Option<NoiseRekeyConfig>,
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
+        reputation_config: Option<ReputationConfig>,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = dijets_channel::new(
@@ -222,6 +235,12 @@ impl PeerManagerBuilder {
         let (connection_reqs_tx, connection_reqs_rx) =
             dijets_channel::new(QueueStyle::FIFO, channel_size, None);
 
+        let reputation = PeerReputation::new(
+            network_context.clone(),
+            time_service.clone(),
+            reputation_config,
+        );
+
         Self {
             network_context,
             time_service,
@@ -232,6 +251,7 @@ impl PeerManagerBuilder {
                 authentication_mode,
                 trusted_peers.clone(),
                 enable_proxy_protocol,
+                noise_rekey_config,
             )),
             peer_manager_context: Some(PeerManagerContext::new(
                 pm_reqs_tx,
@@ -244,12 +264,14 @@ impl PeerManagerBuilder {
                 max_concurrent_network_reqs,
                 channel_size,
                 max_frame_size,
+                max_message_size,
                 inbound_connection_limit,
                 inbound_rate_limit_config,
                 outbound_rate_limit_config,
             )),
             peer_manager: None,
             listen_address,
+            reputation,
         }
     }
 
@@ -278,6 +300,7 @@ impl PeerManagerBuilder {
         let protos = transport_context.supported_protocols();
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
+        let noise_rekey_config = transport_context.noise_rekey_config;
 
         let (key, auth_mode) = match transport_context.authentication_mode {
             AuthenticationMode::MaybeMutual(key) => (
@@ -291,8 +314,8 @@ impl PeerManagerBuilder {
         };
 
         self.peer_manager = match self.listen_address.as_slice() {
-            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
-                Some(TransportPeerManager::Tcp(self.build_with_transport(
+            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => Some(TransportPeerManager::Tcp(
+                self.build_with_transport(
                     DijetsNetTransport::new(
                         DIJETS_TCP_TRANSPORT.clone(),
                         self.network_context.clone(),
@@ -303,25 +326,31 @@ impl PeerManagerBuilder {
                         chain_id,
                         protos,
                         enable_proxy_protocol,
-                    ),
+                        noise_rekey_config,
+                    )
+                    .with_reputation(self.reputation.clone()),
                     executor,
-                )))
-            }
+                ),
+            )),
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
-            [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
-                DijetsNetTransport::new(
-                    MemoryTransport,
-                    self.network_context.clone(),
-                    self.time_service.clone(),
-                    key,
-                    auth_mode,
-                    HANDSHAKE_VERSION,
-                    chain_id,
-                    protos,
-                    enable_proxy_protocol,
+            [Memory(_)] => Some(TransportPeerManager::Memory(
+                self.build_with_transport(
+                    DijetsNetTransport::new(
+                        MemoryTransport,
+                        self.network_context.clone(),
+                        self.time_service.clone(),
+                        key,
+                        auth_mode,
+                        HANDSHAKE_VERSION,
+                        chain_id,
+                        protos,
+                        enable_proxy_protocol,
+                        noise_rekey_config,
+                    )
+                    .with_reputation(self.reputation.clone()),
+                    executor,
                 ),
-                executor,
-            ))),
+            )),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
                  '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
@@ -373,9 +402,11 @@ impl PeerManagerBuilder {
             pm_context.max_concurrent_network_reqs,
             pm_context.channel_size,
             pm_context.max_frame_size,
+            pm_context.max_message_size,
             pm_context.inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            self.reputation.clone(),
         );
 
         // PeerManager constructor appends a public key to the listen_address.
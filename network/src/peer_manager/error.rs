@@ -28,6 +28,9 @@ pub enum PeerManagerError {
     #[error("Already connected at {0}")]
     AlreadyConnected(NetworkAddress),
 
+    #[error("Peer {0} is banned")]
+    Banned(PeerId),
+
     #[error("Sending end of oneshot dropped")]
     OneshotSenderDropped,
 
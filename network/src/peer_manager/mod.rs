@@ -19,6 +19,7 @@ use crate::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
     },
+    reputation::{Misbehavior, PeerReputation},
     transport,
     transport::{Connection, ConnectionId, ConnectionMetadata},
     ProtocolId,
@@ -90,6 +91,8 @@ pub enum ConnectionRequest {
         PeerId,
         #[serde(skip)] oneshot::Sender<Result<(), PeerManagerError>>,
     ),
+    /// Misbehavior of a peer detected by an application, which may get the peer banned.
+    ReportMisbehavior(PeerId, Misbehavior),
 }
 
 #[derive(Clone, PartialEq, Serialize)]
@@ -234,6 +237,20 @@ impl ConnectionRequestSender {
             .push(peer, ConnectionRequest::DisconnectPeer(peer, oneshot_tx))?;
         oneshot_rx.await?
     }
+
+    /// Reports a misbehavior of `peer` to PeerManager, which bans the peer once it misbehaved too
+    /// much. The function returns when the report has been enqueued.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), PeerManagerError> {
+        self.inner.push(
+            peer,
+            ConnectionRequest::ReportMisbehavior(peer, misbehavior),
+        )?;
+        Ok(())
+    }
 }
 
 pub type IpAddrTokenBucketLimiter = TokenBucketRateLimiter<IpAddr>;
@@ -290,12 +307,16 @@ where
     channel_size: usize,
     /// Max network frame size
     max_frame_size: usize,
+    /// Max network message size
+    max_message_size: usize,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
     /// Keyed storage of all inbound rate limiters
     inbound_rate_limiters: IpAddrTokenBucketLimiter,
     /// Keyed storage of all outbound rate limiters
     outbound_rate_limiters: IpAddrTokenBucketLimiter,
+    /// Reputation of the peers, to ban misbehaving ones
+    reputation: Arc<PeerReputation>,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -322,9 +343,11 @@ where
         channel_size: usize,
         max_concurrent_network_reqs: usize,
         max_frame_size: usize,
+        max_message_size: usize,
         inbound_connection_limit: usize,
         inbound_rate_limiters: IpAddrTokenBucketLimiter,
         outbound_rate_limiters: IpAddrTokenBucketLimiter,
+        reputation: Arc<PeerReputation>,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = channel::new(
             channel_size,
@@ -365,9 +388,11 @@ where
             max_concurrent_network_reqs,
             channel_size,
             max_frame_size,
+            max_message_size,
             inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            reputation,
         }
     }
 
@@ -454,6 +479,21 @@ where
         self.sample_connected_peers();
         match event {
             TransportNotification::NewConnection(mut conn) => {
+                // Banned peers may have been dialed, or passed the transport, before their ban
+                if self.is_banned(&conn.metadata) {
+                    info!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata_with_address(&conn.metadata),
+                        "{} Connection rejected as the peer is banned: {}",
+                        self.network_context,
+                        conn.metadata
+                    );
+                    counters::connections_rejected(&self.network_context, conn.metadata.origin)
+                        .inc();
+                    self.disconnect(conn);
+                    return;
+                }
+
                 match conn.metadata.origin {
                     ConnectionOrigin::Outbound => {
                         // TODO: This is right now a hack around having to feed trusted peers deeper in the outbound path.  Inbound ones are assigned at Noise handshake time.
@@ -604,8 +644,21 @@ where
         self.sample_connected_peers();
         match request {
             ConnectionRequest::DialPeer(requested_peer_id, addr, response_tx) => {
-                // Only dial peers which we aren't already connected with
-                if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id) {
+                // Only dial peers which we aren't already connected with, and aren't banned
+                if self.reputation.is_peer_banned(&requested_peer_id) {
+                    let error = PeerManagerError::Banned(requested_peer_id);
+                    if let Err(send_err) = response_tx.send(Err(error)) {
+                        info!(
+                            NetworkSchema::new(&self.network_context)
+                                .remote_peer(&requested_peer_id),
+                            "{} Failed to notify that peer is banned for Peer {}: {:?}",
+                            self.network_context,
+                            requested_peer_id.short_str(),
+                            send_err
+                        );
+                    }
+                } else if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id)
+                {
                     let error = PeerManagerError::AlreadyConnected(curr_connection.addr.clone());
                     debug!(
                         NetworkSchema::new(&self.network_context)
@@ -659,9 +712,39 @@ where
                     }
                 }
             }
+            ConnectionRequest::ReportMisbehavior(peer_id, misbehavior) => {
+                let ip_addr = self
+                    .active_peers
+                    .get(&peer_id)
+                    .and_then(|(conn_metadata, _)| conn_metadata.addr.find_ip_addr());
+                if self.reputation.report(peer_id, ip_addr, misbehavior) {
+                    // Drop the send end of the PeerRequest channel to close the connection
+                    if let Some((conn_metadata, sender)) = self.active_peers.remove(&peer_id) {
+                        info!(
+                            NetworkSchema::new(&self.network_context)
+                                .connection_metadata_with_address(&conn_metadata),
+                            "{} Closing connection with banned Peer {}",
+                            self.network_context,
+                            peer_id.short_str()
+                        );
+                        drop(sender);
+                    }
+                }
+            }
         }
     }
 
+    /// Returns whether the peer of a new connection, or the address it's connected from, is banned
+    fn is_banned(&self, conn_metadata: &ConnectionMetadata) -> bool {
+        self.reputation
+            .is_peer_banned(&conn_metadata.remote_peer_id)
+            || (conn_metadata.origin == ConnectionOrigin::Inbound
+                && conn_metadata
+                    .addr
+                    .find_ip_addr()
+                    .map_or(false, |ip_addr| self.reputation.is_ip_banned(&ip_addr)))
+    }
+
     async fn handle_request(&mut self, request: PeerManagerRequest) {
         trace!(
             NetworkSchema::new(&self.network_context),
@@ -864,6 +947,7 @@ where
             constants::MAX_CONCURRENT_INBOUND_RPCS,
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
+            self.max_message_size,
             Some(inbound_rate_limiter),
             Some(outbound_rate_limiter),
         );
@@ -12,6 +12,7 @@ use crate::{
         handshake::v1::MessagingProtocolVersion,
         messaging::v1::{ErrorCode, NetworkMessage, NetworkMessageSink, NetworkMessageStream},
     },
+    reputation::{Misbehavior, PeerReputation},
     transport,
     transport::{Connection, ConnectionId, ConnectionMetadata},
     ProtocolId,
@@ -20,8 +21,8 @@ use anyhow::anyhow;
 use bytes::Bytes;
 use channel::{dijets_channel, message_queues::QueueStyle};
 use dijets_config::{
-    config::{PeerRole, MAX_INBOUND_CONNECTIONS},
-    network_id::NetworkContext,
+    config::{PeerRole, ReputationConfig, RoleType, MAX_INBOUND_CONNECTIONS},
+    network_id::{NetworkContext, NetworkId},
 };
 use dijets_infallible::RwLock;
 use dijets_rate_limiter::rate_limit::TokenBucketRateLimiter;
@@ -96,12 +97,24 @@ fn build_test_peer_manager(
     let (connection_reqs_tx, connection_reqs_rx) = dijets_channel::new(QueueStyle::FIFO, 1, None);
     let (hello_tx, hello_rx) = dijets_channel::new(QueueStyle::FIFO, 1, None);
     let (conn_status_tx, conn_status_rx) = conn_notifs_channel::new();
+    let network_context = NetworkContext::mock_with_peer_id(peer_id);
+    let time_service = TimeService::mock();
+    // Peers are never banned on the validator network
+    let reputation = PeerReputation::new(
+        Arc::new(NetworkContext::new(
+            RoleType::FullNode,
+            NetworkId::Public,
+            peer_id,
+        )),
+        time_service.clone(),
+        Some(ReputationConfig::default()),
+    );
 
     let peer_manager = PeerManager::new(
         executor,
-        TimeService::mock(),
+        time_service,
         build_test_transport(),
-        NetworkContext::mock_with_peer_id(peer_id),
+        network_context,
         "/memory/0".parse().unwrap(),
         Arc::new(RwLock::new(HashMap::new())),
         peer_manager_request_rx,
@@ -111,9 +124,11 @@ fn build_test_peer_manager(
         constants::NETWORK_CHANNEL_SIZE,
         constants::MAX_CONCURRENT_NETWORK_REQS,
         constants::MAX_FRAME_SIZE,
+        constants::MAX_MESSAGE_SIZE,
         MAX_INBOUND_CONNECTIONS,
         TokenBucketRateLimiter::open("inbound"),
         TokenBucketRateLimiter::open("outbound"),
+        reputation,
     );
 
     (
@@ -638,3 +653,88 @@ fn test_dial_disconnect() {
 
     runtime.block_on(test);
 }
+
+#[test]
+fn test_ban_misbehaving_peer() {
+    ::dijets_logger::Logger::init_for_testing();
+    let runtime = ::tokio::runtime::Runtime::new().unwrap();
+
+    let ids = ordered_peer_ids(2);
+    let (mut peer_manager, _request_tx, _connection_reqs_tx, _hello_rx, mut conn_status_rx) =
+        build_test_peer_manager(runtime.handle().clone(), ids[1]);
+
+    let test = async move {
+        let addr: NetworkAddress = "/ip4/1.2.3.4/tcp/6180".parse().unwrap();
+        let (outbound, _inbound) = build_test_connection();
+        peer_manager.add_peer(create_connection(
+            outbound,
+            ids[0],
+            addr.clone(),
+            ConnectionOrigin::Inbound,
+            ConnectionId::from(0),
+        ));
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        assert!(matches!(conn_notif, ConnectionNotification::NewPeer(_, _)));
+
+        // Report the peer until it gets banned
+        for _ in 0..2 {
+            assert!(peer_manager.active_peers.contains_key(&ids[0]));
+            peer_manager
+                .handle_connection_request(ConnectionRequest::ReportMisbehavior(
+                    ids[0],
+                    Misbehavior::InvalidSignature,
+                ))
+                .await;
+        }
+
+        // The connection with the banned peer is closed
+        assert!(!peer_manager.active_peers.contains_key(&ids[0]));
+        assert_peer_disconnected_event(
+            ids[0],
+            ConnectionOrigin::Inbound,
+            DisconnectReason::Requested,
+            &mut peer_manager,
+        )
+        .await;
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        assert!(matches!(
+            conn_notif,
+            ConnectionNotification::LostPeer(_, _, _)
+        ));
+
+        // New connections from the banned peer, or from its address, are refused
+        let (outbound, _inbound) = build_test_connection();
+        peer_manager.handle_connection_event(TransportNotification::NewConnection(
+            create_connection(
+                outbound,
+                ids[0],
+                NetworkAddress::mock(),
+                ConnectionOrigin::Inbound,
+                ConnectionId::from(1),
+            ),
+        ));
+        let (outbound, _inbound) = build_test_connection();
+        peer_manager.handle_connection_event(TransportNotification::NewConnection(
+            create_connection(
+                outbound,
+                PeerId::random(),
+                addr.clone(),
+                ConnectionOrigin::Inbound,
+                ConnectionId::from(2),
+            ),
+        ));
+        assert!(peer_manager.active_peers.is_empty());
+
+        // And the banned peer isn't dialed
+        let (dial_resp_tx, dial_resp_rx) = oneshot::channel();
+        peer_manager
+            .handle_connection_request(ConnectionRequest::DialPeer(ids[0], addr, dial_resp_tx))
+            .await;
+        assert!(matches!(
+            dial_resp_rx.await.unwrap(),
+            Err(PeerManagerError::Banned(_))
+        ));
+    };
+
+    runtime.block_on(test);
+}
@@ -10,6 +10,7 @@ use crate::{
         ConnectionNotification, ConnectionRequestSender, PeerManagerNotification,
         PeerManagerRequestSender,
     },
+    reputation::Misbehavior,
     transport::ConnectionMetadata,
     ProtocolId,
 };
@@ -246,6 +247,18 @@ impl<TMessage> NetworkSender<TMessage> {
         self.connection_reqs_tx.disconnect_peer(peer).await?;
         Ok(())
     }
+
+    /// Report a misbehavior of a given Peer, which gets it banned once it misbehaved too much.
+    /// Provides a wrapper over `[peer_manager::ConnectionRequestSender::report_misbehavior]`.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.connection_reqs_tx
+            .report_misbehavior(peer, misbehavior)?;
+        Ok(())
+    }
 }
 
 impl<TMessage: Message> NetworkSender<TMessage> {
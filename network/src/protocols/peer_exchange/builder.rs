@@ -0,0 +1,49 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    connectivity_manager::ConnectivityRequest,
+    protocols::peer_exchange::{
+        PeerExchange, PeerExchangeNetworkEvents, PeerExchangeNetworkSender,
+    },
+};
+use dijets_config::{config::PeerExchangeConfig, network_id::NetworkContext};
+use dijets_crypto::x25519;
+use dijets_time_service::TimeService;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+pub struct PeerExchangeBuilder {
+    service: Option<PeerExchange>,
+}
+
+impl PeerExchangeBuilder {
+    pub fn new(
+        network_context: Arc<NetworkContext>,
+        identity_key: x25519::PrivateKey,
+        time_service: TimeService,
+        config: PeerExchangeConfig,
+        network_tx: PeerExchangeNetworkSender,
+        network_rx: PeerExchangeNetworkEvents,
+        conn_mgr_reqs_tx: channel::Sender<ConnectivityRequest>,
+    ) -> Self {
+        let service = PeerExchange::new(
+            network_context,
+            identity_key,
+            time_service,
+            network_tx,
+            network_rx,
+            conn_mgr_reqs_tx,
+            config,
+        );
+        Self {
+            service: Some(service),
+        }
+    }
+
+    pub fn start(&mut self, executor: &Handle) {
+        if let Some(service) = self.service.take() {
+            executor.spawn(service.start());
+        }
+    }
+}
@@ -0,0 +1,580 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protocol used by public full nodes to discover peers from each other
+//!
+//! A node which only learns about peers from its seeds and discovery sources can't find new
+//! upstreams once those go down. With peer exchange, the PeerExchange actor periodically asks a
+//! random connected peer for a sample of the peers it knows, and feeds the peers it receives to the
+//! ConnectivityManager as the lowest priority [`DiscoverySource::PeerExchange`].
+//!
+//! Nodes only share first-hand knowledge: the peers they successfully dialed, along with the
+//! address they dialed them at. The sender signs each sample with its network identity key,
+//! following XEdDSA, for the peer which requested it. Receivers reject samples which aren't signed
+//! by the key the sender's peer id is derived from, or which were signed for another requester.
+//! The signature makes a sender accountable for the peers it lists, but doesn't vouch for it
+//! having actually reached them, so each record is also checked on its own: outside of the
+//! validator network a peer id is derived from the peer's x25519 key, so a record is only accepted
+//! with addresses carrying the key matching its peer id. A peer can therefore advertise other
+//! peers, including unreachable ones, but can't redirect their identities to addresses it
+//! controls, as dialing such an address fails the Noise handshake.
+//!
+//! Exchanged peers get the `Unknown` role: the ConnectivityManager dials them after the peers of
+//! every other source, but keeps them out of the trusted peers, so their inbound connections
+//! still count against the inbound connection limit.
+//!
+//! To keep a single peer from poisoning the table of exchanged peers:
+//! - at most `sample_size` peers are kept from a response, replacing the peers previously
+//!   received from the same sender,
+//! - at most `max_peers` exchanged peers are kept overall, and the peers received from a sender
+//!   are forgotten after `peer_ttl` unless it sends them again,
+//! - requests from a peer get at most one response every `min_request_interval`.
+use crate::{
+    connectivity_manager::{ConnectivityRequest, DiscoverySource},
+    constants::NETWORK_CHANNEL_SIZE,
+    counters,
+    logging::NetworkSchema,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::{
+        network::{Event, NetworkEvents, NetworkSender, NewNetworkSender},
+        rpc::error::RpcError,
+    },
+    transport::ConnectionMetadata,
+    ProtocolId,
+};
+use anyhow::ensure;
+use bytes::Bytes;
+use channel::message_queues::QueueStyle;
+use dijets_config::{
+    config::{Peer, PeerExchangeConfig, PeerRole, PeerSet},
+    network_id::NetworkContext,
+};
+use dijets_crypto::{ed25519::Ed25519Signature, x25519};
+use dijets_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use dijets_logger::prelude::*;
+use dijets_metrics::IntCounterVec;
+use dijets_time_service::{TimeService, TimeServiceTrait};
+use dijets_types::{
+    account_address::from_identity_public_key, network_address::NetworkAddress, PeerId,
+};
+use futures::{
+    channel::oneshot,
+    stream::{FuturesUnordered, StreamExt},
+};
+use netcore::transport::ConnectionOrigin;
+use rand::{rngs::SmallRng, seq::IteratorRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub mod builder;
+#[cfg(test)]
+mod test;
+
+/// Timeout of a request for peers.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of addresses kept for an exchanged peer.
+pub const MAX_ADDRESSES_PER_PEER: usize = 4;
+
+/// The interface from Network to PeerExchange layer.
+pub type PeerExchangeNetworkEvents = NetworkEvents<PeerExchangeMsg>;
+
+/// The interface from PeerExchange to Networking layer.
+#[derive(Clone)]
+pub struct PeerExchangeNetworkSender {
+    inner: NetworkSender<PeerExchangeMsg>,
+}
+
+/// Configuration for the network endpoints to support PeerExchange.
+pub fn network_endpoint_config() -> (
+    Vec<ProtocolId>,
+    Vec<ProtocolId>,
+    QueueStyle,
+    usize,
+    Option<&'static IntCounterVec>,
+) {
+    (
+        vec![ProtocolId::PeerExchangeRpc],
+        vec![],
+        QueueStyle::LIFO,
+        NETWORK_CHANNEL_SIZE,
+        Some(&counters::PENDING_PEER_EXCHANGE_NETWORK_EVENTS),
+    )
+}
+
+impl NewNetworkSender for PeerExchangeNetworkSender {
+    fn new(
+        peer_mgr_reqs_tx: PeerManagerRequestSender,
+        connection_reqs_tx: ConnectionRequestSender,
+    ) -> Self {
+        Self {
+            inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+        }
+    }
+}
+
+impl PeerExchangeNetworkSender {
+    /// Send a PeerExchange RPC request to remote peer `recipient`. Returns the remote peer's
+    /// future reply.
+    pub async fn send_rpc(
+        &mut self,
+        recipient: PeerId,
+        req_msg: PeerExchangeMsg,
+        timeout: Duration,
+    ) -> Result<PeerExchangeMsg, RpcError> {
+        let protocol = ProtocolId::PeerExchangeRpc;
+        self.inner
+            .send_rpc(recipient, protocol, req_msg, timeout)
+            .await
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PeerExchangeMsg {
+    GetPeers,
+    Peers(SignedPeerSample),
+}
+
+/// A sample of the peers a node dialed, sent to the peer which requested it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, CryptoHasher, BCSCryptoHash)]
+pub struct PeerSample {
+    /// The peer the sample is sent to, so that it can't be passed off to other peers.
+    pub requester: PeerId,
+    pub records: Vec<PeerRecord>,
+}
+
+/// A `PeerSample` signed with the network identity key of its sender.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedPeerSample {
+    /// The network identity key of the sender, which its peer id is derived from.
+    pub public_key: x25519::PublicKey,
+    pub sample: PeerSample,
+    /// The XEdDSA signature of the sample under `public_key`.
+    pub signature: Ed25519Signature,
+}
+
+impl SignedPeerSample {
+    pub fn new(identity_key: &x25519::PrivateKey, sample: PeerSample) -> Self {
+        Self {
+            public_key: identity_key.public_key(),
+            signature: identity_key.sign(&sample),
+            sample,
+        }
+    }
+
+    /// Returns the records of the sample, if it was signed by `sender` for `requester`.
+    pub fn verify(self, sender: PeerId, requester: PeerId) -> anyhow::Result<Vec<PeerRecord>> {
+        ensure!(
+            from_identity_public_key(self.public_key) == sender,
+            "Sample isn't signed with the identity key of {}",
+            sender.short_str()
+        );
+        ensure!(
+            self.sample.requester == requester,
+            "Sample was sent to {}",
+            self.sample.requester.short_str()
+        );
+        self.public_key
+            .verify_signature(&self.sample, &self.signature)?;
+        Ok(self.sample.records)
+    }
+}
+
+/// A peer, along with the addresses it can be dialed at.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerRecord {
+    pub peer_id: PeerId,
+    pub addresses: Vec<NetworkAddress>,
+}
+
+/// The peers received in the latest response of a connected peer.
+struct ReceivedPeers {
+    received_at: Instant,
+    peers: PeerSet,
+}
+
+/// The actor exchanging known peers with connected peers
+pub struct PeerExchange {
+    network_context: Arc<NetworkContext>,
+    /// The network identity key the samples we send are signed with.
+    identity_key: x25519::PrivateKey,
+    /// A handle to a time service for easily mocking time-related operations.
+    time_service: TimeService,
+    /// Channel to send requests to Network layer.
+    network_tx: PeerExchangeNetworkSender,
+    /// Channel to receive notifications from Network layer about new/lost connections.
+    network_rx: PeerExchangeNetworkEvents,
+    /// Channel to send the exchanged peers to the ConnectivityManager.
+    conn_mgr_reqs_tx: channel::Sender<ConnectivityRequest>,
+    /// Connected peers, along with the last time one of their requests got a response.
+    connected: HashMap<PeerId, (ConnectionMetadata, Option<Instant>)>,
+    /// Peers received, by the peer they were received from.
+    received: HashMap<PeerId, ReceivedPeers>,
+    /// Random-number generator.
+    rng: SmallRng,
+    /// Time we wait between each request for peers.
+    interval: Duration,
+    /// Maximum number of peers sent in a response, and kept from a response.
+    sample_size: usize,
+    /// Maximum number of exchanged peers sent to the ConnectivityManager.
+    max_peers: usize,
+    /// Time after which the peers received from a peer are forgotten.
+    peer_ttl: Duration,
+    /// Minimum time between two requests of a peer which get a response.
+    min_request_interval: Duration,
+}
+
+impl PeerExchange {
+    /// Create new instance of the [`PeerExchange`] actor.
+    pub fn new(
+        network_context: Arc<NetworkContext>,
+        identity_key: x25519::PrivateKey,
+        time_service: TimeService,
+        network_tx: PeerExchangeNetworkSender,
+        network_rx: PeerExchangeNetworkEvents,
+        conn_mgr_reqs_tx: channel::Sender<ConnectivityRequest>,
+        config: PeerExchangeConfig,
+    ) -> Self {
+        PeerExchange {
+            network_context,
+            identity_key,
+            time_service,
+            network_tx,
+            network_rx,
+            conn_mgr_reqs_tx,
+            connected: HashMap::new(),
+            received: HashMap::new(),
+            rng: SmallRng::from_entropy(),
+            interval: Duration::from_millis(config.interval_ms),
+            sample_size: config.sample_size,
+            max_peers: config.max_peers,
+            peer_ttl: Duration::from_millis(config.peer_ttl_ms),
+            min_request_interval: Duration::from_millis(config.min_request_interval_ms),
+        }
+    }
+
+    pub async fn start(mut self) {
+        let mut pending_requests = FuturesUnordered::new();
+        info!(
+            NetworkSchema::new(&self.network_context),
+            "{} Peer exchange actor started", self.network_context
+        );
+
+        let ticker = self.time_service.interval(self.interval);
+        tokio::pin!(ticker);
+
+        loop {
+            futures::select! {
+                maybe_event = self.network_rx.next() => {
+                    // Shutdown the PeerExchange when this network instance shuts
+                    // down. This happens when the `PeerManager` drops.
+                    let event = match maybe_event {
+                        Some(event) => event,
+                        None => break,
+                    };
+
+                    match event {
+                        Event::NewPeer(metadata) => {
+                            self.connected.insert(metadata.remote_peer_id, (metadata, None));
+                        }
+                        Event::LostPeer(metadata) => {
+                            self.connected.remove(&metadata.remote_peer_id);
+                        }
+                        Event::RpcRequest(peer_id, msg, res_tx) => {
+                            match msg {
+                                PeerExchangeMsg::GetPeers => self.handle_get_peers(peer_id, res_tx),
+                                _ => {
+                                    warn!(
+                                        SecurityEvent::InvalidPeerExchangeMsg,
+                                        NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                                        rpc_message = msg,
+                                        "{} Unexpected RPC message from {}",
+                                        self.network_context,
+                                        peer_id
+                                    );
+                                }
+                            };
+                        }
+                        Event::Message(peer_id, msg) => {
+                            error!(
+                                SecurityEvent::InvalidNetworkEvent,
+                                NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                                "{} Unexpected direct send from {} msg {:?}",
+                                self.network_context,
+                                peer_id,
+                                msg,
+                            );
+                            debug_assert!(false, "Unexpected network event");
+                        }
+                    }
+                }
+                _ = ticker.select_next_some() => {
+                    if self.expire_received_peers() {
+                        self.update_discovered_peers();
+                    }
+                    if let Some(peer_id) = self.choose_peer() {
+                        pending_requests.push(Self::request_peers(self.network_tx.clone(), peer_id));
+                    }
+                }
+                res = pending_requests.select_next_some() => {
+                    let (peer_id, result) = res;
+                    self.handle_peers_response(peer_id, result);
+                }
+            }
+        }
+        warn!(
+            NetworkSchema::new(&self.network_context),
+            "{} Peer exchange actor terminated", self.network_context
+        );
+    }
+
+    /// Returns a random connected peer supporting peer exchange.
+    fn choose_peer(&mut self) -> Option<PeerId> {
+        self.connected
+            .values()
+            .filter(|(metadata, _)| {
+                metadata
+                    .application_protocols
+                    .contains(ProtocolId::PeerExchangeRpc)
+            })
+            .map(|(metadata, _)| metadata.remote_peer_id)
+            .choose(&mut self.rng)
+    }
+
+    async fn request_peers(
+        mut network_tx: PeerExchangeNetworkSender,
+        peer_id: PeerId,
+    ) -> (PeerId, Result<PeerExchangeMsg, RpcError>) {
+        let result = network_tx
+            .send_rpc(peer_id, PeerExchangeMsg::GetPeers, REQUEST_TIMEOUT)
+            .await;
+        (peer_id, result)
+    }
+
+    fn handle_get_peers(
+        &mut self,
+        peer_id: PeerId,
+        res_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) {
+        let records = if self.allow_request(peer_id) {
+            self.sample_peers(peer_id)
+        } else {
+            debug!(
+                NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                "{} Not sharing peers with {}, it already requested them recently",
+                self.network_context,
+                peer_id.short_str()
+            );
+            Vec::new()
+        };
+        let num_records = records.len();
+
+        let sample = SignedPeerSample::new(
+            &self.identity_key,
+            PeerSample {
+                requester: peer_id,
+                records,
+            },
+        );
+        let message = match bcs::to_bytes(&PeerExchangeMsg::Peers(sample)) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!(
+                    NetworkSchema::new(&self.network_context),
+                    error = ?e,
+                    "{} Unable to serialize peers response: {}", self.network_context, e
+                );
+                return;
+            }
+        };
+        counters::peer_exchange_records(&self.network_context, counters::SENT_LABEL)
+            .inc_by(num_records as u64);
+        let _ = res_tx.send(Ok(message.into()));
+    }
+
+    /// Returns whether a request for peers from `peer_id` gets a response, and if so records it.
+    fn allow_request(&mut self, peer_id: PeerId) -> bool {
+        let now = self.time_service.now();
+        let min_request_interval = self.min_request_interval;
+        match self.connected.get_mut(&peer_id) {
+            Some((_, Some(last_request)))
+                if now.saturating_duration_since(*last_request) < min_request_interval =>
+            {
+                false
+            }
+            Some((_, last_request)) => {
+                *last_request = Some(now);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a sample of the peers we dialed, excluding `requester`.
+    fn sample_peers(&mut self, requester: PeerId) -> Vec<PeerRecord> {
+        self.connected
+            .values()
+            .filter(|(metadata, _)| {
+                metadata.origin == ConnectionOrigin::Outbound
+                    && metadata.remote_peer_id != requester
+            })
+            .map(|(metadata, _)| PeerRecord {
+                peer_id: metadata.remote_peer_id,
+                addresses: vec![metadata.addr.clone()],
+            })
+            .choose_multiple(&mut self.rng, self.sample_size)
+    }
+
+    fn handle_peers_response(
+        &mut self,
+        peer_id: PeerId,
+        result: Result<PeerExchangeMsg, RpcError>,
+    ) {
+        let sample = match result {
+            Ok(PeerExchangeMsg::Peers(sample)) => sample,
+            Ok(msg) => {
+                warn!(
+                    SecurityEvent::InvalidPeerExchangeMsg,
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    rpc_message = msg,
+                    "{} Unexpected RPC response from {}",
+                    self.network_context,
+                    peer_id
+                );
+                return;
+            }
+            Err(err) => {
+                debug!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    error = ?err,
+                    "{} Request for peers failed for peer: {} with error: {:?}",
+                    self.network_context,
+                    peer_id.short_str(),
+                    err
+                );
+                return;
+            }
+        };
+        let records = match sample.verify(peer_id, self.network_context.peer_id()) {
+            Ok(records) => records,
+            Err(err) => {
+                warn!(
+                    SecurityEvent::InvalidPeerExchangeMsg,
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    error = ?err,
+                    "{} Invalid sample of peers from {}: {}",
+                    self.network_context,
+                    peer_id.short_str(),
+                    err
+                );
+                return;
+            }
+        };
+
+        let num_records = records.len();
+        let peers: PeerSet = records
+            .into_iter()
+            .filter_map(|record| self.validate_record(record))
+            .take(self.sample_size)
+            .collect();
+        counters::peer_exchange_records(&self.network_context, counters::RECEIVED_LABEL)
+            .inc_by(peers.len() as u64);
+        if peers.len() < num_records {
+            let num_declined = num_records - peers.len();
+            counters::peer_exchange_records(&self.network_context, counters::DECLINED_LABEL)
+                .inc_by(num_declined as u64);
+            warn!(
+                SecurityEvent::InvalidPeerExchangeMsg,
+                NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                "{} Declined {} of {} peer records from peer: {}",
+                self.network_context,
+                num_declined,
+                num_records,
+                peer_id.short_str()
+            );
+        }
+
+        self.received.insert(
+            peer_id,
+            ReceivedPeers {
+                received_at: self.time_service.now(),
+                peers,
+            },
+        );
+        self.expire_received_peers();
+        self.update_discovered_peers();
+    }
+
+    /// Returns the peer described by `record`, keeping only the addresses carrying the key its
+    /// peer id is derived from.
+    fn validate_record(&self, record: PeerRecord) -> Option<(PeerId, Peer)> {
+        let peer_id = record.peer_id;
+        if peer_id == self.network_context.peer_id() {
+            return None;
+        }
+        let addresses: Vec<_> = record
+            .addresses
+            .into_iter()
+            .filter(|addr| {
+                addr.is_dijetsnet_addr()
+                    && addr.find_noise_proto().map(from_identity_public_key) == Some(peer_id)
+            })
+            .take(MAX_ADDRESSES_PER_PEER)
+            .collect();
+        if addresses.is_empty() {
+            return None;
+        }
+        Some((
+            peer_id,
+            Peer::new(addresses, HashSet::new(), PeerRole::Unknown),
+        ))
+    }
+
+    /// Forgets the peers received more than `peer_ttl` ago. Returns whether any were forgotten.
+    fn expire_received_peers(&mut self) -> bool {
+        let now = self.time_service.now();
+        let peer_ttl = self.peer_ttl;
+        let num_received = self.received.len();
+        self.received
+            .retain(|_, received| now.saturating_duration_since(received.received_at) <= peer_ttl);
+        self.received.len() != num_received
+    }
+
+    /// Sends the union of the received peers to the ConnectivityManager, up to `max_peers` of
+    /// them, preferring the most recently received ones.
+    fn update_discovered_peers(&mut self) {
+        let mut received: Vec<_> = self.received.values().collect();
+        received.sort_by_key(|received| Reverse(received.received_at));
+
+        let mut peers = PeerSet::new();
+        for (peer_id, peer) in received.into_iter().flat_map(|received| &received.peers) {
+            if let Some(known_peer) = peers.get_mut(peer_id) {
+                for addr in &peer.addresses {
+                    if known_peer.addresses.len() < MAX_ADDRESSES_PER_PEER
+                        && !known_peer.addresses.contains(addr)
+                    {
+                        known_peer.addresses.push(addr.clone());
+                    }
+                }
+                known_peer.keys.extend(peer.keys.iter().copied());
+            } else if peers.len() < self.max_peers {
+                peers.insert(*peer_id, peer.clone());
+            }
+        }
+
+        let request =
+            ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::PeerExchange, peers);
+        if let Err(error) = self.conn_mgr_reqs_tx.try_send(request) {
+            warn!(
+                NetworkSchema::new(&self.network_context),
+                "{} Failed to send exchanged peers update {:?}", self.network_context, error
+            );
+        }
+    }
+}
@@ -0,0 +1,446 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    peer::DisconnectReason,
+    peer_manager::{
+        self, conn_notifs_channel, ConnectionRequest, PeerManagerNotification, PeerManagerRequest,
+    },
+    protocols::{network::NewNetworkEvents, rpc::InboundRpcRequest},
+};
+use channel::dijets_channel;
+use dijets_config::config::HANDSHAKE_VERSION;
+use dijets_crypto::{test_utils::TEST_SEED, x25519, Uniform};
+use dijets_time_service::MockTimeService;
+use futures::{executor::block_on, future};
+use rand::rngs::StdRng;
+
+const INTERVAL: Duration = Duration::from_secs(1);
+const PEER_TTL: Duration = Duration::from_secs(10);
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+fn config() -> PeerExchangeConfig {
+    PeerExchangeConfig {
+        interval_ms: INTERVAL.as_millis() as u64,
+        sample_size: 2,
+        max_peers: 3,
+        peer_ttl_ms: PEER_TTL.as_millis() as u64,
+        min_request_interval_ms: MIN_REQUEST_INTERVAL.as_millis() as u64,
+    }
+}
+
+/// Generates `count` peer records whose peer ids are derived from their keys.
+fn peer_records(count: usize) -> Vec<PeerRecord> {
+    let mut rng = StdRng::from_seed(TEST_SEED);
+    (0..count)
+        .map(|i| {
+            let pubkey = x25519::PrivateKey::generate(&mut rng).public_key();
+            let addr: NetworkAddress = format!("/ip4/10.0.0.{}/tcp/6180", i + 1).parse().unwrap();
+            PeerRecord {
+                peer_id: from_identity_public_key(pubkey),
+                addresses: vec![addr.append_prod_protos(pubkey, HANDSHAKE_VERSION)],
+            }
+        })
+        .collect()
+}
+
+/// Generates the network identity key of a peer, along with the peer id derived from it.
+fn identity() -> (x25519::PrivateKey, PeerId) {
+    let identity_key = x25519::PrivateKey::generate_for_testing();
+    let peer_id = from_identity_public_key(identity_key.public_key());
+    (identity_key, peer_id)
+}
+
+fn to_peer_set(records: &[PeerRecord]) -> PeerSet {
+    records
+        .iter()
+        .map(|record| {
+            let peer = Peer::new(record.addresses.clone(), HashSet::new(), PeerRole::Unknown);
+            (record.peer_id, peer)
+        })
+        .collect()
+}
+
+struct TestHarness {
+    self_peer_id: PeerId,
+    public_key: x25519::PublicKey,
+    mock_time: MockTimeService,
+    peer_mgr_reqs_rx: dijets_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    peer_mgr_notifs_tx: dijets_channel::Sender<(PeerId, ProtocolId), PeerManagerNotification>,
+    _connection_reqs_rx: dijets_channel::Receiver<PeerId, ConnectionRequest>,
+    connection_notifs_tx: conn_notifs_channel::Sender,
+    conn_mgr_reqs_rx: channel::Receiver<ConnectivityRequest>,
+}
+
+impl TestHarness {
+    fn new(network_context: Arc<NetworkContext>) -> (Self, PeerExchange) {
+        ::dijets_logger::Logger::init_for_testing();
+        let mock_time = TimeService::mock();
+        let self_peer_id = network_context.peer_id();
+        let identity_key = x25519::PrivateKey::generate_for_testing();
+        let public_key = identity_key.public_key();
+
+        let (peer_mgr_reqs_tx, peer_mgr_reqs_rx) = dijets_channel::new(QueueStyle::FIFO, 1, None);
+        let (connection_reqs_tx, connection_reqs_rx) =
+            dijets_channel::new(QueueStyle::FIFO, 1, None);
+        let (peer_mgr_notifs_tx, peer_mgr_notifs_rx) =
+            dijets_channel::new(QueueStyle::FIFO, 1, None);
+        let (connection_notifs_tx, connection_notifs_rx) = conn_notifs_channel::new();
+        let (conn_mgr_reqs_tx, conn_mgr_reqs_rx) =
+            channel::new(1, &counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS);
+
+        let network_tx = PeerExchangeNetworkSender::new(
+            PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        let network_rx = PeerExchangeNetworkEvents::new(peer_mgr_notifs_rx, connection_notifs_rx);
+        let peer_exchange = PeerExchange::new(
+            network_context,
+            identity_key,
+            mock_time.clone(),
+            network_tx,
+            network_rx,
+            conn_mgr_reqs_tx,
+            config(),
+        );
+
+        (
+            Self {
+                self_peer_id,
+                public_key,
+                mock_time: mock_time.into_mock(),
+                peer_mgr_reqs_rx,
+                peer_mgr_notifs_tx,
+                _connection_reqs_rx: connection_reqs_rx,
+                connection_notifs_tx,
+                conn_mgr_reqs_rx,
+            },
+            peer_exchange,
+        )
+    }
+
+    async fn advance(&self, duration: Duration) {
+        self.mock_time.advance_async(duration).await;
+    }
+
+    /// Notifies PeerExchange of a new peer supporting peer exchange, dialed at `addr` if outbound.
+    async fn send_new_peer_notification(
+        &mut self,
+        peer_id: PeerId,
+        origin: ConnectionOrigin,
+        addr: Option<NetworkAddress>,
+    ) -> ConnectionMetadata {
+        let mut metadata =
+            ConnectionMetadata::mock_with_role_and_origin(peer_id, PeerRole::Unknown, origin);
+        metadata.application_protocols = [ProtocolId::PeerExchangeRpc].iter().into();
+        if let Some(addr) = addr {
+            metadata.addr = addr;
+        }
+
+        let (delivered_tx, delivered_rx) = oneshot::channel();
+        let notif =
+            peer_manager::ConnectionNotification::NewPeer(metadata.clone(), NetworkContext::mock());
+        self.connection_notifs_tx
+            .push_with_feedback(peer_id, notif, Some(delivered_tx))
+            .unwrap();
+        delivered_rx.await.unwrap();
+        metadata
+    }
+
+    async fn send_lost_peer_notification(&mut self, metadata: ConnectionMetadata) {
+        let peer_id = metadata.remote_peer_id;
+        let (delivered_tx, delivered_rx) = oneshot::channel();
+        let notif = peer_manager::ConnectionNotification::LostPeer(
+            metadata,
+            NetworkContext::mock(),
+            DisconnectReason::ConnectionLost,
+        );
+        self.connection_notifs_tx
+            .push_with_feedback(peer_id, notif, Some(delivered_tx))
+            .unwrap();
+        delivered_rx.await.unwrap();
+    }
+
+    /// Sends a request for peers from `peer_id`, and returns the peers in the signed response.
+    async fn send_get_peers(&mut self, peer_id: PeerId) -> Vec<PeerRecord> {
+        let protocol_id = ProtocolId::PeerExchangeRpc;
+        let data = bcs::to_bytes(&PeerExchangeMsg::GetPeers).unwrap().into();
+        let (res_tx, res_rx) = oneshot::channel();
+        let inbound_rpc_req = InboundRpcRequest {
+            protocol_id,
+            data,
+            res_tx,
+        };
+        self.peer_mgr_notifs_tx
+            .push(
+                (peer_id, protocol_id),
+                PeerManagerNotification::RecvRpc(peer_id, inbound_rpc_req),
+            )
+            .unwrap();
+
+        let res_data = res_rx.await.unwrap().unwrap();
+        match bcs::from_bytes(&res_data).unwrap() {
+            PeerExchangeMsg::Peers(sample) => sample
+                .verify(from_identity_public_key(self.public_key), peer_id)
+                .unwrap(),
+            msg => panic!("Unexpected PeerExchangeMsg: {:?}", msg),
+        }
+    }
+
+    /// Expects a request for peers to `source`, and responds with `records` signed by it.
+    async fn expect_get_peers(&mut self, source: &x25519::PrivateKey, records: Vec<PeerRecord>) {
+        let sample = SignedPeerSample::new(
+            source,
+            PeerSample {
+                requester: self.self_peer_id,
+                records,
+            },
+        );
+        self.expect_get_peers_response(from_identity_public_key(source.public_key()), sample)
+            .await;
+    }
+
+    /// Expects a request for peers to `expected_peer_id`, and responds with `sample`.
+    async fn expect_get_peers_response(
+        &mut self,
+        expected_peer_id: PeerId,
+        sample: SignedPeerSample,
+    ) {
+        let (peer_id, rpc_req) = match self.peer_mgr_reqs_rx.next().await.unwrap() {
+            PeerManagerRequest::SendRpc(peer_id, rpc_req) => (peer_id, rpc_req),
+            req => panic!("Unexpected PeerManagerRequest: {:?}", req),
+        };
+        assert_eq!(peer_id, expected_peer_id);
+        assert_eq!(rpc_req.protocol_id, ProtocolId::PeerExchangeRpc);
+        assert_eq!(
+            bcs::from_bytes::<PeerExchangeMsg>(&rpc_req.data).unwrap(),
+            PeerExchangeMsg::GetPeers
+        );
+
+        let res_data = bcs::to_bytes(&PeerExchangeMsg::Peers(sample)).unwrap();
+        rpc_req.res_tx.send(Ok(res_data.into())).unwrap();
+    }
+
+    /// Expects the exchanged peers to be sent to the ConnectivityManager.
+    async fn expect_discovered_peers(&mut self) -> PeerSet {
+        match self.conn_mgr_reqs_rx.next().await.unwrap() {
+            ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::PeerExchange, peers) => {
+                peers
+            }
+            req => panic!("Unexpected ConnectivityRequest: {:?}", req),
+        }
+    }
+}
+
+#[test]
+fn share_dialed_peers() {
+    let (mut harness, peer_exchange) = TestHarness::new(NetworkContext::mock());
+    let records = peer_records(2);
+
+    let test = async move {
+        // Only the peers we dialed are shared, as we don't know the address of inbound peers
+        harness
+            .send_new_peer_notification(
+                records[0].peer_id,
+                ConnectionOrigin::Outbound,
+                Some(records[0].addresses[0].clone()),
+            )
+            .await;
+        harness
+            .send_new_peer_notification(records[1].peer_id, ConnectionOrigin::Inbound, None)
+            .await;
+        let requester = PeerId::random();
+        harness
+            .send_new_peer_notification(requester, ConnectionOrigin::Inbound, None)
+            .await;
+
+        assert_eq!(harness.send_get_peers(requester).await, records[..1]);
+        // A peer isn't shared with itself
+        assert!(harness.send_get_peers(records[0].peer_id).await.is_empty());
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
+
+#[test]
+fn rate_limit_requests() {
+    let (mut harness, peer_exchange) = TestHarness::new(NetworkContext::mock());
+    let records = peer_records(1);
+
+    let test = async move {
+        harness
+            .send_new_peer_notification(
+                records[0].peer_id,
+                ConnectionOrigin::Outbound,
+                Some(records[0].addresses[0].clone()),
+            )
+            .await;
+        let requester = PeerId::random();
+        harness
+            .send_new_peer_notification(requester, ConnectionOrigin::Inbound, None)
+            .await;
+
+        assert_eq!(harness.send_get_peers(requester).await, records);
+        // Requests coming in too often get an empty response
+        assert!(harness.send_get_peers(requester).await.is_empty());
+        // Until enough time has passed
+        harness.mock_time.advance(MIN_REQUEST_INTERVAL);
+        assert_eq!(harness.send_get_peers(requester).await, records);
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
+
+#[test]
+fn discover_received_peers() {
+    let network_context = NetworkContext::mock();
+    let self_peer_id = network_context.peer_id();
+    let (mut harness, peer_exchange) = TestHarness::new(network_context);
+    let records = peer_records(3);
+
+    let test = async move {
+        let (source_key, source) = identity();
+        harness
+            .send_new_peer_notification(source, ConnectionOrigin::Outbound, None)
+            .await;
+        harness.advance(INTERVAL).await;
+
+        // Records for ourselves, or whose addresses don't match their peer ids are declined
+        let mut hijacked = records[1].clone();
+        hijacked.addresses = records[2].addresses.clone();
+        let mut self_record = records[2].clone();
+        self_record.peer_id = self_peer_id;
+        harness
+            .expect_get_peers(&source_key, vec![records[0].clone(), hijacked, self_record])
+            .await;
+
+        assert_eq!(
+            harness.expect_discovered_peers().await,
+            to_peer_set(&records[..1])
+        );
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
+
+#[test]
+fn reject_invalid_samples() {
+    let (mut harness, peer_exchange) = TestHarness::new(NetworkContext::mock());
+    let self_peer_id = harness.self_peer_id;
+    let records = peer_records(2);
+
+    let test = async move {
+        let (source_key, source) = identity();
+        harness
+            .send_new_peer_notification(source, ConnectionOrigin::Outbound, None)
+            .await;
+        let sample = PeerSample {
+            requester: self_peer_id,
+            records: records[1..].to_vec(),
+        };
+
+        // A sample signed by another key than the sender's
+        let (other_key, _) = identity();
+        harness.advance(INTERVAL).await;
+        harness
+            .expect_get_peers_response(source, SignedPeerSample::new(&other_key, sample.clone()))
+            .await;
+
+        // A sample signed for another requester
+        let mut other_sample = sample.clone();
+        other_sample.requester = PeerId::random();
+        harness.advance(INTERVAL).await;
+        harness
+            .expect_get_peers_response(source, SignedPeerSample::new(&source_key, other_sample))
+            .await;
+
+        // A sample whose records were changed after it was signed
+        let mut tampered = SignedPeerSample::new(&source_key, sample);
+        tampered.sample.records = records[..1].to_vec();
+        harness.advance(INTERVAL).await;
+        harness.expect_get_peers_response(source, tampered).await;
+
+        // None of them were discovered, only the peers of a valid sample are
+        harness.advance(INTERVAL).await;
+        harness
+            .expect_get_peers(&source_key, records[..1].to_vec())
+            .await;
+        assert_eq!(
+            harness.expect_discovered_peers().await,
+            to_peer_set(&records[..1])
+        );
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
+
+#[test]
+fn cap_received_peers() {
+    let (mut harness, peer_exchange) = TestHarness::new(NetworkContext::mock());
+    let records = peer_records(6);
+
+    let test = async move {
+        let (first_source_key, first_source) = identity();
+        let metadata = harness
+            .send_new_peer_notification(first_source, ConnectionOrigin::Outbound, None)
+            .await;
+        harness.advance(INTERVAL).await;
+
+        // Only `sample_size` peers are kept from a response
+        harness
+            .expect_get_peers(&first_source_key, records[..4].to_vec())
+            .await;
+        assert_eq!(
+            harness.expect_discovered_peers().await,
+            to_peer_set(&records[..2])
+        );
+
+        // Only `max_peers` are kept overall, preferring the most recently received
+        harness.send_lost_peer_notification(metadata).await;
+        let (second_source_key, second_source) = identity();
+        harness
+            .send_new_peer_notification(second_source, ConnectionOrigin::Outbound, None)
+            .await;
+        harness.advance(INTERVAL).await;
+        harness
+            .expect_get_peers(&second_source_key, records[4..].to_vec())
+            .await;
+        let peers = harness.expect_discovered_peers().await;
+        assert_eq!(peers.len(), 3);
+        for record in &records[4..] {
+            assert!(peers.contains_key(&record.peer_id));
+        }
+
+        // A new response replaces the peers previously received from the same source
+        harness.advance(INTERVAL).await;
+        harness.expect_get_peers(&second_source_key, vec![]).await;
+        assert_eq!(
+            harness.expect_discovered_peers().await,
+            to_peer_set(&records[..2])
+        );
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
+
+#[test]
+fn expire_received_peers() {
+    let (mut harness, peer_exchange) = TestHarness::new(NetworkContext::mock());
+    let records = peer_records(1);
+
+    let test = async move {
+        let (source_key, source) = identity();
+        let metadata = harness
+            .send_new_peer_notification(source, ConnectionOrigin::Outbound, None)
+            .await;
+        harness.advance(INTERVAL).await;
+        harness.expect_get_peers(&source_key, records.clone()).await;
+        assert_eq!(
+            harness.expect_discovered_peers().await,
+            to_peer_set(&records)
+        );
+
+        // Peers are remembered after their source disconnects, until they expire
+        harness.send_lost_peer_notification(metadata).await;
+        harness.advance(PEER_TTL).await;
+        harness.advance(INTERVAL).await;
+        assert!(harness.expect_discovered_peers().await.is_empty());
+    };
+    block_on(future::join(peer_exchange.start(), test));
+}
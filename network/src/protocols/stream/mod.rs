@@ -0,0 +1,251 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protocol for streaming [`NetworkMessage`]s which are larger than the maximum frame size.
+//!
+//! A message whose serialized size exceeds the frame size is split into a [`StreamHeader`],
+//! carrying the message along with the first part of its payload, followed by up to
+//! [`MAX_FRAGMENTS`] [`StreamFragment`]s carrying the rest of the payload. The writer task of a
+//! [`Peer`](crate::peer::Peer) writes all the frames of a stream back to back, so the receiving
+//! side only ever reassembles a single stream per connection at a time.
+//!
+//! To bound the memory a remote peer can make us hold on to, reassembly is limited to
+//! `max_message_size` bytes, and a stream which isn't completed within the inbound stream timeout
+//! is discarded.
+//!
+//! Messages which fit in a single frame are sent as-is, so peers which do not understand streams
+//! are only affected by messages which they couldn't have received before anyway.
+
+use crate::protocols::wire::messaging::v1::{
+    NetworkMessage, StreamFragment, StreamHeader, StreamId, StreamMessage, StreamedMessage,
+};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[cfg(test)]
+mod test;
+
+/// Maximum number of fragments following a [`StreamHeader`].
+pub const MAX_FRAGMENTS: usize = u8::MAX as usize;
+
+/// Upper bound on the serialization overhead of a [`StreamHeader`] or [`StreamFragment`] frame
+/// beyond the bytes of its payload.
+pub const STREAM_FRAME_OVERHEAD: usize = 64;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum StreamError {
+    #[error("message of {0} bytes exceeds the maximum message size of {1} bytes")]
+    MessageTooLarge(usize, usize),
+
+    #[error("stream {0} has no fragments")]
+    NoFragments(StreamId),
+
+    #[error("stream {0} was interrupted by a new stream {1}")]
+    Interrupted(StreamId, StreamId),
+
+    #[error("fragment of stream {0} received without a matching stream header")]
+    UnexpectedFragment(StreamId),
+
+    #[error("stream {0}: expected fragment {1}, received fragment {2}")]
+    OutOfOrderFragment(StreamId, u8, u8),
+
+    #[error("stream {0} timed out after {1:?}")]
+    Timeout(StreamId, Duration),
+}
+
+/// Splits outbound messages which don't fit in a single frame into streams.
+pub struct OutboundStream {
+    max_frame_size: usize,
+    max_message_size: usize,
+    next_stream_id: StreamId,
+}
+
+impl OutboundStream {
+    pub fn new(max_frame_size: usize, max_message_size: usize) -> Self {
+        Self {
+            max_frame_size,
+            max_message_size,
+            next_stream_id: 0,
+        }
+    }
+
+    /// Returns the frames to send for `message`: the message itself if it fits in a frame,
+    /// otherwise a stream header followed by its fragments.
+    pub fn fragment(
+        &mut self,
+        message: NetworkMessage,
+    ) -> Result<Vec<NetworkMessage>, StreamError> {
+        // Serializing a message only fails for unsupported types, which a NetworkMessage never
+        // contains. In that case the sink surfaces the error instead.
+        let message_len = bcs::serialized_size(&message).unwrap_or(0);
+        if message_len <= self.max_frame_size {
+            return Ok(vec![message]);
+        }
+        if message_len > self.max_message_size {
+            return Err(StreamError::MessageTooLarge(
+                message_len,
+                self.max_message_size,
+            ));
+        }
+
+        let mut message = match message {
+            NetworkMessage::RpcRequest(request) => StreamedMessage::RpcRequest(request),
+            NetworkMessage::RpcResponse(response) => StreamedMessage::RpcResponse(response),
+            NetworkMessage::DirectSendMsg(message) => StreamedMessage::DirectSendMsg(message),
+            message => return Ok(vec![message]),
+        };
+
+        let chunk_size = self
+            .max_frame_size
+            .saturating_sub(STREAM_FRAME_OVERHEAD)
+            .max(1);
+        let payload = std::mem::take(message.payload_mut());
+        let mut chunks = payload.chunks(chunk_size);
+        *message.payload_mut() = chunks.next().map(<[u8]>::to_vec).unwrap_or_default();
+        let fragments: Vec<_> = chunks.map(<[u8]>::to_vec).collect();
+        if fragments.len() > MAX_FRAGMENTS {
+            return Err(StreamError::MessageTooLarge(
+                message_len,
+                chunk_size * (MAX_FRAGMENTS + 1),
+            ));
+        }
+
+        let stream_id = self.next_stream_id;
+        self.next_stream_id = self.next_stream_id.wrapping_add(1);
+
+        let mut messages = Vec::with_capacity(fragments.len() + 1);
+        messages.push(NetworkMessage::Stream(StreamMessage::Header(
+            StreamHeader {
+                stream_id,
+                num_fragments: fragments.len() as u8,
+                message,
+            },
+        )));
+        messages.extend(fragments.into_iter().enumerate().map(|(index, raw_data)| {
+            NetworkMessage::Stream(StreamMessage::Fragment(StreamFragment {
+                stream_id,
+                fragment_id: (index + 1) as u8,
+                raw_data,
+            }))
+        }));
+        Ok(messages)
+    }
+}
+
+/// A stream whose fragments are still being received.
+struct InboundStream {
+    stream_id: StreamId,
+    num_fragments: u8,
+    received_fragments: u8,
+    message: StreamedMessage,
+    started_at: Instant,
+}
+
+/// Reassembles inbound streams into the original messages.
+pub struct InboundStreamBuffer {
+    max_message_size: usize,
+    timeout: Duration,
+    stream: Option<InboundStream>,
+}
+
+impl InboundStreamBuffer {
+    pub fn new(max_message_size: usize, timeout: Duration) -> Self {
+        Self {
+            max_message_size,
+            timeout,
+            stream: None,
+        }
+    }
+
+    /// Handles a stream header or fragment, returning the reassembled message once the last
+    /// fragment of its stream is received. Any error discards the stream in progress.
+    pub fn handle_message(
+        &mut self,
+        message: StreamMessage,
+        now: Instant,
+    ) -> Result<Option<NetworkMessage>, StreamError> {
+        match message {
+            StreamMessage::Header(header) => self.new_stream(header, now).map(|_| None),
+            StreamMessage::Fragment(fragment) => self.append_fragment(fragment),
+        }
+    }
+
+    /// Discards the stream in progress if it was started more than `timeout` ago.
+    pub fn expire(&mut self, now: Instant) -> Result<(), StreamError> {
+        match &self.stream {
+            Some(stream) if now.saturating_duration_since(stream.started_at) > self.timeout => {
+                let stream_id = stream.stream_id;
+                self.stream = None;
+                Err(StreamError::Timeout(stream_id, self.timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn new_stream(&mut self, mut header: StreamHeader, now: Instant) -> Result<(), StreamError> {
+        // A new header while a stream is in progress means the previous stream is incomplete,
+        // since the frames of a stream are always sent back to back.
+        let interrupted = self.stream.take().map(|stream| stream.stream_id);
+
+        if header.num_fragments == 0 {
+            return Err(StreamError::NoFragments(header.stream_id));
+        }
+        let payload_len = header.message.payload_mut().len();
+        if payload_len > self.max_message_size {
+            return Err(StreamError::MessageTooLarge(
+                payload_len,
+                self.max_message_size,
+            ));
+        }
+
+        self.stream = Some(InboundStream {
+            stream_id: header.stream_id,
+            num_fragments: header.num_fragments,
+            received_fragments: 0,
+            message: header.message,
+            started_at: now,
+        });
+
+        match interrupted {
+            Some(stream_id) => Err(StreamError::Interrupted(stream_id, header.stream_id)),
+            None => Ok(()),
+        }
+    }
+
+    fn append_fragment(
+        &mut self,
+        fragment: StreamFragment,
+    ) -> Result<Option<NetworkMessage>, StreamError> {
+        let mut stream = match self.stream.take() {
+            Some(stream) if stream.stream_id == fragment.stream_id => stream,
+            _ => return Err(StreamError::UnexpectedFragment(fragment.stream_id)),
+        };
+
+        let expected_fragment_id = stream.received_fragments + 1;
+        if fragment.fragment_id != expected_fragment_id {
+            return Err(StreamError::OutOfOrderFragment(
+                stream.stream_id,
+                expected_fragment_id,
+                fragment.fragment_id,
+            ));
+        }
+
+        let payload = stream.message.payload_mut();
+        let payload_len = payload.len().saturating_add(fragment.raw_data.len());
+        if payload_len > self.max_message_size {
+            return Err(StreamError::MessageTooLarge(
+                payload_len,
+                self.max_message_size,
+            ));
+        }
+        payload.extend_from_slice(&fragment.raw_data);
+        stream.received_fragments = expected_fragment_id;
+
+        if stream.received_fragments == stream.num_fragments {
+            Ok(Some(stream.message.into()))
+        } else {
+            self.stream = Some(stream);
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    protocols::wire::messaging::v1::{DirectSendMsg, RpcResponse},
+    ProtocolId,
+};
+
+const MAX_FRAME_SIZE: usize = 256;
+const MAX_MESSAGE_SIZE: usize = 4096;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn direct_send(len: usize) -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::MempoolDirectSend,
+        priority: 0,
+        raw_msg: (0..len).map(|i| i as u8).collect(),
+    })
+}
+
+fn stream_message(message: NetworkMessage) -> StreamMessage {
+    match message {
+        NetworkMessage::Stream(message) => message,
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn small_messages_are_not_streamed() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    let message = direct_send(100);
+    assert_eq!(outbound.fragment(message.clone()).unwrap(), vec![message]);
+}
+
+#[test]
+fn fragment_and_reassemble() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    let mut inbound = InboundStreamBuffer::new(MAX_MESSAGE_SIZE, TIMEOUT);
+    let now = Instant::now();
+
+    for message in vec![
+        direct_send(1000),
+        NetworkMessage::RpcResponse(RpcResponse {
+            request_id: 7,
+            priority: 0,
+            raw_response: vec![1; 3000],
+        }),
+    ] {
+        let frames = outbound.fragment(message.clone()).unwrap();
+        assert!(frames.len() > 1);
+        for frame in &frames {
+            assert!(bcs::serialized_size(frame).unwrap() <= MAX_FRAME_SIZE);
+        }
+
+        let (last, rest) = frames.split_last().unwrap();
+        for frame in rest {
+            let result = inbound.handle_message(stream_message(frame.clone()), now);
+            assert_eq!(result, Ok(None));
+        }
+        let result = inbound.handle_message(stream_message(last.clone()), now);
+        assert_eq!(result, Ok(Some(message)));
+    }
+}
+
+#[test]
+fn outbound_message_too_large() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    assert!(matches!(
+        outbound.fragment(direct_send(MAX_MESSAGE_SIZE + 1)),
+        Err(StreamError::MessageTooLarge(_, MAX_MESSAGE_SIZE))
+    ));
+}
+
+#[test]
+fn inbound_message_too_large() {
+    // The sender allows larger messages than the receiver
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, 2 * MAX_MESSAGE_SIZE);
+    let mut inbound = InboundStreamBuffer::new(MAX_MESSAGE_SIZE, TIMEOUT);
+    let now = Instant::now();
+
+    let frames = outbound
+        .fragment(direct_send(MAX_MESSAGE_SIZE + 1))
+        .unwrap();
+    let result = frames
+        .into_iter()
+        .map(|frame| inbound.handle_message(stream_message(frame), now))
+        .find(Result::is_err);
+    assert!(matches!(
+        result,
+        Some(Err(StreamError::MessageTooLarge(_, MAX_MESSAGE_SIZE)))
+    ));
+    assert!(inbound.stream.is_none());
+}
+
+#[test]
+fn out_of_order_fragments() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    let mut inbound = InboundStreamBuffer::new(MAX_MESSAGE_SIZE, TIMEOUT);
+    let now = Instant::now();
+
+    let mut frames = outbound.fragment(direct_send(1000)).unwrap().into_iter();
+    let header = stream_message(frames.next().unwrap());
+    let _ = frames.next().unwrap();
+    let second_fragment = stream_message(frames.next().unwrap());
+
+    inbound.handle_message(header, now).unwrap();
+    assert_eq!(
+        inbound.handle_message(second_fragment.clone(), now),
+        Err(StreamError::OutOfOrderFragment(0, 1, 2))
+    );
+    // The stream was discarded
+    assert_eq!(
+        inbound.handle_message(second_fragment, now),
+        Err(StreamError::UnexpectedFragment(0))
+    );
+}
+
+#[test]
+fn interrupted_stream() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    let mut inbound = InboundStreamBuffer::new(MAX_MESSAGE_SIZE, TIMEOUT);
+    let now = Instant::now();
+
+    let first = outbound.fragment(direct_send(1000)).unwrap();
+    let second = outbound.fragment(direct_send(1000)).unwrap();
+
+    inbound
+        .handle_message(stream_message(first[0].clone()), now)
+        .unwrap();
+    assert_eq!(
+        inbound.handle_message(stream_message(second[0].clone()), now),
+        Err(StreamError::Interrupted(0, 1))
+    );
+    // The new stream is still reassembled
+    let (last, rest) = second[1..].split_last().unwrap();
+    for frame in rest {
+        inbound
+            .handle_message(stream_message(frame.clone()), now)
+            .unwrap();
+    }
+    assert_eq!(
+        inbound.handle_message(stream_message(last.clone()), now),
+        Ok(Some(direct_send(1000)))
+    );
+}
+
+#[test]
+fn stream_timeout() {
+    let mut outbound = OutboundStream::new(MAX_FRAME_SIZE, MAX_MESSAGE_SIZE);
+    let mut inbound = InboundStreamBuffer::new(MAX_MESSAGE_SIZE, TIMEOUT);
+    let now = Instant::now();
+
+    let frames = outbound.fragment(direct_send(1000)).unwrap();
+    inbound
+        .handle_message(stream_message(frames[0].clone()), now)
+        .unwrap();
+
+    assert_eq!(inbound.expire(now + TIMEOUT), Ok(()));
+    assert_eq!(
+        inbound.expire(now + TIMEOUT + Duration::from_millis(1)),
+        Err(StreamError::Timeout(0, TIMEOUT))
+    );
+    assert_eq!(
+        inbound.handle_message(stream_message(frames[1].clone()), now),
+        Err(StreamError::UnexpectedFragment(0))
+    );
+}
@@ -12,11 +12,17 @@
 //!
 //! [DijetsNet Handshake v1 Specification]: https://github.com/dijets/dijets/blob/main/specifications/network/handshake-v1.md
 
-use anyhow::anyhow;
+use crate::{constants::MAX_DECOMPRESSED_MESSAGE_SIZE, counters};
+use anyhow::{anyhow, ensure};
 use dijets_config::network_id::NetworkId;
 use dijets_types::chain_id::ChainId;
-use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, convert::TryInto, fmt, iter::Iterator};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
+    fmt,
+    iter::Iterator,
+};
 use thiserror::Error;
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -42,6 +48,18 @@ pub enum ProtocolId {
     HealthCheckerRpc = 5,
     // json provides flexibility for backwards compatible upgrade
     ConsensusDirectSendJSON = 6,
+    // lz4 compressed bcs, for the protocols carrying large and highly compressible messages
+    MempoolDirectSendCompressed = 7,
+    StateSyncDirectSendCompressed = 8,
+    PeerExchangeRpc = 9,
+}
+
+/// The encoding of the messages of a [`ProtocolId`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Bcs,
+    CompressedBcs,
+    Json,
 }
 
 impl ProtocolId {
@@ -55,6 +73,9 @@ impl ProtocolId {
             DiscoveryDirectSend => "DiscoveryDirectSend",
             HealthCheckerRpc => "HealthCheckerRpc",
             ConsensusDirectSendJSON => "ConsensusDirectSendCbor",
+            MempoolDirectSendCompressed => "MempoolDirectSendCompressed",
+            StateSyncDirectSendCompressed => "StateSyncDirectSendCompressed",
+            PeerExchangeRpc => "PeerExchangeRpc",
         }
     }
 
@@ -67,26 +88,82 @@ impl ProtocolId {
             ProtocolId::DiscoveryDirectSend,
             ProtocolId::HealthCheckerRpc,
             ProtocolId::ConsensusDirectSendJSON,
+            ProtocolId::MempoolDirectSendCompressed,
+            ProtocolId::StateSyncDirectSendCompressed,
+            ProtocolId::PeerExchangeRpc,
         ]
     }
 
-    pub fn to_bytes<T: Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+    pub fn encoding(self) -> Encoding {
         match self {
-            ProtocolId::ConsensusDirectSendJSON => {
-                serde_json::to_vec(value).map_err(|e| anyhow!("{:?}", e))
+            ProtocolId::ConsensusDirectSendJSON => Encoding::Json,
+            ProtocolId::MempoolDirectSendCompressed | ProtocolId::StateSyncDirectSendCompressed => {
+                Encoding::CompressedBcs
             }
-            _ => bcs::to_bytes(value).map_err(|e| anyhow! {"{:?}", e}),
+            _ => Encoding::Bcs,
         }
     }
 
-    pub fn from_bytes<'a, T: Deserialize<'a>>(&self, bytes: &'a [u8]) -> anyhow::Result<T> {
-        match self {
-            ProtocolId::ConsensusDirectSendJSON => {
-                serde_json::from_slice(bytes).map_err(|e| anyhow!("{:?}", e))
+    pub fn to_bytes<T: Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+        match self.encoding() {
+            Encoding::Bcs => bcs::to_bytes(value).map_err(|e| anyhow! {"{:?}", e}),
+            Encoding::CompressedBcs => {
+                let bytes = bcs::to_bytes(value).map_err(|e| anyhow! {"{:?}", e})?;
+                Ok(self.compress(&bytes))
             }
-            _ => bcs::from_bytes(bytes).map_err(|e| anyhow! {"{:?}", e}),
+            Encoding::Json => serde_json::to_vec(value).map_err(|e| anyhow!("{:?}", e)),
         }
     }
+
+    pub fn from_bytes<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<T> {
+        match self.encoding() {
+            Encoding::Bcs => bcs::from_bytes(bytes).map_err(|e| anyhow! {"{:?}", e}),
+            Encoding::CompressedBcs => {
+                let bytes = self.decompress(bytes)?;
+                bcs::from_bytes(&bytes).map_err(|e| anyhow! {"{:?}", e})
+            }
+            Encoding::Json => serde_json::from_slice(bytes).map_err(|e| anyhow!("{:?}", e)),
+        }
+    }
+
+    /// Compresses `bytes` with lz4, prefixed by their length as a little endian u32.
+    fn compress(self, bytes: &[u8]) -> Vec<u8> {
+        let compressed = lz4_flex::compress_prepend_size(bytes);
+        counters::compression_bytes(
+            self,
+            counters::COMPRESS_LABEL,
+            bytes.len(),
+            compressed.len(),
+        );
+        compressed
+    }
+
+    /// Inverse of [`ProtocolId::compress`]. The decompressed size is checked up-front to bound
+    /// the memory a malicious peer can make us allocate.
+    fn decompress(self, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        ensure!(
+            bytes.len() >= 4,
+            "compressed message is missing its size prefix"
+        );
+        let (size, compressed) = bytes.split_at(4);
+        let size = u32::from_le_bytes(<[u8; 4]>::try_from(size)?) as usize;
+        ensure!(
+            size <= MAX_DECOMPRESSED_MESSAGE_SIZE,
+            "decompressed message size {} exceeds limit {}",
+            size,
+            MAX_DECOMPRESSED_MESSAGE_SIZE
+        );
+        let decompressed = lz4_flex::decompress(compressed, size)
+            .map_err(|e| anyhow!("failed to decompress message: {}", e))?;
+        ensure!(
+            decompressed.len() == size,
+            "decompressed message size {} doesn't match its size prefix {}",
+            decompressed.len(),
+            size
+        );
+        counters::compression_bytes(self, counters::DECOMPRESS_LABEL, size, bytes.len());
+        Ok(decompressed)
+    }
 }
 
 impl fmt::Debug for ProtocolId {
@@ -115,13 +192,12 @@ impl TryInto<Vec<ProtocolId>> for SupportedProtocols {
 
     fn try_into(self) -> bcs::Result<Vec<ProtocolId>> {
         let mut protocols = Vec::with_capacity(self.0.count_ones() as usize);
-        if let Some(last_bit) = self.0.last_set_bit() {
-            for i in 0..=last_bit {
-                if self.0.is_set(i) {
-                    let protocol: ProtocolId = bcs::from_bytes(&[i])?;
-                    protocols.push(protocol);
-                }
-            }
+        for i in self.0.iter_ones() {
+            // A ProtocolId is encoded as a single byte
+            let i = u8::try_from(i)
+                .map_err(|_| bcs::Error::Custom(format!("Unknown ProtocolId: {}", i)))?;
+            let protocol: ProtocolId = bcs::from_bytes(&[i])?;
+            protocols.push(protocol);
         }
         Ok(protocols)
     }
@@ -130,7 +206,7 @@ impl TryInto<Vec<ProtocolId>> for SupportedProtocols {
 impl<'a, T: Iterator<Item = &'a ProtocolId>> From<T> for SupportedProtocols {
     fn from(protocols: T) -> Self {
         let mut bv = bitvec::BitVec::default();
-        protocols.for_each(|p| bv.set(*p as u8));
+        protocols.for_each(|p| bv.set(*p as u16));
         Self(bv)
     }
 }
@@ -143,7 +219,15 @@ impl SupportedProtocols {
 
     /// Returns if the protocol is set.
     pub fn contains(&self, protocol: ProtocolId) -> bool {
-        self.0.is_set(protocol as u8)
+        self.0.is_set(protocol as u16)
+    }
+
+    /// Returns the first of `protocols`, sorted from most to least preferred, which is set.
+    pub fn preferred(&self, protocols: &[ProtocolId]) -> Option<ProtocolId> {
+        protocols
+            .iter()
+            .copied()
+            .find(|protocol| self.contains(*protocol))
     }
 }
 
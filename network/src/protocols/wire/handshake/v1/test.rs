@@ -27,6 +27,17 @@ fn protocols_to_from_vec() {
     );
 }
 
+#[test]
+fn protocols_out_of_range() {
+    // Positions beyond u8::MAX can't be a ProtocolId
+    let mut bv = bitvec::BitVec::default();
+    bv.set(ProtocolId::ConsensusRpc as u16);
+    bv.set(300);
+    let supported_protocols = SupportedProtocols(bv);
+    assert!(supported_protocols.contains(ProtocolId::ConsensusRpc));
+    assert!((supported_protocols.try_into() as Result<Vec<ProtocolId>, _>).is_err());
+}
+
 #[test]
 fn represents_same_network() {
     let mut handshake_msg = HandshakeMsg::new_for_testing();
@@ -115,3 +126,54 @@ fn common_protocols() {
         h1.perform_handshake(&h2).unwrap()
     );
 }
+
+#[test]
+fn compressed_protocols_roundtrip() {
+    let message = vec![7u8; 10_000];
+    for protocol in ProtocolId::all() {
+        let bytes = protocol.to_bytes(&message).unwrap();
+        if protocol.encoding() == Encoding::CompressedBcs {
+            assert!(bytes.len() < message.len());
+        }
+        let decoded: Vec<u8> = protocol.from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, message);
+    }
+}
+
+#[test]
+fn compressed_protocols_reject_oversized_messages() {
+    let protocol = ProtocolId::StateSyncDirectSendCompressed;
+    // empty
+    protocol.from_bytes::<Vec<u8>>(&[]).unwrap_err();
+    // size prefix larger than the limit
+    let mut bytes = ((MAX_DECOMPRESSED_MESSAGE_SIZE + 1) as u32)
+        .to_le_bytes()
+        .to_vec();
+    bytes.extend_from_slice(&[0u8; 16]);
+    protocol.from_bytes::<Vec<u8>>(&bytes).unwrap_err();
+    // size prefix not matching the compressed data
+    let mut bytes = protocol.to_bytes(&vec![7u8; 100]).unwrap();
+    bytes[..4].copy_from_slice(&1000u32.to_le_bytes());
+    protocol.from_bytes::<Vec<u8>>(&bytes).unwrap_err();
+}
+
+#[test]
+fn preferred_protocol() {
+    let supported_protocols: SupportedProtocols = [
+        ProtocolId::MempoolDirectSend,
+        ProtocolId::StateSyncDirectSend,
+    ]
+    .iter()
+    .into();
+    assert_eq!(
+        supported_protocols.preferred(&[
+            ProtocolId::StateSyncDirectSendCompressed,
+            ProtocolId::StateSyncDirectSend
+        ]),
+        Some(ProtocolId::StateSyncDirectSend)
+    );
+    assert_eq!(
+        supported_protocols.preferred(&[ProtocolId::MempoolDirectSendCompressed]),
+        None
+    );
+}
@@ -43,6 +43,8 @@ pub enum NetworkMessage {
     RpcRequest(RpcRequest),
     RpcResponse(RpcResponse),
     DirectSendMsg(DirectSendMsg),
+    /// Part of a message too large to fit in a single frame.
+    Stream(StreamMessage),
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -123,6 +125,71 @@ pub struct DirectSendMsg {
     pub raw_msg: Vec<u8>,
 }
 
+/// Create alias StreamId for u32.
+pub type StreamId = u32;
+
+/// A message too large to fit in a single frame is sent as a [`StreamHeader`]
+/// followed by `num_fragments` [`StreamFragment`]s, all written back to back.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub enum StreamMessage {
+    Header(StreamHeader),
+    Fragment(StreamFragment),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct StreamHeader {
+    /// StreamId shared by the header and all fragments of the stream.
+    pub stream_id: StreamId,
+    /// Number of fragments following this header.
+    pub num_fragments: u8,
+    /// The streamed message, holding only the first part of its payload.
+    pub message: StreamedMessage,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct StreamFragment {
+    /// StreamId of the corresponding header.
+    pub stream_id: StreamId,
+    /// Index of the fragment in the stream, starting at 1.
+    pub fragment_id: u8,
+    /// Next part of the payload of the streamed message.
+    #[serde(with = "serde_bytes")]
+    pub raw_data: Vec<u8>,
+}
+
+/// The kinds of [`NetworkMessage`] which can be streamed.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub enum StreamedMessage {
+    RpcRequest(RpcRequest),
+    RpcResponse(RpcResponse),
+    DirectSendMsg(DirectSendMsg),
+}
+
+impl StreamedMessage {
+    /// The application payload of the message.
+    pub fn payload_mut(&mut self) -> &mut Vec<u8> {
+        match self {
+            StreamedMessage::RpcRequest(request) => &mut request.raw_request,
+            StreamedMessage::RpcResponse(response) => &mut response.raw_response,
+            StreamedMessage::DirectSendMsg(message) => &mut message.raw_msg,
+        }
+    }
+}
+
+impl From<StreamedMessage> for NetworkMessage {
+    fn from(message: StreamedMessage) -> Self {
+        match message {
+            StreamedMessage::RpcRequest(request) => NetworkMessage::RpcRequest(request),
+            StreamedMessage::RpcResponse(response) => NetworkMessage::RpcResponse(response),
+            StreamedMessage::DirectSendMsg(message) => NetworkMessage::DirectSendMsg(message),
+        }
+    }
+}
+
 /// Errors from reading and deserializing network messages off the wire.
 #[derive(Debug, Error)]
 pub enum ReadError {
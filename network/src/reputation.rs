@@ -0,0 +1,392 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reputation of the remote peers, and temporary bans of the misbehaving ones.
+//!
+//! Applications detect misbehaving peers (e.g. invalid state sync chunks, unacknowledged mempool
+//! broadcasts or consensus messages with invalid signatures), and report them through their
+//! `NetworkSender`. Each report adds a penalty to the peer's score, and once the penalties
+//! received within `penalty_window` reach `ban_threshold`, the peer is banned for `ban_duration`:
+//! its connection is closed, it isn't dialed, and its inbound connections are refused.
+//!
+//! As peer ids are cheap to generate outside of the validator network, the IP address the peer
+//! was connected from can be banned along with it. Unspecified and loopback addresses are never
+//! banned, as they are shared by every peer connecting through a local proxy.
+//!
+//! Peers are never banned on the validator network: they are the validators of the current epoch,
+//! and banning one, e.g. after a bug or a key rotation made its messages fail verification, would
+//! reduce the voting power available to consensus. Their misbehavior is only counted.
+
+use crate::{counters, logging::NetworkSchema};
+use dijets_config::{config::ReputationConfig, network_id::NetworkContext};
+use dijets_infallible::Mutex;
+use dijets_logger::prelude::*;
+use dijets_time_service::{TimeService, TimeServiceTrait};
+use dijets_types::PeerId;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    net::IpAddr,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+
+/// The reputations of all the networks of this node, for the admin view of the ban lists.
+static REPUTATIONS: Lazy<Mutex<Vec<Weak<PeerReputation>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Misbehavior of a remote peer, detected by an application.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Misbehavior {
+    /// The peer sent a message which failed validation, e.g. an invalid state sync chunk.
+    InvalidMessage,
+    /// The peer sent a message with an invalid signature.
+    InvalidSignature,
+    /// The peer didn't respond to a request in time.
+    Timeout,
+}
+
+impl Misbehavior {
+    /// The penalty added to the score of a peer for this misbehavior. Timeouts are also caused by
+    /// slow or overloaded peers, so it takes many of them to get a peer banned.
+    pub fn penalty(self) -> u64 {
+        match self {
+            Misbehavior::InvalidMessage => 25,
+            Misbehavior::InvalidSignature => 50,
+            Misbehavior::Timeout => 1,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Misbehavior::InvalidMessage => "invalid_message",
+            Misbehavior::InvalidSignature => "invalid_signature",
+            Misbehavior::Timeout => "timeout",
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A banned peer, as shown in the admin view of the ban list.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BannedPeer {
+    pub peer_id: PeerId,
+    /// The IP address banned along with the peer, if any.
+    pub ip_addr: Option<IpAddr>,
+    /// The misbehavior which got the peer banned.
+    pub reason: Misbehavior,
+    /// Seconds until the ban is lifted.
+    pub remaining_secs: u64,
+}
+
+struct Ban {
+    ip_addr: Option<IpAddr>,
+    reason: Misbehavior,
+    until: Instant,
+}
+
+#[derive(Default)]
+struct ReputationState {
+    /// The penalties received within the penalty window, by peer.
+    penalties: HashMap<PeerId, Vec<(Instant, u64)>>,
+    banned_peers: HashMap<PeerId, Ban>,
+    banned_ips: HashMap<IpAddr, Instant>,
+}
+
+/// The reputation of the peers of a network, shared between the applications reporting
+/// misbehavior, the PeerManager and the transport.
+pub struct PeerReputation {
+    network_context: Arc<NetworkContext>,
+    time_service: TimeService,
+    /// The configuration, misbehaving peers are never banned if not specified, nor on the
+    /// validator network.
+    config: Option<ReputationConfig>,
+    state: Mutex<ReputationState>,
+}
+
+impl PeerReputation {
+    pub fn new(
+        network_context: Arc<NetworkContext>,
+        time_service: TimeService,
+        config: Option<ReputationConfig>,
+    ) -> Arc<Self> {
+        let reputation = Arc::new(Self {
+            network_context,
+            time_service,
+            config,
+            state: Mutex::new(ReputationState::default()),
+        });
+        let mut reputations = REPUTATIONS.lock();
+        reputations.retain(|reputation| reputation.strong_count() > 0);
+        reputations.push(Arc::downgrade(&reputation));
+        reputation
+    }
+
+    /// Records a misbehavior of `peer_id`, connected from `ip_addr`. Returns whether the peer got
+    /// banned, in which case its connection should be closed.
+    pub fn report(
+        &self,
+        peer_id: PeerId,
+        ip_addr: Option<IpAddr>,
+        misbehavior: Misbehavior,
+    ) -> bool {
+        counters::misbehavior_reports(&self.network_context, misbehavior.as_str()).inc();
+        let config = match self.config {
+            Some(config) if !self.network_context.network_id().is_validator_network() => config,
+            _ => return false,
+        };
+
+        let now = self.time_service.now();
+        let penalty_window = Duration::from_millis(config.penalty_window_ms);
+        let mut state = self.state.lock();
+        if matches!(state.banned_peers.get(&peer_id), Some(ban) if ban.until > now) {
+            return false;
+        }
+
+        // Forget the penalties which are out of the window, for every peer
+        state.penalties.retain(|_, penalties| {
+            penalties.retain(|(at, _)| now.saturating_duration_since(*at) < penalty_window);
+            !penalties.is_empty()
+        });
+        let penalties = state.penalties.entry(peer_id).or_insert_with(Vec::new);
+        penalties.push((now, misbehavior.penalty()));
+        let score: u64 = penalties.iter().map(|(_, penalty)| penalty).sum();
+        if score < config.ban_threshold {
+            return false;
+        }
+
+        state.penalties.remove(&peer_id);
+        let ip_addr = ip_addr.filter(|ip_addr| {
+            config.ban_ips && !ip_addr.is_unspecified() && !ip_addr.is_loopback()
+        });
+        let until = now + Duration::from_millis(config.ban_duration_ms);
+        if let Some(ip_addr) = ip_addr {
+            state.banned_ips.insert(ip_addr, until);
+        }
+        state.banned_peers.insert(
+            peer_id,
+            Ban {
+                ip_addr,
+                reason: misbehavior,
+                until,
+            },
+        );
+        counters::peer_bans(&self.network_context, misbehavior.as_str()).inc();
+        warn!(
+            NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+            "{} Banned peer {} at {:?} for {} ms after {}",
+            self.network_context,
+            peer_id,
+            ip_addr,
+            config.ban_duration_ms,
+            misbehavior
+        );
+        true
+    }
+
+    /// Returns whether `peer_id` is currently banned.
+    pub fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
+        if self.config.is_none() {
+            return false;
+        }
+        let now = self.time_service.now();
+        let mut state = self.state.lock();
+        match state.banned_peers.get(peer_id) {
+            Some(ban) if ban.until > now => true,
+            Some(_) => {
+                state.banned_peers.remove(peer_id);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether connections from `ip_addr` are currently refused.
+    pub fn is_ip_banned(&self, ip_addr: &IpAddr) -> bool {
+        if self.config.is_none() {
+            return false;
+        }
+        let now = self.time_service.now();
+        let mut state = self.state.lock();
+        match state.banned_ips.get(ip_addr) {
+            Some(until) if *until > now => true,
+            Some(_) => {
+                state.banned_ips.remove(ip_addr);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the peers currently banned.
+    pub fn banned_peers(&self) -> Vec<BannedPeer> {
+        let now = self.time_service.now();
+        let mut state = self.state.lock();
+        state.banned_peers.retain(|_, ban| ban.until > now);
+        state.banned_ips.retain(|_, until| *until > now);
+        state
+            .banned_peers
+            .iter()
+            .map(|(peer_id, ban)| BannedPeer {
+                peer_id: *peer_id,
+                ip_addr: ban.ip_addr,
+                reason: ban.reason,
+                remaining_secs: ban.until.saturating_duration_since(now).as_secs(),
+            })
+            .collect()
+    }
+}
+
+/// Returns the peers currently banned by each network of this node, for the admin view.
+pub fn banned_peers() -> BTreeMap<String, Vec<BannedPeer>> {
+    REPUTATIONS
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .map(|reputation| {
+            (
+                reputation.network_context.to_string(),
+                reputation.banned_peers(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dijets_config::{config::RoleType, network_id::NetworkId};
+
+    const PENALTY_WINDOW: Duration = Duration::from_secs(60);
+    const BAN_DURATION: Duration = Duration::from_secs(600);
+
+    fn test_reputation(ban_ips: bool) -> (Arc<PeerReputation>, TimeService) {
+        let time_service = TimeService::mock();
+        let config = ReputationConfig {
+            ban_threshold: 50,
+            penalty_window_ms: PENALTY_WINDOW.as_millis() as u64,
+            ban_duration_ms: BAN_DURATION.as_millis() as u64,
+            ban_ips,
+        };
+        let reputation =
+            PeerReputation::new(public_network_context(), time_service.clone(), Some(config));
+        (reputation, time_service)
+    }
+
+    fn public_network_context() -> Arc<NetworkContext> {
+        Arc::new(NetworkContext::new(
+            RoleType::FullNode,
+            NetworkId::Public,
+            PeerId::random(),
+        ))
+    }
+
+    #[test]
+    fn test_ban_after_threshold() {
+        let (reputation, _) = test_reputation(true);
+        let peer_id = PeerId::random();
+        let ip_addr: IpAddr = "1.2.3.4".parse().unwrap();
+
+        assert!(!reputation.report(peer_id, Some(ip_addr), Misbehavior::InvalidMessage));
+        assert!(!reputation.is_peer_banned(&peer_id));
+        assert!(reputation.report(peer_id, Some(ip_addr), Misbehavior::InvalidMessage));
+        assert!(reputation.is_peer_banned(&peer_id));
+        assert!(reputation.is_ip_banned(&ip_addr));
+
+        // Other peers aren't affected
+        assert!(!reputation.is_peer_banned(&PeerId::random()));
+        assert!(!reputation.is_ip_banned(&"1.2.3.5".parse().unwrap()));
+
+        assert_eq!(
+            reputation.banned_peers(),
+            vec![BannedPeer {
+                peer_id,
+                ip_addr: Some(ip_addr),
+                reason: Misbehavior::InvalidMessage,
+                remaining_secs: BAN_DURATION.as_secs(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_penalties_expire() {
+        let (reputation, time_service) = test_reputation(true);
+        let mock_time = time_service.into_mock();
+        let peer_id = PeerId::random();
+
+        assert!(!reputation.report(peer_id, None, Misbehavior::InvalidMessage));
+        mock_time.advance(PENALTY_WINDOW);
+        assert!(!reputation.report(peer_id, None, Misbehavior::InvalidMessage));
+        assert!(!reputation.is_peer_banned(&peer_id));
+    }
+
+    #[test]
+    fn test_ban_expires() {
+        let (reputation, time_service) = test_reputation(true);
+        let mock_time = time_service.into_mock();
+        let peer_id = PeerId::random();
+        let ip_addr: IpAddr = "1.2.3.4".parse().unwrap();
+
+        assert!(reputation.report(peer_id, Some(ip_addr), Misbehavior::InvalidSignature));
+        mock_time.advance(BAN_DURATION);
+        assert!(!reputation.is_peer_banned(&peer_id));
+        assert!(!reputation.is_ip_banned(&ip_addr));
+        assert!(reputation.banned_peers().is_empty());
+    }
+
+    #[test]
+    fn test_ip_bans() {
+        // Loopback addresses are never banned
+        let (reputation, _) = test_reputation(true);
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!(reputation.report(
+            PeerId::random(),
+            Some(loopback),
+            Misbehavior::InvalidSignature
+        ));
+        assert!(!reputation.is_ip_banned(&loopback));
+
+        // Nor any address when disabled
+        let (reputation, _) = test_reputation(false);
+        let ip_addr: IpAddr = "1.2.3.4".parse().unwrap();
+        assert!(reputation.report(
+            PeerId::random(),
+            Some(ip_addr),
+            Misbehavior::InvalidSignature
+        ));
+        assert!(!reputation.is_ip_banned(&ip_addr));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let reputation = PeerReputation::new(public_network_context(), TimeService::mock(), None);
+        let peer_id = PeerId::random();
+        for _ in 0..10 {
+            assert!(!reputation.report(peer_id, None, Misbehavior::InvalidSignature));
+        }
+        assert!(!reputation.is_peer_banned(&peer_id));
+    }
+
+    #[test]
+    fn test_validator_network() {
+        let reputation = PeerReputation::new(
+            NetworkContext::mock(),
+            TimeService::mock(),
+            Some(ReputationConfig::default()),
+        );
+        let peer_id = PeerId::random();
+        let ip_addr: IpAddr = "1.2.3.4".parse().unwrap();
+        for _ in 0..10 {
+            assert!(!reputation.report(peer_id, Some(ip_addr), Misbehavior::InvalidSignature));
+        }
+        assert!(!reputation.is_peer_banned(&peer_id));
+        assert!(!reputation.is_ip_banned(&ip_addr));
+    }
+}
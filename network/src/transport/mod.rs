@@ -8,9 +8,10 @@ use crate::{
         identity::exchange_handshake,
         wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, SupportedProtocols},
     },
+    reputation::PeerReputation,
 };
 use dijets_config::{
-    config::{PeerRole, HANDSHAKE_VERSION},
+    config::{NoiseRekeyConfig, PeerRole, HANDSHAKE_VERSION},
     network_id::{NetworkContext, NetworkId},
 };
 use dijets_crypto::x25519;
@@ -26,7 +27,10 @@ use futures::{
     io::{AsyncRead, AsyncWrite},
     stream::{Stream, StreamExt, TryStreamExt},
 };
-use netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use netcore::transport::{
+    proxy_protocol::{self, ProxyTlvs},
+    tcp, ConnectionOrigin, Transport,
+};
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
 use std::{
@@ -105,6 +109,8 @@ pub struct ConnectionMetadata {
     pub messaging_protocol: MessagingProtocolVersion,
     pub application_protocols: SupportedProtocols,
     pub role: PeerRole,
+    /// TLVs of the proxy protocol header the connection was received with, if any.
+    pub proxy_tlvs: ProxyTlvs,
 }
 
 impl ConnectionMetadata {
@@ -125,6 +131,7 @@ impl ConnectionMetadata {
             messaging_protocol,
             application_protocols,
             role,
+            proxy_tlvs: ProxyTlvs::default(),
         }
     }
 
@@ -151,6 +158,7 @@ impl ConnectionMetadata {
             addr: NetworkAddress::mock(),
             messaging_protocol: MessagingProtocolVersion::V1,
             application_protocols: [].iter().into(),
+            proxy_tlvs: ProxyTlvs::default(),
         }
     }
 }
@@ -239,20 +247,21 @@ fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAdd
 /// authentication and then negotiate common supported protocols. If
 /// `ctxt.trusted_peers` is `Some(_)`, then we will only allow connections from
 /// peers with a pubkey in this set. Otherwise, we will allow inbound connections
-/// from any pubkey.
+/// from any pubkey. Connections from peers or addresses banned in `reputation` are refused.
 async fn upgrade_inbound<T: TSocket>(
     ctxt: Arc<UpgradeContext>,
     fut_socket: impl Future<Output = io::Result<T>>,
     addr: NetworkAddress,
     proxy_protocol_enabled: bool,
+    reputation: Option<Arc<PeerReputation>>,
 ) -> io::Result<Connection<NoiseStream<T>>> {
     let origin = ConnectionOrigin::Inbound;
     let mut socket = fut_socket.await?;
 
     // If we have proxy protocol enabled, process the event, otherwise skip it
     // TODO: This would make more sense to build this in at instantiation so we don't need to put the if statement here
-    let addr = if proxy_protocol_enabled {
-        proxy_protocol::read_header(&addr, &mut socket)
+    let (addr, proxy_tlvs) = if proxy_protocol_enabled {
+        let header = proxy_protocol::read_header(&addr, &mut socket)
             .await
             .map_err(|err| {
                 debug!(
@@ -262,11 +271,22 @@ async fn upgrade_inbound<T: TSocket>(
                     err
                 );
                 err
-            })?
+            })?;
+        (header.source_addr, header.tlvs)
     } else {
-        addr
+        (addr, ProxyTlvs::default())
     };
 
+    // refuse banned addresses before spending any effort on the handshake
+    if let (Some(reputation), Some(ip_addr)) = (&reputation, addr.find_ip_addr()) {
+        if reputation.is_ip_banned(&ip_addr) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("inbound connection from banned address: {}", addr),
+            ));
+        }
+    }
+
     // try authenticating via noise handshake
     let (mut socket, remote_peer_id, peer_role) =
         ctxt.noise.upgrade_inbound(socket).await.map_err(|err| {
@@ -288,6 +308,22 @@ async fn upgrade_inbound<T: TSocket>(
     let remote_pubkey = socket.get_remote_static();
     let addr = addr.append_prod_protos(remote_pubkey, HANDSHAKE_VERSION);
 
+    if let Some(reputation) = &reputation {
+        if reputation.is_peer_banned(&remote_peer_id) {
+            return Err(add_pp_addr(
+                proxy_protocol_enabled,
+                io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "inbound connection from banned peer: {}",
+                        remote_peer_id.short_str()
+                    ),
+                ),
+                &addr,
+            ));
+        }
+    }
+
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
         supported_protocols: ctxt.supported_protocols.clone(),
@@ -315,18 +351,17 @@ async fn upgrade_inbound<T: TSocket>(
         })?;
 
     // return successful connection
-    Ok(Connection {
-        socket,
-        metadata: ConnectionMetadata::new(
-            remote_peer_id,
-            CONNECTION_ID_GENERATOR.next(),
-            addr,
-            origin,
-            messaging_protocol,
-            application_protocols,
-            peer_role,
-        ),
-    })
+    let mut metadata = ConnectionMetadata::new(
+        remote_peer_id,
+        CONNECTION_ID_GENERATOR.next(),
+        addr,
+        origin,
+        messaging_protocol,
+        application_protocols,
+        peer_role,
+    );
+    metadata.proxy_tlvs = proxy_tlvs;
+    Ok(Connection { socket, metadata })
 }
 
 /// Upgrade an inbound connection. This means we run a Noise IK handshake for
@@ -417,6 +452,7 @@ pub struct DijetsNetTransport<TTransport> {
     time_service: TimeService,
     identity_pubkey: x25519::PublicKey,
     enable_proxy_protocol: bool,
+    reputation: Option<Arc<PeerReputation>>,
 }
 
 impl<TTransport> DijetsNetTransport<TTransport>
@@ -437,6 +473,7 @@ where
         chain_id: ChainId,
         application_protocols: SupportedProtocols,
         enable_proxy_protocol: bool,
+        noise_rekey_config: Option<NoiseRekeyConfig>,
     ) -> Self {
         // build supported protocols
         let mut supported_protocols = BTreeMap::new();
@@ -446,7 +483,8 @@ where
         let network_id = network_context.network_id().clone();
 
         let upgrade_context = UpgradeContext::new(
-            NoiseUpgrader::new(network_context, identity_key, auth_mode),
+            NoiseUpgrader::new(network_context, identity_key, auth_mode)
+                .with_rekey_config(noise_rekey_config),
             handshake_version,
             supported_protocols,
             chain_id,
@@ -459,9 +497,16 @@ where
             time_service,
             identity_pubkey,
             enable_proxy_protocol,
+            reputation: None,
         }
     }
 
+    /// Refuse inbound connections from the peers and addresses banned in `reputation`.
+    pub fn with_reputation(mut self, reputation: Arc<PeerReputation>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
     fn parse_dial_addr(
         addr: &NetworkAddress,
     ) -> io::Result<(NetworkAddress, x25519::PublicKey, u8)> {
@@ -603,6 +648,7 @@ where
         let ctxt = self.ctxt.clone();
         let time_service = self.time_service.clone();
         let enable_proxy_protocol = self.enable_proxy_protocol;
+        let reputation = self.reputation.clone();
         // stream of inbound upgrade tasks
         let inbounds = listener.map_ok(move |(fut_socket, addr)| {
             // inbound upgrade task
@@ -611,6 +657,7 @@ where
                 fut_socket,
                 addr.clone(),
                 enable_proxy_protocol,
+                reputation.clone(),
             );
             let fut_upgrade = timeout_io(time_service.clone(), TRANSPORT_TIMEOUT, fut_upgrade);
             (fut_upgrade, addr)
@@ -157,6 +157,7 @@ where
         chain_id,
         supported_protocols.clone(),
         false, /* Disable proxy protocol */
+        None,
     );
 
     let dialer_transport = DijetsNetTransport::new(
@@ -169,6 +170,7 @@ where
         chain_id,
         supported_protocols.clone(),
         false, /* Disable proxy protocol */
+        None,
     );
 
     (
@@ -13,8 +13,8 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MetadataView, SimulatedTransactionView, StateProofView,
+        TransactionView, TransactionWithProofView, TransactionsWithProofsView,
     },
     Error, Result, Retry, State,
 };
@@ -195,6 +195,14 @@ impl BlockingClient {
         ))
     }
 
+    pub fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        self.send(MethodRequest::get_transaction_by_hash(hash, include_events))
+    }
+
     pub fn get_events(
         &self,
         key: EventKey,
@@ -274,6 +282,19 @@ impl BlockingClient {
         ))
     }
 
+    pub fn get_transaction_by_hash_with_proof(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+        ledger_version: Option<u64>,
+    ) -> Result<Response<Option<TransactionWithProofView>>> {
+        self.send(MethodRequest::get_transaction_by_hash_with_proof(
+            hash,
+            include_events,
+            ledger_version,
+        ))
+    }
+
     pub fn get_events_with_proofs(
         &self,
         key: EventKey,
@@ -291,6 +312,16 @@ impl BlockingClient {
         self.send(MethodRequest::get_event_by_version_with_proof(key, version))
     }
 
+    /// Executes `txn` against the latest ledger state without committing it. The signature of
+    /// `txn` is not checked, so this can be used to estimate gas or catch aborts before
+    /// submitting.
+    pub fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<Response<SimulatedTransactionView>> {
+        self.send(MethodRequest::simulate_transaction(txn).map_err(Error::request)?)
+    }
+
     /// Return the events of type `T` that have been emitted to `event_key` since `start_seq`, with a max of `limit`
     /// results
     /// Returns an empty vector if there are no such event
@@ -13,8 +13,8 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MetadataView, SimulatedTransactionView, StateProofView,
+        TransactionView, TransactionWithProofView, TransactionsWithProofsView,
     },
     Error, Result, Retry, State,
 };
@@ -210,6 +210,15 @@ impl Client {
         .await
     }
 
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        self.send(MethodRequest::get_transaction_by_hash(hash, include_events))
+            .await
+    }
+
     pub async fn get_events(
         &self,
         key: EventKey,
@@ -295,6 +304,20 @@ impl Client {
         .await
     }
 
+    pub async fn get_transaction_by_hash_with_proof(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+        ledger_version: Option<u64>,
+    ) -> Result<Response<Option<TransactionWithProofView>>> {
+        self.send(MethodRequest::get_transaction_by_hash_with_proof(
+            hash,
+            include_events,
+            ledger_version,
+        ))
+        .await
+    }
+
     pub async fn get_events_with_proofs(
         &self,
         key: EventKey,
@@ -314,6 +337,17 @@ impl Client {
             .await
     }
 
+    /// Executes `txn` against the latest ledger state without committing it. The signature of
+    /// `txn` is not checked, so this can be used to estimate gas or catch aborts before
+    /// submitting.
+    pub async fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<Response<SimulatedTransactionView>> {
+        self.send(MethodRequest::simulate_transaction(txn).map_err(Error::request)?)
+            .await
+    }
+
     /// Return the events of type `T` that have been emitted to `event_key` since `start_seq`, with a max of `limit`
     /// results
     /// Returns an empty vector if there are no such events
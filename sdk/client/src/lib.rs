@@ -74,6 +74,7 @@ pub enum Method {
     GetTransactions,
     GetAccountTransaction,
     GetAccountTransactions,
+    GetTransactionByHash,
     GetEvents,
     GetCurrencies,
     GetNetworkStatus,
@@ -86,8 +87,10 @@ pub enum Method {
     GetAccountStateWithProof,
     GetTransactionsWithProofs,
     GetAccountTransactionsWithProofs,
+    GetTransactionByHashWithProof,
     GetEventsWithProofs,
     GetEventByVersionWithProof,
+    SimulateTransaction,
 }
 
 cfg_async_or_blocking! {
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{JsonRpcVersion, Method};
+use dijets_crypto::HashValue;
 use dijets_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
@@ -18,6 +19,7 @@ pub enum MethodRequest {
     GetTransactions(u64, u64, bool),
     GetAccountTransaction(AccountAddress, u64, bool),
     GetAccountTransactions(AccountAddress, u64, u64, bool),
+    GetTransactionByHash(HashValue, bool),
     GetEvents(EventKey, u64, u64),
     GetCurrencies([(); 0]),
     GetNetworkStatus([(); 0]),
@@ -30,8 +32,10 @@ pub enum MethodRequest {
     GetAccountStateWithProof(AccountAddress, Option<u64>, Option<u64>),
     GetTransactionsWithProofs(u64, u64, bool),
     GetAccountTransactionsWithProofs(AccountAddress, u64, u64, bool, Option<u64>),
+    GetTransactionByHashWithProof(HashValue, bool, Option<u64>),
     GetEventsWithProofs(EventKey, u64, u64),
     GetEventByVersionWithProof(EventKey, Option<u64>),
+    SimulateTransaction((String,)),
 }
 
 impl MethodRequest {
@@ -77,6 +81,10 @@ impl MethodRequest {
         Self::GetAccountTransactions(address, start_seq, limit, include_events)
     }
 
+    pub fn get_transaction_by_hash(hash: HashValue, include_events: bool) -> Self {
+        Self::GetTransactionByHash(hash, include_events)
+    }
+
     pub fn get_events(key: EventKey, start_seq: u64, limit: u64) -> Self {
         Self::GetEvents(key, start_seq, limit)
     }
@@ -136,6 +144,14 @@ impl MethodRequest {
         )
     }
 
+    pub fn get_transaction_by_hash_with_proof(
+        hash: HashValue,
+        include_events: bool,
+        ledger_version: Option<u64>,
+    ) -> Self {
+        Self::GetTransactionByHashWithProof(hash, include_events, ledger_version)
+    }
+
     pub fn get_events_with_proofs(key: EventKey, start_seq: u64, limit: u64) -> Self {
         Self::GetEventsWithProofs(key, start_seq, limit)
     }
@@ -144,6 +160,11 @@ impl MethodRequest {
         Self::GetEventByVersionWithProof(key, version)
     }
 
+    pub fn simulate_transaction(txn: &SignedTransaction) -> Result<Self, bcs::Error> {
+        let txn_payload = hex::encode(bcs::to_bytes(txn)?);
+        Ok(Self::SimulateTransaction((txn_payload,)))
+    }
+
     pub fn method(&self) -> Method {
         match self {
             MethodRequest::Submit(_) => Method::Submit,
@@ -152,6 +173,7 @@ impl MethodRequest {
             MethodRequest::GetTransactions(_, _, _) => Method::GetTransactions,
             MethodRequest::GetAccountTransaction(_, _, _) => Method::GetAccountTransaction,
             MethodRequest::GetAccountTransactions(_, _, _, _) => Method::GetAccountTransactions,
+            MethodRequest::GetTransactionByHash(_, _) => Method::GetTransactionByHash,
             MethodRequest::GetEvents(_, _, _) => Method::GetEvents,
             MethodRequest::GetCurrencies(_) => Method::GetCurrencies,
             MethodRequest::GetNetworkStatus(_) => Method::GetNetworkStatus,
@@ -164,8 +186,12 @@ impl MethodRequest {
             MethodRequest::GetAccountTransactionsWithProofs(_, _, _, _, _) => {
                 Method::GetAccountTransactionsWithProofs
             }
+            MethodRequest::GetTransactionByHashWithProof(_, _, _) => {
+                Method::GetTransactionByHashWithProof
+            }
             MethodRequest::GetEventsWithProofs(_, _, _) => Method::GetEventsWithProofs,
             MethodRequest::GetEventByVersionWithProof(_, _) => Method::GetEventByVersionWithProof,
+            MethodRequest::SimulateTransaction(_) => Method::SimulateTransaction,
         }
     }
 }
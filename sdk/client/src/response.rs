@@ -6,8 +6,8 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MetadataView, SimulatedTransactionView, StateProofView,
+        TransactionView, TransactionWithProofView, TransactionsWithProofsView,
     },
     Error, State,
 };
@@ -62,6 +62,7 @@ pub enum MethodResponse {
     GetTransactions(Vec<TransactionView>),
     GetAccountTransaction(Option<TransactionView>),
     GetAccountTransactions(Vec<TransactionView>),
+    GetTransactionByHash(Option<TransactionView>),
     GetEvents(Vec<EventView>),
     GetCurrencies(Vec<CurrencyInfoView>),
     GetNetworkStatus(u64),
@@ -74,8 +75,10 @@ pub enum MethodResponse {
     GetAccountStateWithProof(AccountStateWithProofView),
     GetTransactionsWithProofs(Option<TransactionsWithProofsView>),
     GetAccountTransactionsWithProofs(AccountTransactionsWithProofView),
+    GetTransactionByHashWithProof(Option<TransactionWithProofView>),
     GetEventsWithProofs(Vec<EventWithProofView>),
     GetEventByVersionWithProof(EventByVersionWithProofView),
+    SimulateTransaction(SimulatedTransactionView),
 }
 
 impl MethodResponse {
@@ -93,6 +96,9 @@ impl MethodResponse {
             Method::GetAccountTransactions => {
                 MethodResponse::GetAccountTransactions(serde_json::from_value(json)?)
             }
+            Method::GetTransactionByHash => {
+                MethodResponse::GetTransactionByHash(serde_json::from_value(json)?)
+            }
             Method::GetEvents => MethodResponse::GetEvents(serde_json::from_value(json)?),
             Method::GetCurrencies => MethodResponse::GetCurrencies(serde_json::from_value(json)?),
             Method::GetNetworkStatus => {
@@ -111,12 +117,18 @@ impl MethodResponse {
             Method::GetAccountTransactionsWithProofs => {
                 MethodResponse::GetAccountTransactionsWithProofs(serde_json::from_value(json)?)
             }
+            Method::GetTransactionByHashWithProof => {
+                MethodResponse::GetTransactionByHashWithProof(serde_json::from_value(json)?)
+            }
             Method::GetEventsWithProofs => {
                 MethodResponse::GetEventsWithProofs(serde_json::from_value(json)?)
             }
             Method::GetEventByVersionWithProof => {
                 MethodResponse::GetEventByVersionWithProof(serde_json::from_value(json)?)
             }
+            Method::SimulateTransaction => {
+                MethodResponse::SimulateTransaction(serde_json::from_value(json)?)
+            }
         };
 
         Ok(response)
@@ -130,6 +142,7 @@ impl MethodResponse {
             MethodResponse::GetTransactions(_) => Method::GetTransactions,
             MethodResponse::GetAccountTransaction(_) => Method::GetAccountTransaction,
             MethodResponse::GetAccountTransactions(_) => Method::GetAccountTransactions,
+            MethodResponse::GetTransactionByHash(_) => Method::GetTransactionByHash,
             MethodResponse::GetEvents(_) => Method::GetEvents,
             MethodResponse::GetCurrencies(_) => Method::GetCurrencies,
             MethodResponse::GetNetworkStatus(_) => Method::GetNetworkStatus,
@@ -142,8 +155,12 @@ impl MethodResponse {
             MethodResponse::GetAccountTransactionsWithProofs(_) => {
                 Method::GetAccountTransactionsWithProofs
             }
+            MethodResponse::GetTransactionByHashWithProof(_) => {
+                Method::GetTransactionByHashWithProof
+            }
             MethodResponse::GetEventsWithProofs(_) => Method::GetEventsWithProofs,
             MethodResponse::GetEventByVersionWithProof(_) => Method::GetEventByVersionWithProof,
+            MethodResponse::SimulateTransaction(_) => Method::SimulateTransaction,
         }
     }
 
@@ -240,6 +257,28 @@ impl MethodResponse {
         }
     }
 
+    pub fn try_into_get_transaction_by_hash(self) -> Result<Option<TransactionView>, Error> {
+        match self {
+            MethodResponse::GetTransactionByHash(tx) => Ok(tx),
+            _ => Err(Error::rpc_response(format!(
+                "expected MethodResponse::GetTransactionByHash found MethodResponse::{:?}",
+                self.method()
+            ))),
+        }
+    }
+
+    pub fn try_into_get_transaction_by_hash_with_proof(
+        self,
+    ) -> Result<Option<TransactionWithProofView>, Error> {
+        match self {
+            MethodResponse::GetTransactionByHashWithProof(tx) => Ok(tx),
+            _ => Err(Error::rpc_response(format!(
+                "expected MethodResponse::GetTransactionByHashWithProof found MethodResponse::{:?}",
+                self.method()
+            ))),
+        }
+    }
+
     pub fn try_into_get_account_state_with_proof(self) -> Result<AccountStateWithProofView, Error> {
         match self {
             MethodResponse::GetAccountStateWithProof(view) => Ok(view),
@@ -291,4 +330,14 @@ impl MethodResponse {
             ))),
         }
     }
+
+    pub fn try_into_simulate_transaction(self) -> Result<SimulatedTransactionView, Error> {
+        match self {
+            MethodResponse::SimulateTransaction(view) => Ok(view),
+            _ => Err(Error::rpc_response(format!(
+                "expected MethodResponse::SimulateTransaction found MethodResponse::{:?}",
+                self.method()
+            ))),
+        }
+    }
 }
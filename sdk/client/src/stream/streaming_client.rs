@@ -4,12 +4,15 @@
 use crate::{stream::websocket_transport::WebsocketTransport, StreamError, StreamResult};
 use dijets_json_rpc_types::{
     stream::{
-        request::{StreamMethodRequest, SubscribeToEventsParams, SubscribeToTransactionsParams},
+        request::{
+            StreamMethodRequest, SubscribeToAccountChangesParams, SubscribeToEventsParams,
+            SubscribeToTransactionsParams, TransactionFilter,
+        },
         response::StreamJsonRpcResponse,
     },
     Id,
 };
-use dijets_types::event::EventKey;
+use dijets_types::{account_address::AccountAddress, event::EventKey};
 use futures::Stream;
 use std::{
     collections::HashMap,
@@ -143,6 +146,22 @@ impl StreamingClient {
         let request = StreamMethodRequest::SubscribeToTransactions(SubscribeToTransactionsParams {
             starting_version,
             include_events,
+            filter: None,
+        });
+        self.send_subscription(request).await
+    }
+
+    /// Like `subscribe_transactions`, but the server only sends transactions matching `filter`
+    pub async fn subscribe_filtered_transactions(
+        &mut self,
+        starting_version: u64,
+        include_events: Option<bool>,
+        filter: TransactionFilter,
+    ) -> StreamResult<SubscriptionStream> {
+        let request = StreamMethodRequest::SubscribeToTransactions(SubscribeToTransactionsParams {
+            starting_version,
+            include_events,
+            filter: Some(filter),
         });
         self.send_subscription(request).await
     }
@@ -159,6 +178,18 @@ impl StreamingClient {
         self.send_subscription(request).await
     }
 
+    /// Receives the account's state, and then an update whenever it changes
+    pub async fn subscribe_account_changes(
+        &mut self,
+        address: AccountAddress,
+    ) -> StreamResult<SubscriptionStream> {
+        let request =
+            StreamMethodRequest::SubscribeToAccountChanges(SubscribeToAccountChangesParams {
+                address,
+            });
+        self.send_subscription(request).await
+    }
+
     pub(crate) async fn send_unsubscribe(&mut self, id: &Id) -> StreamResult<()> {
         debug!("StreamingClient sending unsubscribe for: {:?}", id);
         self.client
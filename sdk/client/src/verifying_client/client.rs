@@ -304,6 +304,16 @@ impl<S: StateStore> VerifyingClient<S> {
         .and_then(MethodResponse::try_into_get_account_transactions)
     }
 
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        include_events: bool,
+    ) -> Result<Response<Option<TransactionView>>> {
+        self.request(MethodRequest::get_transaction_by_hash(hash, include_events))
+            .await?
+            .and_then(MethodResponse::try_into_get_transaction_by_hash)
+    }
+
     pub async fn get_events(
         &self,
         key: EventKey,
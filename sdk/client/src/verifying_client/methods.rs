@@ -7,9 +7,13 @@ use crate::{
     response::{MethodResponse, Response},
     state::State,
 };
+use dijets_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    HashValue,
+};
 use dijets_json_rpc_types::views::{
     AccountStateWithProofView, AccountView, CurrencyInfoView, EventView, MetadataView,
-    TransactionListView,
+    TransactionListView, TransactionView,
 };
 use dijets_types::{
     account_address::AccountAddress,
@@ -20,9 +24,12 @@ use dijets_types::{
     contract_event::{EventByVersionWithProof, EventWithProof},
     event::EventKey,
     ledger_info::LedgerInfo,
-    proof::{AccumulatorConsistencyProof, TransactionAccumulatorSummary},
+    proof::{
+        accumulator::InMemoryAccumulator, AccumulatorConsistencyProof,
+        TransactionAccumulatorSummary,
+    },
     state_proof::StateProof,
-    transaction::{AccountTransactionsWithProof, Version},
+    transaction::{AccountTransactionsWithProof, TransactionWithProof, Version},
     trusted_state::TrustedState,
 };
 use std::convert::TryFrom;
@@ -269,6 +276,9 @@ impl From<MethodRequest> for VerifyingRequest {
             MethodRequest::GetAccountTransaction(address, seq_num, include_events) => {
                 get_account_transaction(address, seq_num, include_events)
             }
+            MethodRequest::GetTransactionByHash(hash, include_events) => {
+                get_transaction_by_hash(hash, include_events)
+            }
             MethodRequest::GetEvents(key, start_seq, limit) => get_events(key, start_seq, limit),
             MethodRequest::GetCurrencies([]) => get_currencies(),
             MethodRequest::GetNetworkStatus([]) => get_network_status(),
@@ -583,6 +593,94 @@ fn get_account_transaction(
     })
 }
 
+fn get_transaction_by_hash(hash: HashValue, include_events: bool) -> VerifyingRequest {
+    let request = MethodRequest::GetTransactionByHash(hash, include_events);
+    let subrequests = vec![MethodRequest::GetTransactionByHashWithProof(
+        hash,
+        include_events,
+        None, /* ledger_version must be None so the proof is verifiable at the latest state */
+    )];
+    let callback: RequestCallback = Box::new(move |ctxt, subresponses| {
+        let maybe_txn_with_proof_view = match subresponses {
+            [MethodResponse::GetTransactionByHashWithProof(ref inner)] => inner,
+            subresponses => {
+                return Err(Error::rpc_response(format!(
+                    "expected [GetTransactionByHashWithProof] subresponses, received: {:?}",
+                    subresponses,
+                )))
+            }
+        };
+
+        // Note: we have no way to verify that a transaction with this hash is actually absent
+        // from the ledger, since the server doesn't return a non-inclusion proof.
+        let txn_with_proof_view = if let Some(txn_with_proof_view) = maybe_txn_with_proof_view {
+            txn_with_proof_view
+        } else {
+            return Ok(MethodResponse::GetTransactionByHash(None));
+        };
+
+        let txn_with_proof =
+            TransactionWithProof::try_from(txn_with_proof_view).map_err(Error::decode)?;
+
+        // Check that the server returned the transaction we actually asked for.
+        let txn_hash = txn_with_proof.transaction.hash();
+        if txn_hash != hash {
+            return Err(Error::invalid_proof(format!(
+                "transaction hash mismatch: expected: {}, received: {}",
+                hash, txn_hash,
+            )));
+        }
+        let txn_info = &txn_with_proof.proof.transaction_info;
+        if txn_info.transaction_hash() != hash {
+            return Err(Error::invalid_proof(format!(
+                "transaction info hash mismatch: expected: {}, received: {}",
+                hash,
+                txn_info.transaction_hash(),
+            )));
+        }
+
+        // Check that the presence of events in the response matches our expectation.
+        let has_events = txn_with_proof.events.is_some();
+        if include_events != has_events {
+            return Err(Error::rpc_response(format!(
+                "expected events: {}, received events: {}",
+                include_events, has_events
+            )));
+        }
+        if let Some(events) = &txn_with_proof.events {
+            let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+            let event_root_hash =
+                InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes)
+                    .root_hash();
+            if event_root_hash != txn_info.event_root_hash() {
+                return Err(Error::invalid_proof(format!(
+                    "event root hash mismatch: expected: {}, received: {}",
+                    txn_info.event_root_hash(),
+                    event_root_hash,
+                )));
+            }
+        }
+
+        // Verify the transaction info against the latest ledger info's accumulator.
+        let latest_li = ctxt.state_proof.latest_ledger_info();
+        txn_with_proof
+            .proof
+            .verify(latest_li, txn_with_proof.version)
+            .map_err(Error::invalid_proof)?;
+
+        let txn_view = TransactionView::try_from_tx_and_events(
+            txn_with_proof.version,
+            txn_with_proof.transaction,
+            txn_with_proof.proof.transaction_info,
+            txn_with_proof.events.unwrap_or_default(),
+        )
+        .map_err(Error::decode)?;
+
+        Ok(MethodResponse::GetTransactionByHash(Some(txn_view)))
+    });
+    VerifyingRequest::new(request, subrequests, callback)
+}
+
 fn get_events(key: EventKey, start_seq: u64, limit: u64) -> VerifyingRequest {
     let request = MethodRequest::GetEvents(key, start_seq, limit);
     let subrequests = vec![MethodRequest::GetEventsWithProofs(key, start_seq, limit)];
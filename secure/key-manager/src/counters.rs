@@ -17,13 +17,19 @@ static COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
 /// Metric counter keys.
 const CHECK_KEYS: &str = "check_keys";
 const CONSENSUS_KEY: &str = "consensus_key";
+const VALIDATOR_NETWORK_KEY: &str = "validator_network_key";
 
 /// Metric counter states.
 pub const KEYS_STILL_FRESH: &[&str] = &[CHECK_KEYS, "keys_still_fresh"];
 pub const LIVENESS_ERROR_ENCOUNTERED: &[&str] = &[CHECK_KEYS, "liveness_error_encountered"];
+pub const NETWORK_KEY_ROTATED_IN_STORAGE: &[&str] = &[VALIDATOR_NETWORK_KEY, "rotated_in_storage"];
+pub const NETWORK_KEY_SUBMITTED_ROTATION_TRANSACTION: &[&str] =
+    &[VALIDATOR_NETWORK_KEY, "submitted_rotation_transaction"];
 pub const ROTATED_IN_STORAGE: &[&str] = &[CONSENSUS_KEY, "rotated_in_storage"];
 pub const SUBMITTED_ROTATION_TRANSACTION: &[&str] =
     &[CONSENSUS_KEY, "submitted_rotation_transaction"];
+pub const WAITING_ON_NETWORK_KEY_IN_USE: &[&str] =
+    &[VALIDATOR_NETWORK_KEY, "waiting_on_network_key_in_use"];
 pub const WAITING_ON_RECONFIGURATION: &[&str] = &[CHECK_KEYS, "waiting_on_reconfiguration"];
 pub const WAITING_ON_TRANSACTION_EXECUTION: &[&str] =
     &[CHECK_KEYS, "waiting_on_transaction_execution"];
@@ -34,8 +40,11 @@ pub fn initialize_all_metric_counters() {
     let metric_counter_states = &[
         KEYS_STILL_FRESH,
         LIVENESS_ERROR_ENCOUNTERED,
+        NETWORK_KEY_ROTATED_IN_STORAGE,
+        NETWORK_KEY_SUBMITTED_ROTATION_TRANSACTION,
         ROTATED_IN_STORAGE,
         SUBMITTED_ROTATION_TRANSACTION,
+        WAITING_ON_NETWORK_KEY_IN_USE,
         WAITING_ON_RECONFIGURATION,
         WAITING_ON_TRANSACTION_EXECUTION,
         UNEXPECTED_ERROR_ENCOUNTERED,
@@ -1,11 +1,11 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-//! The purpose of KeyManager is to rotate consensus key (and eventually the network key). It is not
-//! responsible for generating the first key and fails if the stores have not been properly setup.
-//! During rotation, it first updates the local store, then submits a transaction to rotate to the
-//! new key. After some period of time and upon restarts of the process, it will evaluate the
-//! current status of the system including:
+//! The purpose of KeyManager is to rotate the consensus key and the validator network key. It is
+//! not responsible for generating the first keys and fails if the stores have not been properly
+//! setup. During rotation, it first updates the local store, then submits a transaction to rotate
+//! to the new key. After some period of time and upon restarts of the process, it will evaluate
+//! the current status of the system including:
 //! * last rotation time, and rotate if it is too long ago
 //! * if the latest key in the store matches the latest key in the ValidatorConfig, upon mismatch
 //! it will try to submit a transaction to update the ValidatorConfig to the current key in the
@@ -14,6 +14,13 @@
 //! evaluates the current time from the last reconfiguration and logs that delta with greater
 //! levels of severity depending on the delta.
 //!
+//! The keys are rotated one at a time, and only once both are in sync with the blockchain, as
+//! each rotation transaction sets the whole ValidatorConfig. As a validator only loads its network
+//! key upon start, the network key rotation keeps the addresses of the previous key in the
+//! ValidatorConfig, after those of the new key, so that peers accept both keys. The following
+//! rotation drops them, so it waits until the validator records that it loaded the current key,
+//! i.e., until it is restarted. Otherwise, its connections would be dropped.
+//!
 //! KeyManager talks to Dijets via the DijetsInterface that may either be a direct link into
 //! `DijetsDB`/`Executor`, JSON-RPC, or some other concoction.
 //! KeyManager talks to its own storage through the `DijetsSecureStorage::Storage trait.
@@ -21,25 +28,32 @@
 
 use crate::{
     counters::{
-        KEYS_STILL_FRESH, LIVENESS_ERROR_ENCOUNTERED, ROTATED_IN_STORAGE,
-        SUBMITTED_ROTATION_TRANSACTION, UNEXPECTED_ERROR_ENCOUNTERED, WAITING_ON_RECONFIGURATION,
+        KEYS_STILL_FRESH, LIVENESS_ERROR_ENCOUNTERED, NETWORK_KEY_ROTATED_IN_STORAGE,
+        NETWORK_KEY_SUBMITTED_ROTATION_TRANSACTION, ROTATED_IN_STORAGE,
+        SUBMITTED_ROTATION_TRANSACTION, UNEXPECTED_ERROR_ENCOUNTERED,
+        WAITING_ON_NETWORK_KEY_IN_USE, WAITING_ON_RECONFIGURATION,
         WAITING_ON_TRANSACTION_EXECUTION,
     },
     dijets_interface::DijetsInterface,
     logging::{LogEntry, LogEvent, LogSchema},
 };
-use dijets_crypto::ed25519::Ed25519PublicKey;
-use dijets_global_constants::{CONSENSUS_KEY, OPERATOR_ACCOUNT, OPERATOR_KEY, OWNER_ACCOUNT};
+use dijets_crypto::{ed25519::Ed25519PublicKey, x25519};
+use dijets_global_constants::{
+    CONSENSUS_KEY, OPERATOR_ACCOUNT, OPERATOR_KEY, OWNER_ACCOUNT, VALIDATOR_NETWORK_KEY,
+    VALIDATOR_NETWORK_KEY_IN_USE,
+};
 use dijets_logger::prelude::*;
+use dijets_network_address_encryption::Encryptor;
 use dijets_secure_storage::{CryptoStorage, KVStorage};
 use dijets_time_service::{TimeService, TimeServiceTrait};
 use dijets_types::{
     account_address::AccountAddress,
     account_config::XUS_NAME,
     chain_id::ChainId,
+    network_address::{NetworkAddress, Protocol},
     transaction::{RawTransaction, SignedTransaction, Transaction},
 };
-use std::time::Duration;
+use std::{convert::TryFrom, time::Duration};
 use thiserror::Error;
 
 pub mod counters;
@@ -59,12 +73,20 @@ pub enum Action {
     NoAction,
     /// Sufficient time has passed for another key rotation (keys are stale).
     FullKeyRotation,
+    /// Sufficient time has passed for another network key rotation (the network key is stale).
+    FullNetworkKeyRotation,
     /// Storage and the blockchain are inconsistent, submit a new rotation transaction.
     SubmitKeyRotationTransaction,
+    /// Storage and the blockchain network keys are inconsistent, submit a new network key
+    /// rotation transaction.
+    SubmitNetworkKeyRotationTransaction,
     /// The validator config and the validator set are inconsistent, wait for reconfiguration.
     WaitForReconfiguration,
     /// Storage and the blockchain are inconsistent, wait for rotation transaction execution.
     WaitForTransactionExecution,
+    /// The network key is stale, but the node may still use the previous key, wait for it to load
+    /// the current key (e.g., upon restart).
+    WaitForNetworkKeyInUse,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -74,6 +96,10 @@ pub enum Error {
     ConfigInfoKeyMismatch(Ed25519PublicKey, Ed25519PublicKey),
     #[error("Key mismatch, config: {0}, storage: {1}")]
     ConfigStorageKeyMismatch(Ed25519PublicKey, Ed25519PublicKey),
+    #[error("Network key mismatch, config: {0:?}, info: {1:?}")]
+    ConfigInfoNetworkKeyMismatch(Vec<x25519::PublicKey>, Vec<x25519::PublicKey>),
+    #[error("Network key mismatch, config: {0:?}, storage: {1}")]
+    ConfigStorageNetworkKeyMismatch(Vec<x25519::PublicKey>, x25519::PublicKey),
     #[error("Data does not exist: {0}")]
     DataDoesNotExist(String),
     #[error(
@@ -82,6 +108,8 @@ pub enum Error {
     LivenessError(u64, u64),
     #[error("Unable to retrieve the account address: {0}, storage error: {1}")]
     MissingAccountAddress(String, String),
+    #[error("Network address error: {0}")]
+    NetworkAddressError(String),
     #[error("Storage error: {0}")]
     StorageError(String),
     #[error("ValidatorInfo not found in ValidatorConfig: {0}")]
@@ -108,6 +136,12 @@ impl From<bcs::Error> for Error {
     }
 }
 
+impl From<dijets_network_address_encryption::Error> for Error {
+    fn from(error: dijets_network_address_encryption::Error) -> Self {
+        Error::NetworkAddressError(error.to_string())
+    }
+}
+
 impl From<dijets_secure_storage::Error> for Error {
     fn from(error: dijets_secure_storage::Error) -> Self {
         Error::StorageError(error.to_string())
@@ -228,6 +262,42 @@ where
         Ok(())
     }
 
+    pub fn compare_network_storage_to_config(&mut self) -> Result<(), Error> {
+        let owner_account = self.get_account_from_storage(OWNER_ACCOUNT)?;
+        let validator_config = self.dijets.retrieve_validator_config(owner_account)?;
+
+        // The addresses of the current key come first, those of the previous key may follow
+        let storage_key = self.network_key_from_storage()?;
+        let config_keys =
+            self.network_keys(&validator_config.validator_network_addresses, owner_account)?;
+        if config_keys.first() != Some(&storage_key) {
+            return Err(Error::ConfigStorageNetworkKeyMismatch(
+                config_keys,
+                storage_key,
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn compare_network_info_to_config(&mut self) -> Result<(), Error> {
+        let owner_account = self.get_account_from_storage(OWNER_ACCOUNT)?;
+        let validator_config = self.dijets.retrieve_validator_config(owner_account)?;
+        let validator_info = self.dijets.retrieve_validator_info(owner_account)?;
+
+        let info_keys = self.network_keys(
+            &validator_info.config().validator_network_addresses,
+            owner_account,
+        )?;
+        let config_keys =
+            self.network_keys(&validator_config.validator_network_addresses, owner_account)?;
+        if config_keys != info_keys {
+            return Err(Error::ConfigInfoNetworkKeyMismatch(config_keys, info_keys));
+        }
+
+        Ok(())
+    }
+
     pub fn last_reconfiguration(&self) -> Result<u64, Error> {
         // Convert the time to seconds
         Ok(self.dijets.last_reconfiguration()? / 1_000_000)
@@ -237,6 +307,13 @@ where
         Ok(self.storage.get_public_key(CONSENSUS_KEY)?.last_update)
     }
 
+    pub fn last_network_rotation(&self) -> Result<u64, Error> {
+        Ok(self
+            .storage
+            .get_public_key(VALIDATOR_NETWORK_KEY)?
+            .last_update)
+    }
+
     pub fn dijets_timestamp(&self) -> Result<u64, Error> {
         // Convert the time to seconds
         Ok(self.dijets.dijets_timestamp()? / 1_000_000)
@@ -265,21 +342,118 @@ where
     ) -> Result<Ed25519PublicKey, Error> {
         info!(LogSchema::new(LogEntry::TransactionSubmitted).event(LogEvent::Pending));
 
-        let operator_account = self.get_account_from_storage(OPERATOR_ACCOUNT)?;
-        let seq_id = self.dijets.retrieve_sequence_number(operator_account)?;
-        let expiration = self.time_service.now_secs() + self.txn_expiration_secs;
+        // Retrieve existing network information as registered on-chain
+        let owner_account = self.get_account_from_storage(OWNER_ACCOUNT)?;
+        let validator_config = self.dijets.retrieve_validator_config(owner_account)?;
+
+        self.submit_validator_config_transaction(
+            &consensus_key,
+            validator_config.validator_network_addresses,
+            validator_config.fullnode_network_addresses,
+        )?;
+
+        info!(LogSchema::new(LogEntry::TransactionSubmitted).event(LogEvent::Success));
+        counters::increment_metric_counter(SUBMITTED_ROTATION_TRANSACTION);
+
+        Ok(consensus_key)
+    }
+
+    pub fn resubmit_network_key_transaction(&mut self) -> Result<(), Error> {
+        let network_key = self.network_key_from_storage()?;
+        self.submit_network_key_rotation_transaction(network_key)
+            .map(|_| ())
+    }
+
+    pub fn rotate_network_key(&mut self) -> Result<x25519::PublicKey, Error> {
+        info!(LogSchema::new(LogEntry::KeyRotatedInStorage).event(LogEvent::Pending));
+        let network_key = to_x25519(&self.storage.rotate_key(VALIDATOR_NETWORK_KEY)?)?;
+        info!(LogSchema::new(LogEntry::KeyRotatedInStorage)
+            .event(LogEvent::Success)
+            .network_key(&network_key));
+        counters::increment_metric_counter(NETWORK_KEY_ROTATED_IN_STORAGE);
+
+        self.submit_network_key_rotation_transaction(network_key)
+    }
+
+    /// Submits a transaction setting the validator network addresses to those registered on-chain
+    /// for the previous network key, with `network_key` in their place, followed by the addresses
+    /// of the previous key itself. The addresses of any older key are dropped, so this should only
+    /// be called once the node uses the previous key (see `network_key_in_use`).
+    pub fn submit_network_key_rotation_transaction(
+        &mut self,
+        network_key: x25519::PublicKey,
+    ) -> Result<x25519::PublicKey, Error> {
+        info!(LogSchema::new(LogEntry::TransactionSubmitted).event(LogEvent::Pending));
 
         // Retrieve existing network information as registered on-chain
         let owner_account = self.get_account_from_storage(OWNER_ACCOUNT)?;
         let validator_config = self.dijets.retrieve_validator_config(owner_account)?;
+        let addresses = Encryptor::new(&mut self.storage)
+            .decrypt(&validator_config.validator_network_addresses, owner_account)?;
+
+        // Without a previous key, e.g., after a manual rotation, all addresses move to the new key
+        let previous_key = match self
+            .storage
+            .get_public_key_previous_version(VALIDATOR_NETWORK_KEY)
+        {
+            Ok(previous_key) => Some(to_x25519(&previous_key)?),
+            Err(dijets_secure_storage::Error::KeyVersionNotFound(..)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let previous_addresses: Vec<_> = addresses
+            .iter()
+            .filter(|address| previous_key.is_some() && address.find_noise_proto() == previous_key)
+            .cloned()
+            .collect();
+        let rotated_addresses = if previous_addresses.is_empty() {
+            &addresses
+        } else {
+            &previous_addresses
+        };
+        let mut network_addresses = rotated_addresses
+            .iter()
+            .map(|address| with_noise_key(address, network_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        network_addresses.extend(previous_addresses);
+
+        let operator_account = self.get_account_from_storage(OPERATOR_ACCOUNT)?;
+        let seq_id = self.dijets.retrieve_sequence_number(operator_account)?;
+        let validator_network_addresses =
+            Encryptor::new(&mut self.storage).encrypt(&network_addresses, owner_account, seq_id)?;
+        let consensus_key = self.storage.get_public_key(CONSENSUS_KEY)?.public_key;
+
+        self.submit_validator_config_transaction(
+            &consensus_key,
+            validator_network_addresses,
+            validator_config.fullnode_network_addresses,
+        )?;
+
+        info!(LogSchema::new(LogEntry::TransactionSubmitted).event(LogEvent::Success));
+        counters::increment_metric_counter(NETWORK_KEY_SUBMITTED_ROTATION_TRANSACTION);
+
+        Ok(network_key)
+    }
+
+    /// Signs and submits a transaction setting the ValidatorConfig and triggering a
+    /// reconfiguration.
+    fn submit_validator_config_transaction(
+        &mut self,
+        consensus_key: &Ed25519PublicKey,
+        validator_network_addresses: Vec<u8>,
+        fullnode_network_addresses: Vec<u8>,
+    ) -> Result<(), Error> {
+        let operator_account = self.get_account_from_storage(OPERATOR_ACCOUNT)?;
+        let seq_id = self.dijets.retrieve_sequence_number(operator_account)?;
+        let expiration = self.time_service.now_secs() + self.txn_expiration_secs;
+        let owner_account = self.get_account_from_storage(OWNER_ACCOUNT)?;
 
         let txn = build_rotation_transaction(
             owner_account,
             operator_account,
             seq_id,
-            &consensus_key,
-            validator_config.validator_network_addresses,
-            validator_config.fullnode_network_addresses,
+            consensus_key,
+            validator_network_addresses,
+            fullnode_network_addresses,
             expiration,
             self.chain_id,
         );
@@ -289,12 +463,7 @@ where
         let signed_txn = SignedTransaction::new(txn, operator_pubkey, txn_signature);
 
         self.dijets
-            .submit_transaction(Transaction::UserTransaction(signed_txn))?;
-
-        info!(LogSchema::new(LogEntry::TransactionSubmitted).event(LogEvent::Success));
-        counters::increment_metric_counter(SUBMITTED_ROTATION_TRANSACTION);
-
-        Ok(consensus_key)
+            .submit_transaction(Transaction::UserTransaction(signed_txn))
     }
 
     /// Ensures that the dijets_timestamp() value registered on-chain is strictly monotonically
@@ -344,8 +513,43 @@ where
             Err(e) => return Err(e),
         };
 
+        // Compare the validator network keys in the validator config to the validator set
+        match self.compare_network_info_to_config() {
+            Ok(()) => { /* Expected */ }
+            Err(Error::ConfigInfoNetworkKeyMismatch(..)) => {
+                return Ok(Action::WaitForReconfiguration)
+            }
+            Err(e) => return Err(e),
+        }
+
+        let last_network_rotation = self.last_network_rotation()?;
+
+        // Compare the validator network keys in the validator config to secure storage
+        match self.compare_network_storage_to_config() {
+            Ok(()) => { /* Expected */ }
+            Err(Error::ConfigStorageNetworkKeyMismatch(..)) => {
+                return if last_network_rotation + self.txn_expiration_secs
+                    <= self.time_service.now_secs()
+                {
+                    Ok(Action::SubmitNetworkKeyRotationTransaction)
+                } else {
+                    Ok(Action::WaitForTransactionExecution)
+                };
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Only rotate once both keys are in sync, as each rotation transaction sets both of them
         if last_rotation + self.rotation_period_secs <= self.time_service.now_secs() {
             Ok(Action::FullKeyRotation)
+        } else if last_network_rotation + self.rotation_period_secs <= self.time_service.now_secs()
+        {
+            // The rotation drops the addresses of the previous key, which the node may still use
+            if self.network_key_in_use()? == Some(self.network_key_from_storage()?) {
+                Ok(Action::FullNetworkKeyRotation)
+            } else {
+                Ok(Action::WaitForNetworkKeyInUse)
+            }
         } else {
             Ok(Action::NoAction)
         }
@@ -358,11 +562,21 @@ where
                 self.rotate_consensus_key().map(|_| ())?;
                 info!(LogSchema::new(LogEntry::FullKeyRotation).event(LogEvent::Success));
             }
+            Action::FullNetworkKeyRotation => {
+                info!(LogSchema::new(LogEntry::FullNetworkKeyRotation).event(LogEvent::Pending));
+                self.rotate_network_key().map(|_| ())?;
+                info!(LogSchema::new(LogEntry::FullNetworkKeyRotation).event(LogEvent::Success));
+            }
             Action::SubmitKeyRotationTransaction => {
                 info!(LogSchema::new(LogEntry::TransactionResubmission).event(LogEvent::Pending));
                 self.resubmit_consensus_key_transaction()?;
                 info!(LogSchema::new(LogEntry::TransactionResubmission).event(LogEvent::Success));
             }
+            Action::SubmitNetworkKeyRotationTransaction => {
+                info!(LogSchema::new(LogEntry::TransactionResubmission).event(LogEvent::Pending));
+                self.resubmit_network_key_transaction()?;
+                info!(LogSchema::new(LogEntry::TransactionResubmission).event(LogEvent::Success));
+            }
             Action::NoAction => {
                 info!(LogSchema::new(LogEntry::KeyStillFresh));
                 counters::increment_metric_counter(KEYS_STILL_FRESH);
@@ -375,6 +589,10 @@ where
                 warn!(LogSchema::new(LogEntry::WaitForTransactionExecution));
                 counters::increment_metric_counter(WAITING_ON_TRANSACTION_EXECUTION);
             }
+            Action::WaitForNetworkKeyInUse => {
+                warn!(LogSchema::new(LogEntry::WaitForNetworkKeyInUse));
+                counters::increment_metric_counter(WAITING_ON_NETWORK_KEY_IN_USE);
+            }
         };
 
         Ok(())
@@ -386,6 +604,64 @@ where
             .map(|v| v.value)
             .map_err(|e| Error::MissingAccountAddress(account_name.into(), e.to_string()))
     }
+
+    fn network_key_from_storage(&self) -> Result<x25519::PublicKey, Error> {
+        to_x25519(
+            &self
+                .storage
+                .get_public_key(VALIDATOR_NETWORK_KEY)?
+                .public_key,
+        )
+    }
+
+    /// Returns the network key the node loaded upon its last start, if it recorded one.
+    fn network_key_in_use(&self) -> Result<Option<x25519::PublicKey>, Error> {
+        match self
+            .storage
+            .get::<x25519::PublicKey>(VALIDATOR_NETWORK_KEY_IN_USE)
+        {
+            Ok(response) => Ok(Some(response.value)),
+            Err(dijets_secure_storage::Error::KeyNotSet(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the noise keys of the encrypted validator network addresses, in order.
+    fn network_keys(
+        &mut self,
+        validator_network_addresses: &[u8],
+        owner_account: AccountAddress,
+    ) -> Result<Vec<x25519::PublicKey>, Error> {
+        let addresses = Encryptor::new(&mut self.storage)
+            .decrypt(validator_network_addresses, owner_account)?;
+        let mut keys: Vec<_> = addresses
+            .iter()
+            .filter_map(NetworkAddress::find_noise_proto)
+            .collect();
+        keys.dedup();
+        Ok(keys)
+    }
+}
+
+fn to_x25519(key: &Ed25519PublicKey) -> Result<x25519::PublicKey, Error> {
+    x25519::PublicKey::from_ed25519_public_bytes(&key.to_bytes())
+        .map_err(|e| Error::UnknownError(format!("Unable to convert network key: {}", e)))
+}
+
+/// Returns the address with its noise key replaced by `network_key`.
+fn with_noise_key(
+    address: &NetworkAddress,
+    network_key: x25519::PublicKey,
+) -> Result<NetworkAddress, Error> {
+    let protocols = address
+        .as_slice()
+        .iter()
+        .map(|protocol| match protocol {
+            Protocol::NoiseIK(_) => Protocol::NoiseIK(network_key),
+            protocol => protocol.clone(),
+        })
+        .collect::<Vec<_>>();
+    NetworkAddress::try_from(protocols).map_err(|e| Error::NetworkAddressError(e.to_string()))
 }
 
 pub fn build_rotation_transaction(
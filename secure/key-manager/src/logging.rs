@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::Error;
-use dijets_crypto::ed25519::Ed25519PublicKey;
+use dijets_crypto::{ed25519::Ed25519PublicKey, x25519};
 use dijets_logger::Schema;
 use serde::Serialize;
 
@@ -15,6 +15,8 @@ pub struct LogSchema<'a> {
     json_rpc_endpoint: Option<&'a str>,
     #[schema(display)]
     liveness_error: Option<&'a Error>,
+    #[schema(display)]
+    network_key: Option<&'a x25519::PublicKey>,
     sleep_duration: Option<u64>,
     #[schema(display)]
     unexpected_error: Option<&'a Error>,
@@ -28,6 +30,7 @@ impl<'a> LogSchema<'a> {
             consensus_key: None,
             json_rpc_endpoint: None,
             liveness_error: None,
+            network_key: None,
             sleep_duration: None,
             unexpected_error: None,
         }
@@ -39,12 +42,14 @@ impl<'a> LogSchema<'a> {
 pub enum LogEntry {
     CheckKeyStatus,
     FullKeyRotation,
+    FullNetworkKeyRotation,
     Initialized,
     KeyRotatedInStorage,
     KeyStillFresh,
     Sleep,
     TransactionResubmission,
     TransactionSubmitted,
+    WaitForNetworkKeyInUse,
     WaitForReconfiguration,
     WaitForTransactionExecution,
 }
@@ -11,11 +11,13 @@ use dijets_config::{
     utils,
     utils::get_genesis_txn,
 };
-use dijets_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+use dijets_crypto::{ed25519::Ed25519PrivateKey, x25519, HashValue, PrivateKey, Uniform};
 use dijets_global_constants::{
     CONSENSUS_KEY, OPERATOR_ACCOUNT, OPERATOR_KEY, OWNER_ACCOUNT, OWNER_KEY,
+    VALIDATOR_NETWORK_ADDRESS_KEYS, VALIDATOR_NETWORK_KEY, VALIDATOR_NETWORK_KEY_IN_USE,
 };
-use dijets_secure_storage::{InMemoryStorage, KVStorage};
+use dijets_network_address_encryption::ValidatorKeys;
+use dijets_secure_storage::{CryptoStorage, InMemoryStorage, KVStorage, Storage};
 use dijets_time_service::{MockTimeService, TimeService, TimeServiceTrait};
 use dijets_types::{
     account_address::AccountAddress,
@@ -369,7 +371,8 @@ fn setup_node<T: DijetsInterface + Clone>(
 }
 
 // Creates and returns a secure storage implementation (based on an in memory storage engine) for
-// testing. As part of the initialization, the consensus key is created.
+// testing. As part of the initialization, the consensus and validator network keys are created,
+// and the validator network key is recorded as in use, as done by the node upon start.
 fn setup_secure_storage(config: &NodeConfig, time: TimeService) -> InMemoryStorage {
     let mut sec_storage = InMemoryStorage::new_with_time_service(time);
     let test_config = config.clone().test.unwrap();
@@ -397,6 +400,29 @@ fn setup_secure_storage(config: &NodeConfig, time: TimeService) -> InMemoryStora
         .set(crate::CONSENSUS_KEY, consensus_prikey)
         .unwrap();
 
+    // Initialize the validator network key and the network address encryption keys in storage
+    let validator_network = config.validator_network.as_ref().unwrap();
+    let validator_storage = Storage::from(&validator_network.identity_from_storage().backend);
+    let network_prikey = validator_storage
+        .export_private_key(VALIDATOR_NETWORK_KEY)
+        .unwrap();
+    let network_pubkey = x25519::PrivateKey::from_ed25519_private_bytes(&network_prikey.to_bytes())
+        .unwrap()
+        .public_key();
+    sec_storage
+        .set(VALIDATOR_NETWORK_KEY, network_prikey)
+        .unwrap();
+    sec_storage
+        .set(VALIDATOR_NETWORK_KEY_IN_USE, network_pubkey)
+        .unwrap();
+    let address_keys = validator_storage
+        .get::<ValidatorKeys>(VALIDATOR_NETWORK_ADDRESS_KEYS)
+        .unwrap()
+        .value;
+    sec_storage
+        .set(VALIDATOR_NETWORK_ADDRESS_KEYS, address_keys)
+        .unwrap();
+
     sec_storage
 }
 
@@ -638,11 +664,168 @@ fn verify_execute<T: DijetsInterface>(mut node: Node<T>) {
     node.update_dijets_timestamp();
     node.key_manager.execute_once().unwrap();
     node.execute_and_commit(node.dijets.take_all_transactions());
+    assert_ne!(0, node.dijets.last_reconfiguration().unwrap());
+
+    // Verify the network key is rotated once the consensus key is in sync
+    assert_eq!(
+        Action::FullNetworkKeyRotation,
+        node.key_manager.evaluate_status().unwrap()
+    );
+    node.update_dijets_timestamp();
+    node.key_manager.execute_once().unwrap();
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::WaitForTransactionExecution,
+        node.key_manager.evaluate_status().unwrap()
+    );
+
+    // Verify the rotation transaction expires and is resubmitted, then executed
+    node.time
+        .advance_secs(key_manager_config.txn_expiration_secs);
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::SubmitNetworkKeyRotationTransaction,
+        node.key_manager.evaluate_status().unwrap()
+    );
+    node.dijets.take_all_transactions();
+    node.update_dijets_timestamp();
+    node.key_manager.execute_once().unwrap();
+    node.execute_and_commit(node.dijets.take_all_transactions());
     assert_eq!(
         Action::NoAction,
         node.key_manager.evaluate_status().unwrap()
     );
-    assert_ne!(0, node.dijets.last_reconfiguration().unwrap());
+}
+
+#[test]
+// This verifies that the validator network key can be rotated, and that the addresses of the
+// previous key remain registered on-chain until the node is known to use the new key.
+fn test_network_key_rotation() {
+    // Test the mock dijets interface implementation
+    let node = setup_node_using_test_mocks();
+    verify_network_key_rotation(node);
+
+    // Test the json dijets interface implementation
+    let (node, _runtime) = setup_node_using_json_rpc();
+    verify_network_key_rotation(node);
+}
+
+fn verify_network_key_rotation<T: DijetsInterface>(mut node: Node<T>) {
+    let (_, key_manager_config) = get_test_configs();
+
+    // Verify correct initialization (on-chain and in storage)
+    node.key_manager
+        .compare_network_storage_to_config()
+        .unwrap();
+    node.key_manager.compare_network_info_to_config().unwrap();
+    assert_eq!(
+        node.time.now_secs(),
+        node.key_manager.last_network_rotation().unwrap()
+    );
+    let owner_account = node.get_account_from_storage(OWNER_ACCOUNT);
+    let genesis_key = node.key_manager.network_key_from_storage().unwrap();
+    assert_eq!(
+        vec![genesis_key],
+        network_keys_on_chain(&mut node, owner_account)
+    );
+
+    // Perform key rotation locally, the validator set is unchanged until execution
+    let new_key = node.key_manager.rotate_network_key().unwrap();
+    assert_ne!(genesis_key, new_key);
+    assert_eq!(
+        vec![genesis_key],
+        network_keys_on_chain(&mut node, owner_account)
+    );
+    match node.key_manager.compare_network_storage_to_config() {
+        Err(Error::ConfigStorageNetworkKeyMismatch(..)) => { /* Expected */ }
+        result => panic!("Expected a network key mismatch, but got: {:?}", result),
+    }
+
+    // Increment time to 5min + 1sec, so that the rotation passes through rate limits
+    node.time.advance_secs(301);
+
+    // Execute key rotation on-chain, both keys are now accepted
+    node.execute_and_commit(node.dijets.take_all_transactions());
+    assert_eq!(
+        vec![new_key, genesis_key],
+        network_keys_on_chain(&mut node, owner_account)
+    );
+    node.key_manager
+        .compare_network_storage_to_config()
+        .unwrap();
+    node.key_manager.compare_network_info_to_config().unwrap();
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::NoAction,
+        node.key_manager.evaluate_status().unwrap()
+    );
+
+    // Verify the consensus key rotation keeps both network keys
+    node.time
+        .advance_secs(key_manager_config.rotation_period_secs);
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::FullKeyRotation,
+        node.key_manager.evaluate_status().unwrap()
+    );
+    node.update_dijets_timestamp();
+    node.key_manager.execute_once().unwrap();
+    node.execute_and_commit(node.dijets.take_all_transactions());
+    assert_eq!(
+        vec![new_key, genesis_key],
+        network_keys_on_chain(&mut node, owner_account)
+    );
+
+    // Verify the stale network key isn't rotated while the node may still use the genesis key
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::WaitForNetworkKeyInUse,
+        node.key_manager.evaluate_status().unwrap()
+    );
+    node.update_dijets_timestamp();
+    node.key_manager.execute_once().unwrap();
+    assert!(node.dijets.take_all_transactions().is_empty());
+    assert_eq!(
+        new_key,
+        node.key_manager.network_key_from_storage().unwrap()
+    );
+
+    // Restart the node with the new key, the next rotation drops the genesis key
+    node.key_manager
+        .storage
+        .set(VALIDATOR_NETWORK_KEY_IN_USE, new_key)
+        .unwrap();
+    node.update_dijets_timestamp();
+    assert_eq!(
+        Action::FullNetworkKeyRotation,
+        node.key_manager.evaluate_status().unwrap()
+    );
+    node.update_dijets_timestamp();
+    node.key_manager.execute_once().unwrap();
+    let newer_key = node.key_manager.network_key_from_storage().unwrap();
+    assert_ne!(new_key, newer_key);
+    node.time.advance_secs(301);
+    node.execute_and_commit(node.dijets.take_all_transactions());
+    assert_eq!(
+        vec![newer_key, new_key],
+        network_keys_on_chain(&mut node, owner_account)
+    );
+
+    // The consensus key is untouched
+    let consensus_pubkey = node.get_key_from_storage(CONSENSUS_KEY).public_key();
+    let info = node.dijets.retrieve_validator_info(owner_account).unwrap();
+    assert_eq!(&consensus_pubkey, info.consensus_public_key());
+}
+
+// Returns the network keys of the validator in the validator set, in order.
+fn network_keys_on_chain<T: DijetsInterface>(
+    node: &mut Node<T>,
+    owner_account: AccountAddress,
+) -> Vec<x25519::PublicKey> {
+    let info = node.dijets.retrieve_validator_info(owner_account).unwrap();
+    node.key_manager
+        .network_keys(&info.config().validator_network_addresses, owner_account)
+        .unwrap()
 }
 
 #[test]
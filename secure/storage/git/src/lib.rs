@@ -0,0 +1,311 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use std::{fs, path::PathBuf, process::Command};
+use thiserror::Error;
+
+/// The number of times a write is retried when another writer pushed to the remote first.
+const MAX_PUSH_ATTEMPTS: usize = 3;
+/// The identity of commits, when none is configured for the repository.
+const DEFAULT_USER_NAME: &str = "dijets-management";
+const DEFAULT_USER_EMAIL: &str = "dijets-management@localhost";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Git command 'git {0}' failed: {1}")]
+    CommandError(String, String),
+    #[error("Internal error: {0}")]
+    InternalError(String),
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::InternalError(format!("{}", error))
+    }
+}
+
+/// Client provides a file system approach to key / value storage over a git repository, using
+/// the git command line. Each key is a file within the working tree at `path`, and each write is
+/// a commit. If a remote is set, i.e., a URL or the path to another, typically bare, repository,
+/// the working tree is reset to its branch before every operation and every commit is pushed to
+/// it, which lets multiple peers exchange data through the remote without any hosted service.
+///
+/// Like the GitHub client, this is not intended for storing private data.
+pub struct Client {
+    path: PathBuf,
+    remote: Option<String>,
+    branch: String,
+}
+
+impl Client {
+    pub fn new(path: PathBuf, remote: Option<String>, branch: String) -> Self {
+        Self {
+            path,
+            remote,
+            branch,
+        }
+    }
+
+    /// Verifies that the repository can be initialized and, if set, that the remote is reachable.
+    pub fn available(&self) -> Result<(), Error> {
+        self.init()?;
+        if let Some(remote) = &self.remote {
+            self.git(&["ls-remote", "--heads", remote.as_str()])?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves the contents of a file along with the time of the last commit that changed it,
+    /// in seconds since the Unix Epoch.
+    pub fn get_file(&self, key: &str) -> Result<(Vec<u8>, u64), Error> {
+        let file_path = self.file_path(key)?;
+        self.sync()?;
+
+        if !file_path.is_file() {
+            return Err(Error::NotFound(key.into()));
+        }
+        let contents = fs::read(&file_path)?;
+        let commit_time = self.git(&["log", "-1", "--format=%ct", "--", key])?;
+        let commit_time = commit_time.trim().parse().map_err(|_| {
+            Error::InternalError(format!("No commit found for {}: {:?}", key, commit_time))
+        })?;
+        Ok((contents, commit_time))
+    }
+
+    /// Creates or updates a file and commits it, even if its contents did not change.
+    pub fn put(&self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        let file_path = self.file_path(key)?;
+        self.commit(&format!("[dijets-management] {}", key), || {
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file_path, contents)?;
+            self.git(&["add", "--", key]).map(|_| ())
+        })
+    }
+
+    /// Deletes all files in a single commit.
+    pub fn delete_all(&self) -> Result<(), Error> {
+        self.commit("[dijets-management] delete all", || {
+            self.git(&["rm", "-r", "-q", "--ignore-unmatch", "--", "."])
+                .map(|_| ())
+        })
+    }
+
+    /// Keys are relative paths within the working tree, they may not escape it nor touch the git
+    /// directory.
+    fn file_path(&self, key: &str) -> Result<PathBuf, Error> {
+        let mut components = key.split('/').peekable();
+        if components.peek() == Some(&".git") {
+            return Err(Error::InvalidKey(key.into()));
+        }
+        if components.any(|c| c.is_empty() || c == "." || c == "..") {
+            return Err(Error::InvalidKey(key.into()));
+        }
+        Ok(self.path.join(key))
+    }
+
+    /// Applies `change` to the index and commits it, then pushes the commit if a remote is set.
+    /// If another writer pushed first, the working tree is reset to the remote and the change is
+    /// applied again.
+    fn commit<F: Fn() -> Result<(), Error>>(&self, message: &str, change: F) -> Result<(), Error> {
+        let mut last_error = None;
+        for _ in 0..MAX_PUSH_ATTEMPTS {
+            self.sync()?;
+            change()?;
+            let mut args = self.identity();
+            args.extend(
+                ["commit", "-q", "--allow-empty", "-m", message]
+                    .iter()
+                    .map(|arg| arg.to_string()),
+            );
+            self.git(&args)?;
+
+            let remote = match &self.remote {
+                Some(remote) => remote,
+                None => return Ok(()),
+            };
+            let refspec = format!("HEAD:refs/heads/{}", self.branch);
+            match self.git(&["push", "-q", remote, &refspec]) {
+                Ok(_) => return Ok(()),
+                // The push was most likely rejected as non fast-forward, the next sync drops the
+                // local commit in favor of the remote branch.
+                Err(e @ Error::CommandError(_, _)) => last_error = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.expect("At least one push was attempted"))
+    }
+
+    /// Resets the working tree to the branch of the remote, if any, so that reads and writes see
+    /// all the data pushed by other peers.
+    fn sync(&self) -> Result<(), Error> {
+        self.init()?;
+        let remote = match &self.remote {
+            Some(remote) => remote,
+            None => return Ok(()),
+        };
+        let heads = self.git(&["ls-remote", "--heads", remote, &self.branch])?;
+        if heads.trim().is_empty() {
+            // Nothing was pushed yet, start from an empty branch
+            let reference = format!("refs/heads/{}", self.branch);
+            if self
+                .git(&["rev-parse", "-q", "--verify", &reference])
+                .is_ok()
+            {
+                self.git(&["update-ref", "-d", &reference])?;
+            }
+            self.git(&["read-tree", "--empty"])?;
+            self.git(&["clean", "-d", "-f", "-q"])?;
+            return Ok(());
+        }
+        self.git(&["fetch", "-q", remote, &self.branch])?;
+        self.git(&["reset", "-q", "--hard", "FETCH_HEAD"])?;
+        self.git(&["clean", "-d", "-f", "-q"])?;
+        Ok(())
+    }
+
+    /// Initializes the repository on the branch, if it does not exist yet.
+    fn init(&self) -> Result<(), Error> {
+        if self.path.join(".git").exists() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.path)?;
+        self.git(&["init", "-q"])?;
+        self.git(&[
+            "symbolic-ref",
+            "HEAD",
+            &format!("refs/heads/{}", self.branch),
+        ])?;
+        Ok(())
+    }
+
+    /// The identity configured for the repository takes precedence over the default one.
+    fn identity(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.git(&["config", "user.name"]).is_err() {
+            args.push("-c".into());
+            args.push(format!("user.name={}", DEFAULT_USER_NAME));
+        }
+        if self.git(&["config", "user.email"]).is_err() {
+            args.push("-c".into());
+            args.push(format!("user.email={}", DEFAULT_USER_EMAIL));
+        }
+        args
+    }
+
+    /// Runs git within the repository and returns its standard output.
+    fn git<S: AsRef<str>>(&self, args: &[S]) -> Result<String, Error> {
+        let output = Command::new("git")
+            .current_dir(&self.path)
+            .args(args.iter().map(|arg| arg.as_ref()))
+            .output()?;
+        if !output.status.success() {
+            let command = args
+                .iter()
+                .map(|arg| arg.as_ref())
+                .collect::<Vec<_>>()
+                .join(" ");
+            return Err(Error::CommandError(
+                command,
+                String::from_utf8_lossy(&output.stderr).trim().into(),
+            ));
+        }
+        String::from_utf8(output.stdout).map_err(|e| Error::InternalError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dijets_temppath::TempPath;
+
+    #[test]
+    fn test_invalid_keys() {
+        let client = Client::new(PathBuf::from("/repository"), None, "main".into());
+        for key in &[
+            "",
+            "/key",
+            "key/",
+            "a//b",
+            "../key",
+            "a/../../b",
+            "./key",
+            ".git/config",
+        ] {
+            assert_eq!(
+                client.file_path(key),
+                Err(Error::InvalidKey(key.to_string()))
+            );
+        }
+        assert_eq!(
+            client.file_path("namespace/key").unwrap(),
+            PathBuf::from("/repository/namespace/key")
+        );
+    }
+
+    #[test]
+    fn test_put_get() {
+        let path = TempPath::new();
+        let client = Client::new(path.path().to_path_buf(), None, "main".into());
+        assert_eq!(
+            client.get_file("namespace/key"),
+            Err(Error::NotFound("namespace/key".into()))
+        );
+
+        client.put("namespace/key", b"value").unwrap();
+        let (contents, _) = client.get_file("namespace/key").unwrap();
+        assert_eq!(contents, b"value");
+        let log = client.git(&["log", "--format=%s", "main"]).unwrap();
+        assert_eq!(log.trim(), "[dijets-management] namespace/key");
+
+        client.delete_all().unwrap();
+        assert_eq!(
+            client.get_file("namespace/key"),
+            Err(Error::NotFound("namespace/key".into()))
+        );
+    }
+
+    #[test]
+    fn test_remote() {
+        let remote = TempPath::new();
+        remote.create_as_dir().unwrap();
+        Command::new("git")
+            .args(&["init", "-q", "--bare"])
+            .arg(remote.path())
+            .status()
+            .unwrap();
+        let remote_url = remote.path().to_str().unwrap().to_string();
+
+        let path_1 = TempPath::new();
+        let client_1 = Client::new(
+            path_1.path().to_path_buf(),
+            Some(remote_url.clone()),
+            "genesis".into(),
+        );
+        let path_2 = TempPath::new();
+        let client_2 = Client::new(
+            path_2.path().to_path_buf(),
+            Some(remote_url),
+            "genesis".into(),
+        );
+        client_1.available().unwrap();
+
+        client_1.put("key_1", b"value_1").unwrap();
+        client_2.put("key_2", b"value_2").unwrap();
+        assert_eq!(client_1.get_file("key_2").unwrap().0, b"value_2");
+        assert_eq!(client_2.get_file("key_1").unwrap().0, b"value_1");
+
+        client_1.delete_all().unwrap();
+        assert_eq!(
+            client_2.get_file("key_1"),
+            Err(Error::NotFound("key_1".into()))
+        );
+    }
+}
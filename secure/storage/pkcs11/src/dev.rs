@@ -0,0 +1,136 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Client;
+use dijets_temppath::TempPath;
+use once_cell::sync::Lazy;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+static DIJETS_SOFTHSM: Lazy<Option<SoftHsmRunner>> = Lazy::new(|| match SoftHsmRunner::run() {
+    Err(err) => {
+        assert!(
+            std::env::var("DIJETS_REQUIRE_PKCS11_TESTS").is_err(),
+            "SoftHSM is not available: {}",
+            err
+        );
+        println!("SoftHSM is not available: {}", err);
+        None
+    }
+    Ok(runner) => Some(runner),
+});
+
+/// This will return the SoftHSM token, if it was initialized successfully. If SoftHSM is expected
+/// to be available, an assertion will cause this to fail.
+pub fn test_token_safe() -> Option<&'static SoftHsmRunner> {
+    DIJETS_SOFTHSM.as_ref()
+}
+
+/// This will return the SoftHSM token or panic.
+pub fn test_token() -> &'static SoftHsmRunner {
+    test_token_safe().unwrap()
+}
+
+const BINARY: &str = "softhsm2-util";
+/// Overrides the path to the SoftHSM library.
+const LIBRARY_ENV: &str = "DIJETS_PKCS11_LIBRARY";
+/// The default paths to the SoftHSM library on Linux and macOS.
+const LIBRARIES: &[&str] = &[
+    "/usr/lib/softhsm/libsofthsm2.so",
+    "/usr/lib/x86_64-linux-gnu/softhsm/libsofthsm2.so",
+    "/usr/local/lib/softhsm/libsofthsm2.so",
+    "/opt/homebrew/lib/softhsm/libsofthsm2.so",
+];
+pub const TOKEN_LABEL: &str = "dijets";
+pub const USER_PIN: &str = "1234";
+const SO_PIN: &str = "5678";
+
+/// Provide a SoftHSM token if SoftHSM is installed on the current machine. The token lives in a
+/// temporary directory, so it starts empty.
+pub struct SoftHsmRunner {
+    _dir: TempPath,
+    library: PathBuf,
+}
+
+impl SoftHsmRunner {
+    /// Initializes a new SoftHSM token if SoftHSM is available or returns a String error stating
+    /// where it was unable to make progress.
+    pub fn run() -> Result<Self, String> {
+        let library = Self::_library()?;
+
+        let dir = TempPath::new();
+        dir.create_as_dir().map_err(|e| e.to_string())?;
+        let token_dir = dir.path().join("tokens");
+        fs::create_dir(&token_dir).map_err(|e| e.to_string())?;
+        let config = dir.path().join("softhsm2.conf");
+        fs::write(
+            &config,
+            format!(
+                "directories.tokendir = {}\nobjectstore.backend = file\n",
+                token_dir.display()
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let output = Command::new(BINARY)
+            .env("SOFTHSM2_CONF", &config)
+            .arg("--init-token")
+            .arg("--free")
+            .arg("--label")
+            .arg(TOKEN_LABEL)
+            .arg("--pin")
+            .arg(USER_PIN)
+            .arg("--so-pin")
+            .arg(SO_PIN)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into());
+        }
+
+        // The library reads its configuration when it gets initialized by the first client
+        std::env::set_var("SOFTHSM2_CONF", &config);
+        Ok(Self { _dir: dir, library })
+    }
+
+    fn _library() -> Result<PathBuf, String> {
+        if let Ok(library) = std::env::var(LIBRARY_ENV) {
+            return Ok(PathBuf::from(library));
+        }
+        LIBRARIES
+            .iter()
+            .map(PathBuf::from)
+            .find(|library| library.exists())
+            .ok_or_else(|| format!("Unable to find the SoftHSM library, set {}", LIBRARY_ENV))
+    }
+
+    pub fn library(&self) -> &Path {
+        &self.library
+    }
+
+    pub fn token_label(&self) -> &str {
+        TOKEN_LABEL
+    }
+
+    pub fn pin(&self) -> &str {
+        USER_PIN
+    }
+
+    pub fn client(&self) -> Client {
+        Client::new(
+            self.library().to_path_buf(),
+            self.token_label().to_string(),
+            self.pin().to_string(),
+        )
+    }
+}
+
+#[test]
+fn run_test_token() {
+    if let Some(token) = test_token_safe() {
+        token.client().available().unwrap();
+    }
+}
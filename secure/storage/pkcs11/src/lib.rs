@@ -0,0 +1,483 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+pub mod dev;
+
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    error::RvError,
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    slot::Slot,
+};
+use dijets_crypto::ed25519::{
+    Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_PUBLIC_KEY_LENGTH,
+};
+use dijets_infallible::{duration_since_epoch, Mutex};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// The max number of key versions held in the token at any one time.
+/// Keys are trimmed in FIFO order.
+const MAX_NUM_KEY_VERSIONS: u32 = 4;
+
+/// The application of the data objects holding key/value pairs, which tells them apart from the
+/// other data objects of the token.
+const DATA_APPLICATION: &[u8] = b"dijets";
+
+/// The DER encoding of the object identifier of Ed25519 (1.3.101.112), used as CKA_EC_PARAMS.
+const ED25519_EC_PARAMS: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// The DER tag of an OCTET STRING, which wraps the public key in CKA_EC_POINT.
+const DER_OCTET_STRING: u8 = 0x04;
+
+/// The length of the CKA_ID of keys: a big endian u32 version followed by a big endian u64
+/// creation time, in seconds since the Unix Epoch.
+const KEY_ID_LENGTH: usize = 12;
+
+/// A PKCS#11 library can only be initialized once per process and finalizing it closes all of its
+/// sessions, so the contexts are shared by all clients and never dropped.
+static CONTEXTS: Lazy<Mutex<HashMap<PathBuf, Pkcs11>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Internal error: {0}")]
+    InternalError(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11Error(String),
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+    #[error("Token not found: {0}")]
+    TokenNotFound(String),
+}
+
+impl From<cryptoki::error::Error> for Error {
+    fn from(error: cryptoki::error::Error) -> Self {
+        match error {
+            cryptoki::error::Error::Pkcs11(RvError::PinIncorrect)
+            | cryptoki::error::Error::Pkcs11(RvError::PinLocked)
+            | cryptoki::error::Error::Pkcs11(RvError::UserNotLoggedIn) => {
+                Self::PermissionDenied(format!("{}", error))
+            }
+            _ => Self::Pkcs11Error(format!("{}", error)),
+        }
+    }
+}
+
+impl From<dijets_crypto::traits::CryptoMaterialError> for Error {
+    fn from(error: dijets_crypto::traits::CryptoMaterialError) -> Self {
+        Self::SerializationError(format!("{}", error))
+    }
+}
+
+/// Client provides a client around a token of a PKCS#11 library, e.g., a hardware security module
+/// or SoftHSM. Learn more here: https://docs.oasis-open.org/pkcs11/pkcs11-base/v3.0/pkcs11-base-v3.0.html
+///
+/// A brief overview of how the token is used:
+///
+/// * Key/value pairs are data objects (CKO_DATA) of the dijets application, labelled with the key.
+/// * Ed25519 keys are key pairs (CKK_EC_EDWARDS) labelled with the name of the key. Each version
+/// of a key is a distinct key pair, whose CKA_ID holds its version and its creation time.
+/// * Private keys are generated by the token as sensitive and non-extractable objects, so they
+/// can be used to sign but can never be read out of the token.
+pub struct Client {
+    library: PathBuf,
+    token_label: String,
+    pin: String,
+    session: Mutex<Option<Session>>,
+}
+
+impl Client {
+    pub fn new(library: PathBuf, token_label: String, pin: String) -> Self {
+        Self {
+            library,
+            token_label,
+            pin,
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Returns an error if the token is not present or the PIN is rejected.
+    pub fn available(&self) -> Result<(), Error> {
+        self.with_session(|_| Ok(()))
+    }
+
+    pub fn read_data(&self, label: &str) -> Result<Vec<u8>, Error> {
+        self.with_session(|session| {
+            let object = session
+                .find_objects(&data_template(label))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::NotFound(label.into()))?;
+            match session
+                .get_attributes(object, &[AttributeType::Value])?
+                .into_iter()
+                .next()
+            {
+                Some(Attribute::Value(value)) => Ok(value),
+                _ => Err(Error::InternalError(format!("Missing value of {}", label))),
+            }
+        })
+    }
+
+    /// Writes the data object `label`, replacing the previous one only once the new one exists.
+    pub fn write_data(&self, label: &str, value: &[u8]) -> Result<(), Error> {
+        self.with_session(|session| {
+            let previous = session.find_objects(&data_template(label))?;
+            let mut template = data_template(label);
+            template.extend(vec![
+                Attribute::Token(true),
+                Attribute::Private(true),
+                Attribute::Value(value.to_vec()),
+            ]);
+            session.create_object(&template)?;
+            for object in previous {
+                session.destroy_object(object)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Generates version `version` of the Ed25519 key `label` inside the token and returns its
+    /// public key.
+    pub fn create_ed25519_key(&self, label: &str, version: u32) -> Result<Ed25519PublicKey, Error> {
+        self.with_session(|session| {
+            let id = key_id(version, duration_since_epoch().as_secs());
+            let (public_key, _) = session.generate_key_pair(
+                &Mechanism::EccEdwardsKeyPairGen,
+                &public_key_template(label, &id),
+                &private_key_template(label, &id),
+            )?;
+            read_public_key(session, public_key)
+        })
+    }
+
+    /// Imports `key` as version `version` of the Ed25519 key `label`. Once imported, the private
+    /// key can no longer be read out of the token.
+    pub fn import_ed25519_key(
+        &self,
+        label: &str,
+        version: u32,
+        key: &Ed25519PrivateKey,
+    ) -> Result<(), Error> {
+        self.with_session(|session| {
+            let id = key_id(version, duration_since_epoch().as_secs());
+            let mut public_key = public_key_template(label, &id);
+            public_key.push(Attribute::EcPoint(encode_ec_point(
+                &Ed25519PublicKey::from(key),
+            )));
+            let mut private_key = private_key_template(label, &id);
+            private_key.push(Attribute::Value(key.to_bytes().to_vec()));
+            session.create_object(&public_key)?;
+            session.create_object(&private_key)?;
+            Ok(())
+        })
+    }
+
+    /// Returns all the versions of the Ed25519 key `label`.
+    pub fn read_ed25519_key(
+        &self,
+        label: &str,
+    ) -> Result<Vec<ReadResponse<Ed25519PublicKey>>, Error> {
+        self.with_session(|session| {
+            let mut versions = Vec::new();
+            for object in session.find_objects(&key_template(ObjectClass::PUBLIC_KEY, label))? {
+                let (version, creation_time) = read_key_id(session, object)?;
+                let public_key = read_public_key(session, object)?;
+                versions.push(ReadResponse::new(creation_time, public_key, version));
+            }
+            if versions.is_empty() {
+                Err(Error::NotFound(label.into()))
+            } else {
+                Ok(versions)
+            }
+        })
+    }
+
+    /// Destroys version `version` of the Ed25519 key `label`. This cannot be undone.
+    pub fn delete_ed25519_key(&self, label: &str, version: u32) -> Result<(), Error> {
+        self.with_session(|session| {
+            for class in &[ObjectClass::PUBLIC_KEY, ObjectClass::PRIVATE_KEY] {
+                session.destroy_object(find_key(session, *class, label, version)?)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Trims the key versions of the Ed25519 key `label`, so that at most MAX_NUM_KEY_VERSIONS
+    /// are held in the token, and returns the most recent (i.e., highest versioned) public key.
+    pub fn trim_key_versions(&self, label: &str) -> Result<Ed25519PublicKey, Error> {
+        let all_pub_keys = self.read_ed25519_key(label)?;
+        let newest_pub_key = all_pub_keys
+            .iter()
+            .max_by_key(|pub_key| pub_key.version)
+            .ok_or_else(|| Error::NotFound(label.into()))?;
+        let min_available_version = newest_pub_key
+            .version
+            .saturating_sub(MAX_NUM_KEY_VERSIONS - 1);
+        for pub_key in &all_pub_keys {
+            if pub_key.version < min_available_version {
+                self.delete_ed25519_key(label, pub_key.version)?;
+            }
+        }
+        Ok(newest_pub_key.value.clone())
+    }
+
+    /// Signs `data` with version `version` of the Ed25519 key `label`, inside the token.
+    pub fn sign_ed25519(
+        &self,
+        label: &str,
+        data: &[u8],
+        version: u32,
+    ) -> Result<Ed25519Signature, Error> {
+        self.with_session(|session| {
+            let private_key = find_key(session, ObjectClass::PRIVATE_KEY, label, version)?;
+            let signature = session.sign(&Mechanism::Eddsa, private_key, data)?;
+            Ok(Ed25519Signature::try_from(signature.as_slice())?)
+        })
+    }
+
+    /// Destroys all the key/value pairs and keys of the token. This cannot be undone and is only
+    /// intended for tests.
+    pub fn reset(&self) -> Result<(), Error> {
+        self.with_session(|session| {
+            let mut objects = session.find_objects(&[
+                Attribute::Class(ObjectClass::DATA),
+                Attribute::Application(DATA_APPLICATION.to_vec()),
+            ])?;
+            for class in &[ObjectClass::PUBLIC_KEY, ObjectClass::PRIVATE_KEY] {
+                objects.extend(session.find_objects(&[
+                    Attribute::Class(*class),
+                    Attribute::KeyType(KeyType::EC_EDWARDS),
+                ])?);
+            }
+            for object in objects {
+                session.destroy_object(object)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs `f` within a logged in session, opening it on first use. The session is dropped when
+    /// the token returns an error, so that a new one gets opened, e.g., once the token is back.
+    fn with_session<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&Session) -> Result<T, Error>,
+    {
+        let mut session = self.session.lock();
+        let result = match session.as_ref() {
+            Some(session) => f(session),
+            None => {
+                let new_session = self.open_session()?;
+                let result = f(&new_session);
+                *session = Some(new_session);
+                result
+            }
+        };
+        if let Err(Error::Pkcs11Error(_)) = result {
+            *session = None;
+        }
+        result
+    }
+
+    fn open_session(&self) -> Result<Session, Error> {
+        let pkcs11 = context(&self.library)?;
+        let slot = find_slot(&pkcs11, &self.token_label)?;
+        let session = pkcs11.open_rw_session(slot)?;
+        match session.login(UserType::User, Some(self.pin.as_str())) {
+            // The login state is shared by all the sessions of the process
+            Ok(()) | Err(cryptoki::error::Error::Pkcs11(RvError::UserAlreadyLoggedIn)) => {
+                Ok(session)
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Provides a simple wrapper for all read APIs.
+#[derive(Debug)]
+pub struct ReadResponse<T> {
+    /// Time since Unix Epoch in seconds.
+    pub creation_time: u64,
+    pub value: T,
+    pub version: u32,
+}
+
+impl<T> ReadResponse<T> {
+    pub fn new(creation_time: u64, value: T, version: u32) -> Self {
+        Self {
+            creation_time,
+            value,
+            version,
+        }
+    }
+}
+
+fn context(library: &Path) -> Result<Pkcs11, Error> {
+    let mut contexts = CONTEXTS.lock();
+    if let Some(pkcs11) = contexts.get(library) {
+        return Ok(pkcs11.clone());
+    }
+    let pkcs11 = Pkcs11::new(library)?;
+    pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    contexts.insert(library.to_path_buf(), pkcs11.clone());
+    Ok(pkcs11)
+}
+
+fn find_slot(pkcs11: &Pkcs11, token_label: &str) -> Result<Slot, Error> {
+    for slot in pkcs11.get_slots_with_token()? {
+        // Token labels are padded with blanks
+        if pkcs11.get_token_info(slot)?.label().trim_end() == token_label {
+            return Ok(slot);
+        }
+    }
+    Err(Error::TokenNotFound(token_label.into()))
+}
+
+fn data_template(label: &str) -> Vec<Attribute> {
+    vec![
+        Attribute::Class(ObjectClass::DATA),
+        Attribute::Application(DATA_APPLICATION.to_vec()),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ]
+}
+
+fn key_template(class: ObjectClass, label: &str) -> Vec<Attribute> {
+    vec![
+        Attribute::Class(class),
+        Attribute::KeyType(KeyType::EC_EDWARDS),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ]
+}
+
+fn public_key_template(label: &str, id: &[u8]) -> Vec<Attribute> {
+    let mut template = key_template(ObjectClass::PUBLIC_KEY, label);
+    template.extend(vec![
+        Attribute::Id(id.to_vec()),
+        Attribute::Token(true),
+        Attribute::Verify(true),
+        Attribute::EcParams(ED25519_EC_PARAMS.to_vec()),
+    ]);
+    template
+}
+
+fn private_key_template(label: &str, id: &[u8]) -> Vec<Attribute> {
+    let mut template = key_template(ObjectClass::PRIVATE_KEY, label);
+    template.extend(vec![
+        Attribute::Id(id.to_vec()),
+        Attribute::Token(true),
+        Attribute::Private(true),
+        Attribute::Sensitive(true),
+        Attribute::Extractable(false),
+        Attribute::Sign(true),
+        Attribute::EcParams(ED25519_EC_PARAMS.to_vec()),
+    ]);
+    template
+}
+
+fn find_key(
+    session: &Session,
+    class: ObjectClass,
+    label: &str,
+    version: u32,
+) -> Result<ObjectHandle, Error> {
+    for object in session.find_objects(&key_template(class, label))? {
+        if read_key_id(session, object)?.0 == version {
+            return Ok(object);
+        }
+    }
+    Err(Error::NotFound(format!("{}, version: {}", label, version)))
+}
+
+fn key_id(version: u32, creation_time: u64) -> Vec<u8> {
+    let mut id = version.to_be_bytes().to_vec();
+    id.extend_from_slice(&creation_time.to_be_bytes());
+    id
+}
+
+/// Returns the version and the creation time of a key.
+fn read_key_id(session: &Session, object: ObjectHandle) -> Result<(u32, u64), Error> {
+    match session
+        .get_attributes(object, &[AttributeType::Id])?
+        .into_iter()
+        .next()
+    {
+        Some(Attribute::Id(id)) if id.len() == KEY_ID_LENGTH => {
+            let mut version = [0u8; 4];
+            version.copy_from_slice(&id[..4]);
+            let mut creation_time = [0u8; 8];
+            creation_time.copy_from_slice(&id[4..]);
+            Ok((
+                u32::from_be_bytes(version),
+                u64::from_be_bytes(creation_time),
+            ))
+        }
+        _ => Err(Error::SerializationError("Invalid key id".into())),
+    }
+}
+
+fn read_public_key(session: &Session, object: ObjectHandle) -> Result<Ed25519PublicKey, Error> {
+    match session
+        .get_attributes(object, &[AttributeType::EcPoint])?
+        .into_iter()
+        .next()
+    {
+        Some(Attribute::EcPoint(point)) => decode_ec_point(&point),
+        _ => Err(Error::SerializationError("Missing public key".into())),
+    }
+}
+
+/// Encodes a public key as a DER OCTET STRING, as expected in CKA_EC_POINT.
+fn encode_ec_point(public_key: &Ed25519PublicKey) -> Vec<u8> {
+    let mut point = vec![DER_OCTET_STRING, ED25519_PUBLIC_KEY_LENGTH as u8];
+    point.extend_from_slice(&public_key.to_bytes());
+    point
+}
+
+/// Decodes CKA_EC_POINT, which some libraries return without its DER encoding.
+fn decode_ec_point(point: &[u8]) -> Result<Ed25519PublicKey, Error> {
+    let bytes = match point {
+        [DER_OCTET_STRING, length, bytes @ ..]
+            if bytes.len() == ED25519_PUBLIC_KEY_LENGTH && *length as usize == bytes.len() =>
+        {
+            bytes
+        }
+        _ => point,
+    };
+    Ok(Ed25519PublicKey::try_from(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dijets_crypto::{PrivateKey, Uniform};
+
+    #[test]
+    fn test_key_id() {
+        let id = key_id(3, 1_600_000_000);
+        assert_eq!(id.len(), KEY_ID_LENGTH);
+        assert_eq!(&id[..4], &[0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn test_ec_point() {
+        let public_key = Ed25519PrivateKey::generate_for_testing().public_key();
+        let point = encode_ec_point(&public_key);
+        assert_eq!(point.len(), ED25519_PUBLIC_KEY_LENGTH + 2);
+        assert_eq!(decode_ec_point(&point).unwrap(), public_key);
+        assert_eq!(decode_ec_point(&public_key.to_bytes()).unwrap(), public_key);
+        assert!(decode_ec_point(&point[1..]).is_err());
+    }
+}
@@ -0,0 +1,501 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoStorage, Error, GetResponse, KVStorage, PublicKeyResponse};
+use dijets_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::{CryptoHash, HashValue},
+};
+use dijets_infallible::Mutex;
+use dijets_logger::prelude::*;
+use dijets_time_service::{TimeService, TimeServiceTrait};
+use hmac::{Hmac, Mac, NewMac};
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The size of the blocks in which the end of a log file is read, looking for its last record.
+const READ_BLOCK_SIZE: u64 = 4096;
+
+/// The storage operations recorded in the audit log.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Get,
+    Set,
+    ResetAndClear,
+    CreateKey,
+    ExportPrivateKey,
+    ExportPrivateKeyForVersion,
+    ImportPrivateKey,
+    GetPublicKey,
+    GetPublicKeyPreviousVersion,
+    RotateKey,
+    Sign,
+    SignUsingVersion,
+}
+
+/// The outcome of an operation, with the error on failure.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Success,
+    Failure(String),
+}
+
+impl<T> From<&Result<T, Error>> for Outcome {
+    fn from(result: &Result<T, Error>) -> Self {
+        match result {
+            Ok(_) => Outcome::Success,
+            Err(e) => Outcome::Failure(e.to_string()),
+        }
+    }
+}
+
+/// A single entry of the audit log. Each record holds the MAC of the previous one, so that
+/// modifying, inserting or removing a record breaks the chain of all the following records. The
+/// MACs are keyed with a secret kept outside of the log, so that whoever can write the log cannot
+/// forge a new chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// The position of the record in the log, starting at 0.
+    pub sequence_number: u64,
+    /// Time since Unix Epoch in seconds.
+    pub timestamp: u64,
+    /// The identity of the process that accessed the storage.
+    pub identity: String,
+    pub operation: Operation,
+    /// The name of the key the operation was performed on.
+    pub key: String,
+    pub outcome: Outcome,
+    /// The MAC of the previous record, or zero for the first one.
+    pub previous_mac: HashValue,
+    /// The HMAC-SHA256 of all the other fields of the record, under the key of the log.
+    pub mac: HashValue,
+}
+
+impl AuditRecord {
+    fn compute_mac(&self, key: &[u8]) -> HashValue {
+        let fields = (
+            self.sequence_number,
+            self.timestamp,
+            &self.identity,
+            self.operation,
+            &self.key,
+            &self.outcome,
+            self.previous_mac,
+        );
+        let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC takes keys of any size");
+        mac.update(&bcs::to_bytes(&fields).expect("Unable to serialize audit record"));
+        HashValue::from_slice(mac.finalize().into_bytes().as_slice())
+            .expect("HMAC-SHA256 is 32 bytes long")
+    }
+}
+
+/// AuditSink persists audit records, e.g., to a file.
+pub trait AuditSink: Send {
+    /// Durably appends a record to the sink.
+    fn append(&mut self, record: &AuditRecord) -> Result<(), Error>;
+
+    /// Returns the last record appended to the sink, if any, so that the chain can be continued.
+    fn last(&self) -> Result<Option<AuditRecord>, Error>;
+}
+
+/// Appends records as lines of JSON to a file, which is synced after each record.
+pub struct FileAuditSink {
+    file_path: PathBuf,
+    file: File,
+}
+
+impl FileAuditSink {
+    /// Opens the file, and truncates the partial record left at its end if the process crashed
+    /// while appending it, so that the next record starts on a new line.
+    pub fn new(file_path: PathBuf) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)?;
+        let sink = Self { file_path, file };
+        let (end, _) = sink.last_line()?;
+        let len = sink.file.metadata()?.len();
+        if end < len {
+            warn!(
+                "Truncating partial audit record of {} bytes at the end of {}",
+                len - end,
+                sink.file_path.display()
+            );
+            sink.file.set_len(end)?;
+            sink.file.sync_data()?;
+        }
+        Ok(sink)
+    }
+
+    /// Returns the end of the last complete line of the file and its contents, if any, reading
+    /// the file backwards from its end.
+    fn last_line(&self) -> Result<(u64, Option<Vec<u8>>), Error> {
+        let mut file = File::open(&self.file_path)?;
+        let len = file.metadata()?.len();
+        // The bytes from `offset` to the end of the file
+        let mut tail = Vec::new();
+        let mut offset = len;
+        loop {
+            if let Some(newline) = tail.iter().rposition(|byte| *byte == b'\n') {
+                let start = tail[..newline].iter().rposition(|byte| *byte == b'\n');
+                if start.is_some() || offset == 0 {
+                    let start = start.map_or(0, |start| start + 1);
+                    let end = offset + newline as u64 + 1;
+                    return Ok((end, Some(tail[start..newline].to_vec())));
+                }
+            } else if offset == 0 {
+                return Ok((0, None));
+            }
+            let block_size = std::cmp::min(offset, READ_BLOCK_SIZE);
+            offset -= block_size;
+            let mut block = vec![0; block_size as usize];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut block)?;
+            block.extend_from_slice(&tail);
+            tail = block;
+        }
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn append(&mut self, record: &AuditRecord) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    fn last(&self) -> Result<Option<AuditRecord>, Error> {
+        match self.last_line()? {
+            (_, Some(line)) => Ok(Some(serde_json::from_slice(&line)?)),
+            (_, None) => Ok(None),
+        }
+    }
+}
+
+/// Keeps records in memory, which is only useful for testing. Clones share the same records.
+#[derive(Clone, Default)]
+pub struct InMemoryAuditSink {
+    records: Arc<Mutex<Vec<AuditRecord>>>,
+}
+
+impl InMemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records.lock().clone()
+    }
+}
+
+impl AuditSink for InMemoryAuditSink {
+    fn append(&mut self, record: &AuditRecord) -> Result<(), Error> {
+        self.records.lock().push(record.clone());
+        Ok(())
+    }
+
+    fn last(&self) -> Result<Option<AuditRecord>, Error> {
+        Ok(self.records.lock().last().cloned())
+    }
+}
+
+/// A hash chain of audit records over a sink. A log must have a single writer, so it is shared
+/// by all the storages audited into the same sink.
+pub struct AuditLog {
+    sink: Box<dyn AuditSink>,
+    /// The key of the MACs of the records.
+    key: Vec<u8>,
+    time_service: TimeService,
+    /// The sequence number and the previous MAC of the next record.
+    next: (u64, HashValue),
+}
+
+/// The file logs of this process, shared so that storages audited into the same file don't fork
+/// its chain.
+static FILE_LOGS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<AuditLog>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+impl AuditLog {
+    /// Continues the chain of the records already in the sink, whose last record must have been
+    /// written with the same key.
+    pub fn new(sink: Box<dyn AuditSink>, key: Vec<u8>) -> Result<Self, Error> {
+        Self::new_with_time_service(sink, key, TimeService::real())
+    }
+
+    fn new_with_time_service(
+        sink: Box<dyn AuditSink>,
+        key: Vec<u8>,
+        time_service: TimeService,
+    ) -> Result<Self, Error> {
+        if key.is_empty() {
+            return Err(Error::InternalError("Audit log key is empty".into()));
+        }
+        let next = match sink.last()? {
+            Some(record) if record.mac != record.compute_mac(&key) => {
+                return Err(Error::InternalError(format!(
+                    "Last audit record {} was not written with the key of the log",
+                    record.sequence_number
+                )))
+            }
+            Some(record) => (record.sequence_number + 1, record.mac),
+            None => (0, HashValue::zero()),
+        };
+        Ok(Self {
+            sink,
+            key,
+            time_service,
+            next,
+        })
+    }
+
+    /// Returns the log writing to `file_path`, shared with any other user of that file within
+    /// this process.
+    pub fn file(file_path: &Path, key: Vec<u8>) -> Result<Arc<Mutex<Self>>, Error> {
+        // The canonical path requires the file to exist
+        let file_path = match file_path.canonicalize() {
+            Ok(file_path) => file_path,
+            Err(_) => {
+                FileAuditSink::new(file_path.to_path_buf())?;
+                file_path.canonicalize()?
+            }
+        };
+        let mut logs = FILE_LOGS.lock();
+        if let Some(log) = logs.get(&file_path) {
+            if log.lock().key != key {
+                return Err(Error::InternalError(format!(
+                    "Audit log {} is already open with another key",
+                    file_path.display()
+                )));
+            }
+            return Ok(log.clone());
+        }
+        let log = Arc::new(Mutex::new(Self::new(
+            Box::new(FileAuditSink::new(file_path.clone())?),
+            key,
+        )?));
+        logs.insert(file_path, log.clone());
+        Ok(log)
+    }
+
+    /// Appends a record to the chain and returns it.
+    pub fn append(
+        &mut self,
+        identity: &str,
+        operation: Operation,
+        key: &str,
+        outcome: Outcome,
+    ) -> Result<AuditRecord, Error> {
+        let (sequence_number, previous_mac) = self.next;
+        let mut record = AuditRecord {
+            sequence_number,
+            timestamp: self.time_service.now_secs(),
+            identity: identity.into(),
+            operation,
+            key: key.into(),
+            outcome,
+            previous_mac,
+            mac: HashValue::zero(),
+        };
+        record.mac = record.compute_mac(&self.key);
+        self.sink
+            .append(&record)
+            .map_err(|e| Error::InternalError(format!("Unable to write audit record: {}", e)))?;
+        self.next = (sequence_number + 1, record.mac);
+        Ok(record)
+    }
+}
+
+/// Reads all the records of a file written by a FileAuditSink.
+pub fn read_audit_log(file_path: &Path) -> Result<Vec<AuditRecord>, Error> {
+    let contents = fs::read_to_string(file_path)?;
+    contents
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| e.into()))
+        .collect()
+}
+
+/// Verifies that `records` form an unbroken chain starting from the first record of a log, with
+/// MACs under `key`, and returns the MAC of the last record, if any. As the chain cannot tell
+/// whether records were removed from its end, this MAC should be compared to one recorded
+/// elsewhere.
+pub fn verify_audit_log(records: &[AuditRecord], key: &[u8]) -> Result<Option<HashValue>, Error> {
+    let mut next = (0, HashValue::zero());
+    for record in records {
+        if record.sequence_number != next.0 {
+            return Err(Error::InternalError(format!(
+                "Audit record {} found where record {} was expected",
+                record.sequence_number, next.0
+            )));
+        }
+        if record.previous_mac != next.1 {
+            return Err(Error::InternalError(format!(
+                "Audit record {} does not chain to the previous record",
+                record.sequence_number
+            )));
+        }
+        if record.mac != record.compute_mac(key) {
+            return Err(Error::InternalError(format!(
+                "Audit record {} has an invalid MAC",
+                record.sequence_number
+            )));
+        }
+        next = (record.sequence_number + 1, record.mac);
+    }
+    Ok(records.last().map(|_| next.1))
+}
+
+/// This provides a light wrapper around storages, analogous to Namespaced, that records every
+/// operation on the inner storage in an audit log: which identity performed which operation on
+/// which key and its outcome. If the record cannot be written, the operation fails and its result
+/// is withheld, so that no key material is returned without a trace.
+pub struct Audited<S> {
+    identity: String,
+    log: Arc<Mutex<AuditLog>>,
+    inner: S,
+}
+
+impl<S> Audited<S> {
+    pub fn new<I: Into<String>>(identity: I, log: Arc<Mutex<AuditLog>>, inner: S) -> Self {
+        Self {
+            identity: identity.into(),
+            log,
+            inner,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn audit<T>(
+        &self,
+        operation: Operation,
+        key: &str,
+        result: Result<T, Error>,
+    ) -> Result<T, Error> {
+        self.log
+            .lock()
+            .append(&self.identity, operation, key, Outcome::from(&result))?;
+        result
+    }
+}
+
+impl<S: KVStorage> KVStorage for Audited<S> {
+    fn available(&self) -> Result<(), Error> {
+        self.inner.available()
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<T>, Error> {
+        self.audit(Operation::Get, key, self.inner.get(key))
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), Error> {
+        let result = self.inner.set(key, value);
+        self.audit(Operation::Set, key, result)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        let result = self.inner.reset_and_clear();
+        self.audit(Operation::ResetAndClear, "", result)
+    }
+}
+
+impl<S: CryptoStorage> CryptoStorage for Audited<S> {
+    fn create_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let result = self.inner.create_key(name);
+        self.audit(Operation::CreateKey, name, result)
+    }
+
+    fn export_private_key(&self, name: &str) -> Result<Ed25519PrivateKey, Error> {
+        self.audit(
+            Operation::ExportPrivateKey,
+            name,
+            self.inner.export_private_key(name),
+        )
+    }
+
+    fn import_private_key(&mut self, name: &str, key: Ed25519PrivateKey) -> Result<(), Error> {
+        let result = self.inner.import_private_key(name, key);
+        self.audit(Operation::ImportPrivateKey, name, result)
+    }
+
+    fn export_private_key_for_version(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+    ) -> Result<Ed25519PrivateKey, Error> {
+        self.audit(
+            Operation::ExportPrivateKeyForVersion,
+            name,
+            self.inner.export_private_key_for_version(name, version),
+        )
+    }
+
+    fn get_public_key(&self, name: &str) -> Result<PublicKeyResponse, Error> {
+        self.audit(
+            Operation::GetPublicKey,
+            name,
+            self.inner.get_public_key(name),
+        )
+    }
+
+    fn get_public_key_previous_version(&self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        self.audit(
+            Operation::GetPublicKeyPreviousVersion,
+            name,
+            self.inner.get_public_key_previous_version(name),
+        )
+    }
+
+    fn rotate_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let result = self.inner.rotate_key(name);
+        self.audit(Operation::RotateKey, name, result)
+    }
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        self.audit(Operation::Sign, name, self.inner.sign(name, message))
+    }
+
+    fn sign_using_version<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        self.audit(
+            Operation::SignUsingVersion,
+            name,
+            self.inner.sign_using_version(name, version, message),
+        )
+    }
+}
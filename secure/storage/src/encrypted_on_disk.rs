@@ -0,0 +1,254 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    from_base64, to_base64, CryptoKVStorage, Error, GetResponse, KVStorage, OnDiskStorage,
+};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead, Payload},
+    Aes256Gcm,
+};
+use dijets_temppath::TempPath;
+use dijets_time_service::{TimeService, TimeServiceTrait};
+use rand::{rngs::OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The version of the file format, bumped on any change to the header or the encryption.
+const VERSION: u8 = 1;
+/// The scrypt parameters used for new files, as recommended for interactive logins.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const SALT_LENGTH: usize = 32;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// EncryptedOnDiskStorage is a key value store persisted to a single file on the local
+/// filesystem, like OnDiskStorage, but the file is sealed with AES-256-GCM. The encryption key is
+/// derived with scrypt from a secret, e.g., a passphrase or the contents of a key file, and a
+/// random salt kept in the file header. Every write uses a fresh nonce and atomically replaces
+/// the file, so a crash never leaves a partially written file behind. As with OnDiskStorage, it is
+/// intended for single threads (or must be wrapped by a Arc<RwLock<>>) and it holds copies of all
+/// key material in memory while reading and writing the file.
+pub struct EncryptedOnDiskStorage {
+    file_path: PathBuf,
+    temp_path: TempPath,
+    time_service: TimeService,
+    header: Header,
+    key: [u8; KEY_LENGTH],
+}
+
+/// The parameters needed to derive the encryption key from the secret. They are authenticated as
+/// the associated data of the ciphertext.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Header {
+    version: u8,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    salt: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct EncryptedFile {
+    header: Header,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    nonce: Vec<u8>,
+    #[serde(serialize_with = "to_base64", deserialize_with = "from_base64")]
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedOnDiskStorage {
+    /// Opens the storage at `file_path` with the encryption key derived from `secret`, or creates
+    /// an empty one if the file does not exist. Fails if the secret does not decrypt the file.
+    pub fn new(file_path: PathBuf, secret: &[u8]) -> Result<Self, Error> {
+        Self::new_with_time_service(file_path, secret, TimeService::real())
+    }
+
+    fn new_with_time_service(
+        file_path: PathBuf,
+        secret: &[u8],
+        time_service: TimeService,
+    ) -> Result<Self, Error> {
+        // The parent will be one when only a filename is supplied. Therefore use the current
+        // working directory provided by PathBuf::new().
+        let file_dir = file_path
+            .parent()
+            .map_or(PathBuf::new(), |p| p.to_path_buf());
+
+        let existing = Self::read_file(&file_path)?;
+        let header = match &existing {
+            Some(file) => file.header.clone(),
+            None => {
+                let mut salt = vec![0; SALT_LENGTH];
+                OsRng.fill_bytes(&mut salt);
+                Header {
+                    version: VERSION,
+                    log_n: SCRYPT_LOG_N,
+                    r: SCRYPT_R,
+                    p: SCRYPT_P,
+                    salt,
+                }
+            }
+        };
+        let key = derive_key(secret, &header)?;
+
+        let storage = Self {
+            file_path,
+            temp_path: TempPath::new_with_temp_dir(file_dir),
+            time_service,
+            header,
+            key,
+        };
+        match existing {
+            // Decrypting the file upfront catches a wrong secret before any use of the storage
+            Some(file) => storage.decrypt(&file).map(|_| ())?,
+            None => storage.write(&HashMap::new())?,
+        }
+        Ok(storage)
+    }
+
+    /// Creates a new storage at `file_path` holding all the data of `plaintext`, including the
+    /// last update times of its values. The plaintext storage is left untouched.
+    pub fn migrate_from(
+        plaintext: &OnDiskStorage,
+        file_path: PathBuf,
+        secret: &[u8],
+    ) -> Result<Self, Error> {
+        if file_path.exists() {
+            return Err(Error::InternalError(format!(
+                "Unable to migrate, {} already exists",
+                file_path.display()
+            )));
+        }
+        let storage = Self::new(file_path, secret)?;
+        storage.write(&plaintext.read()?)?;
+        Ok(storage)
+    }
+
+    fn read_file(file_path: &Path) -> Result<Option<EncryptedFile>, Error> {
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read(file_path)?;
+        let file: EncryptedFile = serde_json::from_slice(&contents)?;
+        if file.header.version != VERSION {
+            return Err(Error::InternalError(format!(
+                "Unsupported encrypted storage version: {}",
+                file.header.version
+            )));
+        }
+        Ok(Some(file))
+    }
+
+    fn decrypt(&self, file: &EncryptedFile) -> Result<HashMap<String, Value>, Error> {
+        if file.header != self.header {
+            return Err(Error::InternalError(
+                "Encrypted storage was re-keyed by another writer".into(),
+            ));
+        }
+        if file.nonce.len() != NONCE_LENGTH {
+            return Err(Error::SerializationError(format!(
+                "Invalid nonce length: {}",
+                file.nonce.len()
+            )));
+        }
+        let aad = bcs::to_bytes(&file.header)?;
+        let plaintext = Aes256Gcm::new(GenericArray::from_slice(&self.key))
+            .decrypt(
+                GenericArray::from_slice(&file.nonce),
+                Payload {
+                    msg: &file.ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| {
+                Error::InternalError(
+                    "Unable to decrypt storage, the secret is wrong or the file is corrupted"
+                        .into(),
+                )
+            })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn read(&self) -> Result<HashMap<String, Value>, Error> {
+        let file = Self::read_file(&self.file_path)?.ok_or_else(|| {
+            Error::InternalError(format!("{} is missing", self.file_path.display()))
+        })?;
+        self.decrypt(&file)
+    }
+
+    fn write(&self, data: &HashMap<String, Value>) -> Result<(), Error> {
+        let plaintext = serde_json::to_vec(data)?;
+        let aad = bcs::to_bytes(&self.header)?;
+        let mut nonce = vec![0; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(&self.key))
+            .encrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| Error::InternalError(format!("Unable to encrypt storage: {}", e)))?;
+        let contents = serde_json::to_vec(&EncryptedFile {
+            header: self.header.clone(),
+            nonce,
+            ciphertext,
+        })?;
+
+        let mut file = File::create(self.temp_path.path())?;
+        file.write_all(&contents)?;
+        file.sync_all()?;
+        fs::rename(&self.temp_path, &self.file_path)?;
+        Ok(())
+    }
+}
+
+fn derive_key(secret: &[u8], header: &Header) -> Result<[u8; KEY_LENGTH], Error> {
+    let params = scrypt::Params::new(header.log_n, header.r, header.p)
+        .map_err(|e| Error::InternalError(format!("Invalid scrypt parameters: {}", e)))?;
+    let mut key = [0; KEY_LENGTH];
+    scrypt::scrypt(secret, &header.salt, &params, &mut key)
+        .map_err(|e| Error::InternalError(format!("Unable to derive key: {}", e)))?;
+    Ok(key)
+}
+
+impl KVStorage for EncryptedOnDiskStorage {
+    fn available(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get<V: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<V>, Error> {
+        let mut data = self.read()?;
+        data.remove(key)
+            .ok_or_else(|| Error::KeyNotSet(key.to_string()))
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.into()))
+    }
+
+    fn set<V: Serialize>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let mut data = self.read()?;
+        data.insert(
+            key.to_string(),
+            serde_json::to_value(&GetResponse::new(value, now))?,
+        );
+        self.write(&data)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        self.write(&HashMap::new())
+    }
+}
+
+impl CryptoKVStorage for EncryptedOnDiskStorage {}
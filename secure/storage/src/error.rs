@@ -63,6 +63,25 @@ impl From<dijets_vault_client::Error> for Error {
     }
 }
 
+impl From<dijets_pkcs11_client::Error> for Error {
+    fn from(error: dijets_pkcs11_client::Error) -> Self {
+        match error {
+            dijets_pkcs11_client::Error::NotFound(key) => Self::KeyNotSet(key),
+            dijets_pkcs11_client::Error::PermissionDenied(_) => Self::PermissionDenied,
+            _ => Self::InternalError(format!("{}", error)),
+        }
+    }
+}
+
+impl From<dijets_git_client::Error> for Error {
+    fn from(error: dijets_git_client::Error) -> Self {
+        match error {
+            dijets_git_client::Error::NotFound(key) => Self::KeyNotSet(key),
+            _ => Self::InternalError(format!("{}", error)),
+        }
+    }
+}
+
 impl From<dijets_github_client::Error> for Error {
     fn from(error: dijets_github_client::Error) -> Self {
         match error {
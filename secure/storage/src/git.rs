@@ -0,0 +1,49 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoKVStorage, Error, GetResponse, KVStorage};
+use dijets_git_client::Client;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/// GitStorage leverages a git repository to provide a file system approach to key / value
+/// storage, like GitHubStorage, but without a hosted service: the repository is local and is
+/// optionally synchronized with a remote repository, e.g., a bare repository on shared or
+/// removable media. Each set is a commit, and the last update of a value is the time of the last
+/// commit that changed it. This is not intended for storing private data but for organizing
+/// public data.
+pub struct GitStorage {
+    client: Client,
+}
+
+impl GitStorage {
+    pub fn new(path: PathBuf, remote: Option<String>, branch: String) -> Self {
+        Self {
+            client: Client::new(path, remote, branch),
+        }
+    }
+}
+
+impl KVStorage for GitStorage {
+    fn available(&self) -> Result<(), Error> {
+        Ok(self.client.available()?)
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<T>, Error> {
+        let (data, last_update) = self.client.get_file(key)?;
+        let value = serde_json::from_slice(&data)?;
+        Ok(GetResponse::new(value, last_update))
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), Error> {
+        let data = serde_json::to_vec_pretty(&value)?;
+        Ok(self.client.put(key, &data)?)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        Ok(self.client.delete_all()?)
+    }
+}
+
+impl CryptoKVStorage for GitStorage {}
@@ -3,27 +3,38 @@
 
 #![forbid(unsafe_code)]
 
+mod audited;
 mod crypto_kv_storage;
 mod crypto_storage;
+mod encrypted_on_disk;
 mod error;
+mod git;
 mod github;
 mod in_memory;
 mod kv_storage;
 mod namespaced;
 mod on_disk;
+mod pkcs11;
 mod policy;
 mod storage;
 mod vault;
 
 pub use crate::{
+    audited::{
+        read_audit_log, verify_audit_log, AuditLog, AuditRecord, AuditSink, Audited, FileAuditSink,
+        InMemoryAuditSink, Operation, Outcome,
+    },
     crypto_kv_storage::CryptoKVStorage,
     crypto_storage::{CryptoStorage, PublicKeyResponse},
+    encrypted_on_disk::EncryptedOnDiskStorage,
     error::Error,
+    git::GitStorage,
     github::GitHubStorage,
     in_memory::InMemoryStorage,
     kv_storage::{GetResponse, KVStorage},
     namespaced::Namespaced,
     on_disk::OnDiskStorage,
+    pkcs11::Pkcs11Storage,
     policy::{Capability, Identity, Permission, Policy},
     storage::Storage,
     vault::VaultStorage,
@@ -0,0 +1,179 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoStorage, Error, GetResponse, KVStorage, PublicKeyResponse};
+use dijets_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+};
+use dijets_pkcs11_client::{Client, ReadResponse};
+use dijets_time_service::{TimeService, TimeServiceTrait};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/// Pkcs11Storage utilizes a PKCS#11 token, e.g., a hardware security module, for maintaining data
+/// and keys for Dijets. Ed25519 keys are generated by the token and never leave it: signing
+/// happens within the token and private keys cannot be exported. Each rotation generates a new
+/// key version, and older versions are trimmed in the same way as in VaultStorage. Key/value
+/// pairs are stored as data objects on the token.
+pub struct Pkcs11Storage {
+    client: Client,
+    time_service: TimeService,
+}
+
+impl Pkcs11Storage {
+    pub fn new(library: PathBuf, token_label: String, pin: String) -> Self {
+        Self {
+            client: Client::new(library, token_label, pin),
+            time_service: TimeService::real(),
+        }
+    }
+
+    fn key_versions(&self, name: &str) -> Result<Vec<ReadResponse<Ed25519PublicKey>>, Error> {
+        Ok(self.client.read_ed25519_key(name)?)
+    }
+
+    fn latest_key_version(&self, name: &str) -> Result<ReadResponse<Ed25519PublicKey>, Error> {
+        self.key_versions(name)?
+            .into_iter()
+            .max_by_key(|pubkey| pubkey.version)
+            .ok_or_else(|| Error::KeyNotSet(name.into()))
+    }
+
+    fn key_version(&self, name: &str, version: &Ed25519PublicKey) -> Result<u32, Error> {
+        let pubkeys = self.key_versions(name)?;
+        let pubkey = pubkeys.iter().find(|pubkey| version == &pubkey.value);
+        Ok(pubkey
+            .ok_or_else(|| Error::KeyVersionNotFound(name.into(), version.to_string()))?
+            .version)
+    }
+
+    fn sign_version<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        version: u32,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let mut bytes = <T::Hasher as dijets_crypto::hash::CryptoHasher>::seed().to_vec();
+        bcs::serialize_into(&mut bytes, &message).map_err(|e| {
+            Error::InternalError(format!(
+                "Serialization of signable material should not fail, yet returned Error:{}",
+                e
+            ))
+        })?;
+        Ok(self.client.sign_ed25519(name, &bytes, version)?)
+    }
+}
+
+impl KVStorage for Pkcs11Storage {
+    fn available(&self) -> Result<(), Error> {
+        Ok(self.client.available()?)
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<T>, Error> {
+        let response = self.client.read_data(key)?;
+        serde_json::from_slice(&response).map_err(|e| e.into())
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let value = serde_json::to_vec(&GetResponse::new(value, now))?;
+        Ok(self.client.write_data(key, &value)?)
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        Ok(self.client.reset()?)
+    }
+}
+
+impl CryptoStorage for Pkcs11Storage {
+    fn create_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        match self.get_public_key(name) {
+            Ok(_) => return Err(Error::KeyAlreadyExists(name.into())),
+            Err(Error::KeyNotSet(_)) => (/* Expected this for new keys! */),
+            Err(e) => return Err(e),
+        }
+
+        Ok(self.client.create_ed25519_key(name, 0)?)
+    }
+
+    /// Private keys never leave the token.
+    fn export_private_key(&self, _name: &str) -> Result<Ed25519PrivateKey, Error> {
+        Err(Error::PermissionDenied)
+    }
+
+    /// Private keys never leave the token.
+    fn export_private_key_for_version(
+        &self,
+        _name: &str,
+        _version: Ed25519PublicKey,
+    ) -> Result<Ed25519PrivateKey, Error> {
+        Err(Error::PermissionDenied)
+    }
+
+    fn import_private_key(&mut self, name: &str, key: Ed25519PrivateKey) -> Result<(), Error> {
+        match self.get_public_key(name) {
+            Ok(_) => return Err(Error::KeyAlreadyExists(name.into())),
+            Err(Error::KeyNotSet(_)) => (/* Expected this for new keys! */),
+            Err(e) => return Err(e),
+        }
+
+        Ok(self.client.import_ed25519_key(name, 0, &key)?)
+    }
+
+    fn get_public_key(&self, name: &str) -> Result<PublicKeyResponse, Error> {
+        let last_key = self.latest_key_version(name)?;
+        Ok(PublicKeyResponse {
+            last_update: last_key.creation_time,
+            public_key: last_key.value,
+        })
+    }
+
+    fn get_public_key_previous_version(&self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let pubkeys = self.key_versions(name)?;
+        let highest_version = pubkeys.iter().map(|pubkey| pubkey.version).max();
+        match highest_version {
+            Some(version) => {
+                let pubkey = pubkeys
+                    .iter()
+                    .find(|pubkey| Some(pubkey.version) == version.checked_sub(1));
+                Ok(pubkey
+                    .ok_or_else(|| {
+                        Error::KeyVersionNotFound(name.into(), "previous version".into())
+                    })?
+                    .value
+                    .clone())
+            }
+            None => Err(Error::KeyVersionNotFound(
+                name.into(),
+                "previous version".into(),
+            )),
+        }
+    }
+
+    fn rotate_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let last_key = self.latest_key_version(name)?;
+        self.client.create_ed25519_key(name, last_key.version + 1)?;
+        Ok(self.client.trim_key_versions(name)?)
+    }
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let last_key = self.latest_key_version(name)?;
+        self.sign_version(name, last_key.version, message)
+    }
+
+    fn sign_using_version<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let vers = self.key_version(name, &version)?;
+        self.sign_version(name, vers, message)
+    }
+}
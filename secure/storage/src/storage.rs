@@ -1,8 +1,9 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    CryptoStorage, Error, GetResponse, GitHubStorage, InMemoryStorage, KVStorage, Namespaced,
-    OnDiskStorage, PublicKeyResponse, VaultStorage,
+    Audited, CryptoStorage, EncryptedOnDiskStorage, Error, GetResponse, GitHubStorage, GitStorage,
+    InMemoryStorage, KVStorage, Namespaced, OnDiskStorage, Pkcs11Storage, PublicKeyResponse,
+    VaultStorage,
 };
 use dijets_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
 use enum_dispatch::enum_dispatch;
@@ -17,10 +18,14 @@ use serde::{de::DeserializeOwned, Serialize};
 #[enum_dispatch(KVStorage, CryptoStorage)]
 pub enum Storage {
     GitHubStorage(GitHubStorage),
+    GitStorage(GitStorage),
     VaultStorage(VaultStorage),
     InMemoryStorage(InMemoryStorage),
     NamespacedStorage(Namespaced<Box<Storage>>),
+    AuditedStorage(Audited<Box<Storage>>),
     OnDiskStorage(OnDiskStorage),
+    EncryptedOnDiskStorage(EncryptedOnDiskStorage),
+    Pkcs11Storage(Pkcs11Storage),
 }
 
 impl KVStorage for Box<Storage> {
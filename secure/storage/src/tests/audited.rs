@@ -0,0 +1,182 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    read_audit_log, tests::suite, verify_audit_log, AuditLog, AuditRecord, AuditSink, Audited,
+    CryptoStorage, Error, FileAuditSink, InMemoryAuditSink, InMemoryStorage, KVStorage, Operation,
+    Outcome, Storage,
+};
+use dijets_infallible::Mutex;
+use dijets_temppath::TempPath;
+use std::{fs::OpenOptions, io::Write, sync::Arc};
+
+const IDENTITY: &str = "safety_rules";
+const KEY: &[u8] = b"audit log key";
+
+fn audited(sink: &InMemoryAuditSink) -> Audited<InMemoryStorage> {
+    let log = AuditLog::new(Box::new(sink.clone()), KEY.to_vec()).unwrap();
+    Audited::new(IDENTITY, Arc::new(Mutex::new(log)), InMemoryStorage::new())
+}
+
+#[test]
+fn audited_storage() {
+    let sink = InMemoryAuditSink::new();
+    let mut storage = Storage::from(Audited::new(
+        IDENTITY,
+        Arc::new(Mutex::new(
+            AuditLog::new(Box::new(sink.clone()), KEY.to_vec()).unwrap(),
+        )),
+        Box::new(Storage::from(InMemoryStorage::new())),
+    ));
+    suite::execute_all_storage_tests(&mut storage);
+
+    let records = sink.records();
+    assert!(!records.is_empty());
+    assert!(verify_audit_log(&records, KEY).unwrap().is_some());
+}
+
+#[test]
+fn audited_records() {
+    let sink = InMemoryAuditSink::new();
+    let mut storage = audited(&sink);
+
+    storage.create_key("consensus").unwrap();
+    storage.export_private_key("consensus").unwrap();
+    storage.get::<u64>("missing").unwrap_err();
+
+    let records = sink.records();
+    let operations: Vec<_> = records
+        .iter()
+        .map(|record| (record.operation, record.key.as_str(), &record.outcome))
+        .collect();
+    assert_eq!(
+        operations,
+        vec![
+            (Operation::CreateKey, "consensus", &Outcome::Success),
+            (Operation::ExportPrivateKey, "consensus", &Outcome::Success),
+            (
+                Operation::Get,
+                "missing",
+                &Outcome::Failure(Error::KeyNotSet("missing".into()).to_string())
+            ),
+        ]
+    );
+    assert!(records.iter().all(|record| record.identity == IDENTITY));
+    verify_audit_log(&records, KEY).unwrap();
+}
+
+#[test]
+fn audited_tampering() {
+    let sink = InMemoryAuditSink::new();
+    let mut storage = audited(&sink);
+    for value in 0..4u64 {
+        storage.set("key", value).unwrap();
+    }
+    let records = sink.records();
+    verify_audit_log(&records, KEY).unwrap();
+
+    // Modified record
+    let mut modified = records.clone();
+    modified[1].operation = Operation::Get;
+    verify_audit_log(&modified, KEY).unwrap_err();
+
+    // Removed record
+    let mut removed = records.clone();
+    removed.remove(1);
+    verify_audit_log(&removed, KEY).unwrap_err();
+
+    // Removed first record
+    verify_audit_log(&records[1..], KEY).unwrap_err();
+
+    // Modified last record
+    let mut modified = records.clone();
+    modified[3].key = "other_key".into();
+    verify_audit_log(&modified, KEY).unwrap_err();
+
+    // Chain rewritten without the key of the log
+    let other_sink = InMemoryAuditSink::new();
+    let mut other = AuditLog::new(Box::new(other_sink.clone()), b"other key".to_vec()).unwrap();
+    for record in &records {
+        other
+            .append(
+                &record.identity,
+                Operation::Get,
+                &record.key,
+                record.outcome.clone(),
+            )
+            .unwrap();
+    }
+    verify_audit_log(&other_sink.records(), KEY).unwrap_err();
+
+    // Nor can the chain be continued with another key
+    AuditLog::new(Box::new(sink), b"other key".to_vec()).unwrap_err();
+}
+
+#[test]
+fn audited_file() {
+    let path = TempPath::new();
+    let log = AuditLog::file(path.path(), KEY.to_vec()).unwrap();
+    let mut storage = Audited::new(IDENTITY, log, InMemoryStorage::new());
+    storage.set("key", 0u64).unwrap();
+
+    // Storages audited into the same file share its chain, and its key
+    AuditLog::file(path.path(), b"other key".to_vec()).unwrap_err();
+    let log = AuditLog::file(path.path(), KEY.to_vec()).unwrap();
+    let mut other = Audited::new("key_manager", log, InMemoryStorage::new());
+    other.set("key", 1u64).unwrap();
+    storage.set("key", 2u64).unwrap();
+
+    let records = read_audit_log(path.path()).unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1].identity, "key_manager");
+    verify_audit_log(&records, KEY).unwrap();
+}
+
+#[test]
+fn audited_file_partial_record() {
+    let path = TempPath::new();
+    let sink = FileAuditSink::new(path.path().to_path_buf()).unwrap();
+    let mut log = AuditLog::new(Box::new(sink), KEY.to_vec()).unwrap();
+    // Enough records for the last one to be found across several blocks
+    for _ in 0..50 {
+        log.append(IDENTITY, Operation::Get, "key", Outcome::Success)
+            .unwrap();
+    }
+    let records = read_audit_log(path.path()).unwrap();
+
+    // A crash while appending leaves a partial record, which is truncated when the log is opened
+    let mut file = OpenOptions::new().append(true).open(path.path()).unwrap();
+    file.write_all(b"{\"sequence_number\":50,").unwrap();
+    let sink = FileAuditSink::new(path.path().to_path_buf()).unwrap();
+    assert_eq!(sink.last().unwrap().as_ref(), records.last());
+    let mut log = AuditLog::new(Box::new(sink), KEY.to_vec()).unwrap();
+    log.append(IDENTITY, Operation::Get, "key", Outcome::Success)
+        .unwrap();
+
+    let records = read_audit_log(path.path()).unwrap();
+    assert_eq!(records.len(), 51);
+    verify_audit_log(&records, KEY).unwrap();
+}
+
+struct FailingSink;
+
+impl AuditSink for FailingSink {
+    fn append(&mut self, _record: &AuditRecord) -> Result<(), Error> {
+        Err(Error::InternalError("Sink is full".into()))
+    }
+
+    fn last(&self) -> Result<Option<AuditRecord>, Error> {
+        Ok(None)
+    }
+}
+
+#[test]
+fn audited_failing_sink() {
+    let mut inner = InMemoryStorage::new();
+    inner.create_key("consensus").unwrap();
+    let log = AuditLog::new(Box::new(FailingSink), KEY.to_vec()).unwrap();
+    let storage = Audited::new(IDENTITY, Arc::new(Mutex::new(log)), inner);
+
+    // Key material is never returned without a trace
+    storage.export_private_key("consensus").unwrap_err();
+}
@@ -0,0 +1,88 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    tests::suite, CryptoStorage, EncryptedOnDiskStorage, Error, KVStorage, OnDiskStorage, Storage,
+};
+use dijets_temppath::TempPath;
+
+const SECRET: &[u8] = b"correct horse battery staple";
+
+#[test]
+fn encrypted_on_disk() {
+    let path = TempPath::new();
+    let mut storage =
+        Storage::from(EncryptedOnDiskStorage::new(path.path().to_path_buf(), SECRET).unwrap());
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn encrypted_on_disk_reopen() {
+    let path = TempPath::new();
+    let mut storage = EncryptedOnDiskStorage::new(path.path().to_path_buf(), SECRET).unwrap();
+    storage.set("key", 5u64).unwrap();
+    let public_key = storage.create_key("consensus").unwrap();
+
+    let contents = std::fs::read_to_string(path.path()).unwrap();
+    assert!(!contents.contains("consensus"));
+
+    let storage = EncryptedOnDiskStorage::new(path.path().to_path_buf(), SECRET).unwrap();
+    assert_eq!(storage.get::<u64>("key").unwrap().value, 5);
+    assert_eq!(
+        storage.get_public_key("consensus").unwrap().public_key,
+        public_key
+    );
+}
+
+#[test]
+fn encrypted_on_disk_wrong_secret() {
+    let path = TempPath::new();
+    EncryptedOnDiskStorage::new(path.path().to_path_buf(), SECRET).unwrap();
+    assert!(EncryptedOnDiskStorage::new(path.path().to_path_buf(), b"wrong secret").is_err());
+}
+
+#[test]
+fn encrypted_on_disk_tampered() {
+    let path = TempPath::new();
+    let mut storage = EncryptedOnDiskStorage::new(path.path().to_path_buf(), SECRET).unwrap();
+    storage.set("key", 5u64).unwrap();
+
+    let mut file: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(path.path()).unwrap()).unwrap();
+    let mut ciphertext = base64::decode(file["ciphertext"].as_str().unwrap()).unwrap();
+    ciphertext[0] ^= 1;
+    file["ciphertext"] = base64::encode(ciphertext).into();
+    std::fs::write(path.path(), serde_json::to_vec(&file).unwrap()).unwrap();
+
+    assert!(matches!(
+        storage.get::<u64>("key"),
+        Err(Error::InternalError(_))
+    ));
+}
+
+#[test]
+fn encrypted_on_disk_migrate() {
+    let plaintext_path = TempPath::new();
+    let mut plaintext = OnDiskStorage::new(plaintext_path.path().to_path_buf());
+    plaintext.set("key", 5u64).unwrap();
+    let public_key = plaintext.create_key("consensus").unwrap();
+    let last_update = plaintext.get::<u64>("key").unwrap().last_update;
+
+    let path = TempPath::new();
+    let storage =
+        EncryptedOnDiskStorage::migrate_from(&plaintext, path.path().to_path_buf(), SECRET)
+            .unwrap();
+    let response = storage.get::<u64>("key").unwrap();
+    assert_eq!(response.value, 5);
+    assert_eq!(response.last_update, last_update);
+    assert_eq!(
+        storage.get_public_key("consensus").unwrap().public_key,
+        public_key
+    );
+
+    // Migrating never overwrites an existing storage
+    assert!(
+        EncryptedOnDiskStorage::migrate_from(&plaintext, path.path().to_path_buf(), SECRET)
+            .is_err()
+    );
+}
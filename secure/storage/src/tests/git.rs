@@ -0,0 +1,76 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, GitStorage, KVStorage, Namespaced, Storage};
+use dijets_temppath::TempPath;
+use std::process::Command;
+
+const BRANCH: &str = "genesis";
+
+#[test]
+fn git_storage() {
+    let path = TempPath::new();
+    let mut storage = Storage::from(GitStorage::new(
+        path.path().to_path_buf(),
+        None,
+        BRANCH.into(),
+    ));
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+#[test]
+fn git_storage_with_remote() {
+    let remote = create_remote();
+    let path = TempPath::new();
+    let mut storage = Storage::from(GitStorage::new(
+        path.path().to_path_buf(),
+        Some(remote.path().to_str().unwrap().into()),
+        BRANCH.into(),
+    ));
+    suite::execute_all_storage_tests(&mut storage);
+}
+
+/// Peers sharing a remote see each other's writes, as in a genesis ceremony.
+#[test]
+fn git_storage_shared_remote() {
+    let remote = create_remote();
+    let remote_url: String = remote.path().to_str().unwrap().into();
+    let path_1 = TempPath::new();
+    let mut storage_1 = Namespaced::new(
+        "owner",
+        GitStorage::new(
+            path_1.path().to_path_buf(),
+            Some(remote_url.clone()),
+            BRANCH.into(),
+        ),
+    );
+    let path_2 = TempPath::new();
+    let mut storage_2 = Namespaced::new(
+        "operator",
+        GitStorage::new(path_2.path().to_path_buf(), Some(remote_url), BRANCH.into()),
+    );
+
+    storage_1.set("key", 1u64).unwrap();
+    storage_2.set("key", 2u64).unwrap();
+
+    let path_3 = TempPath::new();
+    let storage_3 = GitStorage::new(
+        path_3.path().to_path_buf(),
+        Some(remote.path().to_str().unwrap().into()),
+        BRANCH.into(),
+    );
+    assert_eq!(storage_3.get::<u64>("owner/key").unwrap().value, 1);
+    assert_eq!(storage_3.get::<u64>("operator/key").unwrap().value, 2);
+}
+
+fn create_remote() -> TempPath {
+    let remote = TempPath::new();
+    remote.create_as_dir().unwrap();
+    let status = Command::new("git")
+        .args(&["init", "-q", "--bare"])
+        .arg(remote.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    remote
+}
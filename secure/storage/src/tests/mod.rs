@@ -1,8 +1,12 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod audited;
+mod encrypted_on_disk;
+mod git;
 mod github;
 mod in_memory;
 mod on_disk;
+mod pkcs11;
 mod suite;
 mod vault;
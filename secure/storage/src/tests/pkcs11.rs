@@ -0,0 +1,163 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, CryptoStorage, Error, KVStorage, Namespaced, Pkcs11Storage, Storage};
+use dijets_crypto::{
+    ed25519::Ed25519PrivateKey, test_utils::TestDijetsCrypto, PrivateKey, Signature, Uniform,
+};
+use dijets_pkcs11_client::dev;
+
+/// Pkcs11Storage namespace constants
+const PKCS11_NAMESPACE_1: &str = "namespace_1";
+const PKCS11_NAMESPACE_2: &str = "namespace_2";
+
+/// Pkcs11Storage key names
+const CRYPTO_KEY: &str = "crypto_key";
+
+/// This holds the canonical list of PKCS#11 storage tests. Each test uses the same SoftHSM token,
+/// so tests run sequentially and the storage is reset after each test.
+const PKCS11_TESTS: &[fn()] = &[
+    test_suite_multiple_namespaces,
+    test_suite_no_namespaces,
+    test_pkcs11_import_key,
+    test_pkcs11_key_versions,
+    test_pkcs11_no_export,
+];
+
+/// A test for verifying Pkcs11Storage properly implements the DijetsSecureStorage API. This test
+/// depends on SoftHSM being installed, see `dijets_pkcs11_client::dev`.
+#[test]
+fn execute_storage_tests_pkcs11() {
+    if dev::test_token_safe().is_none() {
+        return;
+    }
+    let mut storage = create_pkcs11();
+    storage.reset_and_clear().unwrap();
+
+    for test in PKCS11_TESTS.iter() {
+        test();
+        storage.reset_and_clear().unwrap();
+    }
+}
+
+/// Runs the test suite on a Pkcs11Storage instance that does not use distinct namespaces
+fn test_suite_no_namespaces() {
+    let mut storage = Storage::from(create_pkcs11());
+    suite::execute_non_exporting_storage_tests(&mut storage);
+}
+
+/// Runs the test suite on Pkcs11Storage instances that share the token under distinct namespaces.
+fn test_suite_multiple_namespaces() {
+    let mut storage_1 = create_pkcs11_with_namespace(PKCS11_NAMESPACE_1);
+    let mut storage_2 = create_pkcs11_with_namespace(PKCS11_NAMESPACE_2);
+
+    suite::execute_non_exporting_storage_tests(&mut storage_1);
+    suite::execute_non_exporting_storage_tests(&mut storage_2);
+
+    // Keys of one namespace are not visible in another one
+    storage_1.create_key(CRYPTO_KEY).unwrap();
+    assert_eq!(
+        storage_2.get_public_key(CRYPTO_KEY).unwrap_err(),
+        Error::KeyNotSet(format!("{}/{}", PKCS11_NAMESPACE_2, CRYPTO_KEY))
+    );
+}
+
+/// Imported keys can sign, but can't be imported twice or exported.
+fn test_pkcs11_import_key() {
+    let mut storage = create_pkcs11();
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key = private_key.public_key();
+
+    storage
+        .import_private_key(CRYPTO_KEY, private_key.clone())
+        .unwrap();
+    assert_eq!(
+        storage.get_public_key(CRYPTO_KEY).unwrap().public_key,
+        public_key
+    );
+
+    let message = TestDijetsCrypto("Hello, World".to_string());
+    let signature = storage.sign(CRYPTO_KEY, &message).unwrap();
+    signature.verify(&message, &public_key).unwrap();
+
+    assert_eq!(
+        storage
+            .import_private_key(CRYPTO_KEY, private_key)
+            .unwrap_err(),
+        Error::KeyAlreadyExists(CRYPTO_KEY.to_string())
+    );
+    assert_eq!(
+        storage.export_private_key(CRYPTO_KEY).unwrap_err(),
+        Error::PermissionDenied
+    );
+}
+
+/// Rotations keep the previous versions usable, up to the trimmed ones.
+fn test_pkcs11_key_versions() {
+    let mut storage = create_pkcs11();
+    let message = TestDijetsCrypto("Hello, World".to_string());
+
+    let first_public_key = storage.create_key(CRYPTO_KEY).unwrap();
+    let mut public_key = first_public_key.clone();
+    for _ in 0..5 {
+        let new_public_key = storage.rotate_key(CRYPTO_KEY).unwrap();
+        assert_eq!(
+            storage.get_public_key(CRYPTO_KEY).unwrap().public_key,
+            new_public_key
+        );
+        assert_eq!(
+            storage.get_public_key_previous_version(CRYPTO_KEY).unwrap(),
+            public_key
+        );
+
+        let signature = storage
+            .sign_using_version(CRYPTO_KEY, public_key.clone(), &message)
+            .unwrap();
+        signature.verify(&message, &public_key).unwrap();
+        let signature = storage.sign(CRYPTO_KEY, &message).unwrap();
+        signature.verify(&message, &new_public_key).unwrap();
+
+        public_key = new_public_key;
+    }
+
+    // The first version was trimmed
+    assert_eq!(
+        storage
+            .sign_using_version(CRYPTO_KEY, first_public_key.clone(), &message)
+            .unwrap_err(),
+        Error::KeyVersionNotFound(CRYPTO_KEY.to_string(), first_public_key.to_string())
+    );
+}
+
+/// Private keys never leave the token.
+fn test_pkcs11_no_export() {
+    let mut storage = create_pkcs11();
+    let public_key = storage.create_key(CRYPTO_KEY).unwrap();
+
+    assert_eq!(
+        storage.export_private_key(CRYPTO_KEY).unwrap_err(),
+        Error::PermissionDenied
+    );
+    assert_eq!(
+        storage
+            .export_private_key_for_version(CRYPTO_KEY, public_key)
+            .unwrap_err(),
+        Error::PermissionDenied
+    );
+}
+
+fn create_pkcs11() -> Pkcs11Storage {
+    let token = dev::test_token();
+    Pkcs11Storage::new(
+        token.library().to_path_buf(),
+        token.token_label().to_string(),
+        token.pin().to_string(),
+    )
+}
+
+fn create_pkcs11_with_namespace(namespace: &str) -> Storage {
+    Storage::from(Namespaced::new(
+        namespace,
+        Box::new(Storage::from(create_pkcs11())),
+    ))
+}
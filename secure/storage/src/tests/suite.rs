@@ -34,6 +34,22 @@ const STORAGE_TESTS: &[fn(&mut Storage)] = &[
     test_verify_incorrect_value_types,
 ];
 
+/// This holds the secure storage tests that don't export private keys, for the storage backends
+/// whose private keys never leave them (e.g., PKCS#11 tokens).
+const NON_EXPORTING_STORAGE_TESTS: &[fn(&mut Storage)] = &[
+    test_set_reset_get,
+    test_create_and_get_non_existent_version,
+    test_create_get_key_pair,
+    test_create_sign_rotate_sign,
+    test_ensure_storage_is_available,
+    test_get_non_existent,
+    test_get_set,
+    test_get_uncreated_key_pair,
+    test_hash_value,
+    test_incremental_timestamp,
+    test_verify_incorrect_value_types,
+];
+
 /// Storage data constants for testing purposes.
 const CRYPTO_KEY: &str = "Private_Key";
 const U64_KEY: &str = "U64_Key";
@@ -48,6 +64,15 @@ pub fn execute_all_storage_tests(storage: &mut Storage) {
     }
 }
 
+/// Executes the storage tests that don't export private keys on a given storage backend.
+pub fn execute_non_exporting_storage_tests(storage: &mut Storage) {
+    storage.reset_and_clear().unwrap();
+    for test in NON_EXPORTING_STORAGE_TESTS.iter() {
+        test(storage);
+        storage.reset_and_clear().unwrap();
+    }
+}
+
 /// This test tries to set a key, reset the storage and then retrieve its
 // value, checking that the reset is indeed performed in testing mode. It
 /// should be performed first, as other tests will depend on it.
@@ -12,11 +12,14 @@ use crate::{
     network::{StateSyncEvents, StateSyncMessage, StateSyncSender},
     request_manager::RequestManager,
     shared_components::SyncState,
+    snapshot_request::GetStateSnapshotRequest,
+    snapshot_response::GetStateSnapshotResponse,
 };
 use dijets_config::{
     config::{NodeConfig, PeerNetworkId, RoleType, StateSyncConfig},
     network_id::NodeNetworkId,
 };
+use dijets_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
 use dijets_logger::prelude::*;
 use dijets_mempool::{CommitResponse, CommittedTransaction};
 use dijets_types::{
@@ -58,6 +61,9 @@ struct PendingRequestInfo {
 /// higher within the timeout interval).
 /// * Validator: the ChunkRequests are generated on demand for a specific target LedgerInfo to
 /// synchronize to.
+/// A new FullNode can also skip replaying the history up to its waypoint: it then first restores
+/// the account state snapshot at the waypoint version (see `GetStateSnapshotRequest`) and only
+/// then switches to ChunkRequests.
 pub(crate) struct StateSyncCoordinator<T> {
     // used to process client requests
     client_events: mpsc::UnboundedReceiver<CoordinatorMessage>,
@@ -89,6 +95,9 @@ pub(crate) struct StateSyncCoordinator<T> {
     // queue of incoming long polling requests
     // peer will be notified about new chunk of transactions if it's available before expiry time
     subscriptions: HashMap<PeerNetworkId, PendingRequestInfo>,
+    // If we're a new full node restoring the account state snapshot at the waypoint version,
+    // this holds the index of the next account to request.
+    state_snapshot_next_index: Option<u64>,
     executor_proxy: T,
 }
 
@@ -123,6 +132,18 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
             network_senders,
         );
 
+        // A new full node (i.e., one that only has the genesis state) can restore the account
+        // state snapshot at the waypoint version instead of replaying all transactions up to it.
+        let state_snapshot_next_index = if node_config.state_sync.enable_state_snapshot_sync
+            && role == RoleType::FullNode
+            && initial_state.synced_version() == 0
+            && waypoint.version() > 0
+        {
+            Some(0)
+        } else {
+            None
+        };
+
         Ok(Self {
             client_events,
             state_sync_to_mempool_sender,
@@ -135,6 +156,7 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
             sync_request: None,
             target_ledger_info: None,
             initialization_listener: None,
+            state_snapshot_next_index,
             executor_proxy,
         })
     }
@@ -301,6 +323,54 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
                 // Process chunk response
                 self.process_chunk_response(&peer, *response).await
             }
+            StateSyncMessage::GetStateSnapshotRequest(request) => {
+                // Time request handling
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::STATE_SNAPSHOT_REQUEST_MSG_LABEL,
+                    ])
+                    .start_timer();
+
+                // Process state snapshot request
+                let process_result =
+                    self.process_state_snapshot_request(peer.clone(), *request.clone());
+                let result_label = if let Err(ref error) = process_result {
+                    error!(LogSchema::event_log(
+                        LogEntry::ProcessStateSnapshotRequest,
+                        LogEvent::Fail
+                    )
+                    .peer(&peer)
+                    .error(&error.clone())
+                    .local_li_version(self.local_state.committed_version())
+                    .state_snapshot_request(*request));
+                    counters::FAIL_LABEL
+                } else {
+                    counters::SUCCESS_LABEL
+                };
+                counters::PROCESS_STATE_SNAPSHOT_REQUEST_COUNT
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        result_label,
+                    ])
+                    .inc();
+                process_result
+            }
+            StateSyncMessage::GetStateSnapshotResponse(response) => {
+                // Time response handling
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::STATE_SNAPSHOT_RESPONSE_MSG_LABEL,
+                    ])
+                    .start_timer();
+
+                // Process state snapshot response
+                self.process_state_snapshot_response(&peer, *response).await
+            }
         }
     }
 
@@ -1113,6 +1183,11 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
         }
 
         // Verify the chunk has the expected type for the current syncing mode
+        if self.is_syncing_state_snapshot() {
+            return Err(Error::ReceivedWrongChunkType(
+                "Received a chunk response, but we're syncing a state snapshot!".into(),
+            ));
+        }
         match &response.response_li {
             ResponseLedgerInfo::LedgerInfoForWaypoint {
                 waypoint_li,
@@ -1451,6 +1526,318 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
             .execute_chunk(txn_list_with_proof, target, intermediate_end_of_epoch_li)
     }
 
+    /// Returns true iff we're a new full node restoring the account state snapshot at the
+    /// waypoint version.
+    fn is_syncing_state_snapshot(&self) -> bool {
+        self.state_snapshot_next_index.is_some()
+    }
+
+    /// Sends the chunk of the account state snapshot at the waypoint version for the given
+    /// request.
+    fn process_state_snapshot_request(
+        &mut self,
+        peer: PeerNetworkId,
+        request: GetStateSnapshotRequest,
+    ) -> Result<(), Error> {
+        debug!(
+            LogSchema::event_log(LogEntry::ProcessStateSnapshotRequest, LogEvent::Received)
+                .peer(&peer)
+                .state_snapshot_request(request.clone())
+                .local_li_version(self.local_state.committed_version())
+        );
+        self.sync_state_with_local_storage()?;
+
+        // Verify the request is valid before trying to process it. If it's invalid,
+        // penalize the peer's score.
+        if let Err(error) = self.verify_state_snapshot_request_is_valid(&request) {
+            self.request_manager.process_invalid_chunk_request(&peer);
+            return Err(error);
+        }
+
+        // The snapshot is proven relative to the epoch ending LI at the requested version (i.e.,
+        // the LI of the requester's waypoint): the transaction at that version carries the state
+        // root hash the account states are proven against.
+        let version = request.version;
+        let waypoint_li = self.executor_proxy.get_epoch_ending_ledger_info(version)?;
+        let txn_list_with_proof = self.executor_proxy.get_chunk(version - 1, 1, version)?;
+        let limit = cmp::min(request.limit, self.config.max_state_snapshot_chunk_limit);
+        let account_states_chunk =
+            self.executor_proxy
+                .get_account_states_chunk(version, request.start_index, limit)?;
+
+        let response =
+            GetStateSnapshotResponse::new(waypoint_li, txn_list_with_proof, account_states_chunk);
+        let log = LogSchema::event_log(
+            LogEntry::ProcessStateSnapshotRequest,
+            LogEvent::DeliverStateSnapshotChunk,
+        )
+        .state_snapshot_response(response.clone())
+        .peer(&peer);
+        let msg = StateSyncMessage::GetStateSnapshotResponse(Box::new(response));
+        let send_result = self.request_manager.send_chunk_response(&peer, msg);
+        let send_result_label = if send_result.is_err() {
+            counters::SEND_FAIL_LABEL
+        } else {
+            debug!(log);
+            counters::SEND_SUCCESS_LABEL
+        };
+        counters::RESPONSES_SENT
+            .with_label_values(&[
+                &peer.raw_network_id().to_string(),
+                &peer.peer_id().to_string(),
+                send_result_label,
+            ])
+            .inc();
+
+        send_result.map_err(|e| {
+            error!(log.error(&e));
+            Error::UnexpectedError(format!(
+                "Network error in sending state snapshot response to {}",
+                peer
+            ))
+        })
+    }
+
+    fn verify_state_snapshot_request_is_valid(
+        &self,
+        request: &GetStateSnapshotRequest,
+    ) -> Result<(), Error> {
+        // Ensure the snapshot is not the genesis state (which every node already has)
+        if request.version == 0 {
+            return Err(Error::InvalidStateSnapshotRequest(
+                "State snapshot at genesis requested! Discarding request.".into(),
+            ));
+        }
+
+        // Ensure the snapshot version has been committed locally
+        let committed_version = self.local_state.committed_version();
+        if request.version > committed_version {
+            return Err(Error::InvalidStateSnapshotRequest(format!(
+                "State snapshot version {} is higher than the local committed version {}! Discarding request.",
+                request.version, committed_version
+            )));
+        }
+
+        // Ensure the chunk limit is not zero
+        if request.limit == 0 {
+            return Err(Error::InvalidStateSnapshotRequest(
+                "State snapshot request limit is 0. Discarding request.".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// * Verifies and stores the account states chunk in the given response.
+    /// * Once the whole snapshot has been restored, triggers the post-commit actions and switches
+    /// to syncing transactions from the waypoint version.
+    async fn process_state_snapshot_response(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetStateSnapshotResponse,
+    ) -> Result<(), Error> {
+        // Verify the response is well formed before trying to process it.
+        self.verify_state_snapshot_response_is_valid(peer, &response)?;
+
+        let snapshot_completed = match self.apply_state_snapshot_chunk(peer, response.clone()) {
+            Ok(snapshot_completed) => {
+                counters::APPLY_STATE_SNAPSHOT_CHUNK_COUNT
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::SUCCESS_LABEL,
+                    ])
+                    .inc();
+                snapshot_completed
+            }
+            Err(error) => {
+                error!(LogSchema::event_log(
+                    LogEntry::ProcessStateSnapshotResponse,
+                    LogEvent::ApplyChunkFail
+                )
+                .peer(peer)
+                .error(&error));
+                counters::APPLY_STATE_SNAPSHOT_CHUNK_COUNT
+                    .with_label_values(&[
+                        &peer.raw_network_id().to_string(),
+                        &peer.peer_id().to_string(),
+                        counters::FAIL_LABEL,
+                    ])
+                    .inc();
+                return Err(error);
+            }
+        };
+
+        // Request the next chunk until the snapshot is complete
+        if !snapshot_completed {
+            if let Some(next_index) = self.state_snapshot_next_index {
+                self.request_manager.process_success_response(peer);
+                return self.send_state_snapshot_request_and_log_error(
+                    next_index,
+                    LogEntry::ProcessStateSnapshotResponse,
+                );
+            }
+        }
+
+        // The snapshot has been committed at the waypoint version: from now on we're syncing
+        // transactions from there.
+        self.state_snapshot_next_index = None;
+        info!(
+            LogSchema::event_log(LogEntry::ProcessStateSnapshotResponse, LogEvent::Complete)
+                .peer(peer)
+                .version(self.waypoint.version())
+        );
+        self.process_commit_notification(
+            response.txn_list_with_proof.transactions,
+            None,
+            vec![],
+            Some(peer),
+        )
+        .await
+        .map_err(|error| {
+            error!(LogSchema::event_log(
+                LogEntry::ProcessStateSnapshotResponse,
+                LogEvent::PostCommitFail
+            )
+            .peer(peer)
+            .error(&error));
+            error
+        })
+    }
+
+    fn verify_state_snapshot_response_is_valid(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: &GetStateSnapshotResponse,
+    ) -> Result<(), Error> {
+        // Verify response comes from known peer
+        if !self.request_manager.is_known_state_sync_peer(peer) {
+            counters::RESPONSE_FROM_DOWNSTREAM_COUNT
+                .with_label_values(&[
+                    &peer.raw_network_id().to_string(),
+                    &peer.peer_id().to_string(),
+                ])
+                .inc();
+            self.request_manager.process_chunk_from_downstream(peer);
+            return Err(Error::ReceivedChunkFromDownstream(peer.to_string()));
+        }
+
+        // Verify we're syncing a state snapshot
+        let next_index = self.state_snapshot_next_index.ok_or_else(|| {
+            Error::ReceivedWrongChunkType(
+                "Received a state snapshot response, but we're not syncing a state snapshot!"
+                    .into(),
+            )
+        })?;
+
+        // Verify the chunk is not empty and that it starts at the next account to restore.
+        // A mismatch is not penalized: it's most likely a stale response to a multicast request.
+        let account_states_chunk = &response.account_states_chunk;
+        if account_states_chunk.account_blobs.is_empty() {
+            self.request_manager.process_empty_chunk(peer);
+            return Err(Error::ReceivedEmptyChunk(peer.to_string()));
+        }
+        if account_states_chunk.first_index != next_index {
+            return Err(Error::ReceivedNonSequentialChunk(
+                peer.to_string(),
+                next_index.to_string(),
+                account_states_chunk.first_index.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies and stores the account states chunk in the given response. Returns true iff the
+    /// chunk completes the snapshot (which is then committed).
+    fn apply_state_snapshot_chunk(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetStateSnapshotResponse,
+    ) -> Result<bool, Error> {
+        debug!(
+            LogSchema::event_log(LogEntry::ProcessStateSnapshotResponse, LogEvent::Received)
+                .state_snapshot_response(response.clone())
+                .peer(peer)
+        );
+
+        self.verify_and_store_state_snapshot_chunk(response)
+            .map_err(|error| {
+                self.request_manager.process_invalid_chunk(peer);
+                Error::ProcessInvalidChunk(error.to_string())
+            })
+    }
+
+    fn verify_and_store_state_snapshot_chunk(
+        &mut self,
+        response: GetStateSnapshotResponse,
+    ) -> Result<bool, Error> {
+        let GetStateSnapshotResponse {
+            waypoint_li,
+            txn_list_with_proof,
+            account_states_chunk,
+        } = response;
+
+        // Verify the waypoint LI and the transaction at the waypoint version, which carries the
+        // state root hash the account states are proven against.
+        self.waypoint
+            .verify(waypoint_li.ledger_info())
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Waypoint verification failed: {}", error))
+            })?;
+        let version = waypoint_li.ledger_info().version();
+        if txn_list_with_proof.len() != 1 {
+            return Err(Error::ProcessInvalidChunk(format!(
+                "Expected the single transaction at the snapshot version, but got {} transactions!",
+                txn_list_with_proof.len()
+            )));
+        }
+        txn_list_with_proof
+            .verify(waypoint_li.ledger_info(), Some(version))
+            .map_err(|error| {
+                Error::ProcessInvalidChunk(format!(
+                    "Snapshot transaction verification failed: {}",
+                    error
+                ))
+            })?;
+        let expected_root_hash = txn_list_with_proof.proof.transaction_infos()[0].state_root_hash();
+
+        // Verify the chunk indices match the number of accounts in the chunk
+        let first_index = account_states_chunk.first_index;
+        let last_index = account_states_chunk.last_index;
+        let num_accounts = account_states_chunk.account_blobs.len() as u64;
+        if last_index.checked_sub(first_index).map(|diff| diff + 1) != Some(num_accounts) {
+            return Err(Error::ProcessInvalidChunk(format!(
+                "Account indices [{} - {}] don't match the number of accounts: {}",
+                first_index, last_index, num_accounts
+            )));
+        }
+
+        // The chunk is the last one iff there are no accounts to its right in the state tree
+        // (i.e., all right siblings in its range proof are placeholders).
+        let is_last_chunk = account_states_chunk
+            .proof
+            .right_siblings()
+            .iter()
+            .all(|sibling| *sibling == *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        self.executor_proxy.save_account_states_chunk(
+            version,
+            expected_root_hash,
+            account_states_chunk,
+        )?;
+        let next_index = last_index
+            .checked_add(1)
+            .ok_or_else(|| Error::IntegerOverflow("Next account index has overflown!".into()))?;
+        counters::STATE_SNAPSHOT_SYNCED_ACCOUNTS.set(next_index as i64);
+        self.state_snapshot_next_index = Some(next_index);
+
+        if is_last_chunk {
+            self.executor_proxy
+                .finalize_state_snapshot(txn_list_with_proof, waypoint_li)?;
+        }
+        Ok(is_last_chunk)
+    }
+
     /// Returns true if consensus is currently executing and state sync should
     /// therefore not write to storage. Reads are still permitted (e.g., to
     /// handle chunk requests).
@@ -1509,6 +1896,11 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
             counters::TIMEOUT.inc();
             warn!(LogSchema::new(LogEntry::Timeout).version(known_version));
 
+            if let Some(next_index) = self.state_snapshot_next_index {
+                return self
+                    .send_state_snapshot_request_and_log_error(next_index, LogEntry::Timeout);
+            }
+
             let trusted_epoch = self.local_state.trusted_epoch();
             let chunk_target = if !self.is_initialized() {
                 self.create_waypoint_chunk_target()
@@ -1578,6 +1970,35 @@ impl<T: ExecutorProxyTrait> StateSyncCoordinator<T> {
         self.request_manager.send_chunk_request(req)
     }
 
+    /// Sends a request for the chunk of the account state snapshot (at the waypoint version)
+    /// starting at `start_index`. Immediately logs any errors returned by the operation using
+    /// the given log entry.
+    fn send_state_snapshot_request_and_log_error(
+        &mut self,
+        start_index: u64,
+        log_entry: LogEntry,
+    ) -> Result<(), Error> {
+        let known_version = self.local_state.synced_version();
+        let request = GetStateSnapshotRequest::new(
+            self.waypoint.version(),
+            start_index,
+            self.config.state_snapshot_chunk_limit,
+        );
+        if let Err(error) = self
+            .request_manager
+            .send_state_snapshot_request(known_version, request)
+        {
+            error!(
+                LogSchema::event_log(log_entry, LogEvent::SendChunkRequestFail)
+                    .version(known_version)
+                    .error(&error)
+            );
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+
     fn deliver_subscription(
         &mut self,
         peer: PeerNetworkId,
@@ -1690,6 +2111,8 @@ mod tests {
         executor_proxy::ExecutorProxy,
         network::StateSyncMessage,
         shared_components::{test_utils, test_utils::create_coordinator_with_config_and_waypoint},
+        snapshot_request::GetStateSnapshotRequest,
+        snapshot_response::GetStateSnapshotResponse,
     };
     use dijets_config::{
         config::{NodeConfig, PeerNetworkId, PeerRole, RoleType},
@@ -1702,10 +2125,11 @@ mod tests {
     use dijets_mempool::CommitResponse;
     use dijets_types::{
         account_address::AccountAddress,
+        account_state_blob::{AccountStateBlob, AccountStatesChunkWithProof},
         block_info::BlockInfo,
         chain_id::ChainId,
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-        proof::TransactionListProof,
+        proof::{SparseMerkleRangeProof, TransactionListProof},
         transaction::{
             RawTransaction, Script, SignedTransaction, Transaction, TransactionListWithProof,
             TransactionPayload, Version,
@@ -2271,6 +2695,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_state_snapshot_request_messages() {
+        // Create a coordinator for a validator node
+        let mut validator_coordinator = test_utils::create_validator_coordinator();
+        let peer_network_id = PeerNetworkId::random();
+
+        // Create state snapshot requests at genesis and ahead of the committed version
+        let snapshot_requests: Vec<_> = [0, 10]
+            .iter()
+            .map(|version| {
+                StateSyncMessage::GetStateSnapshotRequest(Box::new(GetStateSnapshotRequest::new(
+                    *version, 0, 250,
+                )))
+            })
+            .collect();
+
+        // Verify invalid request errors are thrown
+        for request in &snapshot_requests {
+            let result = block_on(validator_coordinator.process_chunk_message(
+                peer_network_id.network_id(),
+                peer_network_id.peer_id(),
+                request.clone(),
+            ));
+            if !matches!(result, Err(Error::InvalidStateSnapshotRequest(..))) {
+                panic!(
+                    "Expected an invalid state snapshot request, but got: {:?}",
+                    result
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_process_state_snapshot_response_messages() {
+        // Create a coordinator for a validator node and add a known peer
+        let waypoint_ledger_info = create_ledger_info_at_version(10);
+        let waypoint = Waypoint::new_any(waypoint_ledger_info.ledger_info());
+        let mut node_config = NodeConfig::default();
+        node_config.state_sync.enable_state_snapshot_sync = true;
+        node_config.base.role = RoleType::Validator;
+        let mut validator_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config.clone(), waypoint);
+        let peer_network_id = PeerNetworkId::random_validator();
+        process_new_peer_event(&mut validator_coordinator, &peer_network_id);
+
+        // Verify validators never sync a state snapshot
+        assert!(!validator_coordinator.is_syncing_state_snapshot());
+        let snapshot_response =
+            create_state_snapshot_response_message(waypoint_ledger_info.clone(), 0, 10);
+        verify_all_chunk_responses_are_the_wrong_type(
+            &mut validator_coordinator,
+            &peer_network_id,
+            &[snapshot_response],
+        );
+
+        // Create a coordinator for a new full node and add a known peer
+        node_config.base.role = RoleType::FullNode;
+        let mut full_node_coordinator =
+            create_coordinator_with_config_and_waypoint(node_config, waypoint);
+        process_new_peer_event(&mut full_node_coordinator, &peer_network_id);
+        assert!(full_node_coordinator.is_syncing_state_snapshot());
+
+        // Verify chunk responses are rejected while syncing the state snapshot
+        let chunk_responses = create_non_empty_chunk_responses(1);
+        verify_all_chunk_responses_are_the_wrong_type(
+            &mut full_node_coordinator,
+            &peer_network_id,
+            &chunk_responses,
+        );
+
+        // Verify we get an empty chunk error
+        let snapshot_response =
+            create_state_snapshot_response_message(waypoint_ledger_info.clone(), 0, 0);
+        let result = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            snapshot_response,
+        ));
+        if !matches!(result, Err(Error::ReceivedEmptyChunk(..))) {
+            panic!("Expected an empty chunk error, got: {:?}", result);
+        }
+
+        // Verify we get a non-sequential error if the chunk doesn't start at the first account
+        let snapshot_response =
+            create_state_snapshot_response_message(waypoint_ledger_info.clone(), 5, 10);
+        let result = block_on(full_node_coordinator.process_chunk_message(
+            peer_network_id.network_id(),
+            peer_network_id.peer_id(),
+            snapshot_response,
+        ));
+        if !matches!(result, Err(Error::ReceivedNonSequentialChunk(..))) {
+            panic!("Expected a non-sequential error, but got: {:?}", result);
+        }
+
+        // Verify chunks that can't be verified against the waypoint are rejected
+        let snapshot_response =
+            create_state_snapshot_response_message(create_ledger_info_at_version(20), 0, 10);
+        verify_all_chunk_responses_are_invalid(
+            &mut full_node_coordinator,
+            &peer_network_id,
+            &[snapshot_response],
+        );
+        assert!(full_node_coordinator.is_syncing_state_snapshot());
+    }
+
     fn create_test_transaction() -> Transaction {
         let private_key = Ed25519PrivateKey::generate_for_testing();
         let public_key = private_key.public_key();
@@ -2424,6 +2953,32 @@ mod tests {
         vec![waypoint_response, target_response, highest_response]
     }
 
+    fn create_state_snapshot_response_message(
+        waypoint_li: LedgerInfoWithSignatures,
+        first_index: u64,
+        num_accounts: u64,
+    ) -> StateSyncMessage {
+        let account_blobs = (0..num_accounts)
+            .map(|_| (HashValue::random(), AccountStateBlob::from(vec![])))
+            .collect();
+        let account_states_chunk = AccountStatesChunkWithProof {
+            first_index,
+            last_index: (first_index + num_accounts).saturating_sub(1),
+            first_key: HashValue::zero(),
+            last_key: HashValue::zero(),
+            account_blobs,
+            proof: SparseMerkleRangeProof::new(vec![]),
+            root_hash: HashValue::zero(),
+        };
+        let version = waypoint_li.ledger_info().version();
+        let state_snapshot_response = GetStateSnapshotResponse::new(
+            waypoint_li,
+            create_dummy_transaction_list_with_proof(version),
+            account_states_chunk,
+        );
+        StateSyncMessage::GetStateSnapshotResponse(Box::new(state_snapshot_response))
+    }
+
     fn verify_all_chunk_requests_are_invalid(
         coordinator: &mut StateSyncCoordinator<ExecutorProxy>,
         peer_network_id: &PeerNetworkId,
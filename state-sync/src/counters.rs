@@ -17,6 +17,8 @@ pub const SYNC_MSG_LABEL: &str = "sync";
 pub const COMMIT_MSG_LABEL: &str = "commit";
 pub const CHUNK_REQUEST_MSG_LABEL: &str = "chunk_request";
 pub const CHUNK_RESPONSE_MSG_LABEL: &str = "chunk_response";
+pub const STATE_SNAPSHOT_REQUEST_MSG_LABEL: &str = "state_snapshot_request";
+pub const STATE_SNAPSHOT_RESPONSE_MSG_LABEL: &str = "state_snapshot_response";
 
 pub fn set_timestamp(timestamp_type: TimestampType, time_as_usecs: u64) {
     TIMESTAMP
@@ -154,6 +156,34 @@ pub static PROCESS_CHUNK_REQUEST_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of attempts to apply a state snapshot chunk
+pub static APPLY_STATE_SNAPSHOT_CHUNK_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_state_sync_apply_state_snapshot_chunk_total",
+        "Number of results of applying a state snapshot chunk",
+        &["network", "sender", "result"]
+    )
+    .unwrap()
+});
+
+pub static PROCESS_STATE_SNAPSHOT_REQUEST_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "dijets_state_sync_process_state_snapshot_request_total",
+        "Number of times state snapshot request was processed",
+        &["network", "sender", "result"]
+    )
+    .unwrap()
+});
+
+/// Number of accounts restored so far by the ongoing state snapshot sync
+pub static STATE_SNAPSHOT_SYNCED_ACCOUNTS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "dijets_state_sync_state_snapshot_synced_accounts",
+        "Number of accounts restored by the ongoing state snapshot sync"
+    )
+    .unwrap()
+});
+
 /// Number of transactions in a received chunk response
 pub static STATE_SYNC_CHUNK_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
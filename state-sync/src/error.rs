@@ -19,6 +19,8 @@ pub enum Error {
     IntegerOverflow(String),
     #[error("Received an invalid chunk request: {0}")]
     InvalidChunkRequest(String),
+    #[error("Received an invalid state snapshot request: {0}")]
+    InvalidStateSnapshotRequest(String),
     #[error(
         "Unable to add peer as they are not a valid state sync peer: {0}. Connection origin: {1}"
     )]
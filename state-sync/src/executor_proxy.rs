@@ -7,20 +7,22 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     shared_components::SyncState,
 };
+use dijets_crypto::HashValue;
 use dijets_logger::prelude::*;
 use dijets_types::{
     account_state::AccountState,
+    account_state_blob::{AccountStateBlob, AccountStatesChunkWithProof},
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
     move_resource::MoveStorage,
     on_chain_config,
     on_chain_config::{config_address, OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY},
-    transaction::TransactionListWithProof,
+    transaction::{TransactionListWithProof, Version},
 };
 use executor_types::{ChunkExecutor, ExecutedTrees};
 use itertools::Itertools;
 use std::{collections::HashSet, convert::TryFrom, sync::Arc};
-use storage_interface::DbReader;
+use storage_interface::{DbReader, DbReaderWriter, DbWriter, StateSnapshotReceiver};
 use subscription_service::ReconfigSubscription;
 
 /// Proxies interactions with execution and storage for state synchronization
@@ -54,24 +56,54 @@ pub trait ExecutorProxyTrait: Send {
     /// Returns the ledger's timestamp for the given version in microseconds
     fn get_version_timestamp(&self, version: u64) -> Result<u64, Error>;
 
+    /// Gets a chunk of (at most `limit`) account states, starting at `start_index`, with a range
+    /// proof relative to the state root hash at the given version.
+    fn get_account_states_chunk(
+        &self,
+        version: u64,
+        start_index: u64,
+        limit: u64,
+    ) -> Result<AccountStatesChunkWithProof, Error>;
+
+    /// Verifies a chunk of the account state snapshot at the given version against
+    /// `expected_root_hash` and saves it. A snapshot is restored starting from index 0.
+    fn save_account_states_chunk(
+        &mut self,
+        version: u64,
+        expected_root_hash: HashValue,
+        account_states_chunk: AccountStatesChunkWithProof,
+    ) -> Result<(), Error>;
+
+    /// Finishes restoring the account state snapshot and commits the (verified) transaction at
+    /// the snapshot version together with the waypoint ledger info, so that chunk execution can
+    /// resume from the next version.
+    fn finalize_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_waypoint_li: LedgerInfoWithSignatures,
+    ) -> Result<(), Error>;
+
     /// publishes on-chain config updates to subscribed components
     fn publish_on_chain_config_updates(&mut self, events: Vec<ContractEvent>) -> Result<(), Error>;
 }
 
 pub(crate) struct ExecutorProxy {
     storage: Arc<dyn DbReader>,
+    storage_writer: Arc<dyn DbWriter>,
     executor: Box<dyn ChunkExecutor>,
     reconfig_subscriptions: Vec<ReconfigSubscription>,
     on_chain_configs: OnChainConfigPayload,
+    // The version and receiver of the account state snapshot being restored (if any)
+    state_snapshot_receiver: Option<(Version, Box<dyn StateSnapshotReceiver<AccountStateBlob>>)>,
 }
 
 impl ExecutorProxy {
     pub(crate) fn new(
-        storage: Arc<dyn DbReader>,
+        db: DbReaderWriter,
         executor: Box<dyn ChunkExecutor>,
         mut reconfig_subscriptions: Vec<ReconfigSubscription>,
     ) -> Self {
-        let on_chain_configs = Self::fetch_all_configs(&*storage)
+        let on_chain_configs = Self::fetch_all_configs(&*db.reader)
             .expect("[state sync] Failed initial read of on-chain configs");
         for subscription in reconfig_subscriptions.iter_mut() {
             subscription
@@ -79,10 +111,12 @@ impl ExecutorProxy {
                 .expect("[state sync] Failed to publish initial on-chain config");
         }
         Self {
-            storage,
+            storage: db.reader,
+            storage_writer: db.writer,
             executor,
             reconfig_subscriptions,
             on_chain_configs,
+            state_snapshot_receiver: None,
         }
     }
 
@@ -246,6 +280,102 @@ impl ExecutorProxyTrait for ExecutorProxy {
             .map_err(|error| Error::UnexpectedError(error.to_string()))
     }
 
+    fn get_account_states_chunk(
+        &self,
+        version: u64,
+        start_index: u64,
+        limit: u64,
+    ) -> Result<AccountStatesChunkWithProof, Error> {
+        self.storage
+            .get_account_chunk_with_proof(version, start_index as usize, limit as usize)
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to get account states chunk from storage {}",
+                    error
+                ))
+            })
+    }
+
+    fn save_account_states_chunk(
+        &mut self,
+        version: u64,
+        expected_root_hash: HashValue,
+        account_states_chunk: AccountStatesChunkWithProof,
+    ) -> Result<(), Error> {
+        if account_states_chunk.first_index == 0 {
+            // (Re)start the restore of the snapshot
+            let receiver = self
+                .storage_writer
+                .get_state_snapshot_receiver(version, expected_root_hash)
+                .map_err(|error| {
+                    Error::UnexpectedError(format!(
+                        "Failed to create state snapshot receiver: {}",
+                        error
+                    ))
+                })?;
+            self.state_snapshot_receiver = Some((version, receiver));
+        }
+
+        match self.state_snapshot_receiver.as_mut() {
+            Some((receiver_version, receiver)) if *receiver_version == version => receiver
+                .add_chunk(
+                    account_states_chunk.account_blobs,
+                    account_states_chunk.proof,
+                )
+                .map_err(|error| {
+                    Error::UnexpectedError(format!(
+                        "Failed to save account states chunk: {}",
+                        error
+                    ))
+                }),
+            _ => Err(Error::UnexpectedError(format!(
+                "No state snapshot is being restored at version {}",
+                version
+            ))),
+        }
+    }
+
+    fn finalize_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_waypoint_li: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let version = verified_waypoint_li.ledger_info().version();
+        let receiver = match self.state_snapshot_receiver.take() {
+            Some((receiver_version, receiver)) if receiver_version == version => receiver,
+            _ => {
+                return Err(Error::UnexpectedError(format!(
+                    "No state snapshot is being restored at version {}",
+                    version
+                )))
+            }
+        };
+        receiver.finish_box().map_err(|error| {
+            Error::UnexpectedError(format!("Failed to finish state snapshot: {}", error))
+        })?;
+        self.storage_writer
+            .finalize_state_snapshot(version, txn_list_with_proof, verified_waypoint_li)
+            .map_err(|error| {
+                Error::UnexpectedError(format!("Failed to finalize state snapshot: {}", error))
+            })?;
+
+        // The snapshot skips all reconfiguration events up to its version, so every subscriber
+        // is notified of the on-chain configs at the new version.
+        let new_configs = Self::fetch_all_configs(&*self.storage)?;
+        for subscription in self.reconfig_subscriptions.iter_mut() {
+            if let Err(e) = subscription.publish(new_configs.clone()) {
+                error!(
+                    LogSchema::event_log(LogEntry::Reconfig, LogEvent::PublishError)
+                        .subscription_name(subscription.name.clone())
+                        .error(&Error::UnexpectedError(e.to_string())),
+                    "Failed to publish reconfig notification to subscription {}", subscription.name
+                );
+            }
+        }
+        self.on_chain_configs = new_configs;
+        Ok(())
+    }
+
     fn publish_on_chain_config_updates(&mut self, events: Vec<ContractEvent>) -> Result<(), Error> {
         if events.is_empty() {
             return Ok(());
@@ -639,7 +769,7 @@ mod tests {
         // Create test dijets database
         let db_path = dijets_temppath::TempPath::new();
         db_path.create_as_dir().unwrap();
-        let (db, db_rw) = DbReaderWriter::wrap(DijetsDB::new_for_test(db_path.path()));
+        let db_rw = DbReaderWriter::new(DijetsDB::new_for_test(db_path.path()));
 
         // Boostrap the genesis transaction
         let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
@@ -647,8 +777,8 @@ mod tests {
 
         // Create executor proxy with given subscription
         let block_executor = Box::new(Executor::<DijetsVM>::new(db_rw.clone()));
-        let chunk_executor = Box::new(Executor::<DijetsVM>::new(db_rw));
-        let executor_proxy = ExecutorProxy::new(db, chunk_executor, vec![subscription]);
+        let chunk_executor = Box::new(Executor::<DijetsVM>::new(db_rw.clone()));
+        let executor_proxy = ExecutorProxy::new(db_rw, chunk_executor, vec![subscription]);
 
         // Verify initial reconfiguration notification is sent
         assert!(
@@ -8,6 +8,7 @@ use crate::{
     executor_proxy::ExecutorProxy,
     network::StateSyncMessage,
     shared_components::test_utils,
+    snapshot_request::GetStateSnapshotRequest,
 };
 use dijets_config::network_id::{NetworkId, NodeNetworkId};
 use dijets_infallible::Mutex;
@@ -55,6 +56,9 @@ pub fn arb_state_sync_msg() -> impl Strategy<Value = StateSyncMessage> {
         }),
         (any::<GetChunkResponse>()).prop_map(|chunk_response| {
             StateSyncMessage::GetChunkResponse(Box::new(chunk_response))
+        }),
+        (any::<GetStateSnapshotRequest>()).prop_map(|state_snapshot_request| {
+            StateSyncMessage::GetStateSnapshotRequest(Box::new(state_snapshot_request))
         })
     ]
 }
@@ -76,6 +80,18 @@ impl Arbitrary for GetChunkRequest {
     type Strategy = BoxedStrategy<Self>;
 }
 
+impl Arbitrary for GetStateSnapshotRequest {
+    type Parameters = ();
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        (any::<u64>(), any::<u64>(), any::<u64>())
+            .prop_map(|(version, start_index, limit)| {
+                GetStateSnapshotRequest::new(version, start_index, limit)
+            })
+            .boxed()
+    }
+    type Strategy = BoxedStrategy<Self>;
+}
+
 impl Arbitrary for TargetType {
     type Parameters = ();
     fn arbitrary_with(_args: ()) -> Self::Strategy {
@@ -19,6 +19,8 @@ mod logging;
 pub mod network;
 mod request_manager;
 pub mod shared_components;
+pub mod snapshot_request;
+pub mod snapshot_response;
 
 #[cfg(any(feature = "fuzzing", test))]
 pub mod fuzzing;
@@ -3,7 +3,8 @@
 
 use crate::{
     chunk_request::GetChunkRequest, chunk_response::GetChunkResponse, error::Error,
-    request_manager::ChunkRequestInfo,
+    request_manager::ChunkRequestInfo, snapshot_request::GetStateSnapshotRequest,
+    snapshot_response::GetStateSnapshotResponse,
 };
 use dijets_config::{config::PeerNetworkId, network_id::NetworkId};
 use dijets_logger::Schema;
@@ -27,6 +28,10 @@ pub struct LogSchema<'a> {
     #[schema(display)]
     chunk_response: Option<GetChunkResponse>,
     #[schema(display)]
+    state_snapshot_request: Option<GetStateSnapshotRequest>,
+    #[schema(display)]
+    state_snapshot_response: Option<GetStateSnapshotResponse>,
+    #[schema(display)]
     waypoint: Option<Waypoint>,
     subscription_name: Option<String>,
     count: Option<usize>,
@@ -64,6 +69,8 @@ impl<'a> LogSchema<'a> {
             error: None,
             chunk_request: None,
             chunk_response: None,
+            state_snapshot_request: None,
+            state_snapshot_response: None,
             version: None,
             waypoint: None,
             subscription_name: None,
@@ -102,6 +109,9 @@ pub enum LogEntry {
     ProcessChunkRequest,
     ProcessChunkResponse,
     ProcessChunkMessage,
+    SendStateSnapshotRequest,
+    ProcessStateSnapshotRequest,
+    ProcessStateSnapshotResponse,
     NetworkError,
     EpochChange,
     CommitFlow,
@@ -139,6 +149,7 @@ pub enum LogEvent {
     // ProcessChunkRequest events
     PastEpochRequested,
     DeliverChunk,
+    DeliverStateSnapshotChunk,
 
     // Multicast network events
     Failover,
@@ -5,24 +5,39 @@
 
 use crate::{
     chunk_request::GetChunkRequest, chunk_response::GetChunkResponse, counters, error::Error,
+    snapshot_request::GetStateSnapshotRequest, snapshot_response::GetStateSnapshotResponse,
 };
 use channel::message_queues::QueueStyle;
+use dijets_infallible::RwLock;
 use dijets_metrics::IntCounterVec;
 use dijets_types::PeerId;
 use network::{
     peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
-    protocols::network::{NetworkEvents, NetworkSender, NewNetworkSender},
+    protocols::{
+        network::{NetworkEvents, NetworkSender, NewNetworkSender},
+        wire::handshake::v1::SupportedProtocols,
+    },
+    reputation::Misbehavior,
     ProtocolId,
 };
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
 
 const STATE_SYNC_MAX_BUFFER_SIZE: usize = 1;
 
+/// Supported protocols in preferred order (from highest priority to lowest).
+pub const DIRECT_SEND: &[ProtocolId] = &[
+    ProtocolId::StateSyncDirectSendCompressed,
+    ProtocolId::StateSyncDirectSend,
+];
+
 /// State sync network messages
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum StateSyncMessage {
     GetChunkRequest(Box<GetChunkRequest>),
     GetChunkResponse(Box<GetChunkResponse>),
+    GetStateSnapshotRequest(Box<GetStateSnapshotRequest>),
+    GetStateSnapshotResponse(Box<GetStateSnapshotResponse>),
 }
 
 /// The interface from Network to StateSync layer.
@@ -44,6 +59,8 @@ pub type StateSyncEvents = NetworkEvents<StateSyncMessage>;
 #[derive(Clone)]
 pub struct StateSyncSender {
     inner: NetworkSender<StateSyncMessage>,
+    // The protocols negotiated with each connected peer, shared between all clones.
+    peers_protocols: Arc<RwLock<HashMap<PeerId, SupportedProtocols>>>,
 }
 
 impl NewNetworkSender for StateSyncSender {
@@ -53,15 +70,43 @@ impl NewNetworkSender for StateSyncSender {
     ) -> Self {
         Self {
             inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+            peers_protocols: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
 
 impl StateSyncSender {
+    /// Sends a message over the most preferred protocol supported by the peer. Falls back to the
+    /// uncompressed protocol, which every peer supports, if the peer isn't known.
     pub fn send_to(&mut self, recipient: PeerId, message: StateSyncMessage) -> Result<(), Error> {
-        let protocol = ProtocolId::StateSyncDirectSend;
+        let protocol = self
+            .peers_protocols
+            .read()
+            .get(&recipient)
+            .and_then(|protocols| protocols.preferred(DIRECT_SEND))
+            .unwrap_or(ProtocolId::StateSyncDirectSend);
         Ok(self.inner.send_to(recipient, protocol, message)?)
     }
+
+    /// Records the protocols negotiated with a newly connected peer.
+    pub fn add_peer(&self, peer_id: PeerId, supported_protocols: SupportedProtocols) {
+        self.peers_protocols
+            .write()
+            .insert(peer_id, supported_protocols);
+    }
+
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        self.peers_protocols.write().remove(peer_id);
+    }
+
+    /// Reports a misbehaving peer to the network, which bans it once it misbehaves too often.
+    pub fn report_misbehavior(
+        &mut self,
+        peer_id: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), Error> {
+        Ok(self.inner.report_misbehavior(peer_id, misbehavior)?)
+    }
 }
 
 /// Configuration for the network endpoints to support state sync.
@@ -74,7 +119,7 @@ pub fn network_endpoint_config() -> (
 ) {
     (
         vec![],
-        vec![ProtocolId::StateSyncDirectSend],
+        DIRECT_SEND.to_vec(),
         QueueStyle::LIFO,
         STATE_SYNC_MAX_BUFFER_SIZE,
         Some(&counters::PENDING_STATE_SYNC_NETWORK_EVENTS),
@@ -7,6 +7,7 @@ use crate::{
     error::Error,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{StateSyncMessage, StateSyncSender},
+    snapshot_request::GetStateSnapshotRequest,
 };
 use dijets_config::{
     config::{PeerNetworkId, PeerRole},
@@ -15,7 +16,7 @@ use dijets_config::{
 use dijets_logger::prelude::*;
 use itertools::Itertools;
 use netcore::transport::ConnectionOrigin;
-use network::transport::ConnectionMetadata;
+use network::{reputation::Misbehavior, transport::ConnectionMetadata};
 use rand::{
     distributions::{Distribution, WeightedIndex},
     thread_rng,
@@ -111,6 +112,11 @@ impl RequestManager {
         peer: PeerNetworkId,
         metadata: ConnectionMetadata,
     ) -> Result<(), Error> {
+        // Protocols are tracked for every peer, as we also respond to peers we don't sync from
+        if let Some(sender) = self.network_senders.get(&peer.network_id()) {
+            sender.add_peer(peer.peer_id(), metadata.application_protocols.clone());
+        }
+
         if !self.is_valid_state_sync_peer(&peer, metadata.origin) {
             return Err(Error::InvalidStateSyncPeer(
                 peer.to_string(),
@@ -145,6 +151,10 @@ impl RequestManager {
     pub fn disable_peer(&mut self, peer: &PeerNetworkId) -> Result<(), Error> {
         info!(LogSchema::new(LogEntry::LostPeer).peer(peer));
 
+        if let Some(sender) = self.network_senders.get(&peer.network_id()) {
+            sender.remove_peer(&peer.peer_id());
+        }
+
         if self.peer_scores.contains_key(peer) {
             counters::ACTIVE_UPSTREAM_PEERS
                 .with_label_values(&[&peer.raw_network_id().to_string()])
@@ -257,7 +267,31 @@ impl RequestManager {
 
     pub fn send_chunk_request(&mut self, req: GetChunkRequest) -> Result<(), Error> {
         let log = LogSchema::new(LogEntry::SendChunkRequest).chunk_request(req.clone());
+        let known_version = req.known_version;
+        let msg = StateSyncMessage::GetChunkRequest(Box::new(req));
+        self.send_request(known_version, msg, log)
+    }
+
+    /// Sends a state snapshot request. The local synced version doesn't change while a state
+    /// snapshot is being restored, so the request is tracked under `known_version` (and is
+    /// subject to the same timeouts and multicasting as chunk requests).
+    pub fn send_state_snapshot_request(
+        &mut self,
+        known_version: u64,
+        req: GetStateSnapshotRequest,
+    ) -> Result<(), Error> {
+        let log =
+            LogSchema::new(LogEntry::SendStateSnapshotRequest).state_snapshot_request(req.clone());
+        let msg = StateSyncMessage::GetStateSnapshotRequest(Box::new(req));
+        self.send_request(known_version, msg, log)
+    }
 
+    fn send_request(
+        &mut self,
+        known_version: u64,
+        msg: StateSyncMessage,
+        log: LogSchema,
+    ) -> Result<(), Error> {
         let peers = self.pick_peers();
         if peers.is_empty() {
             warn!(log.event(LogEvent::MissingPeers));
@@ -266,13 +300,12 @@ impl RequestManager {
             ));
         }
 
-        let req_info = self.add_request(req.known_version, peers.clone());
+        let req_info = self.add_request(known_version, peers.clone());
         debug!(log
             .clone()
             .event(LogEvent::ChunkRequestInfo)
             .chunk_req_info(&req_info));
 
-        let msg = StateSyncMessage::GetChunkRequest(Box::new(req));
         let mut failed_peer_sends = vec![];
 
         for peer in peers {
@@ -357,6 +390,19 @@ impl RequestManager {
 
     pub fn process_invalid_chunk(&mut self, peer: &PeerNetworkId) {
         self.update_score(peer, PeerScoreUpdateType::InvalidChunk);
+
+        if let Some(sender) = self.network_senders.get_mut(&peer.network_id()) {
+            if let Err(error) =
+                sender.report_misbehavior(peer.peer_id(), Misbehavior::InvalidMessage)
+            {
+                warn!(
+                    LogSchema::new(LogEntry::NetworkError)
+                        .peer(peer)
+                        .error(&error),
+                    "Failed to report invalid chunk"
+                );
+            }
+        }
     }
 
     pub fn process_invalid_chunk_request(&mut self, peer: &PeerNetworkId) {
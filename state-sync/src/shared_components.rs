@@ -135,15 +135,15 @@ pub(crate) mod test_utils {
         // Create test dijets database
         let db_path = dijets_temppath::TempPath::new();
         db_path.create_as_dir().unwrap();
-        let (db, db_rw) = DbReaderWriter::wrap(DijetsDB::new_for_test(db_path.path()));
+        let db_rw = DbReaderWriter::new(DijetsDB::new_for_test(db_path.path()));
 
         // Bootstrap the genesis transaction
         let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
         bootstrap_genesis::<DijetsVM>(&db_rw, &genesis_txn).unwrap();
 
         // Create executor proxy
-        let chunk_executor = Box::new(Executor::<DijetsVM>::new(db_rw));
-        let executor_proxy = ExecutorProxy::new(db, chunk_executor, vec![]);
+        let chunk_executor = Box::new(Executor::<DijetsVM>::new(db_rw.clone()));
+        let executor_proxy = ExecutorProxy::new(db_rw, chunk_executor, vec![]);
 
         // Get initial state
         let initial_state = executor_proxy.get_local_storage_state().unwrap();
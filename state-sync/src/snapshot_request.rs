@@ -0,0 +1,45 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Requests a chunk of the account state snapshot at `version`. The version must be an epoch
+/// ending version (i.e., the version of a waypoint), so that the responder can prove the snapshot
+/// relative to the corresponding epoch ending LedgerInfo.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetStateSnapshotRequest {
+    /// The version of the account state snapshot.
+    pub version: Version,
+    /// The index (in the order of account key hashes) of the first account in the response.
+    pub start_index: u64,
+    /// Max number of accounts in the response.
+    pub limit: u64,
+}
+
+impl GetStateSnapshotRequest {
+    pub fn new(version: Version, start_index: u64, limit: u64) -> Self {
+        Self {
+            version,
+            start_index,
+            limit,
+        }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[StateSnapshotRequest: version: {}, start index: {}, limit: {}]",
+            self.version, self.start_index, self.limit,
+        )
+    }
+}
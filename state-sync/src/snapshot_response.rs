@@ -0,0 +1,60 @@
+// Copyright (c) The Dijets Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use dijets_types::{
+    account_state_blob::AccountStatesChunkWithProof, ledger_info::LedgerInfoWithSignatures,
+    transaction::TransactionListWithProof,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A chunk of the account state snapshot at the version of `waypoint_li`.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GetStateSnapshotResponse {
+    /// The epoch ending LedgerInfo at the snapshot version, verified using the local waypoint.
+    pub waypoint_li: LedgerInfoWithSignatures,
+    /// The transaction at the snapshot version with proof relative to `waypoint_li`. Its
+    /// transaction info carries the state root hash that the account chunks are proven against.
+    pub txn_list_with_proof: TransactionListWithProof,
+    /// Chunk of account states with a range proof relative to the state root hash.
+    pub account_states_chunk: AccountStatesChunkWithProof,
+}
+
+impl GetStateSnapshotResponse {
+    pub fn new(
+        waypoint_li: LedgerInfoWithSignatures,
+        txn_list_with_proof: TransactionListWithProof,
+        account_states_chunk: AccountStatesChunkWithProof,
+    ) -> Self {
+        Self {
+            waypoint_li,
+            txn_list_with_proof,
+            account_states_chunk,
+        }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let accounts_repr = if self.account_states_chunk.account_blobs.is_empty() {
+            "empty".to_string()
+        } else {
+            format!(
+                "indices [{} - {}]",
+                self.account_states_chunk.first_index, self.account_states_chunk.last_index
+            )
+        };
+        write!(
+            f,
+            "[StateSnapshotResponse: waypoint li: {}, accounts: {}]",
+            self.waypoint_li.ledger_info(),
+            accounts_repr,
+        )
+    }
+}
@@ -230,6 +230,39 @@ fn catch_up_with_waypoints() {
     assert_eq!(fullnode.latest_li().ledger_info().epoch(), 19);
 }
 
+#[test]
+fn catch_up_with_state_snapshot() {
+    let mut env = StateSyncEnvironment::new(2);
+
+    env.start_validator_peer(0, false);
+    let validator_0 = env.get_state_sync_peer(0);
+
+    // The account states are restored in chunks of 10 accounts, i.e., in 3 chunks
+    validator_0.create_account_states(25);
+    validator_0.commit(300);
+    validator_0.move_to_next_epoch(vec![validator_0.get_validator_info().clone()], 0);
+    validator_0.commit(800); // At this point validator is at epoch 2 and version 800
+
+    // Create a waypoint based on LedgerInfo of peer 0 at version 300 (epoch 1)
+    let waypoint_li = validator_0.get_epoch_ending_ledger_info(300);
+    let waypoint = Waypoint::new_epoch_boundary(waypoint_li.ledger_info()).unwrap();
+    let account_states = validator_0.get_account_states();
+    drop(validator_0);
+
+    // Fullnode 1 restores the account states at the waypoint version without replaying the
+    // transactions up to it
+    env.start_state_snapshot_sync_peer(1, waypoint, 10);
+    let fullnode = env.get_state_sync_peer(1);
+    fullnode.wait_until_initialized();
+    assert_eq!(fullnode.get_account_states(), account_states);
+    assert_eq!(fullnode.get_first_txn_version(), 300);
+
+    // Once the snapshot is restored fullnode 1 syncs the transactions from its version
+    assert!(fullnode.wait_for_version(800, None));
+    assert_eq!(fullnode.latest_li().ledger_info().epoch(), 2);
+    assert_eq!(fullnode.get_first_txn_version(), 300);
+}
+
 #[test]
 fn test_lagging_upstream_long_poll() {
     let mut env = StateSyncEnvironment::new(4);
@@ -843,8 +876,8 @@ fn check_chunk_request(message: Message, known_version: u64, target_version: Opt
             assert_eq!(chunk_request.known_version, known_version);
             assert_eq!(chunk_request.target.version(), target_version);
         }
-        StateSyncMessage::GetChunkResponse(_) => {
-            panic!("Received chunk response but expecting chunk request!");
+        _ => {
+            panic!("Received unexpected message but expecting chunk request!");
         }
     }
 }
@@ -857,9 +890,6 @@ fn check_chunk_response(
 ) {
     let chunk_response: StateSyncMessage = bcs::from_bytes(&message.mdata).unwrap();
     match chunk_response {
-        StateSyncMessage::GetChunkRequest(_) => {
-            panic!("Received chunk response but expecting chunk request!");
-        }
         StateSyncMessage::GetChunkResponse(chunk_response) => {
             assert_eq!(chunk_response.response_li.version(), response_li_version);
             assert_eq!(
@@ -874,6 +904,9 @@ fn check_chunk_response(
                 chunk_length
             )
         }
+        _ => {
+            panic!("Received unexpected message but expecting chunk response!");
+        }
     }
 }
 
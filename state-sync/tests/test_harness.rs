@@ -1,21 +1,31 @@
 // Copyright (c) The Dijets Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use accumulator::{test_helpers::MockHashStore, MerkleAccumulator};
 use channel::{dijets_channel, message_queues::QueueStyle};
 use dijets_config::{
     config::{NodeConfig, Peer, PeerRole, RoleType, HANDSHAKE_VERSION},
     network_id::{NetworkContext, NetworkId, NodeNetworkId},
 };
 use dijets_crypto::{
-    hash::ACCUMULATOR_PLACEHOLDER_HASH, test_utils::TEST_SEED, x25519, HashValue, Uniform,
+    hash::{
+        CryptoHash, TransactionAccumulatorHasher, ACCUMULATOR_PLACEHOLDER_HASH,
+        SPARSE_MERKLE_PLACEHOLDER_HASH,
+    },
+    test_utils::TEST_SEED,
+    x25519, HashValue, Uniform,
 };
 use dijets_infallible::RwLock;
+use dijets_jellyfish_merkle::{
+    mock_tree_store::MockTreeStore, restore::JellyfishMerkleRestore, JellyfishMerkleTree,
+};
 use dijets_mempool::mocks::MockSharedMempool;
 use dijets_time_service::TimeService;
 use dijets_transaction_builder::stdlib::encode_peer_to_peer_with_metadata_script;
 use dijets_types::{
     account_address::AccountAddress,
     account_config::xus_tag,
+    account_state_blob::{AccountStateBlob, AccountStatesChunkWithProof},
     block_info::BlockInfo,
     chain_id::ChainId,
     contract_event::ContractEvent,
@@ -26,15 +36,17 @@ use dijets_types::{
         parse_memory, NetworkAddress, Protocol,
     },
     on_chain_config::ValidatorSet,
-    proof::TransactionListProof,
+    proof::{TransactionAccumulatorRangeProof, TransactionListProof},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{
-        authenticator::AuthenticationKey, SignedTransaction, Transaction, TransactionListWithProof,
+        authenticator::AuthenticationKey, SignedTransaction, Transaction, TransactionInfo,
+        TransactionListWithProof, Version,
     },
     validator_config::ValidatorConfig,
     validator_info::ValidatorInfo,
     validator_signer::ValidatorSigner,
     validator_verifier::random_validator_verifier,
+    vm_status::KeptVMStatus,
     waypoint::Waypoint,
     PeerId,
 };
@@ -72,6 +84,7 @@ use std::{
     ops::DerefMut,
     sync::Arc,
 };
+use storage_interface::StateSnapshotReceiver;
 use tokio::runtime::Runtime;
 use vm_genesis::GENESIS_KEYPAIR;
 
@@ -120,6 +133,34 @@ impl StateSyncPeer {
         }
     }
 
+    // Replaces the account states of the peer with `num_accounts` random accounts.
+    pub fn create_account_states(&self, num_accounts: usize) {
+        self.storage_proxy
+            .as_ref()
+            .unwrap()
+            .write()
+            .create_account_states(num_accounts);
+    }
+
+    pub fn get_account_states(&self) -> BTreeMap<HashValue, AccountStateBlob> {
+        self.storage_proxy
+            .as_ref()
+            .unwrap()
+            .read()
+            .account_states()
+            .clone()
+    }
+
+    // Returns the version of the first transaction held by the peer (i.e., the version of the
+    // state snapshot it was restored from, if any).
+    pub fn get_first_txn_version(&self) -> u64 {
+        self.storage_proxy
+            .as_ref()
+            .unwrap()
+            .read()
+            .first_txn_version()
+    }
+
     pub fn get_peer_id(&self, network_id: NetworkId) -> PeerId {
         *self.multi_peer_ids.get(&network_id).unwrap()
     }
@@ -248,6 +289,27 @@ impl StateSyncEnvironment {
         );
     }
 
+    // Starts a new state sync peer with the fullnode role that restores the account state
+    // snapshot at the waypoint version, `state_snapshot_chunk_limit` accounts at a time.
+    pub fn start_state_snapshot_sync_peer(
+        &mut self,
+        peer_index: usize,
+        waypoint: Waypoint,
+        state_snapshot_chunk_limit: u64,
+    ) {
+        let (mut config, network_id) = setup_state_sync_config(RoleType::FullNode, 60_000, 120_000);
+        config.state_sync.enable_state_snapshot_sync = true;
+        config.state_sync.state_snapshot_chunk_limit = state_snapshot_chunk_limit;
+        self.setup_state_sync_peer_with_config(
+            peer_index,
+            default_handler(),
+            config,
+            network_id,
+            waypoint,
+            false,
+        );
+    }
+
     // Sets up and starts the state sync peer at the given node index.
     pub fn start_state_sync_peer(
         &mut self,
@@ -279,6 +341,26 @@ impl StateSyncEnvironment {
         mock_network: bool,
     ) {
         let (config, network_id) = setup_state_sync_config(role, timeout_ms, multicast_timeout_ms);
+        self.setup_state_sync_peer_with_config(
+            index,
+            handler,
+            config,
+            network_id,
+            waypoint,
+            mock_network,
+        );
+    }
+
+    fn setup_state_sync_peer_with_config(
+        &mut self,
+        index: usize,
+        handler: MockRpcHandler,
+        config: NodeConfig,
+        network_id: NetworkId,
+        waypoint: Waypoint,
+        mock_network: bool,
+    ) {
+        let role = config.base.role;
         let network_handles = self.setup_network_handles(index, &role, mock_network, network_id);
         let validators: Vec<ValidatorInfo> = self
             .peers
@@ -574,12 +656,25 @@ fn setup_state_sync_config(
     (config, network_id)
 }
 
+type TransactionAccumulator = MerkleAccumulator<MockHashStore, TransactionAccumulatorHasher>;
+
+// The mock account states don't change with the txns, so their state tree has a single version.
+const STATE_TREE_VERSION: Version = 0;
+
 #[derive(Clone)]
 pub struct MockStorage {
     // some mock transactions in the storage
     transactions: Vec<Transaction>,
+    // the transaction infos of the txns above
+    transaction_infos: Vec<TransactionInfo>,
+    // the version of the first txn above (the txns before a restored state snapshot are skipped)
+    first_txn_version: Version,
     // the executed trees after applying the txns above.
     synced_trees: ExecutedTrees,
+    // the account states by account key hash
+    account_states: BTreeMap<HashValue, AccountStateBlob>,
+    // the root hash of the state tree of the account states above
+    state_root_hash: HashValue,
     // latest ledger info per epoch
     ledger_infos: HashMap<u64, LedgerInfoWithSignatures>,
     // latest epoch number (starts with 1)
@@ -599,7 +694,11 @@ impl MockStorage {
         ledger_infos.insert(0, genesis_li);
         Self {
             transactions: vec![],
+            transaction_infos: vec![],
+            first_txn_version: 1,
             synced_trees: ExecutedTrees::new_empty(),
+            account_states: BTreeMap::new(),
+            state_root_hash: *SPARSE_MERKLE_PLACEHOLDER_HASH,
             ledger_infos,
             epoch_num,
             signer,
@@ -608,18 +707,69 @@ impl MockStorage {
     }
 
     fn add_txns(&mut self, txns: &mut Vec<Transaction>) {
+        for txn in txns.iter() {
+            self.transaction_infos.push(TransactionInfo::new(
+                txn.hash(),
+                self.state_root_hash,
+                *ACCUMULATOR_PLACEHOLDER_HASH, /* no events */
+                0,
+                KeptVMStatus::Executed,
+            ));
+        }
         self.transactions.append(txns);
-        let num_leaves = self.transactions.len() + 1;
+        self.update_synced_trees();
+    }
+
+    fn update_synced_trees(&mut self) {
+        let num_leaves = self.version() + 1;
         let frozen_subtree_roots = vec![HashValue::zero(); num_leaves.count_ones() as usize];
         self.synced_trees = ExecutedTrees::new(
             HashValue::zero(), /* dummy_state_root */
             frozen_subtree_roots,
-            num_leaves as u64,
+            num_leaves,
         );
     }
 
     pub fn version(&self) -> u64 {
-        self.transactions.len() as u64
+        self.first_txn_version + self.transactions.len() as u64 - 1
+    }
+
+    pub fn first_txn_version(&self) -> u64 {
+        self.first_txn_version
+    }
+
+    pub fn account_states(&self) -> &BTreeMap<HashValue, AccountStateBlob> {
+        &self.account_states
+    }
+
+    // Replaces the account states with `num_accounts` random accounts. The txns committed from
+    // now on carry the root hash of their state tree.
+    pub fn create_account_states(&mut self, num_accounts: usize) {
+        self.account_states = (0..num_accounts)
+            .map(|_| {
+                let blob = AccountStateBlob::from(HashValue::random().to_vec());
+                (HashValue::random(), blob)
+            })
+            .collect();
+        let (_, state_root_hash) = self.create_state_tree().unwrap();
+        self.state_root_hash = state_root_hash;
+    }
+
+    // Writes the account states to a new state tree and returns it with its root hash.
+    fn create_state_tree(&self) -> Result<(MockTreeStore<AccountStateBlob>, HashValue), Error> {
+        let state_tree = MockTreeStore::default();
+        let account_states = self
+            .account_states
+            .iter()
+            .map(|(key, blob)| (*key, blob.clone()))
+            .collect();
+        let (root_hash, batch) = JellyfishMerkleTree::new(&state_tree)
+            .put_value_set(account_states, STATE_TREE_VERSION)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        state_tree
+            .write_tree_update_batch(batch)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        Ok((state_tree, root_hash))
     }
 
     pub fn synced_trees(&self) -> &ExecutedTrees {
@@ -657,23 +807,148 @@ impl MockStorage {
         }
     }
 
+    // Returns the txns in the chunk with a proof relative to the transaction accumulator at
+    // `target_version`.
     pub fn get_chunk(
         &self,
         start_version: u64,
         limit: u64,
         target_version: u64,
-    ) -> Vec<Transaction> {
-        let mut res = vec![];
-        if target_version < start_version || start_version == 0 {
-            return res;
+    ) -> Result<TransactionListWithProof, Error> {
+        let mut transactions = vec![];
+        let mut transaction_infos = vec![];
+        if target_version < start_version || start_version < self.first_txn_version {
+            return Ok(TransactionListWithProof::new_empty());
         }
         let mut version = start_version;
         let limit = std::cmp::min(limit, target_version - start_version + 1);
-        while version - 1 < self.transactions.len() as u64 && version - start_version < limit {
-            res.push(self.transactions[(version - 1) as usize].clone());
+        while version <= self.version() && version - start_version < limit {
+            let index = (version - self.first_txn_version) as usize;
+            transactions.push(self.transactions[index].clone());
+            transaction_infos.push(self.transaction_infos[index].clone());
             version += 1;
         }
-        res
+        if transactions.is_empty() {
+            return Ok(TransactionListWithProof::new_empty());
+        }
+
+        let range_proof =
+            self.get_transaction_range_proof(start_version, transactions.len(), target_version)?;
+        Ok(TransactionListWithProof::new(
+            transactions,
+            None,
+            Some(start_version),
+            TransactionListProof::new(range_proof, transaction_infos),
+        ))
+    }
+
+    // Returns the leaves of the transaction accumulator (i.e., the hashes of the transaction
+    // infos) up to the given version.
+    fn get_transaction_accumulator_leaves(&self, version: u64) -> Result<Vec<HashValue>, Error> {
+        if self.first_txn_version != 1 {
+            return Err(Error::UnexpectedError(
+                "Mock storage is missing the txns before its state snapshot!".into(),
+            ));
+        }
+        let genesis_txn_info = TransactionInfo::new(
+            HashValue::zero(),
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            *ACCUMULATOR_PLACEHOLDER_HASH,
+            0,
+            KeptVMStatus::Executed,
+        );
+        Ok(std::iter::once(&genesis_txn_info)
+            .chain(self.transaction_infos.iter())
+            .take(version as usize + 1)
+            .map(CryptoHash::hash)
+            .collect())
+    }
+
+    fn get_transaction_accumulator_root_hash(&self, version: u64) -> Result<HashValue, Error> {
+        let leaves = self.get_transaction_accumulator_leaves(version)?;
+        let (root_hash, _) = TransactionAccumulator::append(&MockHashStore::new(), 0, &leaves)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        Ok(root_hash)
+    }
+
+    fn get_transaction_range_proof(
+        &self,
+        first_version: u64,
+        num_txns: usize,
+        target_version: u64,
+    ) -> Result<TransactionAccumulatorRangeProof, Error> {
+        let leaves = self.get_transaction_accumulator_leaves(target_version)?;
+        let mut hash_store = MockHashStore::new();
+        let (_, writes) = TransactionAccumulator::append(&hash_store, 0, &leaves)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        hash_store.put_many(&writes);
+        TransactionAccumulator::get_range_proof(
+            &hash_store,
+            leaves.len() as u64,
+            Some(first_version),
+            num_txns as u64,
+        )
+        .map_err(|error| Error::UnexpectedError(error.to_string()))
+    }
+
+    // Returns the chunk of (at most `limit`) account states starting at `start_index`, with a
+    // range proof relative to the root hash of their state tree.
+    pub fn get_account_states_chunk(
+        &self,
+        start_index: u64,
+        limit: u64,
+    ) -> Result<AccountStatesChunkWithProof, Error> {
+        let account_blobs: Vec<_> = self
+            .account_states
+            .iter()
+            .skip(start_index as usize)
+            .take(limit as usize)
+            .map(|(key, blob)| (*key, blob.clone()))
+            .collect();
+        let (first_key, last_key) = match (account_blobs.first(), account_blobs.last()) {
+            (Some((first_key, _)), Some((last_key, _))) => (*first_key, *last_key),
+            _ => {
+                return Err(Error::UnexpectedError(format!(
+                    "Mock storage has no account state at index {}!",
+                    start_index
+                )))
+            }
+        };
+
+        let (state_tree, root_hash) = self.create_state_tree()?;
+        let proof = JellyfishMerkleTree::new(&state_tree)
+            .get_range_proof(last_key, STATE_TREE_VERSION)
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        Ok(AccountStatesChunkWithProof {
+            first_index: start_index,
+            last_index: start_index + account_blobs.len() as u64 - 1,
+            first_key,
+            last_key,
+            account_blobs,
+            proof,
+            root_hash,
+        })
+    }
+
+    // Replaces the content of the storage with the restored account states and the txn at the
+    // version of the state snapshot (the txns before it are skipped).
+    pub fn finalize_state_snapshot(
+        &mut self,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        txn_list_with_proof: TransactionListWithProof,
+        waypoint_li: LedgerInfoWithSignatures,
+    ) {
+        let ledger_info = waypoint_li.ledger_info();
+        self.account_states = account_states.into_iter().collect();
+        self.transaction_infos = txn_list_with_proof.proof.transaction_infos().to_vec();
+        self.state_root_hash = self.transaction_infos[0].state_root_hash();
+        self.transactions = txn_list_with_proof.transactions;
+        self.first_txn_version = ledger_info.version();
+        self.update_synced_trees();
+
+        self.epoch_num = ledger_info.epoch() + 1;
+        self.epoch_state = ledger_info.next_epoch_state().unwrap().clone();
+        self.ledger_infos.insert(ledger_info.epoch(), waypoint_li);
     }
 
     pub fn add_txns_with_li(
@@ -747,12 +1022,15 @@ impl MockStorage {
             epoch: self.epoch_num() + 1,
             verifier: (&set).into(),
         });
+        let accumulator_root_hash = self
+            .get_transaction_accumulator_root_hash(self.version())
+            .unwrap();
         let ledger_info = LedgerInfo::new(
             BlockInfo::new(
                 self.epoch_num(),
                 self.version(),
                 HashValue::zero(),
-                HashValue::zero(),
+                accumulator_root_hash,
                 self.version(),
                 0,
                 epoch_state,
@@ -812,11 +1090,26 @@ pub type MockRpcHandler = Box<
 pub struct MockExecutorProxy {
     handler: MockRpcHandler,
     storage: Arc<RwLock<MockStorage>>,
+    // The account state snapshot being restored (if any)
+    state_snapshot: Option<MockStateSnapshot>,
+}
+
+// An account state snapshot being restored in a new state tree, which verifies every chunk
+// against the expected root hash. The account states are saved to the mock storage once the
+// snapshot is complete.
+struct MockStateSnapshot {
+    version: Version,
+    receiver: Box<dyn StateSnapshotReceiver<AccountStateBlob>>,
+    account_states: Vec<(HashValue, AccountStateBlob)>,
 }
 
 impl MockExecutorProxy {
     pub fn new(handler: MockRpcHandler, storage: Arc<RwLock<MockStorage>>) -> Self {
-        Self { handler, storage }
+        Self {
+            handler,
+            storage,
+            state_snapshot: None,
+        }
     }
 }
 
@@ -848,17 +1141,10 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         let start_version = known_version
             .checked_add(1)
             .ok_or_else(|| Error::IntegerOverflow("Known version has overflown!".into()))?;
-        let txns = self
-            .storage
-            .read()
-            .get_chunk(start_version, limit, target_version);
-        let first_txn_version = txns.first().map(|_| start_version);
-        let txns_with_proof = TransactionListWithProof::new(
-            txns,
-            None,
-            first_txn_version,
-            TransactionListProof::new_empty(),
-        );
+        let txns_with_proof =
+            self.storage
+                .read()
+                .get_chunk(start_version, limit, target_version)?;
         (self.handler)(txns_with_proof)
     }
 
@@ -878,6 +1164,87 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         Ok(0)
     }
 
+    fn get_account_states_chunk(
+        &self,
+        _version: u64,
+        start_index: u64,
+        limit: u64,
+    ) -> Result<AccountStatesChunkWithProof, Error> {
+        // Mock account states are the same at every version
+        self.storage
+            .read()
+            .get_account_states_chunk(start_index, limit)
+    }
+
+    fn save_account_states_chunk(
+        &mut self,
+        version: u64,
+        expected_root_hash: HashValue,
+        account_states_chunk: AccountStatesChunkWithProof,
+    ) -> Result<(), Error> {
+        if account_states_chunk.first_index == 0 {
+            // (Re)start the restore of the snapshot
+            let receiver = JellyfishMerkleRestore::new_overwrite(
+                Arc::new(MockTreeStore::default()),
+                version,
+                expected_root_hash,
+            )
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+            self.state_snapshot = Some(MockStateSnapshot {
+                version,
+                receiver: Box::new(receiver),
+                account_states: vec![],
+            });
+        }
+
+        match self.state_snapshot.as_mut() {
+            Some(snapshot) if snapshot.version == version => {
+                snapshot
+                    .receiver
+                    .add_chunk(
+                        account_states_chunk.account_blobs.clone(),
+                        account_states_chunk.proof,
+                    )
+                    .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+                snapshot
+                    .account_states
+                    .extend(account_states_chunk.account_blobs);
+                Ok(())
+            }
+            _ => Err(Error::UnexpectedError(format!(
+                "No state snapshot is being restored at version {}",
+                version
+            ))),
+        }
+    }
+
+    fn finalize_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_waypoint_li: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let version = verified_waypoint_li.ledger_info().version();
+        let snapshot = match self.state_snapshot.take() {
+            Some(snapshot) if snapshot.version == version => snapshot,
+            _ => {
+                return Err(Error::UnexpectedError(format!(
+                    "No state snapshot is being restored at version {}",
+                    version
+                )))
+            }
+        };
+        snapshot
+            .receiver
+            .finish_box()
+            .map_err(|error| Error::UnexpectedError(error.to_string()))?;
+        self.storage.write().finalize_state_snapshot(
+            snapshot.account_states,
+            txn_list_with_proof,
+            verified_waypoint_li,
+        );
+        Ok(())
+    }
+
     fn publish_on_chain_config_updates(
         &mut self,
         _events: Vec<ContractEvent>,